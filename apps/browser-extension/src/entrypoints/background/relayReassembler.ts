@@ -0,0 +1,152 @@
+// ===================================================================
+// RELAY CHUNK REASSEMBLY
+// ===================================================================
+//
+// Native nodes split relays whose serialized data exceeds 16 KiB (SDP with
+// many candidates, DKG package bundles) into "RelayChunk" envelopes that
+// share a payload_id. The signal server forwards them like any other relay.
+// This mirrors the server crate's RelayReassembler (chunking.rs), so chunked
+// payloads are rebuilt before the relay is dispatched.
+// ===================================================================
+
+/** `websocket_msg_type` of a chunk envelope */
+export const CHUNK_MSG_TYPE = "RelayChunk";
+
+/** How long an incomplete payload waits for its missing chunks */
+export const DEFAULT_REASSEMBLY_TTL_MS = 30_000;
+
+/** Incomplete payloads buffered at once; the oldest is dropped beyond this */
+export const DEFAULT_MAX_PENDING_PAYLOADS = 64;
+
+/** Upper bound on `chunk_total`, so one bogus envelope can't allocate unbounded slots */
+export const MAX_CHUNKS_PER_PAYLOAD = 4096;
+
+interface RelayChunk {
+    websocket_msg_type: typeof CHUNK_MSG_TYPE;
+    payload_id: string;
+    /** 0-based position of this chunk */
+    chunk_index: number;
+    chunk_total: number;
+    /** Slice of the serialized payload JSON */
+    chunk: string;
+}
+
+interface PendingPayload {
+    chunks: (string | undefined)[];
+    received: number;
+    startedAt: number;
+}
+
+function asChunk(data: any): RelayChunk | null {
+    if (!data || data.websocket_msg_type !== CHUNK_MSG_TYPE) {
+        return null;
+    }
+    const { payload_id, chunk_index, chunk_total, chunk } = data;
+    if (
+        typeof payload_id !== "string" ||
+        typeof chunk !== "string" ||
+        !Number.isInteger(chunk_index) ||
+        !Number.isInteger(chunk_total)
+    ) {
+        return null;
+    }
+    return data as RelayChunk;
+}
+
+/**
+ * Receiver-side buffer that rebuilds chunked relay payloads
+ */
+export class RelayReassembler {
+    /** Keyed on sender and payload_id */
+    private pending = new Map<string, PendingPayload>();
+    private readonly maxPending: number;
+
+    constructor(
+        private readonly ttlMs: number = DEFAULT_REASSEMBLY_TTL_MS,
+        maxPending: number = DEFAULT_MAX_PENDING_PAYLOADS,
+        private readonly now: () => number = Date.now
+    ) {
+        this.maxPending = Math.max(1, maxPending);
+    }
+
+    /**
+     * Returns the relay data to handle: ordinary relays unchanged, a chunked
+     * payload once its last chunk arrives, and null while still incomplete
+     */
+    accept(from: string, data: any): any | null {
+        if (data?.websocket_msg_type !== CHUNK_MSG_TYPE) {
+            return data;
+        }
+        const chunk = asChunk(data);
+        const now = this.now();
+        this.expire(now);
+        if (
+            !chunk ||
+            chunk.chunk_total <= 0 ||
+            chunk.chunk_total > MAX_CHUNKS_PER_PAYLOAD ||
+            chunk.chunk_index < 0 ||
+            chunk.chunk_index >= chunk.chunk_total
+        ) {
+            return null;
+        }
+
+        const key = JSON.stringify([from, chunk.payload_id]);
+        let payload = this.pending.get(key);
+        if (!payload) {
+            if (this.pending.size >= this.maxPending) {
+                this.dropOldest();
+            }
+            payload = { chunks: new Array(chunk.chunk_total).fill(undefined), received: 0, startedAt: now };
+            this.pending.set(key, payload);
+        }
+        if (payload.chunks.length !== chunk.chunk_total) {
+            return null;
+        }
+        if (payload.chunks[chunk.chunk_index] === undefined) {
+            payload.chunks[chunk.chunk_index] = chunk.chunk;
+            payload.received++;
+        }
+        if (payload.received < payload.chunks.length) {
+            return null;
+        }
+
+        this.pending.delete(key);
+        try {
+            return JSON.parse(payload.chunks.join(""));
+        } catch {
+            console.warn("[RelayReassembler] Dropping chunked relay that is not valid JSON:", chunk.payload_id);
+            return null;
+        }
+    }
+
+    /** Drop payloads still incomplete after the TTL; returns how many were dropped */
+    expire(now: number = this.now()): number {
+        let dropped = 0;
+        for (const [key, payload] of this.pending) {
+            if (now - payload.startedAt >= this.ttlMs) {
+                this.pending.delete(key);
+                dropped++;
+            }
+        }
+        return dropped;
+    }
+
+    /** Number of incomplete payloads currently buffered */
+    get size(): number {
+        return this.pending.size;
+    }
+
+    private dropOldest(): void {
+        let oldest: string | undefined;
+        let oldestStart = Infinity;
+        for (const [key, payload] of this.pending) {
+            if (payload.startedAt < oldestStart) {
+                oldest = key;
+                oldestStart = payload.startedAt;
+            }
+        }
+        if (oldest !== undefined) {
+            this.pending.delete(oldest);
+        }
+    }
+}
@@ -11,6 +11,7 @@
 // ===================================================================
 
 import { WebSocketClient } from "./websocket";
+import { RelayReassembler } from "./relayReassembler";
 import { AppState } from "@mpc-wallet/types/appstate";
 import { SessionManager } from "./sessionManager";
 import type {
@@ -31,6 +32,7 @@ export class WebSocketManager {
     private broadcastToPopup: (message: BackgroundToPopupMessage) => void;
     private sendToOffscreen: (message: OffscreenMessage, description: string) => Promise<{ success: boolean; error?: string }>;
     private stateManager?: any; // StateManager for persistence
+    private relayReassembler = new RelayReassembler();
 
     constructor(
         appState: AppState,
@@ -284,10 +286,14 @@ export class WebSocketManager {
      */
     private handleRelayMessage(msg: ServerMsg & { type: "relay" | "RELAY" }, messageType: string): void {
         console.log(`[WebSocketManager] Received ${messageType} message from server:`, msg);
-        const data = msg.data as WebSocketMessagePayload;
+        if (!msg.data || !(msg.data as any).websocket_msg_type) {
+            console.warn("[WebSocketManager] Invalid relay message data:", msg.data);
+            return;
+        }
 
-        if (!data || !data.websocket_msg_type) {
-            console.warn("[WebSocketManager] Invalid relay message data:", data);
+        // Large relays from native nodes arrive in chunks; wait for the rest
+        const data = this.relayReassembler.accept(msg.from, msg.data) as WebSocketMessagePayload | null;
+        if (!data) {
             return;
         }
 
@@ -303,10 +303,17 @@ describe('WebRTCManager DKG Process', () => {
             await (manager as any)._handleDkgRound2Package('c', { sender_index: 3, sender_id_hex: 'c', data: round2C_for_A_hex });
 
             // After manager handles all Round 2, its internal frostDkg (dkgA) should be ready to finalize
-            // and manager state should be Finalizing (or Complete if _finalizeDkg is called by handler)
             expect((manager as any).frostDkg.can_finalize()).toBe(true);
-            // The _handleDkgRound2Package for the last package should trigger _finalizeDkg
-            expect(manager.dkgState).toBe(DkgState.Complete); // Assuming _finalizeDkg is called and sets state
+            // The last Round 2 package finalizes, then the manager waits for every peer's fingerprint
+            expect(manager.dkgState).toBe(DkgState.Finalizing);
+            expect(() => (manager as any).frostDkg.export_keystore()).toThrow();
+
+            const fingerprint = (manager as any).frostDkg.group_key_fingerprint();
+            manager.handleWebRTCAppMessage('b', { webrtc_msg_type: 'DkgGroupKeyFingerprint', fingerprint });
+            expect(manager.dkgState).toBe(DkgState.Finalizing);
+            manager.handleWebRTCAppMessage('c', { webrtc_msg_type: 'DkgGroupKeyFingerprint', fingerprint });
+            expect(manager.dkgState).toBe(DkgState.Complete);
+            expect((manager as any).frostDkg.export_keystore()).toBeTruthy();
 
         } catch (error) {
             console.error('⚠️ Error during Round 2 setup/processing:', (error as Error).message ? (error as Error).message : error);
@@ -59,6 +59,8 @@ export class WebRTCManager {
   // Package buffering for handling packages that arrive before DKG initialization
   private bufferedRound1Packages: Array<{ fromPeerId: string; packageData: any }> = [];
   private bufferedRound2Packages: Array<{ fromPeerId: string; packageData: any }> = [];
+  // Group key fingerprints from peers, held until our own DKG has finalized
+  private receivedGroupKeyFingerprints: Map<string, string> = new Map();
 
   // FROST Signing integration
   public signingState: SigningState = SigningState.Idle;
@@ -902,6 +904,9 @@ export class WebRTCManager {
             this._handleDkgRound2Package(fromPeerId, (message as any).package);
           }
           break;
+        case 'DkgGroupKeyFingerprint':
+          this._handleDkgGroupKeyFingerprint(fromPeerId, (message as any).fingerprint);
+          break;
         case 'SigningRequest':
           this._handleSigningRequest(fromPeerId, message as any);
           break;
@@ -1517,30 +1522,82 @@ export class WebRTCManager {
         return;
       }
 
-      // Finalize DKG and get group public key
-      this.groupPublicKey = this.frostDkg.finalize_dkg();
+      // Finalize DKG. WASM withholds the keystore until every peer has
+      // reported the same group key, so broadcast ours and wait for theirs.
+      const fingerprint: string = this.frostDkg.finalize_dkg();
+      this._updateDkgState(DkgState.Finalizing);
 
-      // Generate blockchain addresses using proper WASM methods
-      if (this.groupPublicKey) {
-        if (this.currentBlockchain === 'ethereum') {
-          // For Ethereum, use the secp256k1 WASM method
-          this.ethereumAddress = (this.frostDkg as any).get_eth_address();
-          this.walletAddress = this.ethereumAddress;
-        } else {
-          // For Solana, use the Ed25519 WASM method for proper Base58 encoding
-          this.solanaAddress = (this.frostDkg as any).get_address();
-          this.walletAddress = this.solanaAddress;
-        }
+      if (this.sessionInfo) {
+        this.sessionInfo.participants.forEach(peerId => {
+          if (peerId !== this.localPeerId) {
+            const message: WebRTCAppMessage = {
+              webrtc_msg_type: 'DkgGroupKeyFingerprint' as const,
+              fingerprint
+            };
+            this.sendWebRTCAppMessage(peerId, message);
+          }
+        });
       }
+      this._log(`Broadcast group key fingerprint ${fingerprint}`);
 
-      this._updateDkgState(DkgState.Complete);
-      this._log(`DKG completed successfully. Group public key: ${this.groupPublicKey}`);
+      this._checkGroupKeyFingerprints();
     } catch (error) {
       this._log(`Error finalizing DKG: ${this._getErrorMessage(error)}`);
       this._updateDkgState(DkgState.Failed);
     }
   }
 
+  private _handleDkgGroupKeyFingerprint(fromPeerId: string, fingerprint: string): void {
+    if (!this.sessionInfo || !this.sessionInfo.participants.includes(fromPeerId) || typeof fingerprint !== 'string') {
+      this._log(`Ignoring group key fingerprint from ${fromPeerId}: not a session participant`);
+      return;
+    }
+    this.receivedGroupKeyFingerprints.set(fromPeerId, fingerprint);
+    this._checkGroupKeyFingerprints();
+  }
+
+  // Compare every received fingerprint with our group key. A mismatch aborts
+  // the DKG before anything is saved; full agreement completes it.
+  private _checkGroupKeyFingerprints(): void {
+    if (!this.frostDkg || !this.sessionInfo || this.dkgState !== DkgState.Finalizing) {
+      return;
+    }
+
+    try {
+      const participants = this.sessionInfo.participants;
+      this.receivedGroupKeyFingerprints.forEach((fingerprint, peerId) => {
+        const senderIndex = participants.indexOf(peerId) + 1;
+        this.frostDkg.add_peer_fingerprint(senderIndex, fingerprint);
+      });
+      this.receivedGroupKeyFingerprints.clear();
+    } catch (error) {
+      this._log(`Aborting DKG: ${this._getErrorMessage(error)}`);
+      this.receivedGroupKeyFingerprints.clear();
+      this._updateDkgState(DkgState.Failed);
+      return;
+    }
+
+    if (!this.frostDkg.is_group_key_confirmed()) {
+      return;
+    }
+
+    this.groupPublicKey = this.frostDkg.get_group_public_key();
+
+    // Generate blockchain addresses using proper WASM methods
+    if (this.currentBlockchain === 'ethereum') {
+      // For Ethereum, use the secp256k1 WASM method
+      this.ethereumAddress = (this.frostDkg as any).get_eth_address();
+      this.walletAddress = this.ethereumAddress;
+    } else {
+      // For Solana, use the Ed25519 WASM method for proper Base58 encoding
+      this.solanaAddress = (this.frostDkg as any).get_address();
+      this.walletAddress = this.solanaAddress;
+    }
+
+    this._updateDkgState(DkgState.Complete);
+    this._log(`DKG completed successfully. Group public key: ${this.groupPublicKey}`);
+  }
+
   private _resetDkgState(): void {
     this._log(`Resetting DKG state`);
     // Note: Don't reset dkgState here - caller should manage state transitions
@@ -1553,6 +1610,7 @@ export class WebRTCManager {
     this.ethereumAddress = null;
     this.bufferedRound1Packages = [];
     this.bufferedRound2Packages = [];
+    this.receivedGroupKeyFingerprints.clear();
   }
 
   // Add public resetDkgState method for tests
@@ -0,0 +1,86 @@
+import { describe, it, expect } from 'bun:test';
+import {
+    CHUNK_MSG_TYPE,
+    DEFAULT_REASSEMBLY_TTL_MS,
+    MAX_CHUNKS_PER_PAYLOAD,
+    RelayReassembler
+} from '../../../src/entrypoints/background/relayReassembler';
+
+// Envelopes shaped like the signal server's chunk_relay output
+function chunkRelay(data: any, maxLen: number, payloadId = 'p1'): any[] {
+    const payload = JSON.stringify(data);
+    const pieces: string[] = [];
+    for (let i = 0; i < payload.length; i += maxLen) {
+        pieces.push(payload.slice(i, i + maxLen));
+    }
+    return pieces.map((chunk, chunk_index) => ({
+        websocket_msg_type: CHUNK_MSG_TYPE,
+        message_id: `${payloadId}:${chunk_index}`,
+        payload_id: payloadId,
+        chunk_index,
+        chunk_total: pieces.length,
+        chunk
+    }));
+}
+
+const offer = {
+    websocket_msg_type: 'WebRTCSignal',
+    Offer: { sdp: 'a=candidate:1 1 udp 2130706431 10.0.0.1 50000 typ host\r\n'.repeat(40) }
+};
+
+describe('RelayReassembler', () => {
+    it('passes ordinary relays through', () => {
+        const reassembler = new RelayReassembler();
+        const data = { websocket_msg_type: 'SessionResponse', accepted: true };
+        expect(reassembler.accept('tui-1', data)).toBe(data);
+        expect(reassembler.size).toBe(0);
+    });
+
+    it('rebuilds out-of-order and repeated chunks', () => {
+        const reassembler = new RelayReassembler();
+        const chunks = chunkRelay(offer, 200);
+        expect(chunks.length).toBeGreaterThan(3);
+        const last = chunks.shift();
+        chunks.reverse();
+        for (const chunk of chunks) {
+            expect(reassembler.accept('tui-1', chunk)).toBeNull();
+            expect(reassembler.accept('tui-1', chunk)).toBeNull();
+        }
+        expect(reassembler.size).toBe(1);
+        expect(reassembler.accept('tui-1', last)).toEqual(offer);
+        expect(reassembler.size).toBe(0);
+    });
+
+    it('keeps payloads from different senders apart', () => {
+        const reassembler = new RelayReassembler();
+        const [a0, a1] = chunkRelay(offer, Math.ceil(JSON.stringify(offer).length / 2));
+        const [b0] = chunkRelay({ websocket_msg_type: 'WebRTCSignal', other: 'x'.repeat(300) }, 200);
+        expect(reassembler.accept('tui-1', a0)).toBeNull();
+        expect(reassembler.accept('tui-2', b0)).toBeNull();
+        expect(reassembler.accept('tui-1', a1)).toEqual(offer);
+        expect(reassembler.size).toBe(1);
+    });
+
+    it('expires incomplete payloads and bounds how many are buffered', () => {
+        let now = 1_000;
+        const reassembler = new RelayReassembler(DEFAULT_REASSEMBLY_TTL_MS, 2, () => now);
+        for (const sender of ['a', 'b', 'c']) {
+            reassembler.accept(sender, chunkRelay(offer, 200)[0]);
+            now += 1;
+        }
+        expect(reassembler.size).toBe(2);
+
+        now += DEFAULT_REASSEMBLY_TTL_MS;
+        expect(reassembler.expire()).toBe(2);
+        expect(reassembler.size).toBe(0);
+    });
+
+    it('drops malformed envelopes', () => {
+        const reassembler = new RelayReassembler();
+        const [chunk] = chunkRelay(offer, 200);
+        expect(reassembler.accept('a', { ...chunk, chunk_total: MAX_CHUNKS_PER_PAYLOAD + 1 })).toBeNull();
+        expect(reassembler.accept('a', { ...chunk, chunk_index: chunk.chunk_total })).toBeNull();
+        expect(reassembler.accept('a', { ...chunk, chunk: 42 })).toBeNull();
+        expect(reassembler.size).toBe(0);
+    });
+});
@@ -1,13 +1,29 @@
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use std::time::Duration;
 use worker::*;
+use webrtc_signal_server::{admit_participant, validate_session_info};
 
 // Global config: if true, newer registration overrides older for same device_id
 const OVERRIDE_EXISTING_DEVICE: bool = true;
 
+// How often the Durable Object alarm sweeps stale sessions from storage
+const SESSION_GC_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+// How long the GC keeps a session with no active participants, so anyone
+// who dropped can rejoin it
+const SESSION_REJOIN_GRACE: Duration = Duration::from_secs(5 * 60);
+
+// Env var holding the default session lifetime in seconds; unset or 0 means
+// sessions only go away once every participant disconnects
+const SESSION_TTL_VAR: &str = "SESSION_TTL_SECS";
+
+// `SessionRemoved` reason for sessions that outlived their TTL
+const SESSION_EXPIRED_REASON: &str = "expired";
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ServerMsg {
@@ -53,22 +69,50 @@ pub enum ClientMsg {
     QueryMyActiveSessions,
 }
 
+/// Row returned by the `GET /sessions` REST endpoint
+#[derive(Debug, Serialize)]
+pub struct SessionSummary {
+    pub session_code: String,
+    pub active_participants: Vec<String>,
+    pub total: Option<u64>,
+    pub threshold: Option<u64>,
+}
+
 // Durable Object for managing devices
 #[durable_object]
 pub struct Devices {
     devices: Rc<RefCell<HashMap<String, WebSocket>>>,
     state: Rc<State>,
+    /// Lifetime given to newly announced sessions
+    session_ttl: Option<Duration>,
+}
+
+/// Parse the `SESSION_TTL_SECS` value; missing, malformed or zero disables expiry
+fn parse_session_ttl(value: Option<&str>) -> Option<Duration> {
+    match value?.trim().parse::<u64>() {
+        Ok(secs) if secs > 0 => Some(Duration::from_secs(secs)),
+        _ => None,
+    }
 }
 
 impl DurableObject for Devices {
-    fn new(state: State, _env: Env) -> Self {
+    fn new(state: State, env: Env) -> Self {
+        let ttl_var = env.var(SESSION_TTL_VAR).ok().map(|v| v.to_string());
         Self {
             devices: Rc::new(RefCell::new(HashMap::new())),
             state: Rc::new(state),
+            session_ttl: parse_session_ttl(ttl_var.as_deref()),
         }
     }
 
     async fn fetch(&self, req: Request) -> Result<Response> {
+        self.ensure_gc_alarm().await;
+
+        // Plain HTTP polling endpoint for dashboards; no WebSocket upgrade needed
+        if req.method() == Method::Get && req.path() == "/sessions" {
+            return Response::from_json(&self.list_sessions().await);
+        }
+
         let upgrade_header = match req.headers().get("Upgrade") {
             Ok(Some(h)) => h,
             Ok(None) => "".to_string(),
@@ -85,6 +129,7 @@ impl DurableObject for Devices {
 
         let devices = self.devices.clone();
         let state = self.state.clone();
+        let session_ttl = self.session_ttl;
         wasm_bindgen_futures::spawn_local(async move {
             let server = server.clone();
             let devices = devices.clone();
@@ -216,6 +261,13 @@ impl DurableObject for Devices {
                                         }
                                     }
                                     Ok(ClientMsg::AnnounceSession { session_info }) => {
+                                        // A malformed announcement would break discovery for every client
+                                        if let Err(error) = validate_session_info(&session_info) {
+                                            let err = ServerMsg::Error { error };
+                                            let _ = server.send_with_str(serde_json::to_string(&err).unwrap());
+                                            continue;
+                                        }
+
                                         // Store session bound to creator device.
                                         //
                                         // Clients may carry either `session_id` (current TUI) or
@@ -230,10 +282,16 @@ impl DurableObject for Devices {
                                                 .to_string();
 
                                             // Store session with active participants
-                                            let session_data = serde_json::json!({
+                                            let mut session_data = serde_json::json!({
                                                 "session_info": session_info,
                                                 "active_participants": vec![device.clone()]
                                             });
+                                            if let Some(ttl) = session_ttl {
+                                                let expires_at = Date::now().as_millis() + ttl.as_millis() as u64;
+                                                session_data["expires_at"] = serde_json::json!(expires_at);
+                                                // The periodic sweep may be later than the expiry
+                                                schedule_alarm_by(&state, expires_at).await;
+                                            }
                                             let _ = state.storage().put(&format!("session:{}", session_key), &session_data).await;
                                             
                                             // Track session for this device
@@ -432,15 +490,12 @@ impl DurableObject for Devices {
                                         // Update session_info.participants
                                         if let Some(joiner) = joiner {
                                             if let Some(info) = session_data.get_mut("session_info") {
-                                                if let Some(participants) = info
-                                                    .get_mut("participants")
-                                                    .and_then(|v| v.as_array_mut())
-                                                {
-                                                    let joiner_val =
-                                                        serde_json::Value::String(joiner.clone());
-                                                    if !participants.contains(&joiner_val) {
-                                                        participants.push(joiner_val);
-                                                    }
+                                                if let Err(error) = admit_participant(info, &joiner) {
+                                                    let err = ServerMsg::Error { error };
+                                                    let _ = server.send_with_str(
+                                                        serde_json::to_string(&err).unwrap(),
+                                                    );
+                                                    continue;
                                                 }
                                             }
                                             // Track for cleanup on disconnect
@@ -589,13 +644,299 @@ impl DurableObject for Devices {
 
         Response::from_websocket(client)
     }
+
+    async fn alarm(&self) -> Result<Response> {
+        let mut entries = HashMap::new();
+        for key in self.storage_keys().await {
+            if (key.starts_with("session:") || key.starts_with("device_sessions:"))
+                && let Ok(Some(value)) = self.state.storage().get::<serde_json::Value>(&key).await
+            {
+                entries.insert(key, value);
+            }
+        }
+
+        let plan = plan_session_gc(&entries, Date::now().as_millis());
+        for (key, value) in &plan.puts {
+            let _ = self.state.storage().put(key, value).await;
+        }
+        if !plan.deletes.is_empty() {
+            let _ = self.state.storage().delete_multiple(plan.deletes.clone()).await;
+        }
+        for session_id in &plan.removed_sessions {
+            let reason = if plan.expired_sessions.contains(session_id) {
+                SESSION_EXPIRED_REASON
+            } else {
+                "Garbage collected: no active participants"
+            };
+            let msg = ServerMsg::SessionRemoved {
+                session_id: session_id.clone(),
+                reason: reason.to_string(),
+            };
+            let msg_str = serde_json::to_string(&msg).unwrap();
+            for ws in self.devices.borrow().values() {
+                let _ = ws.send_with_str(&msg_str);
+            }
+        }
+
+        self.state.storage().set_alarm(SESSION_GC_INTERVAL).await?;
+        Response::ok(format!("removed {} sessions", plan.removed_sessions.len()))
+    }
+}
+
+/// Storage mutations computed by a session GC sweep
+#[derive(Debug, Default, PartialEq)]
+struct SessionGcPlan {
+    /// Session ids (without the `session:` prefix) that were dropped
+    removed_sessions: Vec<String>,
+    /// The subset of `removed_sessions` dropped because their TTL ran out
+    expired_sessions: Vec<String>,
+    /// Keys whose value must be rewritten
+    puts: Vec<(String, serde_json::Value)>,
+    /// Keys to delete
+    deletes: Vec<String>,
+}
+
+/// Work out which `session:*` and `device_sessions:*` entries are stale.
+///
+/// Sessions whose `expires_at` (ms since the epoch) is not after `now_ms`
+/// are deleted outright. A session whose stored active participant list is
+/// empty is stamped with `emptied_at` and deleted once `SESSION_REJOIN_GRACE`
+/// has passed since then. Membership is left to the connect and disconnect
+/// handlers, so a participant whose socket is briefly gone keeps the session.
+/// `device_sessions` lists are stripped of references to sessions that no
+/// longer exist.
+fn plan_session_gc(entries: &HashMap<String, serde_json::Value>, now_ms: u64) -> SessionGcPlan {
+    let mut plan = SessionGcPlan::default();
+    let mut live_sessions = HashSet::new();
+    let grace_ms = SESSION_REJOIN_GRACE.as_millis() as u64;
+
+    let mut session_keys: Vec<&String> =
+        entries.keys().filter(|k| k.starts_with("session:")).collect();
+    session_keys.sort();
+    for key in session_keys {
+        let session_id = &key["session:".len()..];
+        let session_data = &entries[key];
+        if session_data["expires_at"].as_u64().is_some_and(|expires_at| expires_at <= now_ms) {
+            plan.deletes.push(key.clone());
+            plan.removed_sessions.push(session_id.to_string());
+            plan.expired_sessions.push(session_id.to_string());
+            continue;
+        }
+        let empty = session_data["active_participants"]
+            .as_array()
+            .is_none_or(|active| active.is_empty());
+        match (empty, session_data["emptied_at"].as_u64()) {
+            (true, Some(emptied_at)) if now_ms.saturating_sub(emptied_at) >= grace_ms => {
+                plan.deletes.push(key.clone());
+                plan.removed_sessions.push(session_id.to_string());
+                continue;
+            }
+            (true, None) => {
+                let mut session_data = session_data.clone();
+                session_data["emptied_at"] = serde_json::json!(now_ms);
+                plan.puts.push((key.clone(), session_data));
+            }
+            // Someone rejoined during the grace period
+            (false, Some(_)) => {
+                let mut session_data = session_data.clone();
+                if let Some(fields) = session_data.as_object_mut() {
+                    fields.remove("emptied_at");
+                }
+                plan.puts.push((key.clone(), session_data));
+            }
+            _ => {}
+        }
+        live_sessions.insert(session_id.to_string());
+    }
+
+    let mut device_keys: Vec<&String> = entries
+        .keys()
+        .filter(|k| k.starts_with("device_sessions:"))
+        .collect();
+    device_keys.sort();
+    for key in device_keys {
+        let sessions: Vec<String> =
+            serde_json::from_value(entries[key].clone()).unwrap_or_default();
+        let kept: Vec<String> = sessions
+            .iter()
+            .filter(|id| live_sessions.contains(*id))
+            .cloned()
+            .collect();
+        if kept.is_empty() {
+            plan.deletes.push(key.clone());
+        } else if kept.len() != sessions.len() {
+            plan.puts.push((key.clone(), serde_json::json!(kept)));
+        }
+    }
+
+    plan
+}
+
+/// Bring the pending alarm forward so it fires no later than `at_ms`
+async fn schedule_alarm_by(state: &State, at_ms: u64) {
+    let pending = state.storage().get_alarm().await.ok().flatten();
+    if pending.is_none_or(|pending| pending > at_ms as i64) {
+        let delay = at_ms.saturating_sub(Date::now().as_millis());
+        let _ = state.storage().set_alarm(Duration::from_millis(delay)).await;
+    }
+}
+
+impl Devices {
+    /// Make sure a session GC alarm is pending
+    async fn ensure_gc_alarm(&self) {
+        if let Ok(None) = self.state.storage().get_alarm().await {
+            let _ = self.state.storage().set_alarm(SESSION_GC_INTERVAL).await;
+        }
+    }
+
+    /// All keys currently held in Durable Object storage
+    async fn storage_keys(&self) -> Vec<String> {
+        let Ok(map) = self.state.storage().list().await else {
+            return Vec::new();
+        };
+        map.keys()
+            .into_iter()
+            .flatten()
+            .filter_map(|k| k.as_string())
+            .collect()
+    }
+
+    /// Summarise every `session:*` entry currently held in storage
+    async fn list_sessions(&self) -> Vec<SessionSummary> {
+        let mut sessions = Vec::new();
+        for key_str in self.storage_keys().await {
+            let Some(session_code) = key_str.strip_prefix("session:") else {
+                continue;
+            };
+            // Legacy collision bucket, see RequestActiveSessions
+            if session_code == "unknown" {
+                continue;
+            }
+            let Ok(Some(session_data)) = self
+                .state
+                .storage()
+                .get::<serde_json::Value>(&key_str)
+                .await
+            else {
+                continue;
+            };
+            let info = &session_data["session_info"];
+            let active_participants = session_data["active_participants"]
+                .as_array()
+                .map(|list| {
+                    list.iter()
+                        .filter_map(|p| p.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default();
+            sessions.push(SessionSummary {
+                session_code: session_code.to_string(),
+                active_participants,
+                total: info.get("total").and_then(|v| v.as_u64()),
+                threshold: info.get("threshold").and_then(|v| v.as_u64()),
+            });
+        }
+        sessions
+    }
 }
 
 #[event(fetch)]
 async fn fetch(req: Request, env: Env, _ctx: Context) -> Result<Response> {
-    // Route all websocket requests to the Devices Durable Object
+    // Route all requests (websocket and `GET /sessions`) to the Devices Durable Object
     let devices_ns = env.durable_object("Devices")?;
     let id = devices_ns.id_from_name("global")?;
     let stub = id.get_stub()?;
     stub.fetch_with_request(req).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_gc_removes_stale_session() {
+        let grace_ms = SESSION_REJOIN_GRACE.as_millis() as u64;
+        let mut entries = HashMap::new();
+        entries.insert(
+            "session:stale".to_string(),
+            json!({"session_info": {}, "active_participants": [], "emptied_at": 1_000}),
+        );
+        // Nobody is connected to it, but its stored members are left alone
+        entries.insert(
+            "session:live".to_string(),
+            json!({"session_info": {}, "active_participants": ["alice", "gone"]}),
+        );
+        entries.insert("device_sessions:gone".to_string(), json!(["stale", "live"]));
+        entries.insert("device_sessions:bob".to_string(), json!(["stale"]));
+
+        let plan = plan_session_gc(&entries, 1_000 + grace_ms);
+
+        assert_eq!(plan.removed_sessions, vec!["stale".to_string()]);
+        assert!(plan.expired_sessions.is_empty());
+        assert!(plan.deletes.contains(&"session:stale".to_string()));
+        assert!(plan.deletes.contains(&"device_sessions:bob".to_string()));
+        assert!(!plan.puts.iter().any(|(key, _)| key == "session:live"));
+        assert!(plan.puts.contains(&("device_sessions:gone".to_string(), json!(["live"]))));
+    }
+
+    #[test]
+    fn test_gc_keeps_empty_session_through_rejoin_grace() {
+        let grace_ms = SESSION_REJOIN_GRACE.as_millis() as u64;
+        let mut entries = HashMap::new();
+        entries.insert(
+            "session:s1".to_string(),
+            json!({"session_info": {}, "active_participants": []}),
+        );
+
+        // The first sweep only notes when the session went empty
+        let plan = plan_session_gc(&entries, 5_000);
+        assert!(plan.removed_sessions.is_empty());
+        let stamped = json!({"session_info": {}, "active_participants": [], "emptied_at": 5_000});
+        assert_eq!(plan.puts, vec![("session:s1".to_string(), stamped.clone())]);
+        entries.insert("session:s1".to_string(), stamped);
+        assert!(plan_session_gc(&entries, 5_000 + grace_ms - 1).removed_sessions.is_empty());
+
+        // Alice rejoins within the grace period, so the session survives
+        entries.insert(
+            "session:s1".to_string(),
+            json!({"session_info": {}, "active_participants": ["alice"], "emptied_at": 5_000}),
+        );
+        let plan = plan_session_gc(&entries, 5_000 + grace_ms);
+        assert!(plan.removed_sessions.is_empty());
+        assert_eq!(
+            plan.puts,
+            vec![("session:s1".to_string(), json!({"session_info": {}, "active_participants": ["alice"]}))]
+        );
+    }
+
+    #[test]
+    fn test_gc_expires_session_past_its_ttl() {
+        let ttl = parse_session_ttl(Some("30")).unwrap();
+        assert_eq!(parse_session_ttl(Some("0")), None);
+        assert_eq!(parse_session_ttl(None), None);
+
+        let announced_at = 1_000_000;
+        let expires_at = announced_at + ttl.as_millis() as u64;
+        let mut entries = HashMap::new();
+        // The creator is still connected, so only the TTL can remove it
+        entries.insert(
+            "session:abandoned".to_string(),
+            json!({"session_info": {}, "active_participants": ["alice"], "expires_at": expires_at}),
+        );
+        entries.insert(
+            "session:open".to_string(),
+            json!({"session_info": {}, "active_participants": ["alice"]}),
+        );
+        entries.insert("device_sessions:alice".to_string(), json!(["abandoned", "open"]));
+
+        let before = plan_session_gc(&entries, expires_at - 1);
+        assert_eq!(before, SessionGcPlan::default());
+
+        let plan = plan_session_gc(&entries, expires_at);
+        assert_eq!(plan.removed_sessions, vec!["abandoned".to_string()]);
+        assert_eq!(plan.expired_sessions, vec!["abandoned".to_string()]);
+        assert_eq!(plan.deletes, vec!["session:abandoned".to_string()]);
+        assert_eq!(plan.puts, vec![("device_sessions:alice".to_string(), json!(["open"]))]);
+    }
+}
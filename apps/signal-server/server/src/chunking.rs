@@ -0,0 +1,305 @@
+/// Chunked relays for payloads too large for one comfortable frame
+///
+/// The sender splits the serialized relay `data` into `RelayChunk` envelopes
+/// that share a `payload_id`. The server forwards them like any other relay;
+/// each chunk carries its own `message_id`, so deduplication drops repeated
+/// chunks but never distinct ones. The receiver feeds every relay through a
+/// `RelayReassembler`, which passes ordinary relays straight through and
+/// returns a chunked payload once all of its chunks have arrived. The browser
+/// extension's background worker has a TypeScript port of it
+/// (`relayReassembler.ts`), so chunks reach extension peers intact too.
+use crate::ClientMsg;
+use crate::dedup::MESSAGE_ID_FIELD;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// `websocket_msg_type` of a chunk envelope
+pub const CHUNK_MSG_TYPE: &str = "RelayChunk";
+
+/// Serialized payloads up to this many bytes are relayed unchunked
+pub const DEFAULT_MAX_CHUNK_LEN: usize = 16 * 1024;
+
+/// How long an incomplete payload waits for its missing chunks
+pub const DEFAULT_REASSEMBLY_TTL: Duration = Duration::from_secs(30);
+
+/// Incomplete payloads buffered at once; the oldest is dropped beyond this
+pub const DEFAULT_MAX_PENDING_PAYLOADS: usize = 64;
+
+/// Upper bound on `chunk_total`, so one bogus envelope can't allocate unbounded slots
+pub const MAX_CHUNKS_PER_PAYLOAD: usize = 4096;
+
+/// One piece of a chunked relay payload
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayChunk {
+    pub payload_id: String,
+    /// 0-based position of this chunk
+    pub chunk_index: usize,
+    pub chunk_total: usize,
+    /// Slice of the serialized payload JSON
+    pub chunk: String,
+}
+
+impl RelayChunk {
+    /// Relay `data` for this chunk
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::json!({
+            "websocket_msg_type": CHUNK_MSG_TYPE,
+            MESSAGE_ID_FIELD: format!("{}:{}", self.payload_id, self.chunk_index),
+            "payload_id": self.payload_id,
+            "chunk_index": self.chunk_index,
+            "chunk_total": self.chunk_total,
+            "chunk": self.chunk,
+        })
+    }
+
+    /// Parse relay `data`; `None` if it is not a chunk envelope
+    pub fn from_value(data: &serde_json::Value) -> Option<Self> {
+        if data.get("websocket_msg_type").and_then(|v| v.as_str()) != Some(CHUNK_MSG_TYPE) {
+            return None;
+        }
+        serde_json::from_value(data.clone()).ok()
+    }
+}
+
+fn next_payload_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    format!("{:x}-{:x}", nanos, COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Split `payload` into slices of at most `max_len` bytes on char boundaries
+fn split_utf8(payload: &str, max_len: usize) -> Vec<&str> {
+    // A char is at most 4 bytes, so this always makes progress
+    let max_len = max_len.max(4);
+    let mut pieces = Vec::new();
+    let mut rest = payload;
+    while !rest.is_empty() {
+        let mut end = max_len.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (piece, tail) = rest.split_at(end);
+        pieces.push(piece);
+        rest = tail;
+    }
+    pieces
+}
+
+/// Build the relay messages for `data`: a single `Relay` when the serialized
+/// payload fits in `max_chunk_len` bytes, otherwise one per chunk
+pub fn chunk_relay(to: &str, data: serde_json::Value, max_chunk_len: usize) -> Vec<ClientMsg> {
+    let payload = data.to_string();
+    if payload.len() <= max_chunk_len {
+        return vec![ClientMsg::Relay { to: to.to_string(), data }];
+    }
+
+    let payload_id = next_payload_id();
+    let pieces = split_utf8(&payload, max_chunk_len);
+    let chunk_total = pieces.len();
+    pieces
+        .into_iter()
+        .enumerate()
+        .map(|(chunk_index, piece)| ClientMsg::Relay {
+            to: to.to_string(),
+            data: RelayChunk {
+                payload_id: payload_id.clone(),
+                chunk_index,
+                chunk_total,
+                chunk: piece.to_string(),
+            }
+            .to_value(),
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+struct PendingPayload {
+    chunks: Vec<Option<String>>,
+    received: usize,
+    started_at: Instant,
+}
+
+/// Receiver-side buffer that rebuilds chunked relay payloads
+#[derive(Debug)]
+pub struct RelayReassembler {
+    ttl: Duration,
+    max_pending: usize,
+    /// Keyed on (sender, payload_id)
+    pending: HashMap<(String, String), PendingPayload>,
+}
+
+impl RelayReassembler {
+    pub fn new(ttl: Duration, max_pending: usize) -> Self {
+        Self {
+            ttl,
+            max_pending: max_pending.max(1),
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Returns the relay `data` to handle: ordinary relays unchanged, a chunked
+    /// payload once its last chunk arrives, and `None` while still incomplete
+    pub fn accept(&mut self, from: &str, data: serde_json::Value) -> Option<serde_json::Value> {
+        self.accept_at(from, data, Instant::now())
+    }
+
+    pub fn accept_at(
+        &mut self,
+        from: &str,
+        data: serde_json::Value,
+        now: Instant,
+    ) -> Option<serde_json::Value> {
+        let Some(chunk) = RelayChunk::from_value(&data) else {
+            return Some(data);
+        };
+        self.expire_at(now);
+        if chunk.chunk_total == 0
+            || chunk.chunk_total > MAX_CHUNKS_PER_PAYLOAD
+            || chunk.chunk_index >= chunk.chunk_total
+        {
+            return None;
+        }
+
+        let key = (from.to_string(), chunk.payload_id);
+        if !self.pending.contains_key(&key) && self.pending.len() >= self.max_pending {
+            let oldest = self
+                .pending
+                .iter()
+                .min_by_key(|(_, payload)| payload.started_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.pending.remove(&oldest);
+            }
+        }
+
+        let payload = self.pending.entry(key.clone()).or_insert_with(|| PendingPayload {
+            chunks: vec![None; chunk.chunk_total],
+            received: 0,
+            started_at: now,
+        });
+        if payload.chunks.len() != chunk.chunk_total {
+            return None;
+        }
+        let slot = &mut payload.chunks[chunk.chunk_index];
+        if slot.is_none() {
+            *slot = Some(chunk.chunk);
+            payload.received += 1;
+        }
+        if payload.received < payload.chunks.len() {
+            return None;
+        }
+
+        let payload = self.pending.remove(&key)?;
+        let json: String = payload.chunks.into_iter().flatten().collect();
+        serde_json::from_str(&json).ok()
+    }
+
+    /// Drop payloads still incomplete after the TTL; returns how many were dropped
+    pub fn expire_at(&mut self, now: Instant) -> usize {
+        let ttl = self.ttl;
+        let before = self.pending.len();
+        self.pending
+            .retain(|_, payload| now.saturating_duration_since(payload.started_at) < ttl);
+        before - self.pending.len()
+    }
+
+    /// Number of incomplete payloads currently buffered
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+impl Default for RelayReassembler {
+    fn default() -> Self {
+        Self::new(DEFAULT_REASSEMBLY_TTL, DEFAULT_MAX_PENDING_PAYLOADS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn chunk_data(messages: Vec<ClientMsg>) -> Vec<serde_json::Value> {
+        messages
+            .into_iter()
+            .map(|msg| match msg {
+                ClientMsg::Relay { data, .. } => data,
+                other => panic!("unexpected message: {:?}", other),
+            })
+            .collect()
+    }
+
+    fn round2_bundle() -> serde_json::Value {
+        json!({ "websocket_msg_type": "DKGRound2", "packages": "ab".repeat(500), "note": "ünïcödé" })
+    }
+
+    #[test]
+    fn test_small_payload_is_not_chunked() {
+        let data = json!({ "websocket_msg_type": "WebRTCSignal" });
+        let chunks = chunk_data(chunk_relay("peer", data.clone(), DEFAULT_MAX_CHUNK_LEN));
+        assert_eq!(chunks, vec![data.clone()]);
+        assert_eq!(RelayReassembler::default().accept("a", data.clone()), Some(data));
+    }
+
+    #[test]
+    fn test_reassembles_out_of_order_chunks() {
+        let bundle = round2_bundle();
+        let mut chunks = chunk_data(chunk_relay("peer", bundle.clone(), 100));
+        assert!(chunks.len() > 3);
+        chunks.reverse();
+        chunks.swap(0, 1);
+
+        let mut reassembler = RelayReassembler::default();
+        let last = chunks.pop().unwrap();
+        for chunk in chunks {
+            assert_eq!(reassembler.accept("a", chunk.clone()), None);
+            // A repeated chunk doesn't complete the payload early
+            assert_eq!(reassembler.accept("a", chunk), None);
+        }
+        assert_eq!(reassembler.len(), 1);
+        assert_eq!(reassembler.accept("a", last), Some(bundle));
+        assert!(reassembler.is_empty());
+    }
+
+    #[test]
+    fn test_dropped_chunk_expires() {
+        let now = Instant::now();
+        let mut chunks = chunk_data(chunk_relay("peer", round2_bundle(), 100));
+        let late = chunks.pop().unwrap();
+        chunks.remove(1);
+
+        let mut reassembler = RelayReassembler::default();
+        for chunk in chunks {
+            assert_eq!(reassembler.accept_at("a", chunk, now), None);
+        }
+        assert_eq!(reassembler.len(), 1);
+        assert_eq!(reassembler.expire_at(now + DEFAULT_REASSEMBLY_TTL), 1);
+        assert!(reassembler.is_empty());
+
+        // A straggler after expiry starts a fresh payload rather than completing the old one
+        assert_eq!(reassembler.accept_at("a", late, now + DEFAULT_REASSEMBLY_TTL), None);
+        assert_eq!(reassembler.len(), 1);
+    }
+
+    #[test]
+    fn test_pending_payloads_are_bounded() {
+        let now = Instant::now();
+        let mut reassembler = RelayReassembler::new(DEFAULT_REASSEMBLY_TTL, 2);
+        for (i, sender) in ["a", "b", "c"].iter().enumerate() {
+            let first = chunk_data(chunk_relay("peer", round2_bundle(), 100)).remove(0);
+            reassembler.accept_at(sender, first, now + Duration::from_millis(i as u64));
+        }
+        assert_eq!(reassembler.len(), 2);
+        assert!(!reassembler.pending.keys().any(|(from, _)| from == "a"));
+    }
+}
@@ -0,0 +1,130 @@
+/// Duplicate suppression for relayed messages
+///
+/// A node that reconnects may resend messages (e.g. `SessionProposal`) it
+/// already relayed. Each relay is keyed on sender, recipient and the client's
+/// `message_id` from the relay `data`, or a hash of the payload when no id is
+/// present. A key is only remembered once the relay was actually delivered,
+/// so a retry after the recipient was offline still goes through; a key
+/// delivered again within the TTL is dropped.
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+/// How long a relayed message is remembered
+pub const DEFAULT_DEDUP_TTL: Duration = Duration::from_secs(5);
+
+/// Field in the relay `data` carrying a client-assigned message id
+pub const MESSAGE_ID_FIELD: &str = "message_id";
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum MessageKey {
+    Id(String),
+    Hash(u64),
+}
+
+impl MessageKey {
+    fn for_payload(data: &serde_json::Value) -> Self {
+        match data.get(MESSAGE_ID_FIELD).and_then(|v| v.as_str()) {
+            Some(id) => MessageKey::Id(id.to_string()),
+            None => {
+                let mut hasher = DefaultHasher::new();
+                data.to_string().hash(&mut hasher);
+                MessageKey::Hash(hasher.finish())
+            }
+        }
+    }
+}
+
+/// TTL cache of recently relayed messages
+#[derive(Debug)]
+pub struct RelayDeduplicator {
+    ttl: Duration,
+    seen: HashMap<(String, String, MessageKey), Instant>,
+}
+
+impl RelayDeduplicator {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            seen: HashMap::new(),
+        }
+    }
+
+    /// True if the same message from `from` to `to` was delivered within the TTL
+    pub fn is_duplicate(&mut self, from: &str, to: &str, data: &serde_json::Value) -> bool {
+        self.is_duplicate_at(from, to, data, Instant::now())
+    }
+
+    pub fn is_duplicate_at(
+        &mut self,
+        from: &str,
+        to: &str,
+        data: &serde_json::Value,
+        now: Instant,
+    ) -> bool {
+        let ttl = self.ttl;
+        self.seen
+            .retain(|_, seen_at| now.saturating_duration_since(*seen_at) < ttl);
+        self.seen.contains_key(&Self::key(from, to, data))
+    }
+
+    /// Remember a relay that reached its recipient
+    pub fn record_delivered(&mut self, from: &str, to: &str, data: &serde_json::Value) {
+        self.record_delivered_at(from, to, data, Instant::now())
+    }
+
+    pub fn record_delivered_at(&mut self, from: &str, to: &str, data: &serde_json::Value, now: Instant) {
+        self.seen.insert(Self::key(from, to, data), now);
+    }
+
+    fn key(from: &str, to: &str, data: &serde_json::Value) -> (String, String, MessageKey) {
+        (from.to_string(), to.to_string(), MessageKey::for_payload(data))
+    }
+
+    /// Number of messages currently remembered
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
+
+impl Default for RelayDeduplicator {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEDUP_TTL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_repeated_payload_dropped_within_ttl() {
+        let mut dedup = RelayDeduplicator::default();
+        let now = Instant::now();
+        let proposal = json!({ "websocket_msg_type": "SessionProposal", "session_id": "s1" });
+
+        assert!(!dedup.is_duplicate_at("a", "b", &proposal, now));
+        dedup.record_delivered_at("a", "b", &proposal, now);
+        assert!(dedup.is_duplicate_at("a", "b", &proposal, now + Duration::from_secs(1)));
+        // Same payload to another recipient is a different relay
+        assert!(!dedup.is_duplicate_at("a", "c", &proposal, now));
+        // Expired entries are forgotten
+        assert!(!dedup.is_duplicate_at("a", "b", &proposal, now + DEFAULT_DEDUP_TTL));
+    }
+
+    #[test]
+    fn test_message_id_takes_precedence_over_payload() {
+        let mut dedup = RelayDeduplicator::default();
+        let now = Instant::now();
+
+        dedup.record_delivered_at("a", "b", &json!({ "message_id": "m1", "n": 1 }), now);
+        assert!(dedup.is_duplicate_at("a", "b", &json!({ "message_id": "m1", "n": 2 }), now));
+        assert!(!dedup.is_duplicate_at("a", "b", &json!({ "message_id": "m2", "n": 1 }), now));
+    }
+}
@@ -0,0 +1,92 @@
+/// Ping/pong keepalive tracking for signal server connections
+///
+/// When a node's TCP connection dies without a WebSocket close frame, the
+/// server never sees a `Close` event. Each connection pings its peer on a
+/// fixed interval and records when the last pong arrived; a peer that misses
+/// too many pongs is treated as disconnected.
+use std::time::{Duration, Instant};
+
+/// How often the server pings each connected device
+pub const PING_INTERVAL: Duration = Duration::from_secs(20);
+
+/// Number of consecutive missed pongs before a device is reaped
+pub const MAX_MISSED_PONGS: u32 = 2;
+
+/// Per-connection liveness state
+#[derive(Clone, Debug)]
+pub struct Heartbeat {
+    last_pong: Instant,
+    interval: Duration,
+    max_missed: u32,
+}
+
+impl Heartbeat {
+    /// Create a tracker using the default ping interval and miss budget
+    pub fn new() -> Self {
+        Self::with_config(PING_INTERVAL, MAX_MISSED_PONGS)
+    }
+
+    /// Create a tracker with a custom ping interval and miss budget
+    pub fn with_config(interval: Duration, max_missed: u32) -> Self {
+        Self {
+            last_pong: Instant::now(),
+            interval,
+            max_missed,
+        }
+    }
+
+    /// Record that a pong was received just now
+    pub fn record_pong(&mut self) {
+        self.last_pong = Instant::now();
+    }
+
+    /// Time of the most recent pong (or of connection start)
+    pub fn last_pong(&self) -> Instant {
+        self.last_pong
+    }
+
+    /// Check liveness at the current instant
+    pub fn is_dead(&self) -> bool {
+        self.is_dead_at(Instant::now())
+    }
+
+    /// Check liveness at `now`; dead once `max_missed` full intervals pass without a pong
+    pub fn is_dead_at(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_pong) >= self.interval * self.max_missed
+    }
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fresh_connection_is_alive() {
+        let hb = Heartbeat::new();
+        assert!(!hb.is_dead());
+        assert!(!hb.is_dead_at(hb.last_pong() + PING_INTERVAL));
+    }
+
+    #[test]
+    fn test_silent_peer_dies_after_two_missed_pongs() {
+        let hb = Heartbeat::new();
+        let start = hb.last_pong();
+        assert!(!hb.is_dead_at(start + PING_INTERVAL + Duration::from_secs(19)));
+        assert!(hb.is_dead_at(start + PING_INTERVAL * 2));
+    }
+
+    #[test]
+    fn test_pong_resets_deadline() {
+        let mut hb = Heartbeat::with_config(Duration::from_millis(10), 2);
+        std::thread::sleep(Duration::from_millis(25));
+        assert!(hb.is_dead());
+        hb.record_pong();
+        assert!(!hb.is_dead());
+    }
+}
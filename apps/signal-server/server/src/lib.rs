@@ -2,6 +2,13 @@ use serde::{Deserialize, Serialize};
 
 pub mod session_manager;
 pub mod cloudflare_storage;
+pub mod heartbeat;
+pub mod dedup;
+pub mod chunking;
+#[cfg(feature = "server")]
+pub mod tls;
+#[cfg(all(feature = "server", any(test, feature = "in-memory")))]
+pub mod memory;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct SessionInfo {
@@ -11,6 +18,74 @@ pub struct SessionInfo {
     pub participants: Vec<String>,
 }
 
+/// Check the fields clients rely on for discovery: a non-empty `session_id`
+/// (or the `session_code` older clients announce with), `total >= threshold >= 1`
+/// and a `participants` array
+pub fn validate_session_info(session_info: &serde_json::Value) -> Result<(), String> {
+    let id = |field| session_info.get(field).and_then(|v| v.as_str()).filter(|id| !id.is_empty());
+    if id("session_id").or_else(|| id("session_code")).is_none() {
+        return Err("invalid session_info: missing session_id".to_string());
+    }
+    // FROST identifiers are u16, so larger counts can never form a session
+    let total = session_info
+        .get("total")
+        .and_then(|v| v.as_u64())
+        .and_then(|v| u16::try_from(v).ok())
+        .ok_or("invalid session_info: total must be a positive integer up to 65535")?;
+    let threshold = session_info
+        .get("threshold")
+        .and_then(|v| v.as_u64())
+        .and_then(|v| u16::try_from(v).ok())
+        .ok_or("invalid session_info: threshold must be a positive integer up to 65535")?;
+    if threshold == 0 {
+        return Err("invalid session_info: threshold must be at least 1".to_string());
+    }
+    if threshold > total {
+        return Err(format!(
+            "invalid session_info: threshold {} exceeds total {}",
+            threshold, total
+        ));
+    }
+    let Some(participants) = session_info.get("participants").and_then(|v| v.as_array()) else {
+        return Err("invalid session_info: participants must be an array".to_string());
+    };
+    if participants.len() > usize::from(total) {
+        return Err(format!(
+            "invalid session_info: {} participants exceed total {}",
+            participants.len(),
+            total
+        ));
+    }
+    Ok(())
+}
+
+/// Add `device` to a stored session's `participants`. Returns whether the
+/// roster changed; a device already on it is a no-op. A new device is
+/// refused once the roster holds `total` devices, since the DKG has no
+/// identifier left to give it.
+pub fn admit_participant(session_info: &mut serde_json::Value, device: &str) -> Result<bool, String> {
+    let session_id = session_info.get("session_id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    let total = session_info.get("total").and_then(|v| v.as_u64());
+    let Some(participants) = session_info.get_mut("participants").and_then(|v| v.as_array_mut()) else {
+        return Ok(false);
+    };
+    if participants.iter().any(|p| p.as_str() == Some(device)) {
+        return Ok(false);
+    }
+    if let Some(total) = total
+        && participants.len() as u64 >= total
+    {
+        return Err(format!(
+            "Session {} is full ({}/{} participants)",
+            session_id,
+            participants.len(),
+            total
+        ));
+    }
+    participants.push(serde_json::Value::String(device.to_string()));
+    Ok(true)
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ServerMsg {
@@ -45,7 +120,12 @@ pub enum ServerMsg {
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ClientMsg {
-    Register { device_id: String },
+    Register {
+        device_id: String,
+        // Reconnecting devices ask for their session membership in the same round trip
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        include_sessions: bool,
+    },
     ListDevices,
     Relay { to: String, data: serde_json::Value },
     // Session discovery messages
@@ -55,3 +135,63 @@ pub enum ClientMsg {
     // Simple stateless rejoin support
     QueryMyActiveSessions,  // Device asks: "What sessions am I in?"
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_session_info_rejects_bad_shapes() {
+        let valid = json!({ "session_id": "s1", "total": 3, "threshold": 2, "participants": ["a"] });
+        assert!(validate_session_info(&valid).is_ok());
+        // Older clients announce under `session_code` instead
+        let code_only = json!({ "session_code": "s1", "total": 3, "threshold": 2, "participants": ["a"] });
+        assert!(validate_session_info(&code_only).is_ok());
+
+        let with = |field: &str, value: serde_json::Value| {
+            let mut info = valid.clone();
+            info[field] = value;
+            info
+        };
+        let without = |field: &str| {
+            let mut info = valid.clone();
+            info.as_object_mut().unwrap().remove(field);
+            info
+        };
+        let invalid = [
+            (without("session_id"), "missing session_id"),
+            (with("session_id", json!("")), "missing session_id"),
+            (without("total"), "total must be a positive integer up to 65535"),
+            (with("total", json!("3")), "total must be a positive integer up to 65535"),
+            (with("total", json!(65536)), "total must be a positive integer up to 65535"),
+            (with("threshold", json!(-1)), "threshold must be a positive integer up to 65535"),
+            (with("threshold", json!(u64::MAX)), "threshold must be a positive integer up to 65535"),
+            (with("threshold", json!(0)), "threshold must be at least 1"),
+            (with("threshold", json!(4)), "threshold 4 exceeds total 3"),
+            (without("participants"), "participants must be an array"),
+            (with("participants", json!("a")), "participants must be an array"),
+            (with("participants", json!(["a", "b", "c", "d"])), "4 participants exceed total 3"),
+        ];
+        for (info, reason) in invalid {
+            assert_eq!(
+                validate_session_info(&info),
+                Err(format!("invalid session_info: {}", reason)),
+                "{}",
+                info
+            );
+        }
+    }
+
+    #[test]
+    fn test_admit_participant_refuses_joiners_past_total() {
+        let mut info = json!({ "session_id": "s1", "total": 2, "threshold": 2, "participants": ["a"] });
+        assert_eq!(admit_participant(&mut info, "b"), Ok(true));
+        assert_eq!(admit_participant(&mut info, "b"), Ok(false));
+        assert_eq!(
+            admit_participant(&mut info, "c"),
+            Err("Session s1 is full (2/2 participants)".to_string())
+        );
+        assert_eq!(info["participants"], json!(["a", "b"]));
+    }
+}
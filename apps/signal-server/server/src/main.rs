@@ -10,7 +10,10 @@ use tokio_tungstenite::{accept_async, tungstenite::Message};
 
 // Import shared types from the library crate
 
-use webrtc_signal_server::{ClientMsg, ServerMsg};
+use webrtc_signal_server::dedup::{DEFAULT_DEDUP_TTL, RelayDeduplicator};
+use webrtc_signal_server::heartbeat::{Heartbeat, PING_INTERVAL};
+use webrtc_signal_server::tls;
+use webrtc_signal_server::{ClientMsg, ServerMsg, admit_participant, validate_session_info};
 
 type DeviceSender = mpsc::UnboundedSender<Message>;
 type DeviceMap = Arc<Mutex<HashMap<String, DeviceSender>>>;
@@ -21,19 +24,446 @@ struct StoredSession {
     session_info: serde_json::Value,  // The full announcement as-is
     active_participants: Vec<String>,  // Currently online participants
     last_active: std::time::Instant,  // Updated when participants leave; used for grace period
+    creator: String,  // Device that first announced it; only it may re-announce
 }
 
 type SessionMap = Arc<Mutex<HashMap<String, StoredSession>>>;
 // Map device_id to list of session_ids they're participating in
 type DeviceSessionsMap = Arc<Mutex<HashMap<String, Vec<String>>>>;
+// Shared relay dedup cache; `None` when disabled
+type RelayDedup = Option<Arc<Mutex<RelayDeduplicator>>>;
+
+/// Build the relay dedup cache from `RELAY_DEDUP_TTL_MS`. Off unless set;
+/// 0 also disables it, and an unparseable value falls back to the default TTL.
+fn relay_dedup_from_env() -> RelayDedup {
+    let ttl = std::env::var("RELAY_DEDUP_TTL_MS").ok()?;
+    let ttl = ttl
+        .parse::<u64>()
+        .map(std::time::Duration::from_millis)
+        .unwrap_or(DEFAULT_DEDUP_TTL);
+    if ttl.is_zero() {
+        None
+    } else {
+        Some(Arc::new(Mutex::new(RelayDeduplicator::new(ttl))))
+    }
+}
+
+/// Sessions stored at once unless `MAX_SESSIONS` says otherwise
+const DEFAULT_MAX_SESSIONS: usize = 1_000;
+/// Devices registered at once unless `MAX_DEVICES` says otherwise
+const DEFAULT_MAX_DEVICES: usize = 10_000;
+/// Sessions one device may have stored unless `MAX_SESSIONS_PER_DEVICE` says otherwise
+const DEFAULT_MAX_SESSIONS_PER_DEVICE: usize = 10;
+
+/// Caps on what the server keeps in memory
+#[derive(Clone, Copy, Debug)]
+struct Limits {
+    max_sessions: usize,
+    max_devices: usize,
+    max_sessions_per_device: usize,
+}
+
+/// Read `MAX_SESSIONS`, `MAX_DEVICES` and `MAX_SESSIONS_PER_DEVICE`, falling
+/// back to the defaults
+fn limits_from_env() -> Limits {
+    let read = |name: &str, default: usize| {
+        std::env::var(name)
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(default)
+    };
+    Limits {
+        max_sessions: read("MAX_SESSIONS", DEFAULT_MAX_SESSIONS),
+        max_devices: read("MAX_DEVICES", DEFAULT_MAX_DEVICES),
+        max_sessions_per_device: read("MAX_SESSIONS_PER_DEVICE", DEFAULT_MAX_SESSIONS_PER_DEVICE),
+    }
+}
+
+/// Store an announced session with `device` as its creator and first active
+/// participant. Only the creator may re-announce (and so replace) a stored
+/// session; a new one is refused once `max_sessions` are stored or `device`
+/// already created `max_sessions_per_device` of them.
+fn store_session(
+    sessions: &SessionMap,
+    device_sessions: &DeviceSessionsMap,
+    session_key: &str,
+    session_info: &serde_json::Value,
+    device: &str,
+    limits: &Limits,
+) -> Result<(), String> {
+    let mut sessions_guard = sessions.lock().unwrap();
+    match sessions_guard.get(session_key) {
+        Some(stored) if stored.creator != device => {
+            return Err(format!("session '{}' was announced by another device", session_key));
+        }
+        Some(_) => {}
+        None => {
+            if sessions_guard.len() >= limits.max_sessions {
+                return Err(format!("session limit reached ({} sessions)", limits.max_sessions));
+            }
+            let created = sessions_guard.values().filter(|s| s.creator == device).count();
+            if created >= limits.max_sessions_per_device {
+                return Err(format!(
+                    "per-device session limit reached ({} sessions)",
+                    limits.max_sessions_per_device
+                ));
+            }
+        }
+    }
+    sessions_guard.insert(session_key.to_string(), StoredSession {
+        session_info: session_info.clone(),
+        active_participants: vec![device.to_string()], // Creator is first participant
+        last_active: std::time::Instant::now(),
+        creator: device.to_string(),
+    });
+    drop(sessions_guard);
+
+    // Track that this device is in this session
+    let mut device_sessions_guard = device_sessions.lock().unwrap();
+    let entry = device_sessions_guard.entry(device.to_string()).or_default();
+    if !entry.iter().any(|id| id == session_key) {
+        entry.push(session_key.to_string());
+    }
+    Ok(())
+}
+
+/// Register `reg_id` and broadcast the updated device list. With
+/// `include_sessions` the device also gets back the sessions it belongs to,
+/// as if it had sent `QueryMyActiveSessions`.
+fn register_device(
+    reg_id: &str,
+    include_sessions: bool,
+    tx: &DeviceSender,
+    devices: &DeviceMap,
+    sessions: &SessionMap,
+    device_sessions: &DeviceSessionsMap,
+    max_devices: usize,
+) -> Result<(), String> {
+    let mut devices_guard = devices.lock().unwrap();
+    if devices_guard.contains_key(reg_id) {
+        return Err("device_id already registered".to_string());
+    }
+    if devices_guard.len() >= max_devices {
+        println!("Refusing device {}: device limit reached", reg_id);
+        return Err(format!("device limit reached ({} devices)", max_devices));
+    }
+    devices_guard.insert(reg_id.to_string(), tx.clone());
+    println!("Registered device: {}", reg_id);
+
+    // Broadcast updated device list to all devices (owned Vec)
+    let device_list: Vec<String> = devices_guard.keys().cloned().collect();
+    let msg = ServerMsg::Devices { devices: device_list.clone() };
+    let msg_txt = serde_json::to_string(&msg).unwrap();
+    for (_id, ptx) in devices_guard.iter() {
+        let _ = ptx.send(Message::Text(msg_txt.clone().into()));
+    }
+    drop(devices_guard);
+
+    if include_sessions {
+        send_sessions_for_device(reg_id, tx, sessions, device_sessions);
+    }
+    Ok(())
+}
+
+/// Answer "what sessions am I in?": mark `dev_id` active again in every
+/// session listing it as a participant and send it those sessions.
+fn send_sessions_for_device(
+    dev_id: &str,
+    tx: &DeviceSender,
+    sessions: &SessionMap,
+    device_sessions: &DeviceSessionsMap,
+) {
+    let mut sessions_guard = sessions.lock().unwrap();
+    let mut my_sessions = Vec::new();
+    let mut session_keys_to_track = Vec::new();
+
+    // Check active participants list and update it
+    for (key, session) in sessions_guard.iter_mut() {
+        // Check if device is in participants array
+        if let Some(participants) = session.session_info.get("participants")
+            .and_then(|v| v.as_array()) {
+            let is_participant = participants.iter()
+                .any(|p| p.as_str() == Some(dev_id));
+            if is_participant {
+                // Add to active participants if not already there (rejoin case)
+                if !session.active_participants.iter().any(|p| p == dev_id) {
+                    session.active_participants.push(dev_id.to_string());
+                    println!("Added '{}' back to active participants for session '{}'", dev_id, key);
+                }
+                my_sessions.push(session.session_info.clone());
+                session_keys_to_track.push(key.clone());
+            }
+        }
+    }
+    drop(sessions_guard);
+
+    // Update device sessions map with all sessions this device is in
+    let mut device_sessions_guard = device_sessions.lock().unwrap();
+    device_sessions_guard.insert(dev_id.to_string(), session_keys_to_track);
+    drop(device_sessions_guard);
+
+    // Send response with list of sessions
+    println!("Found {} sessions for device '{}'", my_sessions.len(), dev_id);
+    let response = ServerMsg::SessionsForDevice {
+        sessions: my_sessions,
+    };
+    let msg_txt = serde_json::to_string(&response).unwrap();
+    let _ = tx.send(Message::Text(msg_txt.into()));
+}
+
+/// Deliver a relay to `to` (or to every other device for `"*"`), reporting
+/// unknown recipients back to the sender.
+fn deliver_relay(
+    devices: &DeviceMap,
+    from: Option<&str>,
+    to: &str,
+    data: &serde_json::Value,
+    sender_tx: &DeviceSender,
+) -> bool {
+    let devices_guard = devices.lock().unwrap();
+
+    // Handle broadcast relay to all devices
+    if to == "*" {
+        let relay = ServerMsg::Relay {
+            from: from.unwrap_or_default().to_string(),
+            data: data.clone(),
+        };
+        let relay_text = serde_json::to_string(&relay).unwrap();
+
+        println!("Broadcasting relay from {} to all devices: {:?}",
+            from.unwrap_or("unknown"), data);
+
+        // Send to all devices except the sender
+        let mut delivered = false;
+        for (id, device_tx) in devices_guard.iter() {
+            if Some(id.as_str()) != from {
+                delivered |= device_tx.send(Message::Text(relay_text.clone().into())).is_ok();
+            }
+        }
+        delivered
+    } else {
+        // Handle targeted relay to specific device
+        if let Some(device_tx) = devices_guard.get(to) {
+            let relay = ServerMsg::Relay {
+                from: from.unwrap_or_default().to_string(),
+                data: data.clone(), // Clone data for the message
+            };
+            // Log the relay action
+            println!("Relaying message from {} to {}: {:?}", from.unwrap_or("unknown"), to, data);
+            device_tx.send(Message::Text(serde_json::to_string(&relay).unwrap().into())).is_ok()
+        } else {
+            println!("Relay failed: unknown device {}", to);
+            let err = ServerMsg::Error { error: format!("unknown device: {}", to) };
+            let _ = sender_tx.send(Message::Text(serde_json::to_string(&err).unwrap().into()));
+            false
+        }
+    }
+}
+
+/// The `Relay` arm: drop duplicates, fold session proposals and updates into
+/// the stored sessions, then deliver
+#[allow(clippy::too_many_arguments)]
+fn relay(
+    devices: &DeviceMap,
+    sessions: &SessionMap,
+    device_sessions: &DeviceSessionsMap,
+    dedup: Option<&Mutex<RelayDeduplicator>>,
+    device_id: Option<&str>,
+    to: &str,
+    data: &serde_json::Value,
+    tx: &DeviceSender,
+) {
+    // Drop retransmissions (e.g. after a reconnect) within the TTL
+    let from = device_id.unwrap_or_default();
+    if let Some(dedup) = dedup
+        && dedup.lock().unwrap().is_duplicate(from, to, data)
+    {
+        println!("Dropping duplicate relay from {} to {}", from, to);
+        return;
+    }
+
+    // Check if this is a SessionProposal to update session participants
+    if data.get("websocket_msg_type").and_then(|v| v.as_str()) == Some("SessionProposal")
+        && let (Some(session_id), Some(participants)) = (
+            data.get("session_id").and_then(|v| v.as_str()),
+            data.get("participants").and_then(|v| v.as_array()),
+        )
+    {
+        // Update existing session with participant information; only its
+        // creator may rewrite what was announced
+        let mut sessions_guard = sessions.lock().unwrap();
+        if let Some(session) = sessions_guard.get_mut(session_id)
+            && session.creator == from
+        {
+            // Update stored session_info to include participants
+            session.session_info = data.clone();
+
+            // Update active participants based on who's currently connected
+            session.active_participants.clear();
+            let devices_guard = devices.lock().unwrap();
+            for p in participants {
+                if let Some(participant_id) = p.as_str() {
+                    // Check if this device is currently connected
+                    if devices_guard.contains_key(participant_id) {
+                        session.active_participants.push(participant_id.to_string());
+                    }
+                }
+            }
+            drop(devices_guard);
+            println!("Updated session '{}' with participants: {:?} (active: {:?})", 
+                session_id, participants, session.active_participants);
+        }
+        drop(sessions_guard);
+
+        // Update device sessions map for all participants
+        let mut device_sessions_guard = device_sessions.lock().unwrap();
+        for p in participants {
+            if let Some(participant_id) = p.as_str() {
+                let entry = device_sessions_guard
+                    .entry(participant_id.to_string())
+                    .or_default();
+                if !entry.contains(&session_id.to_string()) {
+                    entry.push(session_id.to_string());
+                    println!("Added session '{}' to device '{}' session list", session_id, participant_id);
+                }
+            }
+        }
+        drop(device_sessions_guard);
+    }
+
+    // Check if this is a SessionUpdate to track active participants  
+    if data.get("websocket_msg_type").and_then(|v| v.as_str()) == Some("SessionUpdate")
+        && let (Some(session_id), Some(accepted_devices)) = (
+            data.get("session_id").and_then(|v| v.as_str()),
+            data.get("accepted_devices").and_then(|v| v.as_array()),
+        )
+    {
+        // Update session's active participants
+        let mut sessions_guard = sessions.lock().unwrap();
+        if let Some(session) = sessions_guard.get_mut(session_id) {
+            // Update active participants based on who's in the accepted_devices and currently connected
+            session.active_participants.clear();
+            let devices_guard = devices.lock().unwrap();
+            for p in accepted_devices {
+                if let Some(participant_id) = p.as_str() {
+                    // Check if this device is currently connected
+                    if devices_guard.contains_key(participant_id) {
+                        session.active_participants.push(participant_id.to_string());
+                    }
+                }
+            }
+            drop(devices_guard);
+            println!("Updated active participants for session '{}': {:?}", 
+                session_id, session.active_participants);
+
+            // Update the stored session_info to include accepted_devices
+            if session.session_info.get("participants").and_then(|v| v.as_array()).is_some() {
+                // Only update if we have participants info, otherwise preserve original session_info
+                let mut updated_info = session.session_info.clone();
+                updated_info.as_object_mut().unwrap().insert("accepted_devices".to_string(), serde_json::Value::Array(accepted_devices.clone()));
+                session.session_info = updated_info;
+            }
+        }
+        drop(sessions_guard);
+
+        // Update device sessions map for accepted devices
+        let mut device_sessions_guard = device_sessions.lock().unwrap();
+        for p in accepted_devices {
+            if let Some(participant_id) = p.as_str() {
+                let entry = device_sessions_guard
+                    .entry(participant_id.to_string())
+                    .or_default();
+                if !entry.contains(&session_id.to_string()) {
+                    entry.push(session_id.to_string());
+                }
+            }
+        }
+        drop(device_sessions_guard);
+    }
+
+    // Only a delivered relay counts, so a retry after
+    // the recipient was offline isn't dropped
+    if deliver_relay(devices, device_id, to, data, tx)
+        && let Some(dedup) = dedup
+    {
+        dedup.lock().unwrap().record_delivered(from, to, data);
+    }
+}
+
+/// Remove a device and drop it from the active participants of its sessions,
+/// then broadcast the updated device list. Used both when a socket closes and
+/// when the heartbeat reaps a silent peer.
+fn disconnect_device(
+    my_id: &str,
+    devices: &DeviceMap,
+    sessions: &SessionMap,
+    device_sessions: &DeviceSessionsMap,
+) {
+    // Remove device from active participants in sessions
+    let device_sessions_guard = device_sessions.lock().unwrap();
+    if let Some(session_ids) = device_sessions_guard.get(my_id) {
+        let mut sessions_guard = sessions.lock().unwrap();
+
+        for session_id in session_ids {
+            if let Some(session) = sessions_guard.get_mut(session_id) {
+                // Remove from active participants
+                session.active_participants.retain(|p| p != my_id);
+                println!("Removed '{}' from active participants in session '{}'", my_id, session_id);
+
+                // Keep session even when all participants disconnect — periodic
+                // cleanup will expire it after a grace period so rejoining works.
+                if session.active_participants.is_empty() {
+                    session.last_active = std::time::Instant::now();
+                    println!("Session '{}' has no active participants, keeping for grace period", session_id);
+                } else {
+                    println!("Session '{}' continues with {} active participants",
+                        session_id, session.active_participants.len());
+                }
+            }
+        }
+
+        drop(sessions_guard);
+    }
+    drop(device_sessions_guard);
+    
+    // Clean up device sessions map
+    let mut device_sessions_guard = device_sessions.lock().unwrap();
+    device_sessions_guard.remove(my_id);
+    drop(device_sessions_guard);
+    
+    // Now remove device from active list
+    let mut devices_guard = devices.lock().unwrap();
+    devices_guard.remove(my_id);
+    println!("Device {} disconnected", my_id);
+
+    // Broadcast updated device list to all devices (owned Vec)
+    let device_list: Vec<String> = devices_guard.keys().cloned().collect();
+    let msg = ServerMsg::Devices {
+        devices: device_list.clone(),
+    };
+    let msg_txt = serde_json::to_string(&msg).unwrap();
+    for (_id, ptx) in devices_guard.iter() {
+        let _ = ptx.send(Message::Text(msg_txt.clone().into()));
+    }
+}
 
 #[tokio::main]
 async fn main() {
     let devices: DeviceMap = Arc::new(Mutex::new(HashMap::new()));
     let sessions: SessionMap = Arc::new(Mutex::new(HashMap::new()));
     let device_sessions: DeviceSessionsMap = Arc::new(Mutex::new(HashMap::new()));
+    let relay_dedup = relay_dedup_from_env();
+    let limits = limits_from_env();
+    let tls_acceptor = match tls::acceptor_from_env() {
+        Ok(acceptor) => acceptor,
+        Err(e) => {
+            eprintln!("TLS configuration error: {}", e);
+            std::process::exit(1);
+        }
+    };
     let listener = TcpListener::bind("0.0.0.0:9000").await.unwrap();
-    println!("Signal server listening on 0.0.0.0:9000");
+    let scheme = if tls_acceptor.is_some() { "wss" } else { "ws" };
+    println!("Signal server listening on {}://0.0.0.0:9000", scheme);
     
     // Periodic cleanup: expire sessions that have had no active participants for >5 minutes
     let sessions_cleanup = sessions.clone();
@@ -66,8 +496,17 @@ async fn main() {
             let devices = devices.clone();
             let sessions = sessions.clone();
             let device_sessions = device_sessions.clone();
+            let relay_dedup = relay_dedup.clone();
+            let tls_acceptor = tls_acceptor.clone();
 
             tokio::spawn(async move {
+                let stream = match tls::accept(tls_acceptor.as_ref(), stream).await {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        eprintln!("TLS handshake failed: {:?}", e);
+                        return;
+                    }
+                };
                 // Handle WebSocket handshake errors gracefully
                 let ws_stream = match accept_async(stream).await {
                     Ok(ws) => ws,
@@ -80,6 +519,9 @@ async fn main() {
                 let (mut ws_sink, mut ws_stream) = ws_stream.split();
                 let (tx, mut rx) = mpsc::unbounded_channel::<Message>();
                 let mut device_id: Option<String> = None;
+                let mut heartbeat = Heartbeat::new();
+                let mut ping_interval = tokio::time::interval(PING_INTERVAL);
+                ping_interval.tick().await; // First tick fires immediately
 
                 // Task to forward messages from rx to ws_sink
                 let ws_sink_task = tokio::spawn(async move {
@@ -98,6 +540,10 @@ async fn main() {
                                     let _ = tx.send(Message::Pong(m.into_data()));
                                     continue;
                                 }
+                                Ok(m) if m.is_pong() => {
+                                    heartbeat.record_pong();
+                                    continue;
+                                }
                                 Ok(m) if m.is_text() => m.into_text().unwrap(),
                                 Ok(m) if m.is_close() => break,
                                 _ => continue,
@@ -106,24 +552,21 @@ async fn main() {
                             let parsed: Result<ClientMsg, _> = serde_json::from_str(&msg);
 
                             match parsed {
-                                Ok(ClientMsg::Register { device_id: reg_id }) => {
-                                    let mut devices_guard = devices.lock().unwrap();
-                                    if devices_guard.contains_key(&reg_id) {
-                                        let err = ServerMsg::Error { error: "device_id already registered".to_string() };
+                                Ok(ClientMsg::Register { device_id: reg_id, include_sessions }) => {
+                                    if let Err(error) = register_device(
+                                        &reg_id,
+                                        include_sessions,
+                                        &tx,
+                                        &devices,
+                                        &sessions,
+                                        &device_sessions,
+                                        limits.max_devices,
+                                    ) {
+                                        let err = ServerMsg::Error { error };
                                         let _ = tx.send(Message::Text(serde_json::to_string(&err).unwrap().into()));
                                         break;
                                     }
-                                    device_id = Some(reg_id.clone());
-                                    devices_guard.insert(reg_id.clone(), tx.clone());
-                                    println!("Registered device: {}", reg_id);
-
-                                    // Broadcast updated device list to all devices (owned Vec)
-                                    let device_list: Vec<String> = devices_guard.keys().cloned().collect();
-                                    let msg = ServerMsg::Devices { devices: device_list.clone() };
-                                    let msg_txt = serde_json::to_string(&msg).unwrap();
-                                    for (_id, ptx) in devices_guard.iter() {
-                                        let _ = ptx.send(Message::Text(msg_txt.clone().into()));
-                                    }
+                                    device_id = Some(reg_id);
                                 }
                                 Ok(ClientMsg::ListDevices) => {
                                     let devices_guard = devices.lock().unwrap();
@@ -132,141 +575,17 @@ async fn main() {
                                     let _ = tx.send(Message::Text(serde_json::to_string(&msg).unwrap().into()));
                                 }
                                 Ok(ClientMsg::Relay { to, data }) => {
-                                    // Check if this is a SessionProposal to update session participants
-                                    if data.get("websocket_msg_type").and_then(|v| v.as_str()) == Some("SessionProposal") {
-                                        if let (Some(session_id), Some(participants)) = (
-                                            data.get("session_id").and_then(|v| v.as_str()),
-                                            data.get("participants").and_then(|v| v.as_array())
-                                        ) {
-                                            // Update existing session with participant information
-                                            let mut sessions_guard = sessions.lock().unwrap();
-                                            if let Some(session) = sessions_guard.get_mut(session_id) {
-                                                // Update stored session_info to include participants
-                                                session.session_info = data.clone();
-                                                
-                                                // Update active participants based on who's currently connected
-                                                session.active_participants.clear();
-                                                let devices_guard = devices.lock().unwrap();
-                                                for p in participants {
-                                                    if let Some(participant_id) = p.as_str() {
-                                                        // Check if this device is currently connected
-                                                        if devices_guard.contains_key(participant_id) {
-                                                            session.active_participants.push(participant_id.to_string());
-                                                        }
-                                                    }
-                                                }
-                                                drop(devices_guard);
-                                                println!("Updated session '{}' with participants: {:?} (active: {:?})", 
-                                                    session_id, participants, session.active_participants);
-                                            }
-                                            drop(sessions_guard);
-                                            
-                                            // Update device sessions map for all participants
-                                            let mut device_sessions_guard = device_sessions.lock().unwrap();
-                                            for p in participants {
-                                                if let Some(participant_id) = p.as_str() {
-                                                    let entry = device_sessions_guard
-                                                        .entry(participant_id.to_string())
-                                                        .or_insert_with(Vec::new);
-                                                    if !entry.contains(&session_id.to_string()) {
-                                                        entry.push(session_id.to_string());
-                                                        println!("Added session '{}' to device '{}' session list", session_id, participant_id);
-                                                    }
-                                                }
-                                            }
-                                            drop(device_sessions_guard);
-                                        }
-                                    }
-                                    
-                                    // Check if this is a SessionUpdate to track active participants  
-                                    if data.get("websocket_msg_type").and_then(|v| v.as_str()) == Some("SessionUpdate") {
-                                        if let (Some(session_id), Some(accepted_devices)) = (
-                                            data.get("session_id").and_then(|v| v.as_str()),
-                                            data.get("accepted_devices").and_then(|v| v.as_array())
-                                        ) {
-                                            // Update session's active participants
-                                            let mut sessions_guard = sessions.lock().unwrap();
-                                            if let Some(session) = sessions_guard.get_mut(session_id) {
-                                                // Update active participants based on who's in the accepted_devices and currently connected
-                                                session.active_participants.clear();
-                                                let devices_guard = devices.lock().unwrap();
-                                                for p in accepted_devices {
-                                                    if let Some(participant_id) = p.as_str() {
-                                                        // Check if this device is currently connected
-                                                        if devices_guard.contains_key(participant_id) {
-                                                            session.active_participants.push(participant_id.to_string());
-                                                        }
-                                                    }
-                                                }
-                                                drop(devices_guard);
-                                                println!("Updated active participants for session '{}': {:?}", 
-                                                    session_id, session.active_participants);
-                                                
-                                                // Update the stored session_info to include accepted_devices
-                                                if session.session_info.get("participants").and_then(|v| v.as_array()).is_some() {
-                                                    // Only update if we have participants info, otherwise preserve original session_info
-                                                    let mut updated_info = session.session_info.clone();
-                                                    updated_info.as_object_mut().unwrap().insert("accepted_devices".to_string(), serde_json::Value::Array(accepted_devices.clone()));
-                                                    session.session_info = updated_info;
-                                                }
-                                            }
-                                            drop(sessions_guard);
-                                            
-                                            // Update device sessions map for accepted devices
-                                            let mut device_sessions_guard = device_sessions.lock().unwrap();
-                                            for p in accepted_devices {
-                                                if let Some(participant_id) = p.as_str() {
-                                                    let entry = device_sessions_guard
-                                                        .entry(participant_id.to_string())
-                                                        .or_insert_with(Vec::new);
-                                                    if !entry.contains(&session_id.to_string()) {
-                                                        entry.push(session_id.to_string());
-                                                    }
-                                                }
-                                            }
-                                            drop(device_sessions_guard);
-                                        }
-                                    }
-                                    
-                                    let devices_guard = devices.lock().unwrap();
-                                    
-                                    // Handle broadcast relay to all devices
-                                    if to == "*" {
-                                        let relay = ServerMsg::Relay {
-                                            from: device_id.as_deref().unwrap_or_default().to_string(),
-                                            data: data.clone(),
-                                        };
-                                        let relay_text = serde_json::to_string(&relay).unwrap();
-                                        
-                                        println!("Broadcasting relay from {} to all devices: {:?}", 
-                                            device_id.as_deref().unwrap_or("unknown"), data);
-                                        
-                                        // Send to all devices except the sender
-                                        for (id, device_tx) in devices_guard.iter() {
-                                            if Some(id) != device_id.as_ref() {
-                                                let _ = device_tx.send(Message::Text(relay_text.clone().into()));
-                                            }
-                                        }
-                                    } else {
-                                        // Handle targeted relay to specific device
-                                        if let Some(device_tx) = devices_guard.get(&to) {
-                                            let relay = ServerMsg::Relay {
-                                                from: device_id.as_deref().unwrap_or_default().to_string(),
-                                                data: data.clone(), // Clone data for the message
-                                            };
-                                            // Log the relay action
-                                            println!("Relaying message from {} to {}: {:?}", device_id.as_deref().unwrap_or("unknown"), to, data);
-                                            let _ = device_tx.send(Message::Text(serde_json::to_string(&relay).unwrap().into()));
-                                        } else {
-                                            println!("Relay failed: unknown device {}", to);
-                                            let err = ServerMsg::Error { error: format!("unknown device: {}", to) };
-                                            let _ = tx.send(Message::Text(serde_json::to_string(&err).unwrap().into()));
-                                        }
-                                    }
-                                    // Explicitly drop the lock
-                                    drop(devices_guard);
+                                    relay(&devices, &sessions, &device_sessions, relay_dedup.as_deref(), device_id.as_deref(), &to, &data, &tx);
                                 }
                                 Ok(ClientMsg::AnnounceSession { session_info }) => {
+                                    // A malformed announcement would break discovery for every client
+                                    if let Err(error) = validate_session_info(&session_info) {
+                                        println!("Rejecting session announcement from {}: {}", device_id.as_deref().unwrap_or("unknown"), error);
+                                        let err = ServerMsg::Error { error };
+                                        let _ = tx.send(Message::Text(serde_json::to_string(&err).unwrap().into()));
+                                        continue;
+                                    }
+
                                     // Store the session for later discovery
                                     if let Some(ref device) = device_id {
                                         // Extract session ID from the announcement
@@ -285,24 +604,19 @@ async fn main() {
                                                 .as_millis())
                                         };
                                         
-                                        // Store session with creator as first active participant
-                                        let stored_session = StoredSession {
-                                            session_info: session_info.clone(),
-                                            active_participants: vec![device.clone()], // Creator is first participant
-                                            last_active: std::time::Instant::now(),
-                                        };
-                                        
-                                        let mut sessions_guard = sessions.lock().unwrap();
-                                        sessions_guard.insert(session_key.clone(), stored_session);
-                                        drop(sessions_guard);
-                                        
-                                        // Track that this device is in this session
-                                        let mut device_sessions_guard = device_sessions.lock().unwrap();
-                                        device_sessions_guard
-                                            .entry(device.clone())
-                                            .or_insert_with(Vec::new)
-                                            .push(session_key.clone());
-                                        drop(device_sessions_guard);
+                                        if let Err(error) = store_session(
+                                            &sessions,
+                                            &device_sessions,
+                                            &session_key,
+                                            &session_info,
+                                            device,
+                                            &limits,
+                                        ) {
+                                            println!("Refusing session '{}' from device '{}': {}", session_key, device, error);
+                                            let err = ServerMsg::Error { error };
+                                            let _ = tx.send(Message::Text(serde_json::to_string(&err).unwrap().into()));
+                                            continue;
+                                        }
                                         
                                         println!("Stored session '{}' from device '{}'", session_key, device);
                                     }
@@ -363,16 +677,8 @@ async fn main() {
                                             let mut sessions_guard = sessions.lock().unwrap();
                                             if let Some(stored_session) = sessions_guard.get_mut(session_id) {
                                                 // Add participant to the participants array in session_info
-                                                if let Some(participants) = stored_session.session_info
-                                                    .get_mut("participants")
-                                                    .and_then(|v| v.as_array_mut()) {
-                                                    
-                                                    // Check if participant isn't already in list
-                                                    let already_joined = participants.iter()
-                                                        .any(|p| p.as_str() == Some(participant_joined));
-                                                    
-                                                    if !already_joined {
-                                                        participants.push(serde_json::Value::String(participant_joined.to_string()));
+                                                match admit_participant(&mut stored_session.session_info, participant_joined) {
+                                                    Ok(true) => {
                                                         println!("Added {} to session {} participants", participant_joined, session_id);
                                                         
                                                         // Also update active_participants
@@ -380,6 +686,14 @@ async fn main() {
                                                             stored_session.active_participants.push(participant_joined.to_string());
                                                         }
                                                     }
+                                                    Ok(false) => {}
+                                                    Err(error) => {
+                                                        drop(sessions_guard);
+                                                        println!("Refused {}: {}", participant_joined, error);
+                                                        let err = ServerMsg::Error { error };
+                                                        let _ = tx.send(Message::Text(serde_json::to_string(&err).unwrap().into()));
+                                                        continue;
+                                                    }
                                                 }
                                                 
                                                 // Broadcast updated session to all participants
@@ -423,43 +737,7 @@ async fn main() {
                                     // Client asks "what sessions am I in?"
                                     if let Some(ref dev_id) = device_id {
                                         println!("Device '{}' querying for active sessions", dev_id);
-                                        
-                                        let mut sessions_guard = sessions.lock().unwrap();
-                                        let mut my_sessions = Vec::new();
-                                        let mut session_keys_to_track = Vec::new();
-                                        
-                                        // Check active participants list and update it
-                                        for (key, session) in sessions_guard.iter_mut() {
-                                            // Check if device is in participants array
-                                            if let Some(participants) = session.session_info.get("participants")
-                                                .and_then(|v| v.as_array()) {
-                                                let is_participant = participants.iter()
-                                                    .any(|p| p.as_str() == Some(dev_id.as_str()));
-                                                if is_participant {
-                                                    // Add to active participants if not already there (rejoin case)
-                                                    if !session.active_participants.contains(dev_id) {
-                                                        session.active_participants.push(dev_id.clone());
-                                                        println!("Added '{}' back to active participants for session '{}'", dev_id, key);
-                                                    }
-                                                    my_sessions.push(session.session_info.clone());
-                                                    session_keys_to_track.push(key.clone());
-                                                }
-                                            }
-                                        }
-                                        drop(sessions_guard);
-                                        
-                                        // Update device sessions map with all sessions this device is in
-                                        let mut device_sessions_guard = device_sessions.lock().unwrap();
-                                        device_sessions_guard.insert(dev_id.clone(), session_keys_to_track);
-                                        drop(device_sessions_guard);
-                                        
-                                        // Send response with list of sessions
-                                        let response = ServerMsg::SessionsForDevice {
-                                            sessions: my_sessions.clone(),
-                                        };
-                                        let msg_txt = serde_json::to_string(&response).unwrap();
-                                        println!("Found {} sessions for device '{}'", my_sessions.len(), dev_id);
-                                        let _ = tx.send(Message::Text(msg_txt.into()));
+                                        send_sessions_for_device(dev_id, &tx, &sessions, &device_sessions);
                                     }
                                 }
                                 Err(_) => {
@@ -468,58 +746,21 @@ async fn main() {
                                 }
                             }
                         }
+                        _ = ping_interval.tick() => {
+                            if heartbeat.is_dead() {
+                                println!("💀 Device {} missed heartbeats, treating as disconnected",
+                                    device_id.as_deref().unwrap_or("unknown"));
+                                break;
+                            }
+                            let _ = tx.send(Message::Ping(Vec::new().into()));
+                        }
                         else => break,
                     }
                 }
 
-                // Cleanup on disconnect
+                // Cleanup on disconnect (graceful close or missed heartbeats)
                 if let Some(my_id) = device_id {
-                    // Remove device from active participants in sessions
-                    let device_sessions_guard = device_sessions.lock().unwrap();
-                    if let Some(session_ids) = device_sessions_guard.get(&my_id) {
-                        let mut sessions_guard = sessions.lock().unwrap();
-
-                        for session_id in session_ids {
-                            if let Some(session) = sessions_guard.get_mut(session_id) {
-                                // Remove from active participants
-                                session.active_participants.retain(|p| p != &my_id);
-                                println!("Removed '{}' from active participants in session '{}'", my_id, session_id);
-
-                                // Keep session even when all participants disconnect — periodic
-                                // cleanup will expire it after a grace period so rejoining works.
-                                if session.active_participants.is_empty() {
-                                    session.last_active = std::time::Instant::now();
-                                    println!("Session '{}' has no active participants, keeping for grace period", session_id);
-                                } else {
-                                    println!("Session '{}' continues with {} active participants",
-                                        session_id, session.active_participants.len());
-                                }
-                            }
-                        }
-
-                        drop(sessions_guard);
-                    }
-                    drop(device_sessions_guard);
-                    
-                    // Clean up device sessions map
-                    let mut device_sessions_guard = device_sessions.lock().unwrap();
-                    device_sessions_guard.remove(&my_id);
-                    drop(device_sessions_guard);
-                    
-                    // Now remove device from active list
-                    let mut devices_guard = devices.lock().unwrap();
-                    devices_guard.remove(&my_id);
-                    println!("Device {} disconnected", my_id);
-
-                    // Broadcast updated device list to all devices (owned Vec)
-                    let device_list: Vec<String> = devices_guard.keys().cloned().collect();
-                    let msg = ServerMsg::Devices {
-                        devices: device_list.clone(),
-                    };
-                    let msg_txt = serde_json::to_string(&msg).unwrap();
-                    for (_id, ptx) in devices_guard.iter() {
-                        let _ = ptx.send(Message::Text(msg_txt.clone().into()));
-                    }
+                    disconnect_device(&my_id, &devices, &sessions, &device_sessions);
                 }
                 ws_sink_task.abort();
             });
@@ -533,3 +774,212 @@ async fn main() {
 
     println!("Server has shut down.");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_silent_peer_is_reaped() {
+        let devices: DeviceMap = Arc::new(Mutex::new(HashMap::new()));
+        let sessions: SessionMap = Arc::new(Mutex::new(HashMap::new()));
+        let device_sessions: DeviceSessionsMap = Arc::new(Mutex::new(HashMap::new()));
+
+        let (silent_tx, _silent_rx) = mpsc::unbounded_channel::<Message>();
+        let (alive_tx, mut alive_rx) = mpsc::unbounded_channel::<Message>();
+        devices.lock().unwrap().insert("silent".to_string(), silent_tx);
+        devices.lock().unwrap().insert("alive".to_string(), alive_tx);
+
+        sessions.lock().unwrap().insert("s1".to_string(), StoredSession {
+            session_info: serde_json::json!({ "session_id": "s1" }),
+            active_participants: vec!["silent".to_string(), "alive".to_string()],
+            last_active: Instant::now(),
+            creator: "alive".to_string(),
+        });
+        device_sessions.lock().unwrap().insert("silent".to_string(), vec!["s1".to_string()]);
+
+        // The silent peer never answers a ping: two intervals later it is dead
+        let heartbeat = Heartbeat::with_config(Duration::from_secs(20), 2);
+        assert!(heartbeat.is_dead_at(heartbeat.last_pong() + Duration::from_secs(40)));
+
+        disconnect_device("silent", &devices, &sessions, &device_sessions);
+
+        assert!(!devices.lock().unwrap().contains_key("silent"));
+        assert!(!device_sessions.lock().unwrap().contains_key("silent"));
+        assert_eq!(
+            sessions.lock().unwrap()["s1"].active_participants,
+            vec!["alive".to_string()]
+        );
+
+        // Remaining devices receive the updated device list
+        let msg = alive_rx.try_recv().expect("device list broadcast");
+        let parsed: ServerMsg = serde_json::from_str(msg.to_text().unwrap()).unwrap();
+        match parsed {
+            ServerMsg::Devices { devices } => assert_eq!(devices, vec!["alive".to_string()]),
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_announce_past_session_cap_is_rejected() {
+        let sessions: SessionMap = Arc::new(Mutex::new(HashMap::new()));
+        let device_sessions: DeviceSessionsMap = Arc::new(Mutex::new(HashMap::new()));
+        let info = |id: &str| serde_json::json!({ "session_id": id, "total": 3 });
+        let limits = Limits { max_sessions: 2, max_devices: 10, max_sessions_per_device: 10 };
+
+        assert!(store_session(&sessions, &device_sessions, "s1", &info("s1"), "alice", &limits).is_ok());
+        assert!(store_session(&sessions, &device_sessions, "s2", &info("s2"), "bob", &limits).is_ok());
+
+        let err = store_session(&sessions, &device_sessions, "s3", &info("s3"), "carol", &limits).unwrap_err();
+        assert_eq!(err, "session limit reached (2 sessions)");
+        assert!(!device_sessions.lock().unwrap().contains_key("carol"));
+        {
+            let guard = sessions.lock().unwrap();
+            assert_eq!(guard.len(), 2);
+            assert_eq!(guard["s1"].session_info, info("s1"));
+            assert_eq!(guard["s2"].active_participants, vec!["bob".to_string()]);
+        }
+
+        // Re-announcing a stored session at the cap is still allowed
+        assert!(store_session(&sessions, &device_sessions, "s1", &info("s1"), "alice", &limits).is_ok());
+        assert_eq!(device_sessions.lock().unwrap()["alice"], vec!["s1".to_string()]);
+    }
+
+    #[test]
+    fn test_announce_past_per_device_cap_or_by_non_creator_is_rejected() {
+        let sessions: SessionMap = Arc::new(Mutex::new(HashMap::new()));
+        let device_sessions: DeviceSessionsMap = Arc::new(Mutex::new(HashMap::new()));
+        let info = |id: &str| serde_json::json!({ "session_id": id, "total": 3 });
+        let limits = Limits { max_sessions: 10, max_devices: 10, max_sessions_per_device: 2 };
+
+        assert!(store_session(&sessions, &device_sessions, "s1", &info("s1"), "alice", &limits).is_ok());
+        assert!(store_session(&sessions, &device_sessions, "s2", &info("s2"), "alice", &limits).is_ok());
+        let err = store_session(&sessions, &device_sessions, "s3", &info("s3"), "alice", &limits).unwrap_err();
+        assert_eq!(err, "per-device session limit reached (2 sessions)");
+
+        // Other devices still have room, and the creator may re-announce at its cap
+        assert!(store_session(&sessions, &device_sessions, "s3", &info("s3"), "bob", &limits).is_ok());
+        assert!(store_session(&sessions, &device_sessions, "s1", &info("s1"), "alice", &limits).is_ok());
+
+        // Nobody else may replace a stored session, even under the caps
+        let hijack = serde_json::json!({ "session_id": "s1", "total": 2 });
+        let err = store_session(&sessions, &device_sessions, "s1", &hijack, "bob", &limits).unwrap_err();
+        assert_eq!(err, "session 's1' was announced by another device");
+        let guard = sessions.lock().unwrap();
+        assert_eq!(guard["s1"].session_info, info("s1"));
+        assert_eq!(guard["s1"].creator, "alice");
+        assert_eq!(device_sessions.lock().unwrap()["bob"], vec!["s3".to_string()]);
+    }
+
+    #[test]
+    fn test_reregister_returns_session_membership() {
+        let devices: DeviceMap = Arc::new(Mutex::new(HashMap::new()));
+        let sessions: SessionMap = Arc::new(Mutex::new(HashMap::new()));
+        let device_sessions: DeviceSessionsMap = Arc::new(Mutex::new(HashMap::new()));
+        let info = serde_json::json!({ "session_id": "s1", "participants": ["alice", "bob"] });
+
+        let (alice_tx, _alice_rx) = mpsc::unbounded_channel::<Message>();
+        let (bob_tx, mut bob_rx) = mpsc::unbounded_channel::<Message>();
+        register_device("alice", false, &alice_tx, &devices, &sessions, &device_sessions, 10).unwrap();
+        register_device("bob", false, &bob_tx, &devices, &sessions, &device_sessions, 10).unwrap();
+        let limits = Limits { max_sessions: 10, max_devices: 10, max_sessions_per_device: 10 };
+        store_session(&sessions, &device_sessions, "s1", &info, "alice", &limits).unwrap();
+        disconnect_device("alice", &devices, &sessions, &device_sessions);
+        assert!(sessions.lock().unwrap()["s1"].active_participants.is_empty());
+        while bob_rx.try_recv().is_ok() {}
+
+        let (alice_tx, mut alice_rx) = mpsc::unbounded_channel::<Message>();
+        register_device("alice", true, &alice_tx, &devices, &sessions, &device_sessions, 10).unwrap();
+
+        // Everyone still gets the device list, then alice gets her sessions
+        assert!(matches!(next(&mut bob_rx), ServerMsg::Devices { devices } if devices.len() == 2));
+        assert!(matches!(next(&mut alice_rx), ServerMsg::Devices { .. }));
+        match next(&mut alice_rx) {
+            ServerMsg::SessionsForDevice { sessions } => assert_eq!(sessions, vec![info]),
+            other => panic!("unexpected message: {:?}", other),
+        }
+        assert_eq!(sessions.lock().unwrap()["s1"].active_participants, vec!["alice".to_string()]);
+        assert_eq!(device_sessions.lock().unwrap()["alice"], vec!["s1".to_string()]);
+
+        // Registering the same id twice is still refused
+        let err = register_device("alice", true, &alice_tx, &devices, &sessions, &device_sessions, 10);
+        assert_eq!(err.unwrap_err(), "device_id already registered");
+    }
+
+    #[test]
+    fn test_duplicate_relay_delivered_once() {
+        let devices: DeviceMap = Arc::new(Mutex::new(HashMap::new()));
+        let (sender_tx, _sender_rx) = mpsc::unbounded_channel::<Message>();
+        let (peer_tx, mut peer_rx) = mpsc::unbounded_channel::<Message>();
+        devices.lock().unwrap().insert("peer".to_string(), peer_tx);
+
+        let (sessions, device_sessions): (SessionMap, DeviceSessionsMap) = Default::default();
+        let dedup = Mutex::new(RelayDeduplicator::default());
+        let proposal = serde_json::json!({ "websocket_msg_type": "SessionProposal", "session_id": "s1" });
+
+        // The sender reconnects and relays the same proposal again
+        for _ in 0..2 {
+            relay(&devices, &sessions, &device_sessions, Some(&dedup), Some("sender"), "peer", &proposal, &sender_tx);
+        }
+
+        assert!(peer_rx.try_recv().is_ok());
+        assert!(peer_rx.try_recv().is_err());
+    }
+
+    /// Next message queued for a device, parsed
+    fn next(rx: &mut mpsc::UnboundedReceiver<Message>) -> ServerMsg {
+        serde_json::from_str(rx.try_recv().unwrap().to_text().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_retry_to_offline_peer_is_delivered() {
+        let devices: DeviceMap = Arc::new(Mutex::new(HashMap::new()));
+        let (sender_tx, mut sender_rx) = mpsc::unbounded_channel::<Message>();
+        let (sessions, device_sessions): (SessionMap, DeviceSessionsMap) = Default::default();
+        let dedup = Mutex::new(RelayDeduplicator::default());
+        let proposal = serde_json::json!({ "websocket_msg_type": "SessionProposal", "session_id": "s1" });
+
+        // The peer isn't connected yet, so the first relay bounces
+        relay(&devices, &sessions, &device_sessions, Some(&dedup), Some("sender"), "peer", &proposal, &sender_tx);
+        assert!(matches!(next(&mut sender_rx), ServerMsg::Error { .. }));
+
+        // It comes online and the sender retries within the TTL
+        let (peer_tx, mut peer_rx) = mpsc::unbounded_channel::<Message>();
+        devices.lock().unwrap().insert("peer".to_string(), peer_tx);
+        relay(&devices, &sessions, &device_sessions, Some(&dedup), Some("sender"), "peer", &proposal, &sender_tx);
+        assert!(matches!(next(&mut peer_rx), ServerMsg::Relay { .. }));
+
+        // Once delivered, a further resend is dropped
+        relay(&devices, &sessions, &device_sessions, Some(&dedup), Some("sender"), "peer", &proposal, &sender_tx);
+        assert!(peer_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_chunked_relay_passes_through_dedup() {
+        use webrtc_signal_server::chunking::{RelayReassembler, chunk_relay};
+
+        let devices: DeviceMap = Arc::new(Mutex::new(HashMap::new()));
+        let (sender_tx, _sender_rx) = mpsc::unbounded_channel::<Message>();
+        let (peer_tx, mut peer_rx) = mpsc::unbounded_channel::<Message>();
+        devices.lock().unwrap().insert("peer".to_string(), peer_tx);
+
+        let (sessions, device_sessions): (SessionMap, DeviceSessionsMap) = Default::default();
+        let dedup = Mutex::new(RelayDeduplicator::default());
+        let bundle = serde_json::json!({ "websocket_msg_type": "DKGRound2", "packages": "cd".repeat(2000) });
+        for msg in chunk_relay("peer", bundle.clone(), 512) {
+            let ClientMsg::Relay { to, data } = msg else { unreachable!() };
+            relay(&devices, &sessions, &device_sessions, Some(&dedup), Some("sender"), &to, &data, &sender_tx);
+        }
+
+        let mut reassembler = RelayReassembler::default();
+        let mut complete = None;
+        while let Ok(msg) = peer_rx.try_recv() {
+            let ServerMsg::Relay { from, data } = serde_json::from_str(msg.to_text().unwrap()).unwrap() else {
+                panic!("expected a relay");
+            };
+            complete = reassembler.accept(&from, data).or(complete);
+        }
+        assert_eq!(complete, Some(bundle));
+    }
+}
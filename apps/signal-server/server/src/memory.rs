@@ -0,0 +1,254 @@
+//! In-memory signal server for tests.
+//!
+//! Routes `ClientMsg` frames to `ServerMsg` frames the way the WebSocket
+//! server does, but each client is a pair of channels carrying the same JSON
+//! text a socket would. Tests can wire several nodes together without binding
+//! port 9000, so they run in parallel and never race a real server.
+//!
+//! Only routing is modelled: there are no heartbeats, relay dedup or device
+//! and session limits, and stored sessions never expire.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::mpsc;
+
+use crate::{ClientMsg, ServerMsg, admit_participant, validate_session_info};
+
+#[derive(Default)]
+struct Router {
+    devices: BTreeMap<String, mpsc::UnboundedSender<String>>,
+    sessions: HashMap<String, serde_json::Value>,
+}
+
+impl Router {
+    fn send(tx: &mpsc::UnboundedSender<String>, msg: &ServerMsg) {
+        let _ = tx.send(serde_json::to_string(msg).unwrap());
+    }
+
+    /// Send `msg` to every registered device except `except`
+    fn broadcast(&self, msg: &ServerMsg, except: Option<&str>) {
+        for (id, tx) in &self.devices {
+            if Some(id.as_str()) != except {
+                Self::send(tx, msg);
+            }
+        }
+    }
+
+    fn broadcast_devices(&self) {
+        let devices = self.devices.keys().cloned().collect();
+        self.broadcast(&ServerMsg::Devices { devices }, None);
+    }
+
+    fn sessions_for(&self, device_id: &str) -> Vec<serde_json::Value> {
+        self.sessions
+            .values()
+            .filter(|info| {
+                info.get("participants")
+                    .and_then(|v| v.as_array())
+                    .is_some_and(|p| p.iter().any(|p| p.as_str() == Some(device_id)))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Handle one client frame. Returns `false` when the connection should close.
+    fn handle(
+        &mut self,
+        text: &str,
+        device_id: &mut Option<String>,
+        tx: &mpsc::UnboundedSender<String>,
+    ) -> bool {
+        let Ok(msg) = serde_json::from_str::<ClientMsg>(text) else {
+            Self::send(tx, &ServerMsg::Error { error: "invalid message".to_string() });
+            return true;
+        };
+        match msg {
+            ClientMsg::Register { device_id: reg_id, include_sessions } => {
+                if self.devices.contains_key(&reg_id) {
+                    Self::send(tx, &ServerMsg::Error { error: "device_id already registered".to_string() });
+                    return false;
+                }
+                self.devices.insert(reg_id.clone(), tx.clone());
+                self.broadcast_devices();
+                if include_sessions {
+                    Self::send(tx, &ServerMsg::SessionsForDevice { sessions: self.sessions_for(&reg_id) });
+                }
+                *device_id = Some(reg_id);
+            }
+            ClientMsg::ListDevices => {
+                let devices = self.devices.keys().cloned().collect();
+                Self::send(tx, &ServerMsg::Devices { devices });
+            }
+            ClientMsg::Relay { to, data } => {
+                let relay = ServerMsg::Relay {
+                    from: device_id.clone().unwrap_or_default(),
+                    data,
+                };
+                if to == "*" {
+                    self.broadcast(&relay, device_id.as_deref());
+                } else if let Some(peer_tx) = self.devices.get(&to) {
+                    Self::send(peer_tx, &relay);
+                } else {
+                    Self::send(tx, &ServerMsg::Error { error: format!("unknown device: {}", to) });
+                }
+            }
+            ClientMsg::AnnounceSession { session_info } => {
+                if let Err(error) = validate_session_info(&session_info) {
+                    Self::send(tx, &ServerMsg::Error { error });
+                    return true;
+                }
+                if device_id.is_some()
+                    && let Some(id) = session_info.get("session_id").and_then(|v| v.as_str())
+                {
+                    self.sessions.insert(id.to_string(), session_info.clone());
+                }
+                self.broadcast(&ServerMsg::SessionAvailable { session_info }, device_id.as_deref());
+            }
+            ClientMsg::RequestActiveSessions => {
+                for session_info in self.sessions.values() {
+                    Self::send(tx, &ServerMsg::SessionAvailable { session_info: session_info.clone() });
+                }
+                let request = ServerMsg::SessionListRequest {
+                    from: device_id.clone().unwrap_or_default(),
+                };
+                self.broadcast(&request, device_id.as_deref());
+            }
+            ClientMsg::SessionStatusUpdate { session_info } => {
+                let joined = session_info.get("participant_joined").and_then(|v| v.as_str());
+                let session_id = session_info.get("session_id").and_then(|v| v.as_str());
+                if let (Some(joined), Some(session_id)) = (joined, session_id)
+                    && let Some(stored) = self.sessions.get_mut(session_id)
+                {
+                    if let Err(error) = admit_participant(stored, joined) {
+                        Self::send(tx, &ServerMsg::Error { error });
+                        return true;
+                    }
+                    let update = ServerMsg::Relay {
+                        from: "server".to_string(),
+                        data: serde_json::json!({
+                            "type": "participant_update",
+                            "session_id": session_id,
+                            "session_info": stored.clone(),
+                        }),
+                    };
+                    self.broadcast(&update, None);
+                }
+            }
+            ClientMsg::QueryMyActiveSessions => {
+                if let Some(id) = device_id.as_deref() {
+                    Self::send(tx, &ServerMsg::SessionsForDevice { sessions: self.sessions_for(id) });
+                }
+            }
+        }
+        true
+    }
+
+    fn disconnect(&mut self, device_id: &str) {
+        self.devices.remove(device_id);
+        self.broadcast_devices();
+    }
+}
+
+/// A signal server whose clients are channels instead of sockets
+#[derive(Clone, Default)]
+pub struct InMemorySignalServer {
+    router: Arc<Mutex<Router>>,
+}
+
+/// One client's end of an [`InMemorySignalServer`]. Send `ClientMsg` JSON on
+/// `tx` and read `ServerMsg` JSON from `rx`. Dropping `tx` disconnects.
+pub struct InMemoryConnection {
+    pub tx: mpsc::UnboundedSender<String>,
+    pub rx: mpsc::UnboundedReceiver<String>,
+}
+
+impl InMemorySignalServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open a connection, as a client dialing the server would. Must be
+    /// called from within a tokio runtime.
+    pub fn connect(&self) -> InMemoryConnection {
+        let (client_tx, mut client_rx) = mpsc::unbounded_channel::<String>();
+        let (server_tx, server_rx) = mpsc::unbounded_channel::<String>();
+        let router = self.router.clone();
+        tokio::spawn(async move {
+            let mut device_id = None;
+            while let Some(text) = client_rx.recv().await {
+                if !router.lock().unwrap().handle(&text, &mut device_id, &server_tx) {
+                    break;
+                }
+            }
+            if let Some(id) = device_id {
+                router.lock().unwrap().disconnect(&id);
+            }
+        });
+        InMemoryConnection { tx: client_tx, rx: server_rx }
+    }
+
+    /// Currently registered device ids, sorted
+    pub fn devices(&self) -> Vec<String> {
+        self.router.lock().unwrap().devices.keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(msg: &ClientMsg) -> String {
+        serde_json::to_string(msg).unwrap()
+    }
+
+    async fn next(conn: &mut InMemoryConnection) -> ServerMsg {
+        serde_json::from_str(&conn.rx.recv().await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_relays_between_registered_devices() {
+        let server = InMemorySignalServer::new();
+        let mut alice = server.connect();
+        let mut bob = server.connect();
+        for (conn, id) in [(&alice, "alice"), (&bob, "bob")] {
+            conn.tx
+                .send(frame(&ClientMsg::Register { device_id: id.to_string(), include_sessions: false }))
+                .unwrap();
+        }
+        while server.devices().len() < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        let data = serde_json::json!({ "hello": "bob" });
+        alice.tx.send(frame(&ClientMsg::Relay { to: "bob".to_string(), data: data.clone() })).unwrap();
+        loop {
+            match next(&mut bob).await {
+                ServerMsg::Relay { from, data: got } => {
+                    assert_eq!(from, "alice");
+                    assert_eq!(got, data);
+                    break;
+                }
+                ServerMsg::Devices { .. } => continue,
+                other => panic!("unexpected frame {:?}", other),
+            }
+        }
+
+        alice.tx.send(frame(&ClientMsg::Relay { to: "carol".to_string(), data })).unwrap();
+        loop {
+            match next(&mut alice).await {
+                ServerMsg::Error { error } => {
+                    assert_eq!(error, "unknown device: carol");
+                    break;
+                }
+                ServerMsg::Devices { .. } => continue,
+                other => panic!("unexpected frame {:?}", other),
+            }
+        }
+
+        drop(bob);
+        while server.devices() != ["alice"] {
+            tokio::task::yield_now().await;
+        }
+    }
+}
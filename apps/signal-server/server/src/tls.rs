@@ -0,0 +1,152 @@
+/// Optional TLS termination, so nodes can connect over `wss://`
+///
+/// Set `TLS_CERT_PATH` and `TLS_KEY_PATH` to PEM files (certificate chain and
+/// private key) and every accepted connection is wrapped in TLS before the
+/// WebSocket handshake. With neither set the server keeps speaking plain
+/// `ws://`, which is what local development uses.
+use std::io;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::rustls::crypto::ring;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+
+/// PEM certificate chain served to clients
+pub const TLS_CERT_ENV: &str = "TLS_CERT_PATH";
+/// PEM private key for the certificate
+pub const TLS_KEY_ENV: &str = "TLS_KEY_PATH";
+
+/// Build an acceptor from a PEM certificate chain and private key
+pub fn load_acceptor(cert_path: &Path, key_path: &Path) -> Result<TlsAcceptor, String> {
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("failed to read certificates from {}: {}", cert_path.display(), e))?;
+    if certs.is_empty() {
+        return Err(format!("no certificates in {}", cert_path.display()));
+    }
+    let key = PrivateKeyDer::from_pem_file(key_path)
+        .map_err(|e| format!("failed to read private key from {}: {}", key_path.display(), e))?;
+
+    let config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .and_then(|builder| builder.with_no_client_auth().with_single_cert(certs, key))
+        .map_err(|e| format!("invalid TLS configuration: {}", e))?;
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// The acceptor configured by `TLS_CERT_PATH`/`TLS_KEY_PATH`, or `None` to
+/// serve plain `ws://`. Setting only one of them is an error rather than a
+/// silent fallback to cleartext.
+pub fn acceptor_from_env() -> Result<Option<TlsAcceptor>, String> {
+    match (std::env::var(TLS_CERT_ENV), std::env::var(TLS_KEY_ENV)) {
+        (Ok(cert), Ok(key)) => load_acceptor(Path::new(&cert), Path::new(&key)).map(Some),
+        (Err(_), Err(_)) => Ok(None),
+        _ => Err(format!("set both {} and {} to enable TLS", TLS_CERT_ENV, TLS_KEY_ENV)),
+    }
+}
+
+/// An accepted connection, TLS-wrapped or not
+pub enum ServerStream {
+    Plain(TcpStream),
+    Tls(Box<tokio_rustls::server::TlsStream<TcpStream>>),
+}
+
+/// Run the TLS handshake on `stream` if `acceptor` is set
+pub async fn accept(acceptor: Option<&TlsAcceptor>, stream: TcpStream) -> io::Result<ServerStream> {
+    match acceptor {
+        Some(acceptor) => Ok(ServerStream::Tls(Box::new(acceptor.accept(stream).await?))),
+        None => Ok(ServerStream::Plain(stream)),
+    }
+}
+
+impl AsyncRead for ServerStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            ServerStream::Plain(stream) => Pin::new(stream).poll_read(cx, buf),
+            ServerStream::Tls(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for ServerStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            ServerStream::Plain(stream) => Pin::new(stream).poll_write(cx, buf),
+            ServerStream::Tls(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            ServerStream::Plain(stream) => Pin::new(stream).poll_flush(cx),
+            ServerStream::Tls(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            ServerStream::Plain(stream) => Pin::new(stream).poll_shutdown(cx),
+            ServerStream::Tls(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio::net::TcpListener;
+    use tokio_rustls::TlsConnector;
+    use tokio_rustls::rustls::pki_types::ServerName;
+    use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+    use tokio_tungstenite::tungstenite::Message;
+
+    #[tokio::test]
+    async fn test_wss_handshake_with_configured_tls() {
+        let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let dir = std::env::temp_dir().join(format!("signal-server-tls-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (cert_path, key_path) = (dir.join("cert.pem"), dir.join("key.pem"));
+        std::fs::write(&cert_path, certified.cert.pem()).unwrap();
+        std::fs::write(&key_path, certified.key_pair.serialize_pem()).unwrap();
+        let acceptor = load_acceptor(&cert_path, &key_path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // Echo one message back over wss://
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let stream = accept(Some(&acceptor), stream).await.unwrap();
+            assert!(matches!(stream, ServerStream::Tls(_)));
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let message = ws.next().await.unwrap().unwrap();
+            ws.send(message).await.unwrap();
+        });
+
+        let mut roots = RootCertStore::empty();
+        roots.add(certified.cert.der().clone()).unwrap();
+        let config = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let tcp = TcpStream::connect(addr).await.unwrap();
+        let tls = TlsConnector::from(Arc::new(config))
+            .connect(ServerName::try_from("localhost").unwrap(), tcp)
+            .await
+            .unwrap();
+        let (mut ws, _) = tokio_tungstenite::client_async(format!("wss://localhost:{}/", addr.port()), tls)
+            .await
+            .unwrap();
+        ws.send(Message::Text("hello over tls".into())).await.unwrap();
+        assert_eq!(ws.next().await.unwrap().unwrap(), Message::Text("hello over tls".into()));
+        server.await.unwrap();
+    }
+}
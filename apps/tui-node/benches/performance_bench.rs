@@ -184,6 +184,7 @@ fn bench_connection_pool(c: &mut Criterion) {
                             idle_timeout: Duration::from_secs(60),
                             retry_limit: 3,
                             parallel_attempts: 3,
+                            ..Default::default()
                         }
                     );
                     
@@ -35,12 +35,41 @@ struct Args {
     /// Example: --signal-server ws://localhost:9000
     #[arg(long, default_value = "wss://xiongchenyu.dpdns.org")]
     signal_server: String,
+
+    /// Largest data-channel payload accepted from a peer, in bytes
+    #[arg(long, default_value_t = tui_node::network::framing::MAX_FRAME_LEN)]
+    max_frame_bytes: usize,
+
+    /// Disconnect peers that send a payload over --max-frame-bytes
+    #[arg(long)]
+    disconnect_oversized_peers: bool,
+
+    /// Signal-server reconnect attempts before giving up
+    #[arg(long, default_value_t = 5)]
+    max_reconnect_attempts: u32,
+
+    /// Print the public metadata and address of an exported wallet file,
+    /// then exit without starting the TUI
+    #[arg(long, value_name = "FILE")]
+    inspect_keystore: Option<std::path::PathBuf>,
+
+    /// Build an unsigned EIP-1559 transaction from a JSON file of its
+    /// fields and print it with the digest to sign, then exit
+    #[arg(long, value_name = "FILE")]
+    build_eth_tx: Option<std::path::PathBuf>,
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
+    if let Some(path) = &args.inspect_keystore {
+        return inspect_keystore(path);
+    }
+    if let Some(path) = &args.build_eth_tx {
+        return build_eth_tx(path);
+    }
+
     // Determine device ID
     let device_id = args.device_id.unwrap_or_else(|| {
         gethostname::gethostname()
@@ -90,11 +119,57 @@ async fn main() -> anyhow::Result<()> {
     }
 
     // Run the Elm-based TUI application
-    run_elm_tui(device_id, args.signal_server, args.offline).await
+    let frame_limits = tui_node::network::framing::FrameLimits {
+        max_frame_len: args.max_frame_bytes,
+        disconnect_on_oversize: args.disconnect_oversized_peers,
+    };
+    run_elm_tui(
+        device_id,
+        args.signal_server,
+        args.offline,
+        frame_limits,
+        args.max_reconnect_attempts,
+    )
+    .await
+}
+
+/// Print what `Keystore::inspect` finds in a wallet file
+fn inspect_keystore(path: &std::path::Path) -> anyhow::Result<()> {
+    let data = std::fs::read(path)?;
+    let summary = tui_node::keystore::Keystore::inspect(&data)?;
+    println!("Wallet:       {}", summary.wallet_id);
+    println!("Device:       {}", summary.device_id);
+    println!("Curve:        {}", summary.curve_type);
+    println!("Threshold:    {}-of-{}", summary.threshold, summary.total_participants);
+    println!("Participant:  {}", summary.participant_index);
+    println!("Address:      {} ({})", summary.address, summary.chain);
+    println!("Group key:    {}", summary.group_public_key);
+    println!("Encryption:   {}", summary.algorithm);
+    println!("File version: {}", summary.keystore_version);
+    Ok(())
+}
+
+/// Print the unsigned EIP-1559 transaction described by a JSON file
+fn build_eth_tx(path: &std::path::Path) -> anyhow::Result<()> {
+    use tui_node::utils::eth_tx_builder::Eip1559Transaction;
+
+    let tx = Eip1559Transaction::from_json(&std::fs::read_to_string(path)?).map_err(anyhow::Error::msg)?;
+    let sighash = tx.signing_hash().map_err(anyhow::Error::msg)?;
+    println!("Unsigned tx:  0x{}", hex::encode(tx.encode_unsigned()));
+    println!("Sighash:      0x{}", hex::encode(sighash));
+    println!("Sign the sighash with an ECDSA key; FROST wallets produce Schnorr");
+    println!("signatures, which Ethereum transactions cannot carry.");
+    Ok(())
 }
 
 /// Run the Elm Architecture TUI
-async fn run_elm_tui(device_id: String, signal_server: String, offline: bool) -> anyhow::Result<()> {
+async fn run_elm_tui(
+    device_id: String,
+    signal_server: String,
+    offline: bool,
+    frame_limits: tui_node::network::framing::FrameLimits,
+    max_reconnect_attempts: u32,
+) -> anyhow::Result<()> {
     use crossterm::{
         terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
         execute,
@@ -112,7 +187,8 @@ async fn run_elm_tui(device_id: String, signal_server: String, offline: bool) ->
     ));
 
     // Create and initialize Elm app
-    let mut elm_app = ElmApp::new(device_id.clone(), app_state.clone())?;
+    let mut elm_app = ElmApp::new(device_id.clone(), app_state.clone())?
+        .with_max_reconnect_attempts(max_reconnect_attempts);
     
     // Initialize keystore automatically
     let keystore_path = format!("{}/.frost_keystore", std::env::var("HOME").unwrap_or_else(|_| ".".to_string()));
@@ -130,6 +206,10 @@ async fn run_elm_tui(device_id: String, signal_server: String, offline: bool) ->
                 tracing::error!("❌ Failed to initialize keystore: {}", e);
             }
         }
+        state.stun_turn_config = tui_node::network::ice_config::StunTurnConfig::load_or_default(
+            &tui_node::network::ice_config::StunTurnConfig::default_path(),
+        );
+        state.dc_frame_buffers = tui_node::network::framing::PeerFrameBuffers::new(frame_limits);
     }
 
     // Setup terminal with panic handler for cleanup
@@ -158,6 +238,7 @@ async fn run_elm_tui(device_id: String, signal_server: String, offline: bool) ->
         {
             tracing::error!("Failed to queue initial WebSocket connect: {}", e);
         }
+        tui_node::network::webrtc::spawn_connection_health_checks(app_state.clone()).await;
     } else {
         info!("Running in offline mode - no network connections");
     }
@@ -7,6 +7,7 @@
 //! This module ensures we only generate addresses for compatible chains.
 
 use std::collections::HashMap;
+use mpc_wallet_blockchain::bitcoin::{BitcoinAddressType, BitcoinHandler, BitcoinNetwork};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum CurveType {
@@ -31,6 +32,33 @@ impl CurveType {
     }
 }
 
+/// Address settings a wallet is created with, for chains that offer more
+/// than one address format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddressSettings {
+    pub bitcoin_address_type: BitcoinAddressType,
+    pub bitcoin_network: BitcoinNetwork,
+}
+
+impl Default for AddressSettings {
+    fn default() -> Self {
+        Self {
+            bitcoin_address_type: BitcoinAddressType::P2tr,
+            bitcoin_network: BitcoinNetwork::Mainnet,
+        }
+    }
+}
+
+impl AddressSettings {
+    /// Network name stored with the wallet's addresses on `chain`
+    pub fn network_name(&self, chain: &str) -> &'static str {
+        match (chain, self.bitcoin_network) {
+            ("bitcoin", BitcoinNetwork::Testnet) => "testnet",
+            _ => "mainnet",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BlockchainInfo {
     pub name: &'static str,
@@ -124,6 +152,7 @@ pub fn generate_address_for_chain(
     group_public_key: &[u8],
     curve_str: &str,
     chain: &str,
+    settings: &AddressSettings,
 ) -> Result<String, String> {
     let curve = CurveType::from_string(curve_str)
         .ok_or_else(|| format!("Unknown curve type: {}", curve_str))?;
@@ -154,6 +183,10 @@ pub fn generate_address_for_chain(
             Ok(format!("0x{}", hex::encode(&hash[12..32]))) // Last 20 bytes
         }
         
+        ("bitcoin", CurveType::Secp256k1) => {
+            generate_bitcoin_address(group_public_key, settings.bitcoin_address_type, settings.bitcoin_network)
+        }
+        
         // Solana with ed25519
         ("solana", CurveType::Ed25519) => {
             // Solana addresses are base58 encoded public keys
@@ -187,4 +220,36 @@ pub fn generate_address_for_chain(
             Err(format!("Address generation not implemented for {} with {}", chain, curve.to_string()))
         }
     }
-}
\ No newline at end of file
+}
+
+/// Bitcoin script types a FROST group key can spend from.
+///
+/// None today: the SegWit v0 formats need ECDSA signatures, and a P2TR
+/// output commits to the BIP-86 tweaked key, which the untweaked
+/// `frost-secp256k1` ciphersuite can't sign for. Offering any of them would
+/// receive coins the wallet can never move, so no Bitcoin address is
+/// generated until signing applies the taproot tweak.
+pub fn spendable_bitcoin_address_types() -> &'static [BitcoinAddressType] {
+    &[]
+}
+
+/// Bitcoin address in the format the user picked for a secp256k1 group key
+pub fn generate_bitcoin_address(
+    group_public_key: &[u8],
+    address_type: BitcoinAddressType,
+    network: BitcoinNetwork,
+) -> Result<String, String> {
+    if !spendable_bitcoin_address_types().contains(&address_type) {
+        return Err(format!(
+            "{} addresses cannot be spent by a FROST secp256k1 wallet",
+            address_type.name()
+        ));
+    }
+    let handler = match network {
+        BitcoinNetwork::Mainnet => BitcoinHandler::new(),
+        BitcoinNetwork::Testnet => BitcoinHandler::new_testnet(),
+    };
+    handler
+        .derive_address(group_public_key, address_type)
+        .map_err(|e| e.to_string())
+}
@@ -29,17 +29,40 @@ mod tests {
     fn test_address_generation_incompatibility() {
         // Test that ed25519 cannot generate Ethereum address
         let ed25519_key = vec![0u8; 32]; // Dummy ed25519 key
-        let result = generate_address_for_chain(&ed25519_key, "ed25519", "ethereum");
+        let result = generate_address_for_chain(&ed25519_key, "ed25519", "ethereum", &AddressSettings::default());
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("requires secp256k1 curve"));
         
         // Test that secp256k1 cannot generate Solana address
         let secp256k1_key = vec![0x02; 33]; // Dummy secp256k1 key (compressed)
-        let result = generate_address_for_chain(&secp256k1_key, "secp256k1", "solana");
+        let result = generate_address_for_chain(&secp256k1_key, "secp256k1", "solana", &AddressSettings::default());
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("requires ed25519 curve"));
     }
     
+    #[test]
+    fn test_bitcoin_address_formats() {
+        use mpc_wallet_blockchain::bitcoin::{BitcoinAddressType, BitcoinNetwork};
+
+        let key = hex::decode("02cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115").unwrap();
+        let testnet = AddressSettings { bitcoin_network: BitcoinNetwork::Testnet, ..AddressSettings::default() };
+        assert_eq!(testnet.network_name("bitcoin"), "testnet");
+        assert_eq!(testnet.network_name("ethereum"), "mainnet");
+
+        // SegWit v0 needs ECDSA and P2TR the taproot tweak, neither of which
+        // the FROST signer produces, so no Bitcoin address is offered
+        assert!(spendable_bitcoin_address_types().is_empty());
+        for address_type in [BitcoinAddressType::P2wpkh, BitcoinAddressType::P2shP2wpkh, BitcoinAddressType::P2tr] {
+            for network in [BitcoinNetwork::Mainnet, BitcoinNetwork::Testnet] {
+                assert!(generate_bitcoin_address(&key, address_type, network).is_err());
+                let settings = AddressSettings { bitcoin_address_type: address_type, bitcoin_network: network };
+                assert!(generate_address_for_chain(&key, "secp256k1", "bitcoin", &settings).is_err());
+            }
+        }
+        // Other secp256k1 chains are unaffected
+        assert!(generate_address_for_chain(&key, "secp256k1", "ethereum", &AddressSettings::default()).is_ok());
+    }
+    
     #[test]
     fn test_curve_type_parsing() {
         assert_eq!(CurveType::from_string("secp256k1"), Some(CurveType::Secp256k1));
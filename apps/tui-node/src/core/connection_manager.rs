@@ -242,4 +242,65 @@ impl ConnectionManager {
             .filter(|c| c.status == ConnectionStatus::Connected)
             .count()
     }
+    
+    /// Pick the `threshold` best connected peers for a signing package.
+    /// Call again after a selected peer drops to get the next best.
+    pub async fn select_signers(&self, threshold: usize) -> CoreResult<Vec<String>> {
+        select_signers(&self.state.mesh_connections.lock().await, threshold)
+    }
+}
+
+/// Rank connected peers by quality, then latency, and take the top
+/// `threshold`. Disconnected, failed and still-connecting peers are skipped.
+pub fn select_signers(connections: &[ConnectionInfo], threshold: usize) -> CoreResult<Vec<String>> {
+    let mut candidates: Vec<&ConnectionInfo> = connections.iter()
+        .filter(|c| c.status == ConnectionStatus::Connected)
+        .collect();
+    if candidates.len() < threshold {
+        return Err(CoreError::Network(format!(
+            "Only {} connected peers, need {} signers",
+            candidates.len(),
+            threshold
+        )));
+    }
+    
+    candidates.sort_by(|a, b| {
+        b.quality.total_cmp(&a.quality)
+            .then(a.latency_ms.cmp(&b.latency_ms))
+            .then_with(|| a.peer_id.cmp(&b.peer_id))
+    });
+    Ok(candidates.into_iter()
+        .take(threshold)
+        .map(|c| c.peer_id.clone())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn peer(peer_id: &str, status: ConnectionStatus, latency_ms: u32, quality: f32) -> ConnectionInfo {
+        ConnectionInfo { peer_id: peer_id.to_string(), status, latency_ms, quality }
+    }
+    
+    #[test]
+    fn test_select_signers_prefers_best_connections() {
+        let mut mesh = vec![
+            peer("alice", ConnectionStatus::Connected, 40, 0.9),
+            peer("bob", ConnectionStatus::Connected, 300, 0.5),
+            peer("carol", ConnectionStatus::Connected, 20, 1.0),
+            peer("dave", ConnectionStatus::Connected, 80, 0.9),
+            peer("erin", ConnectionStatus::Connecting, 0, 0.0),
+        ];
+        
+        // Alice and Dave tie on quality; lower latency wins
+        assert_eq!(select_signers(&mesh, 3).unwrap(), vec!["carol", "alice", "dave"]);
+        
+        // Carol drops: the next best connected peer fills in
+        mesh[2].status = ConnectionStatus::Disconnected;
+        assert_eq!(select_signers(&mesh, 3).unwrap(), vec!["alice", "dave", "bob"]);
+        
+        mesh[0].status = ConnectionStatus::Failed;
+        assert!(matches!(select_signers(&mesh, 3), Err(CoreError::Network(_))));
+    }
 }
\ No newline at end of file
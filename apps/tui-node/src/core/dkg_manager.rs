@@ -2,17 +2,86 @@
 
 use super::{CoreResult, CoreState, ParticipantInfo, ParticipantStatus, UICallback};
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use tracing::{error, info};
 
+/// DKG lifecycle milestones. Every UI learns of them through the same
+/// `update_dkg_status`/`update_dkg_participants` calls, so they all render
+/// the same progress.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DkgEvent {
+    /// `participant` joined the session
+    ParticipantJoined { participant: String },
+    /// Round 1 packages from every participant were received
+    Round1Complete,
+    /// Round 2 packages from every participant were received
+    Round2Complete,
+    /// Key generation finished and the key packages are in place
+    Finalized,
+}
+
+impl DkgEvent {
+    /// The `(active, round, progress)` reported to `update_dkg_status`, or
+    /// `None` if the event only changes the participant list
+    pub fn status(&self) -> Option<(bool, u8, f32)> {
+        match self {
+            DkgEvent::ParticipantJoined { .. } => None,
+            DkgEvent::Round1Complete => Some((true, 1, 0.33)),
+            DkgEvent::Round2Complete => Some((true, 2, 0.66)),
+            DkgEvent::Finalized => Some((false, 3, 1.0)),
+        }
+    }
+}
+
 /// DKG manager that handles the distributed key generation process
 pub struct DkgManager {
     state: Arc<CoreState>,
     ui_callback: Arc<dyn UICallback>,
+    events: broadcast::Sender<DkgEvent>,
 }
 
 impl DkgManager {
     pub fn new(state: Arc<CoreState>, ui_callback: Arc<dyn UICallback>) -> Self {
-        Self { state, ui_callback }
+        let (events, _) = broadcast::channel(32);
+        Self { state, ui_callback, events }
+    }
+    
+    /// Subscribe to DKG lifecycle events
+    pub fn subscribe_events(&self) -> broadcast::Receiver<DkgEvent> {
+        self.events.subscribe()
+    }
+    
+    /// Record `event` in the shared state and pass it on to the UI and subscribers
+    pub async fn handle_event(&self, event: DkgEvent) {
+        let status = event.status();
+        let mut participants = self.state.dkg_participants.lock().await;
+        if let DkgEvent::ParticipantJoined { participant } = &event {
+            if !participants.iter().any(|p| &p.name == participant) {
+                let id = format!("P{}", participants.len() + 1);
+                participants.push(ParticipantInfo {
+                    id,
+                    name: participant.clone(),
+                    status: ParticipantStatus::Ready,
+                    round_completed: 0,
+                });
+            }
+        } else if let Some((_, round, _)) = status {
+            for p in participants.iter_mut() {
+                p.status = ParticipantStatus::Completed;
+                p.round_completed = round;
+            }
+        }
+        let participants_clone = participants.clone();
+        drop(participants);
+        
+        self.ui_callback.update_dkg_participants(participants_clone).await;
+        if let Some((active, round, progress)) = status {
+            *self.state.dkg_active.lock().await = active;
+            *self.state.dkg_round.lock().await = round;
+            *self.state.dkg_progress.lock().await = progress;
+            self.ui_callback.update_dkg_status(active, round, progress).await;
+        }
+        let _ = self.events.send(event);
     }
     
     /// Start the DKG process
@@ -56,9 +125,6 @@ impl DkgManager {
         // Round 3: Finalize
         self.execute_round3().await?;
         
-        // Complete
-        *self.state.dkg_active.lock().await = false;
-        self.ui_callback.update_dkg_status(false, 3, 1.0).await;
         self.ui_callback.show_message("DKG completed successfully!".to_string(), false).await;
         
         Ok(())
@@ -86,16 +152,7 @@ impl DkgManager {
         // Simulate round 1 processing
         tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
         
-        // Update participants after round 1
-        let mut participants = self.state.dkg_participants.lock().await;
-        for p in participants.iter_mut() {
-            p.status = ParticipantStatus::Completed;
-            p.round_completed = 1;
-        }
-        let participants_clone = participants.clone();
-        drop(participants);
-        
-        self.ui_callback.update_dkg_participants(participants_clone).await;
+        self.handle_event(DkgEvent::Round1Complete).await;
         
         Ok(())
     }
@@ -121,16 +178,7 @@ impl DkgManager {
         // Simulate round 2 processing
         tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
         
-        // Update participants after round 2
-        let mut participants = self.state.dkg_participants.lock().await;
-        for p in participants.iter_mut() {
-            p.status = ParticipantStatus::Completed;
-            p.round_completed = 2;
-        }
-        let participants_clone = participants.clone();
-        drop(participants);
-        
-        self.ui_callback.update_dkg_participants(participants_clone).await;
+        self.handle_event(DkgEvent::Round2Complete).await;
         
         Ok(())
     }
@@ -156,19 +204,7 @@ impl DkgManager {
         // Simulate round 3 processing
         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
         
-        // Update participants after completion
-        let mut participants = self.state.dkg_participants.lock().await;
-        for p in participants.iter_mut() {
-            p.status = ParticipantStatus::Completed;
-            p.round_completed = 3;
-        }
-        let participants_clone = participants.clone();
-        drop(participants);
-        
-        *self.state.dkg_progress.lock().await = 1.0;
-        
-        self.ui_callback.update_dkg_participants(participants_clone).await;
-        self.ui_callback.update_dkg_status(true, 3, 1.0).await;
+        self.handle_event(DkgEvent::Finalized).await;
         
         Ok(())
     }
@@ -248,4 +284,74 @@ impl DkgManager {
         
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ConnectionInfo, OperationMode, SDCardOperation, SessionInfo, WalletInfo};
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// Records the DKG updates a UI receives
+    #[derive(Default)]
+    struct RecordingUi {
+        statuses: Mutex<Vec<(bool, u8, f32)>>,
+        participants: Mutex<Vec<Vec<ParticipantInfo>>>,
+    }
+
+    #[async_trait]
+    impl UICallback for RecordingUi {
+        async fn update_connection_status(&self, _: bool, _: bool) {}
+        async fn update_mesh_connections(&self, _: Vec<ConnectionInfo>) {}
+        async fn update_operation_mode(&self, _: OperationMode) {}
+        async fn update_wallets(&self, _: Vec<WalletInfo>) {}
+        async fn update_active_wallet(&self, _: usize) {}
+        async fn update_available_sessions(&self, _: Vec<SessionInfo>) {}
+        async fn update_active_session(&self, _: Option<SessionInfo>) {}
+        async fn update_dkg_status(&self, active: bool, round: u8, progress: f32) {
+            self.statuses.lock().unwrap().push((active, round, progress));
+        }
+        async fn update_dkg_participants(&self, participants: Vec<ParticipantInfo>) {
+            self.participants.lock().unwrap().push(participants);
+        }
+        async fn update_offline_status(&self, _: bool, _: bool) {}
+        async fn update_sd_operations(&self, _: Vec<SDCardOperation>) {}
+        async fn show_message(&self, _: String, _: bool) {}
+        async fn show_progress(&self, _: String, _: f32) {}
+        async fn request_confirmation(&self, _: String) -> bool { true }
+    }
+
+    #[tokio::test]
+    async fn test_events_drive_status_and_participants() {
+        let state = Arc::new(CoreState::new());
+        let ui = Arc::new(RecordingUi::default());
+        let manager = DkgManager::new(state.clone(), ui.clone());
+        let mut events = manager.subscribe_events();
+
+        for device in ["alice", "bob", "alice"] {
+            manager.handle_event(DkgEvent::ParticipantJoined { participant: device.to_string() }).await;
+        }
+        assert!(ui.statuses.lock().unwrap().is_empty());
+        let joined = ui.participants.lock().unwrap().last().unwrap().clone();
+        assert_eq!(joined.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), ["alice", "bob"]);
+        assert!(joined.iter().all(|p| p.status == ParticipantStatus::Ready));
+
+        let stages = [
+            (DkgEvent::Round1Complete, (true, 1, 0.33)),
+            (DkgEvent::Round2Complete, (true, 2, 0.66)),
+            (DkgEvent::Finalized, (false, 3, 1.0)),
+        ];
+        for (event, status) in stages.clone() {
+            manager.handle_event(event).await;
+            assert_eq!(*ui.statuses.lock().unwrap().last().unwrap(), status);
+            let participants = ui.participants.lock().unwrap().last().unwrap().clone();
+            assert!(participants.iter().all(|p| p.round_completed == status.1 && p.status == ParticipantStatus::Completed));
+            assert_eq!(*state.dkg_round.lock().await, status.1);
+        }
+        assert!(!*state.dkg_active.lock().await);
+
+        let fired: Vec<DkgEvent> = std::iter::from_fn(|| events.try_recv().ok()).collect();
+        assert_eq!(fired.len(), 6);
+        assert_eq!(fired[3..], stages.map(|(event, _)| event));
+    }
+}
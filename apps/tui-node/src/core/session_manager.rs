@@ -1,20 +1,92 @@
 //! Session management logic shared between TUI and native nodes
 
-use super::{CoreError, CoreResult, CoreState, SessionInfo, SessionStatus, UICallback};
+use super::{CoreError, CoreResult, CoreState, ParticipantStatus, SessionInfo, SessionStatus, UICallback};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tracing::info;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Mutex};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// How long a participant may stay silent before it is reported as dropped
+pub const DEFAULT_LIVENESS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Session events for the coordinator
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionEvent {
+    /// No message from `participant` for longer than the liveness timeout
+    ParticipantDropped {
+        session_id: String,
+        participant: String,
+        silent_for: Duration,
+    },
+    /// A participant previously reported as dropped sent a message again
+    ParticipantResumed {
+        session_id: String,
+        participant: String,
+    },
+    /// A dropout or resume changed who collects signing commitments and
+    /// aggregates; see `elect_coordinator`
+    CoordinatorChanged {
+        session_id: String,
+        coordinator: Option<String>,
+    },
+}
+
+/// The signing coordinator of a leaderless group: the online participant
+/// with the lowest participant index. Every node computes the same answer
+/// from the same roster and dropouts, so a successor takes over without
+/// any extra messages. `None` if everyone is offline or ids repeat.
+pub fn elect_coordinator(participants: &[String], offline: &HashSet<String>) -> Option<String> {
+    let indices = mpc_wallet_frost_core::participant_index_map(participants).ok()?;
+    indices
+        .into_iter()
+        .filter(|(device, _)| !offline.contains(device))
+        .min_by_key(|(_, index)| *index)
+        .map(|(device, _)| device)
+}
+
+/// Per-participant last-seen times for the active session
+#[derive(Default)]
+struct Liveness {
+    local_device: Option<String>,
+    last_seen: HashMap<String, Instant>,
+    dropped: HashSet<String>,
+}
 
 /// Session manager that handles session lifecycle
 pub struct SessionManager {
     state: Arc<CoreState>,
     ui_callback: Arc<dyn UICallback>,
+    liveness: Mutex<Liveness>,
+    liveness_timeout: Duration,
+    events: broadcast::Sender<SessionEvent>,
 }
 
 impl SessionManager {
     pub fn new(state: Arc<CoreState>, ui_callback: Arc<dyn UICallback>) -> Self {
-        Self { state, ui_callback }
+        let (events, _) = broadcast::channel(32);
+        Self {
+            state,
+            ui_callback,
+            liveness: Mutex::new(Liveness::default()),
+            liveness_timeout: DEFAULT_LIVENESS_TIMEOUT,
+            events,
+        }
+    }
+    
+    /// Override the liveness timeout (default `DEFAULT_LIVENESS_TIMEOUT`)
+    pub fn with_liveness_timeout(mut self, timeout: Duration) -> Self {
+        self.liveness_timeout = timeout;
+        self
+    }
+    
+    /// Subscribe to dropout/resume and coordinator events
+    pub fn subscribe_events(&self) -> broadcast::Receiver<SessionEvent> {
+        self.events.subscribe()
     }
     
+
     /// Create a new session
     pub async fn create_session(
         &self,
@@ -31,7 +103,7 @@ impl SessionManager {
         let session = SessionInfo {
             session_id: session_id.clone(),
             initiator: device_id.clone(),
-            participants: vec![device_id],
+            participants: vec![device_id.clone()],
             threshold: (threshold, total),
             status: SessionStatus::Waiting,
             created_at: chrono::Utc::now().to_rfc3339(),
@@ -39,6 +111,7 @@ impl SessionManager {
         
         // Update state
         *self.state.active_session.lock().await = Some(session.clone());
+        self.reset_liveness(&device_id).await;
         
         // Add to available sessions
         self.state.available_sessions.lock().await.push(session.clone());
@@ -90,6 +163,7 @@ impl SessionManager {
         
         // Set as active session
         *self.state.active_session.lock().await = Some(session_clone.clone());
+        self.reset_liveness(&device_id).await;
         
         // Update UI
         self.ui_callback.update_active_session(Some(session_clone)).await;
@@ -130,6 +204,7 @@ impl SessionManager {
             
             // Clear active session
             *self.state.active_session.lock().await = None;
+            *self.liveness.lock().await = Liveness::default();
             
             // Update UI
             self.ui_callback.update_active_session(None).await;
@@ -165,6 +240,14 @@ impl SessionManager {
     }
     
     
+    /// Make a session negotiated elsewhere (e.g. through the signal server)
+    /// the active one and start tracking its participants' liveness
+    pub async fn track_session(&self, session: SessionInfo, local_device: &str) {
+        *self.state.active_session.lock().await = Some(session.clone());
+        self.reset_liveness(local_device).await;
+        self.ui_callback.update_active_session(Some(session)).await;
+    }
+    
     /// Get current active session
     pub async fn get_active_session(&self) -> Option<SessionInfo> {
         self.state.active_session.lock().await.clone()
@@ -174,4 +257,250 @@ impl SessionManager {
     pub async fn get_available_sessions(&self) -> Vec<SessionInfo> {
         self.state.available_sessions.lock().await.clone()
     }
-}
\ No newline at end of file
+    
+    async fn reset_liveness(&self, local_device: &str) {
+        *self.liveness.lock().await = Liveness {
+            local_device: Some(local_device.to_string()),
+            ..Liveness::default()
+        };
+    }
+    
+    /// Record that a message arrived from `participant`
+    pub async fn record_activity(&self, participant: &str) {
+        let session = self.get_active_session().await;
+        let mut liveness = self.liveness.lock().await;
+        liveness.last_seen.insert(participant.to_string(), Instant::now());
+        let before = session.as_ref().and_then(|s| elect_coordinator(&s.participants, &liveness.dropped));
+        if !liveness.dropped.remove(participant) {
+            return;
+        }
+        let after = session.as_ref().and_then(|s| elect_coordinator(&s.participants, &liveness.dropped));
+        drop(liveness);
+        
+        if let Some(session) = session {
+            info!("Participant {} is responsive again", participant);
+            self.set_participant_status(participant, ParticipantStatus::Ready).await;
+            let _ = self.events.send(SessionEvent::ParticipantResumed {
+                session_id: session.session_id.clone(),
+                participant: participant.to_string(),
+            });
+            if after != before {
+                let _ = self.events.send(SessionEvent::CoordinatorChanged {
+                    session_id: session.session_id,
+                    coordinator: after,
+                });
+            }
+        }
+    }
+    
+    /// When a message from `participant` last arrived, if ever
+    pub async fn last_seen(&self, participant: &str) -> Option<Instant> {
+        self.liveness.lock().await.last_seen.get(participant).copied()
+    }
+    
+    /// The device that should collect commitments and aggregate signatures
+    /// in the active session, given who has dropped out
+    pub async fn signing_coordinator(&self) -> Option<String> {
+        let session = self.get_active_session().await?;
+        elect_coordinator(&session.participants, &self.liveness.lock().await.dropped)
+    }
+    
+    /// Whether this node is the active session's signing coordinator
+    pub async fn is_signing_coordinator(&self) -> bool {
+        let local_device = self.liveness.lock().await.local_device.clone();
+        local_device.is_some() && self.signing_coordinator().await == local_device
+    }
+    
+    /// Report every remote participant of the active session that has been
+    /// silent for longer than the liveness timeout. Each dropout is reported
+    /// once until the participant is heard from again.
+    pub async fn check_liveness(&self) -> Vec<SessionEvent> {
+        self.check_liveness_at(Instant::now()).await
+    }
+    
+    async fn check_liveness_at(&self, now: Instant) -> Vec<SessionEvent> {
+        let Some(session) = self.get_active_session().await else {
+            return Vec::new();
+        };
+        
+        let mut dropped = Vec::new();
+        let (before, after) = {
+            let mut liveness = self.liveness.lock().await;
+            let before = elect_coordinator(&session.participants, &liveness.dropped);
+            for participant in &session.participants {
+                if liveness.local_device.as_ref() == Some(participant) {
+                    continue;
+                }
+                // Participants we have not heard from yet start the clock now
+                let last_seen = *liveness.last_seen.entry(participant.clone()).or_insert(now);
+                let silent_for = now.saturating_duration_since(last_seen);
+                if silent_for > self.liveness_timeout && liveness.dropped.insert(participant.clone()) {
+                    dropped.push((participant.clone(), silent_for));
+                }
+            }
+            (before, elect_coordinator(&session.participants, &liveness.dropped))
+        };
+        
+        let mut events = Vec::new();
+        for (participant, silent_for) in dropped {
+            warn!("Participant {} silent for {:?} in session {}", participant, silent_for, session.session_id);
+            self.set_participant_status(&participant, ParticipantStatus::Offline).await;
+            let event = SessionEvent::ParticipantDropped {
+                session_id: session.session_id.clone(),
+                participant,
+                silent_for,
+            };
+            let _ = self.events.send(event.clone());
+            events.push(event);
+        }
+        if after != before {
+            info!("Signing coordinator for session {} is now {:?}", session.session_id, after);
+            let event = SessionEvent::CoordinatorChanged {
+                session_id: session.session_id.clone(),
+                coordinator: after,
+            };
+            let _ = self.events.send(event.clone());
+            events.push(event);
+        }
+        events
+    }
+    
+    /// Run `check_liveness` every `interval` until the task is aborted
+    pub fn spawn_liveness_monitor(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let manager = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                manager.check_liveness().await;
+            }
+        })
+    }
+    
+    async fn set_participant_status(&self, participant: &str, status: ParticipantStatus) {
+        let mut participants = self.state.dkg_participants.lock().await;
+        let Some(info) = participants.iter_mut().find(|p| p.id == participant) else {
+            return;
+        };
+        info.status = status;
+        let participants_clone = participants.clone();
+        drop(participants);
+        
+        self.ui_callback.update_dkg_participants(participants_clone).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ConnectionInfo, OperationMode, ParticipantInfo, SDCardOperation, WalletInfo};
+    use async_trait::async_trait;
+
+    struct NoopUi;
+
+    #[async_trait]
+    impl UICallback for NoopUi {
+        async fn update_connection_status(&self, _: bool, _: bool) {}
+        async fn update_mesh_connections(&self, _: Vec<ConnectionInfo>) {}
+        async fn update_operation_mode(&self, _: OperationMode) {}
+        async fn update_wallets(&self, _: Vec<WalletInfo>) {}
+        async fn update_active_wallet(&self, _: usize) {}
+        async fn update_available_sessions(&self, _: Vec<SessionInfo>) {}
+        async fn update_active_session(&self, _: Option<SessionInfo>) {}
+        async fn update_dkg_status(&self, _: bool, _: u8, _: f32) {}
+        async fn update_dkg_participants(&self, _: Vec<ParticipantInfo>) {}
+        async fn update_offline_status(&self, _: bool, _: bool) {}
+        async fn update_sd_operations(&self, _: Vec<SDCardOperation>) {}
+        async fn show_message(&self, _: String, _: bool) {}
+        async fn show_progress(&self, _: String, _: f32) {}
+        async fn request_confirmation(&self, _: String) -> bool { true }
+    }
+
+    #[tokio::test]
+    async fn test_dropout_fires_after_liveness_timeout() {
+        let state = Arc::new(CoreState::new());
+        let timeout = Duration::from_secs(10);
+        let manager = SessionManager::new(state.clone(), Arc::new(NoopUi)).with_liveness_timeout(timeout);
+        let mut events = manager.subscribe_events();
+
+        let session_id = manager.create_session("alice".to_string(), 2, 3).await.unwrap();
+        for device in ["bob", "carol"] {
+            state.available_sessions.lock().await[0].participants.push(device.to_string());
+        }
+        *state.active_session.lock().await = Some(state.available_sessions.lock().await[0].clone());
+        *state.dkg_participants.lock().await = ["bob", "carol"]
+            .iter()
+            .map(|id| ParticipantInfo {
+                id: id.to_string(),
+                name: id.to_string(),
+                status: ParticipantStatus::Processing,
+                round_completed: 0,
+            })
+            .collect();
+
+        let start = Instant::now();
+        manager.record_activity("bob").await;
+        manager.record_activity("carol").await;
+        assert!(manager.check_liveness_at(start + timeout / 2).await.is_empty());
+
+        // Bob keeps talking, Carol goes quiet
+        manager.liveness.lock().await.last_seen.insert("bob".to_string(), start + timeout);
+        let later = start + timeout + Duration::from_secs(1);
+        let fired = manager.check_liveness_at(later).await;
+        // Alice stays coordinator, so only the dropout is reported
+        assert_eq!(fired.len(), 1);
+        match &fired[0] {
+            SessionEvent::ParticipantDropped { session_id: id, participant, silent_for } => {
+                assert_eq!(id, &session_id);
+                assert_eq!(participant, "carol");
+                assert!(*silent_for > timeout);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert_eq!(events.try_recv().unwrap(), fired[0]);
+        assert_eq!(state.dkg_participants.lock().await[1].status, ParticipantStatus::Offline);
+
+        // Reported once, then again only after the participant comes back
+        assert!(manager.check_liveness_at(later + Duration::from_secs(1)).await.is_empty());
+        manager.record_activity("carol").await;
+        assert!(matches!(events.try_recv().unwrap(), SessionEvent::ParticipantResumed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_dropout_hands_coordination_to_successor() {
+        // Bob's node in a 2-of-3 group
+        let state = Arc::new(CoreState::new());
+        let timeout = Duration::from_secs(10);
+        let manager = SessionManager::new(state.clone(), Arc::new(NoopUi)).with_liveness_timeout(timeout);
+        manager.create_session("bob".to_string(), 2, 3).await.unwrap();
+        state.active_session.lock().await.as_mut().unwrap().participants =
+            vec!["carol".to_string(), "bob".to_string(), "alice".to_string()];
+        let mut events = manager.subscribe_events();
+
+        let start = Instant::now();
+        manager.record_activity("alice").await;
+        manager.record_activity("carol").await;
+        assert_eq!(manager.signing_coordinator().await.as_deref(), Some("alice"));
+        assert!(!manager.is_signing_coordinator().await);
+
+        // Alice drops mid-signing; Bob has the next index and takes over
+        manager.liveness.lock().await.last_seen.insert("carol".to_string(), start + timeout);
+        let fired = manager.check_liveness_at(start + timeout + Duration::from_secs(1)).await;
+        assert!(matches!(&fired[0], SessionEvent::ParticipantDropped { participant, .. } if participant == "alice"));
+        assert!(matches!(
+            &fired[1],
+            SessionEvent::CoordinatorChanged { coordinator: Some(c), .. } if c == "bob"
+        ));
+        assert!(matches!(events.try_recv().unwrap(), SessionEvent::ParticipantDropped { .. }));
+        assert!(matches!(events.try_recv().unwrap(), SessionEvent::CoordinatorChanged { .. }));
+        assert!(manager.is_signing_coordinator().await);
+
+        // Alice coming back reclaims the role for the next signing
+        manager.record_activity("alice").await;
+        assert!(matches!(events.try_recv().unwrap(), SessionEvent::ParticipantResumed { .. }));
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            SessionEvent::CoordinatorChanged { coordinator: Some(c), .. } if c == "alice"
+        ));
+    }
+}
@@ -94,6 +94,12 @@ where
         Ok(elm_app)
     }
     
+    /// Give up reconnecting to the signal server after `max_attempts` tries
+    pub fn with_max_reconnect_attempts(mut self, max_attempts: u32) -> Self {
+        self.model.network_state.max_reconnect_attempts = max_attempts;
+        self
+    }
+
     /// Mount components based on current screen
     fn mount_components(&mut self) -> anyhow::Result<()> {
         debug!("🔧 Mounting components for screen: {:?}", self.model.current_screen);
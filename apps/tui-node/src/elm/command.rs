@@ -6,10 +6,17 @@
 
 use crate::elm::message::{Message, SigningRequest};
 use crate::elm::model::WalletConfig;
+use crate::core::dkg_manager::DkgEvent;
 use tokio::sync::mpsc::UnboundedSender;
 use std::path::PathBuf;
 use tracing::{info, error, warn};
 
+/// How long the preflight waits for the signal server's device list
+const PREFLIGHT_DEVICE_LIST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+/// How long the preflight waits for data channels that are already being
+/// negotiated to open
+const PREFLIGHT_CHANNEL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
 /// Commands represent side effects to be executed
 #[derive(Debug, Clone)]
 pub enum Command {
@@ -60,10 +67,22 @@ pub enum Command {
     /// `part3` once all Round 2 packages for us have arrived.
     ProcessDKGRound2 { from_device: String, package_bytes: Vec<u8> },
     JoinDKG { session_id: String },
+    /// Reconnect mid-DKG without starting over: redial and re-register with
+    /// the signal server (re-announcing the session), rejoin it if we are not
+    /// the proposer, rebuild the WebRTC mesh with the known participants and
+    /// replay every round 1/round 2 package a peer has not acked yet.
+    ResumeDKG { session_id: String },
     CancelDKG,
+    /// Dry run before a DKG: register if needed, check every participant is
+    /// in the signal server's device list and try to open a data channel to
+    /// each, then report who is reachable. Generates no key material.
+    PreflightDKG { participants: Vec<String> },
     
     // Signing operations
     StartSigning { request: SigningRequest },
+    /// Fetch `tx_hash` from the RPC endpoint configured for `chain` and
+    /// parse it with the chain's handler, for signing by hash with `wallet_id`
+    FetchTransaction { wallet_id: String, chain: String, tx_hash: String },
     ApproveSignature { request_id: String },
     RejectSignature { request_id: String },
     
@@ -76,7 +95,12 @@ pub enum Command {
     RefreshUI,
     
     // Settings operations
-    SaveSettings { websocket_url: String, device_id: String },
+    SaveSettings {
+        websocket_url: String,
+        device_id: String,
+        stun_turn: crate::network::ice_config::StunTurnConfig,
+        address_settings: crate::blockchain_config::AddressSettings,
+    },
     LoadSettings,
     
     // System operations
@@ -134,9 +158,131 @@ pub(crate) fn parse_session_info(
         session_type: SessionType::DKG,
         curve_type,
         coordination_type,
+        participant_indices: Default::default(),
     })
 }
 
+/// Hands the key to the UI once a DKG round's package has completed the
+/// DKG. `finish_dkg` runs `part3` inside whichever of `process_dkg_round1`
+/// or `process_dkg_round2` saw the last package and populates
+/// `public_key_package`; `had_key` is whether it was set before that call.
+async fn report_dkg_completion<C: frost_core::Ciphersuite>(
+    app_state: &std::sync::Arc<tokio::sync::Mutex<crate::utils::appstate_compat::AppState<C>>>,
+    had_key: bool,
+    tx: &UnboundedSender<Message>,
+) {
+    if had_key {
+        return;
+    }
+    let group_key_hex = {
+        let state = app_state.lock().await;
+        state
+            .public_key_package
+            .as_ref()
+            .and_then(|pkg| pkg.verifying_key().serialize().ok())
+            .map(hex::encode)
+    };
+    if let Some(hex) = group_key_hex {
+        let _ = tx.send(Message::DKGKeyGenerated {
+            group_pubkey_hex: hex,
+        });
+    }
+}
+
+/// Routes the protocol's DKG milestones through a `DkgManager`, which
+/// reports them to the Elm loop via `ElmUICallback` the same way it drives
+/// the native UI. Returns the sink, which is also installed on `AppState`
+/// for the protocol layer.
+async fn start_dkg_events<C: frost_core::Ciphersuite>(
+    app_state: &std::sync::Arc<tokio::sync::Mutex<crate::utils::appstate_compat::AppState<C>>>,
+    tx: &UnboundedSender<Message>,
+) -> UnboundedSender<DkgEvent> {
+    let manager = crate::core::dkg_manager::DkgManager::new(
+        std::sync::Arc::new(crate::core::CoreState::new()),
+        std::sync::Arc::new(crate::elm::ui_callback::ElmUICallback::new(tx.clone())),
+    );
+    let (events_tx, mut events_rx) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Some(event) = events_rx.recv().await {
+            manager.handle_event(event).await;
+        }
+    });
+    app_state.lock().await.dkg_events = Some(events_tx.clone());
+    events_tx
+}
+
+/// How often the running session is checked for silent participants
+const LIVENESS_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Start tracking the liveness of the session's participants. Inbound
+/// data-channel and relayed messages feed `record_activity`; dropouts and
+/// resumes reach the update loop as notices.
+async fn start_session_liveness<C: frost_core::Ciphersuite>(
+    app_state: &std::sync::Arc<tokio::sync::Mutex<crate::utils::appstate_compat::AppState<C>>>,
+    tx: &UnboundedSender<Message>,
+) {
+    use crate::core::session_manager::{SessionEvent, SessionManager};
+
+    let (session, device_id, previous) = {
+        let mut state = app_state.lock().await;
+        (state.session.clone(), state.device_id.clone(), state.liveness_monitor.take())
+    };
+    if let Some(previous) = previous {
+        previous.abort();
+    }
+    let Some(session) = session else {
+        return;
+    };
+
+    let manager = std::sync::Arc::new(SessionManager::new(
+        std::sync::Arc::new(crate::core::CoreState::new()),
+        std::sync::Arc::new(crate::elm::ui_callback::ElmUICallback::new(tx.clone())),
+    ));
+    manager
+        .track_session(
+            crate::core::SessionInfo {
+                session_id: session.session_id,
+                initiator: session.proposer_id,
+                participants: session.participants,
+                threshold: (session.threshold, session.total),
+                status: crate::core::SessionStatus::InProgress,
+                created_at: chrono::Utc::now().to_rfc3339(),
+            },
+            &device_id,
+        )
+        .await;
+
+    // Ends once the manager, and with it the sender, is dropped
+    let mut events = manager.subscribe_events();
+    let events_tx = tx.clone();
+    tokio::spawn(async move {
+        loop {
+            let message = match events.recv().await {
+                Ok(SessionEvent::ParticipantDropped { participant, silent_for, .. }) => Message::Error {
+                    message: format!("⚠️ No message from {} for {}s", participant, silent_for.as_secs()),
+                },
+                Ok(SessionEvent::ParticipantResumed { participant, .. }) => Message::Info {
+                    message: format!("✅ {} is responsive again", participant),
+                },
+                Ok(SessionEvent::CoordinatorChanged { coordinator, .. }) => Message::Info {
+                    message: format!(
+                        "Signing coordinator is now {}",
+                        coordinator.as_deref().unwrap_or("nobody")
+                    ),
+                },
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            };
+            let _ = events_tx.send(message);
+        }
+    });
+
+    let monitor = manager.spawn_liveness_monitor(LIVENESS_CHECK_INTERVAL);
+    let mut state = app_state.lock().await;
+    state.session_manager = Some(manager);
+    state.liveness_monitor = Some(monitor);
+}
+
 impl Command {
     /// Execute the command and send resulting messages back to the update loop
     pub async fn execute<C: frost_core::Ciphersuite + Send + Sync + 'static>(
@@ -268,6 +414,7 @@ impl Command {
                     }
                     state.dkg_in_progress = true;
                 }
+                let dkg_events = start_dkg_events(app_state, &tx).await;
 
                 if config.mode == crate::elm::model::WalletMode::Online {
                     // For online mode, use the real DKG session manager
@@ -396,6 +543,7 @@ impl Command {
                             session_type: crate::protocal::signal::SessionType::DKG,
                             curve_type: "unified".to_string(),
                             coordination_type: "Network".to_string(),
+                            participant_indices: Default::default(),
                         });
                     }
 
@@ -471,8 +619,17 @@ impl Command {
                                                         let _ = tx_msg.send(Message::Info {
                                                             message: format!("📡 Connected devices: {:?}", devices),
                                                         });
-                                                        for device in devices.iter() {
-                                                            participants_seen.insert(device.clone());
+                                                        // Only devices that joined this session count;
+                                                        // a lingering stale device must not fill a slot
+                                                        let roster = app_state_clone.lock().await.session.as_ref()
+                                                            .map(|session| session.participants.clone())
+                                                            .unwrap_or_default();
+                                                        for device in devices.iter().filter(|d| roster.contains(d)) {
+                                                            if participants_seen.insert(device.clone()) {
+                                                                let _ = dkg_events.send(DkgEvent::ParticipantJoined {
+                                                                    participant: device.clone(),
+                                                                });
+                                                            }
                                                         }
                                                         let participants_list: Vec<String> =
                                                             participants_seen.iter().cloned().collect();
@@ -596,6 +753,7 @@ impl Command {
                     let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
                     tx
                 });
+                start_session_liveness(app_state, &tx).await;
 
                 info!(
                     "🌐 Triggering unified FROST DKG Round 1 for device_id={}",
@@ -612,6 +770,19 @@ impl Command {
                 let _ = tx.send(Message::Info {
                     message: "✅ DKG Round 1 initiated - exchanging commitments...".to_string(),
                 });
+
+                let timeout_tx = tx.clone();
+                tokio::spawn(crate::protocal::dkg::watch_dkg_round_deadlines(
+                    app_state.clone(),
+                    crate::protocal::dkg::DkgRoundDeadlines::default(),
+                    move |event| {
+                        let (round, missing) = match event {
+                            crate::protocal::dkg::DkgTimeoutEvent::DkgRound1TimedOut { missing, .. } => (1, missing),
+                            crate::protocal::dkg::DkgTimeoutEvent::DkgRound2TimedOut { missing, .. } => (2, missing),
+                        };
+                        let _ = timeout_tx.send(Message::DKGRoundTimedOut { round, missing });
+                    },
+                ));
             }
 
             Command::ProcessDKGRound1 {
@@ -623,15 +794,17 @@ impl Command {
                     from_device,
                     package_bytes.len()
                 );
+                let had_key = app_state.lock().await.public_key_package.is_some();
                 crate::protocal::dkg::process_dkg_round1(
                     app_state.clone(),
                     from_device,
                     package_bytes,
                 )
                 .await;
-                // `process_dkg_round1` internally transitions to Round 2 and
-                // calls `handle_trigger_dkg_round2` when it has received all
-                // `session.total` packages, so nothing else to do here.
+                // `process_dkg_round1` runs Round 2 once it has all
+                // `session.total` packages, and Round 2 packages that beat the last Round 1 package are
+                // already buffered, so Round 2 may have finished the DKG too
+                report_dkg_completion(app_state, had_key, &tx).await;
             }
 
             Command::ProcessDKGRound2 {
@@ -643,28 +816,14 @@ impl Command {
                     from_device,
                     package_bytes.len()
                 );
+                let had_key = app_state.lock().await.public_key_package.is_some();
                 crate::protocal::dkg::process_dkg_round2(
                     app_state.clone(),
                     from_device,
                     package_bytes,
                 )
                 .await;
-                // `process_dkg_round2` runs `part3` internally and populates the
-                // key_package / public_key_package on AppState once complete.
-                // Check whether we've just crossed that threshold and notify UI.
-                let group_key_hex = {
-                    let state = app_state.lock().await;
-                    state
-                        .public_key_package
-                        .as_ref()
-                        .and_then(|pkg| pkg.verifying_key().serialize().ok())
-                        .map(|bytes| hex::encode(bytes))
-                };
-                if let Some(hex) = group_key_hex {
-                    let _ = tx.send(Message::DKGKeyGenerated {
-                        group_pubkey_hex: hex,
-                    });
-                }
+                report_dkg_completion(app_state, had_key, &tx).await;
             }
 
             Command::JoinDKG { session_id } => {
@@ -683,6 +842,7 @@ impl Command {
                     state.dkg_in_progress = true;
                     state.device_id.clone()
                 };
+                let dkg_events = start_dkg_events(app_state, &tx).await;
                 let tx_clone = tx.clone();
                 let (ws_tx, broadcast_tx) = {
                     let state = app_state.lock().await;
@@ -746,6 +906,7 @@ impl Command {
                         session_type: crate::protocal::signal::SessionType::DKG,
                         curve_type,
                         coordination_type: "Network".to_string(),
+                        participant_indices: Default::default(),
                     });
                 }
 
@@ -753,7 +914,7 @@ impl Command {
                 let tx_msg = tx_clone.clone();
                 let session_id_clone = session_id.clone();
                 let device_id_clone = device_id.clone();
-                let session_total = 3u16; // Will be updated from SessionAvailable.
+                let mut session_total = 3u16; // Will be updated from SessionAvailable.
                 let our_session_id = {
                     let state = app_state.lock().await;
                     state.session.as_ref().map(|s| s.session_id.clone())
@@ -762,7 +923,9 @@ impl Command {
                 let mut broadcast_rx = broadcast_tx.subscribe();
 
                         tokio::spawn(async move {
-                            let mut participants_seen = std::collections::HashSet::new();
+                            // Session roster from the creator's announcement, in join order
+                            let mut participants_seen: Vec<String> = Vec::new();
+                            let mut participants_connected: Vec<String> = Vec::new();
                             // Don't add ourselves yet - wait for server to confirm
 
                             loop {
@@ -802,6 +965,7 @@ impl Command {
                                                                     // Also update other session fields
                                                                     if let Some(total) = session_info.get("total").and_then(|v| v.as_u64()) {
                                                                         session.total = total as u16;
+                                                                        session_total = total as u16;
                                                                     }
                                                                     if let Some(threshold) = session_info.get("threshold").and_then(|v| v.as_u64()) {
                                                                         session.threshold = threshold as u16;
@@ -810,17 +974,11 @@ impl Command {
                                                             }
                                                             
                                                             // Update participants list
-                                                            if let Some(participants) = session_info.get("participants").and_then(|v| v.as_array()) {
+                                                            if session_info.get("participants").is_some_and(|v| v.is_array()) {
+                                                                participants_seen = crate::elm::webrtc_signaling::session_roster(session_info);
                                                                 let _ = tx_msg.send(Message::Info { 
-                                                                    message: format!("📋 Session update - participants: {}", participants.len())
+                                                                    message: format!("📋 Session update - participants: {}", participants_seen.len())
                                                                 });
-                                                                
-                                                                participants_seen.clear();
-                                                                for p in participants {
-                                                                    if let Some(pid) = p.as_str() {
-                                                                        participants_seen.insert(pid.to_string());
-                                                                    }
-                                                                }
                                                             }
                                                         }
                                                     }
@@ -831,20 +989,39 @@ impl Command {
                                                     });
                                                     
                                                     // Track previous count to detect new participants
-                                                    let prev_count = participants_seen.len();
+                                                    let prev_count = participants_connected.len();
                                                     
-                                                    // Count unique participants in our session (devices is &Vec<String>)
-                                                    for device in devices.iter() {
-                                                        participants_seen.insert(device.clone());
+                                                    // Count connected devices on the session roster only,
+                                                    // and never more than the session has slots for. The
+                                                    // roster the signaling handler admitted fills in anyone
+                                                    // the announcements haven't shown us yet.
+                                                    let mut roster = participants_seen.clone();
+                                                    if let Some(session) = app_state_clone.lock().await.session.as_ref() {
+                                                        for participant in &session.participants {
+                                                            if !roster.contains(participant) {
+                                                                roster.push(participant.clone());
+                                                            }
+                                                        }
                                                     }
+                                                    let (admitted, _) = crate::elm::webrtc_signaling::split_at_capacity(&roster, session_total as usize);
+                                                    let connected: Vec<String> = devices.iter()
+                                                        .filter(|device| admitted.contains(device))
+                                                        .cloned()
+                                                        .collect();
+                                                    for device in connected.iter().filter(|d| !participants_connected.contains(d)) {
+                                                        let _ = dkg_events.send(DkgEvent::ParticipantJoined {
+                                                            participant: device.clone(),
+                                                        });
+                                                    }
+                                                    participants_connected = connected;
                                                     
                                                     // Send UpdateParticipants message to update the model
-                                                    let participants_list: Vec<String> = participants_seen.iter().cloned().collect();
+                                                    let participants_list: Vec<String> = participants_connected.clone();
                                                     let _ = tx_msg.send(Message::UpdateParticipants { 
                                                         participants: participants_list.clone() 
                                                     });
                                                     
-                                                    let participants_count = participants_seen.len();
+                                                    let participants_count = participants_connected.len();
                                                     
                                                     let _ = tx_msg.send(Message::Info { 
                                                         message: format!("👥 Current participants: {}/{}", 
@@ -859,7 +1036,7 @@ impl Command {
                                                         
                                                         // Get participants list WITHOUT self for WebRTC initiation
                                                         let self_device = device_id_clone.clone();
-                                                        let other_participants: Vec<String> = participants_seen.iter()
+                                                        let other_participants: Vec<String> = participants_connected.iter()
                                                             .filter(|p| **p != self_device)
                                                             .cloned()
                                                             .collect();
@@ -900,6 +1077,14 @@ impl Command {
                                                     }
                                                 }
                                                 webrtc_signal_server::ServerMsg::Relay { from, data } => {
+                                                    if from == "server"
+                                                        && let Some((roster, total)) = crate::elm::webrtc_signaling::participant_update_roster(data, &session_id_clone)
+                                                    {
+                                                        participants_seen = roster;
+                                                        if let Some(total) = total {
+                                                            session_total = total;
+                                                        }
+                                                    }
                                                     crate::elm::webrtc_signaling::handle_relay(
                                                         from.clone(),
                                                         data.clone(),
@@ -910,6 +1095,13 @@ impl Command {
                                                     )
                                                     .await;
                                                 }
+                                                webrtc_signal_server::ServerMsg::Error { error }
+                                                    if error.starts_with(&format!("Session {} is full", session_id_clone)) =>
+                                                {
+                                                    // The server turned our join away
+                                                    let _ = tx_msg.send(Message::DKGFailed { error: error.clone() });
+                                                    break;
+                                                }
                                     _ => {}
                                 }
                             }
@@ -924,6 +1116,203 @@ impl Command {
                         });
             }
             
+            Command::ResumeDKG { session_id } => {
+                info!("Resuming DKG session {} after reconnect", session_id);
+
+                // Redial; this registers again and re-announces `state.session`
+                Box::pin(Command::ReconnectWebSocket.execute::<C>(tx.clone(), app_state)).await?;
+
+                let (session, device_id, ws_tx, broadcast_tx) = {
+                    let state = app_state.lock().await;
+                    // Dial failed; the disconnect path schedules the next attempt
+                    let (Some(ws), Some(bt)) = (
+                        state.websocket_msg_tx.clone(),
+                        state.server_msg_broadcast_tx.clone(),
+                    ) else {
+                        return Ok(());
+                    };
+                    let Some(session) = state.session.clone().filter(|s| s.session_id == session_id) else {
+                        warn!("ResumeDKG: session {} is no longer the active session", session_id);
+                        return Ok(());
+                    };
+                    (session, state.device_id.clone(), ws, bt)
+                };
+
+                // The re-announce only speaks for the proposer; joiners rejoin
+                if session.proposer_id != device_id {
+                    let rejoin = webrtc_signal_server::ClientMsg::SessionStatusUpdate {
+                        session_info: serde_json::json!({
+                            "session_id": session_id.clone(),
+                            "participant_joined": device_id.clone(),
+                        }),
+                    };
+                    match serde_json::to_string(&rejoin) {
+                        Ok(json) => {
+                            let _ = ws_tx.send(json);
+                        }
+                        Err(e) => error!("Serialize SessionStatusUpdate: {}", e),
+                    }
+                }
+
+                crate::elm::webrtc_signaling::spawn_relay_forwarder(
+                    broadcast_tx.subscribe(),
+                    app_state.clone(),
+                    tx.clone(),
+                    device_id.clone(),
+                    session_id.clone(),
+                );
+
+                let _ = tx.send(Message::Info {
+                    message: format!("🔄 Resuming DKG session {} with {} participants", session_id, session.participants.len()),
+                });
+                let _ = tx.send(Message::InitiateWebRTCWithParticipants {
+                    participants: session.participants.clone(),
+                });
+
+                // Replay once the data channels are back
+                let app_state_replay = app_state.clone();
+                let tx_replay = tx.clone();
+                tokio::spawn(async move {
+                    for _ in 0..60 {
+                        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                        if app_state_replay.lock().await.is_full_mesh_ready() {
+                            break;
+                        }
+                    }
+                    let replayed =
+                        crate::protocal::dkg::replay_unacknowledged_dkg_packages(app_state_replay).await;
+                    if replayed > 0 {
+                        let _ = tx_replay.send(Message::Info {
+                            message: format!("📦 Replayed {} DKG package(s) peers had not acknowledged", replayed),
+                        });
+                    }
+                });
+            }
+
+            Command::CancelDKG => {
+                let (session, device_id, ws_tx) = {
+                    let state = app_state.lock().await;
+                    (state.session.clone(), state.device_id.clone(), state.websocket_msg_tx.clone())
+                };
+                let Some(session) = session else {
+                    info!("CancelDKG: no active session to abort");
+                    return Ok(());
+                };
+                let reason = format!("cancelled by {}", device_id);
+
+                // DKG needs every participant, so tell the rest to stop waiting
+                if let Some(ws_tx) = ws_tx {
+                    let abort = crate::protocal::signal::WebSocketMessage::DKGAbort {
+                        session_id: session.session_id.clone(),
+                        reason: reason.clone(),
+                    };
+                    match serde_json::to_value(&abort) {
+                        Ok(data) => {
+                            for peer in session.participants.iter().filter(|p| **p != device_id) {
+                                let relay = webrtc_signal_server::ClientMsg::Relay {
+                                    to: peer.clone(),
+                                    data: data.clone(),
+                                };
+                                if let Ok(json) = serde_json::to_string(&relay) {
+                                    let _ = ws_tx.send(json);
+                                }
+                            }
+                        }
+                        Err(e) => error!("Serialize DKGAbort: {}", e),
+                    }
+                } else {
+                    warn!("CancelDKG: not connected, peers will wait for their own timeout");
+                }
+
+                crate::protocal::dkg::abort_dkg_session(app_state.clone(), &session.session_id, &reason).await;
+                let _ = tx.send(Message::Info {
+                    message: format!("🛑 DKG session {} cancelled", session.session_id),
+                });
+            }
+
+            Command::PreflightDKG { participants } => {
+                info!("Running DKG preflight for {:?}", participants);
+
+                if app_state.lock().await.websocket_msg_tx.is_none() {
+                    Box::pin(Command::ReconnectWebSocket.execute::<C>(tx.clone(), app_state)).await?;
+                }
+                let (device_id, ws_tx, broadcast_tx, device_connections) = {
+                    let state = app_state.lock().await;
+                    let (Some(ws), Some(bt)) = (
+                        state.websocket_msg_tx.clone(),
+                        state.server_msg_broadcast_tx.clone(),
+                    ) else {
+                        let _ = tx.send(Message::Error {
+                            message: "DKG preflight: not connected to the signal server".to_string(),
+                        });
+                        return Ok(());
+                    };
+                    (state.device_id.clone(), ws, bt, state.device_connections.clone())
+                };
+
+                // Subscribe before asking so the reply can't slip past
+                let mut server_msgs = broadcast_tx.subscribe();
+                match serde_json::to_string(&webrtc_signal_server::ClientMsg::ListDevices) {
+                    Ok(json) => {
+                        let _ = ws_tx.send(json);
+                    }
+                    Err(e) => error!("Serialize ListDevices: {}", e),
+                }
+                let online = tokio::time::timeout(PREFLIGHT_DEVICE_LIST_TIMEOUT, async {
+                    loop {
+                        match server_msgs.recv().await {
+                            Ok(msg) => {
+                                if let webrtc_signal_server::ServerMsg::Devices { devices } = &*msg {
+                                    return devices.clone();
+                                }
+                            }
+                            Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                            Err(tokio::sync::broadcast::error::RecvError::Closed) => return Vec::new(),
+                        }
+                    }
+                })
+                .await
+                .unwrap_or_else(|_| {
+                    warn!("DKG preflight: no device list within {:?}", PREFLIGHT_DEVICE_LIST_TIMEOUT);
+                    Vec::new()
+                });
+
+                let candidates: Vec<String> = participants
+                    .iter()
+                    .filter(|p| **p != device_id && online.contains(p))
+                    .cloned()
+                    .collect();
+                // Only probe: dialing here would skip perfect negotiation (the
+                // lower id offers) and arm the relay fallback. A channel counts
+                // whichever side offered it, since both land in `data_channels`;
+                // we only wait on peers whose connection is still negotiating.
+                let deadline = tokio::time::Instant::now() + PREFLIGHT_CHANNEL_TIMEOUT;
+                while tokio::time::Instant::now() < deadline {
+                    let pending: Vec<String> = {
+                        let state = app_state.lock().await;
+                        candidates.iter().filter(|p| !state.has_open_data_channel(p)).cloned().collect()
+                    };
+                    let negotiating = device_connections.lock().await;
+                    if !pending.iter().any(|p| negotiating.contains_key(p)) {
+                        break;
+                    }
+                    drop(negotiating);
+                    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                }
+
+                let report = {
+                    let state = app_state.lock().await;
+                    crate::webrtc::PreflightReport::new(
+                        &participants,
+                        &device_id,
+                        |p| online.contains(p),
+                        |p| state.has_open_data_channel(p),
+                    )
+                };
+                info!("DKG preflight: {}", report);
+                let _ = tx.send(Message::DKGPreflightReport { report });
+            }
+
             Command::InitiateWebRTCConnections { participants } => {
                 info!("Initiating WebRTC connections with {} participants", participants.len());
                 
@@ -1090,67 +1479,31 @@ impl Command {
             Command::VerifyWebRTCMesh => {
                 info!("🔍 Verifying WebRTC mesh connectivity");
                 
-                let (self_device_id, expected_connections) = {
+                let (self_device_id, participants, missing) = {
                     let state = app_state.lock().await;
-                    let expected = if let Some(ref session) = state.session {
-                        session.participants.len() - 1  // Exclude self
+                    let participants = if let Some(ref session) = state.session {
+                        session.participants.clone()
                     } else {
-                        0
+                        vec![]
                     };
-                    (state.device_id.clone(), expected)
+                    (state.device_id.clone(), participants, state.peers_missing_open_channel())
                 };
-                
-                // Check current connection status
-                let connections_status = {
-                    let state = app_state.lock().await;
-                    let device_connections = state.device_connections.clone();
-                    let connections = device_connections.lock().await;
-                    
-                    let mut status_report = Vec::new();
-                    let mut connected_count = 0;
-                    let mut failed_count = 0;
-                    
-                    for (peer_id, pc) in connections.iter() {
-                        let conn_state = pc.connection_state();
-                        let is_connected = conn_state == webrtc::peer_connection::peer_connection_state::RTCPeerConnectionState::Connected;
-                        
-                        if is_connected {
-                            connected_count += 1;
-                            status_report.push(format!("✅ {} -> {}: Connected", self_device_id, peer_id));
-                        } else {
-                            failed_count += 1;
-                            status_report.push(format!("❌ {} -> {}: {:?}", self_device_id, peer_id, conn_state));
-                        }
-                    }
-                    
-                    (connected_count, failed_count, status_report, connections.len())
-                };
-                
-                let (connected_count, failed_count, status_report, _total_connections) = connections_status;
+                let expected_connections = participants.iter().filter(|p| **p != self_device_id).count();
+                let open_count = expected_connections - missing.len();
                 
                 // Send status report
                 let _ = tx.send(Message::Info {
-                    message: format!("📊 Mesh Status: {}/{} connected ({} failed)", 
-                                   connected_count, expected_connections, failed_count)
+                    message: format!("📊 Mesh Status: {}/{} data channels open", 
+                                   open_count, expected_connections)
                 });
                 
-                for status_line in status_report {
-                    info!("{}", status_line);
+                for peer_id in &missing {
+                    info!("❌ {} -> {}: data channel not open", self_device_id, peer_id);
                 }
                 
-                // If not all connections are established, trigger re-initiation
-                if connected_count < expected_connections {
-                    warn!("⚠️ Incomplete mesh: only {}/{} connections established", connected_count, expected_connections);
-                    
-                    // Get participants and re-initiate for missing connections
-                    let participants = {
-                        let state = app_state.lock().await;
-                        if let Some(ref session) = state.session {
-                            session.participants.clone()
-                        } else {
-                            vec![]
-                        }
-                    };
+                // If not every data channel is open, trigger re-initiation
+                if !missing.is_empty() {
+                    warn!("⚠️ Incomplete mesh: only {}/{} data channels open", open_count, expected_connections);
                     
                     if !participants.is_empty() {
                         let _ = tx.send(Message::Info {
@@ -1165,7 +1518,7 @@ impl Command {
                     }
                 } else {
                     let _ = tx.send(Message::Success {
-                        message: format!("✅ Full mesh established: {} connections", connected_count)
+                        message: format!("✅ Full mesh established: {} connections", open_count)
                     });
                 }
             }
@@ -1173,14 +1526,14 @@ impl Command {
             Command::EnsureFullMesh => {
                 info!("🔗 Ensuring full mesh connectivity");
                 
-                let (self_device_id, participants) = {
+                let (self_device_id, participants, missing_connections) = {
                     let state = app_state.lock().await;
                     let participants = if let Some(ref session) = state.session {
                         session.participants.clone()
                     } else {
                         vec![]
                     };
-                    (state.device_id.clone(), participants)
+                    (state.device_id.clone(), participants, state.peers_missing_open_channel())
                 };
                 
                 if participants.is_empty() {
@@ -1190,34 +1543,6 @@ impl Command {
                     return Ok(());
                 }
                 
-                // Check each expected connection
-                let mut missing_connections = Vec::new();
-                {
-                    let state = app_state.lock().await;
-                    let device_connections = state.device_connections.clone();
-                    let connections = device_connections.lock().await;
-                    
-                    for participant in &participants {
-                        if participant == &self_device_id {
-                            continue;
-                        }
-                        
-                        match connections.get(participant) {
-                            Some(pc) => {
-                                let conn_state = pc.connection_state();
-                                if conn_state != webrtc::peer_connection::peer_connection_state::RTCPeerConnectionState::Connected {
-                                    info!("⚠️ Connection to {} is in state: {:?}", participant, conn_state);
-                                    missing_connections.push(participant.clone());
-                                }
-                            }
-                            None => {
-                                info!("❌ No connection exists to {}", participant);
-                                missing_connections.push(participant.clone());
-                            }
-                        }
-                    }
-                }
-                
                 if !missing_connections.is_empty() {
                     let _ = tx.send(Message::Warning {
                         message: format!("Missing connections to: {:?}", missing_connections)
@@ -1263,7 +1588,8 @@ impl Command {
                 //   1. snapshot state, flag as connecting, drop the stale sender
                 //   2. dial the signal server
                 //   3. mint the outbound mpsc + inbound broadcast, stash in state
-                //   4. send Register, and re-announce our own session if any
+                //   4. send Register, re-announce our own session if any, and
+                //      replay whatever the previous socket left unsent
                 //   5. spawn the sender (mpsc → sink, with 30s ping)
                 //   6. spawn the reader (stream → parse → broadcast + Elm dispatch)
                 //   7. tell the Elm loop we're live
@@ -1289,8 +1615,12 @@ impl Command {
                 if let Some(session) = &params.existing_session {
                     ws_runtime::send_reannounce(&mut sink, session, &tx).await;
                 }
+                let replayed = ws_runtime::replay_unsent(&mut sink, &params.replay).await;
+                if replayed > 0 {
+                    info!("Replayed {} message(s) queued before the disconnect", replayed);
+                }
 
-                ws_runtime::spawn_sender_task(sink, channels.ws_msg_rx);
+                ws_runtime::spawn_sender_task(sink, channels.ws_msg_rx, params.replay);
                 ws_runtime::spawn_reader_task(rx, tx.clone(), channels.broadcast_tx);
 
                 let _ = tx.send(Message::WebSocketConnected);
@@ -1324,6 +1654,81 @@ impl Command {
                 info!("UI refresh requested");
             }
             
+            Command::FetchTransaction { wallet_id, chain, tx_hash } => {
+                info!("Fetching {} transaction {} for wallet {}", chain, tx_hash, wallet_id);
+                let wallet_address = {
+                    let state = app_state.lock().await;
+                    match state.keystore.as_ref() {
+                        Some(keystore) => keystore.wallet_address(&wallet_id, &chain),
+                        None => Err("keystore not initialized".to_string()),
+                    }
+                };
+                let wallet_address = match wallet_address {
+                    Ok(address) => address,
+                    Err(e) => {
+                        let _ = tx.send(Message::Error {
+                            message: format!("Could not fetch transaction: {}", e),
+                        });
+                        return Ok(());
+                    }
+                };
+                let config = crate::network::rpc::RpcConfig::load_or_default(
+                    &crate::network::rpc::RpcConfig::default_path(),
+                );
+                match crate::network::rpc::fetch_transaction(&config, &chain, &wallet_address, &tx_hash).await {
+                    Ok(fetched) => {
+                        let _ = tx.send(Message::TransactionFetched {
+                            wallet_id,
+                            chain,
+                            tx_hash,
+                            transaction_data: fetched.raw_bytes,
+                            summary: fetched.summary,
+                        });
+                    }
+                    Err(e) => {
+                        warn!("Fetching transaction {} failed: {}", tx_hash, e);
+                        let _ = tx.send(Message::Error {
+                            message: format!("Could not fetch transaction: {}", e),
+                        });
+                    }
+                }
+            }
+
+            Command::SaveSettings { websocket_url, device_id, stun_turn, address_settings } => {
+                info!("Saving settings for {} ({} ICE servers)", device_id, stun_turn.servers.len());
+
+                let path = crate::network::ice_config::StunTurnConfig::default_path();
+                if let Err(e) = stun_turn.save(&path) {
+                    error!("Failed to save ICE config to {}: {}", path.display(), e);
+                    let _ = tx.send(Message::Error {
+                        message: format!("Failed to save settings: {}", e)
+                    });
+                    return Ok(());
+                }
+
+                {
+                    let mut state = app_state.lock().await;
+                    state.signal_server_url = websocket_url;
+                    // Applies to peer connections created from now on
+                    state.stun_turn_config = stun_turn;
+                    // Applies to wallets created from now on
+                    state.address_settings = address_settings;
+                }
+                let _ = tx.send(Message::Success {
+                    message: "Settings saved".to_string()
+                });
+            }
+            
+            Command::LoadSettings => {
+                let state = app_state.lock().await;
+                let _ = tx.send(Message::SettingsLoaded {
+                    websocket_url: state.signal_server_url.clone(),
+                    device_id: state.device_id.clone(),
+                    stun_turn: state.stun_turn_config.clone(),
+                    address_settings: state.address_settings,
+                });
+            }
+
             Command::Quit => {
                 info!("Application quit requested");
                 // Send quit message to trigger app shutdown
@@ -1362,4 +1767,215 @@ mod tests {
         };
         assert!(matches!(cmd, Command::StartDKG { .. }));
     }
+
+    #[tokio::test]
+    async fn test_round1_command_reports_dkg_finished_by_early_round2_package() {
+        use crate::protocal::signal::{SessionInfo, SessionType};
+        use crate::utils::appstate_compat::AppState;
+        use crate::utils::state::DkgState;
+        use frost_core::Identifier;
+        use frost_secp256k1::Secp256K1Sha256;
+        use frost_secp256k1::rand_core::OsRng;
+        use std::sync::Arc;
+        use tokio::sync::{mpsc, Mutex};
+
+        let (alice_id, bob_id) = (Identifier::try_from(1).unwrap(), Identifier::try_from(2).unwrap());
+        let (alice_secret, alice_round1) =
+            frost_core::keys::dkg::part1::<Secp256K1Sha256, _>(alice_id, 2, 2, OsRng).unwrap();
+        let (bob_secret, bob_round1) = frost_core::keys::dkg::part1::<Secp256K1Sha256, _>(bob_id, 2, 2, OsRng).unwrap();
+        let (_, bob_round2) =
+            frost_core::keys::dkg::part2(bob_secret, &[(alice_id, alice_round1.clone())].into()).unwrap();
+
+        let mut state = AppState::<Secp256K1Sha256>::with_device_id("alice".to_string());
+        state.session = Some(SessionInfo {
+            session_id: "dkg_early_round2".to_string(),
+            proposer_id: "alice".to_string(),
+            total: 2,
+            threshold: 2,
+            participants: vec!["alice".to_string(), "bob".to_string()],
+            session_type: SessionType::DKG,
+            curve_type: "unified".to_string(),
+            coordination_type: "Network".to_string(),
+            participant_indices: Default::default(),
+        });
+        state.dkg_state = DkgState::Round1InProgress;
+        state.dkg_part1_secret_package = Some(alice_secret.serialize().unwrap());
+        state.dkg_round1_packages.insert(alice_id, alice_round1);
+        // Bob finished Round 1 first, so his Round 2 package is already here
+        state.dkg_round2_packages.insert(bob_id, bob_round2[&alice_id].clone());
+        let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+        state.dkg_events = Some(events_tx);
+        let app_state = Arc::new(Mutex::new(state));
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        Command::ProcessDKGRound1 {
+            from_device: "bob".to_string(),
+            package_bytes: bob_round1.serialize().unwrap(),
+        }
+        .execute::<Secp256K1Sha256>(tx, &app_state)
+        .await
+        .unwrap();
+
+        let mut events = Vec::new();
+        while let Ok(event) = events_rx.try_recv() {
+            events.push(event);
+        }
+        let mut group_key = None;
+        while let Ok(msg) = rx.try_recv() {
+            if let Message::DKGKeyGenerated { group_pubkey_hex } = msg {
+                group_key = Some(group_pubkey_hex);
+            }
+        }
+        assert_eq!(events, vec![DkgEvent::Round1Complete, DkgEvent::Round2Complete, DkgEvent::Finalized]);
+        let state = app_state.lock().await;
+        assert_eq!(state.dkg_state, DkgState::Complete);
+        let expected = hex::encode(state.group_public_key.unwrap().serialize().unwrap());
+        assert_eq!(group_key, Some(expected));
+    }
+
+    #[tokio::test]
+    async fn test_dkg_events_reach_elm_through_dkg_manager() {
+        use crate::utils::appstate_compat::AppState;
+        use frost_secp256k1::Secp256K1Sha256;
+        use std::sync::Arc;
+        use tokio::sync::{mpsc, Mutex};
+
+        let app_state = Arc::new(Mutex::new(AppState::<Secp256K1Sha256>::with_device_id("alice".to_string())));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        start_dkg_events(&app_state, &tx).await;
+        {
+            let state = app_state.lock().await;
+            crate::protocal::dkg::emit_dkg_event(&state, DkgEvent::ParticipantJoined { participant: "bob".to_string() });
+            crate::protocal::dkg::emit_dkg_event(&state, DkgEvent::Round1Complete);
+        }
+
+        let mut messages = Vec::new();
+        while messages.len() < 3 {
+            messages.push(rx.recv().await.unwrap());
+        }
+        let bob = Message::DkgParticipants { participants: vec!["bob".to_string()] };
+        assert_eq!(
+            messages,
+            vec![bob.clone(), bob, Message::DkgStatus { active: true, round: 1, progress: 0.33 }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_started_session_tracks_inbound_peer_messages() {
+        use crate::protocal::signal::{SessionInfo, SessionType};
+        use crate::utils::appstate_compat::AppState;
+        use frost_secp256k1::Secp256K1Sha256;
+        use std::sync::Arc;
+        use tokio::sync::{mpsc, Mutex};
+
+        let mut state = AppState::<Secp256K1Sha256>::with_device_id("alice".to_string());
+        state.session = Some(SessionInfo {
+            session_id: "dkg_liveness".to_string(),
+            proposer_id: "alice".to_string(),
+            total: 3,
+            threshold: 2,
+            participants: vec!["alice".to_string(), "bob".to_string(), "carol".to_string()],
+            session_type: SessionType::DKG,
+            curve_type: "unified".to_string(),
+            coordination_type: "Network".to_string(),
+            participant_indices: Default::default(),
+        });
+        let app_state = Arc::new(Mutex::new(state));
+        let (tx, _rx) = mpsc::unbounded_channel();
+        start_session_liveness(&app_state, &tx).await;
+
+        let manager = app_state.lock().await.session_manager.clone().unwrap();
+        assert!(app_state.lock().await.liveness_monitor.is_some());
+        assert!(manager.is_signing_coordinator().await);
+        assert_eq!(manager.last_seen("bob").await, None);
+
+        let msg = webrtc::data_channel::data_channel_message::DataChannelMessage {
+            is_string: true,
+            data: bytes::Bytes::from_static(b"{}"),
+        };
+        crate::network::webrtc::receive_data_channel_msg(msg, "bob".to_string(), app_state.clone(), None).await;
+        crate::elm::webrtc_signaling::handle_relay(
+            "carol".to_string(),
+            serde_json::json!({}),
+            app_state.clone(),
+            tx.clone(),
+            "alice".to_string(),
+            Some("dkg_liveness".to_string()),
+        )
+        .await;
+        assert!(manager.last_seen("bob").await.is_some());
+        assert!(manager.last_seen("carol").await.is_some());
+    }
+
+    /// Accept one connection and return its first text frames
+    async fn read_frames(listener: &tokio::net::TcpListener, count: usize) -> (Vec<String>, crate::elm::ws_runtime::WsStream) {
+        use futures_util::StreamExt;
+        let (tcp, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(tokio_tungstenite::MaybeTlsStream::Plain(tcp))
+            .await
+            .unwrap();
+        let mut frames = Vec::new();
+        while frames.len() < count {
+            if let Some(Ok(tokio_tungstenite::tungstenite::Message::Text(text))) = ws.next().await {
+                frames.push(text.to_string());
+            }
+        }
+        (frames, ws)
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_registers_again_and_replays_unsent() {
+        use crate::utils::appstate_compat::AppState;
+        use frost_secp256k1::Secp256K1Sha256;
+        use std::sync::Arc;
+        use std::time::Duration;
+        use tokio::sync::{mpsc, Mutex};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let app_state = Arc::new(Mutex::new(AppState::<Secp256K1Sha256>::with_device_id_and_server(
+            "mpc-1".to_string(),
+            url,
+        )));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let register = r#"{"type":"register","device_id":"mpc-1"}"#;
+
+        let (connect, server) = tokio::join!(
+            Command::ReconnectWebSocket.execute::<Secp256K1Sha256>(tx.clone(), &app_state),
+            read_frames(&listener, 1),
+        );
+        connect.unwrap();
+        let (frames, mut ws) = server;
+        assert_eq!(frames, vec![register]);
+
+        // The server drops us
+        ws.close(None).await.unwrap();
+        drop(ws);
+        loop {
+            if let Message::WebSocketDisconnected = rx.recv().await.unwrap() {
+                break;
+            }
+        }
+
+        // A message sent into the dead socket is parked rather than lost
+        let ws_tx = app_state.lock().await.websocket_msg_tx.clone().unwrap();
+        ws_tx.send("queued".to_string()).unwrap();
+        let replay = app_state.lock().await.websocket_replay_queue.clone();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while replay.lock().unwrap().is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        let (connect, (frames, _ws)) = tokio::join!(
+            Command::ReconnectWebSocket.execute::<Secp256K1Sha256>(tx.clone(), &app_state),
+            read_frames(&listener, 2),
+        );
+        connect.unwrap();
+        assert_eq!(frames, vec![register, "queued"]);
+        assert!(replay.lock().unwrap().is_empty());
+        assert!(app_state.lock().await.websocket_connected);
+    }
 }
\ No newline at end of file
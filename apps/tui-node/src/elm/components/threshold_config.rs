@@ -4,6 +4,7 @@
 
 use crate::elm::components::{Id, UserEvent, MpcWalletComponent};
 use crate::elm::message::Message;
+use crate::security::assess_threshold_security;
 
 use tuirealm::command::{Cmd, CmdResult, Direction};
 use tuirealm::event::Event;
@@ -338,14 +339,19 @@ impl ThresholdConfigComponent {
         let validation_result = self.validate_threshold();
         let (analysis_text, analysis_color) = match validation_result {
             Ok(_) => {
-                let redundancy = self.participants - self.threshold;
-                (
-                    format!(
-                        "✅ Valid: {} signers required, {} can be offline",
-                        self.threshold, redundancy
-                    ),
-                    Color::Green
-                )
+                let assessment = assess_threshold_security(self.threshold as u16, self.participants as u16);
+                if let Some(warning) = assessment.warnings.first() {
+                    (format!("⚠️ {}", warning.message()), Color::Yellow)
+                } else {
+                    let redundancy = self.participants - self.threshold;
+                    (
+                        format!(
+                            "✅ Valid: {} signers required, {} can be offline",
+                            self.threshold, redundancy
+                        ),
+                        Color::Green
+                    )
+                }
             }
             Err(msg) => (format!("⚠️ {}", msg), Color::Red),
         };
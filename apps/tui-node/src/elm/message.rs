@@ -65,6 +65,11 @@ pub enum Message {
         webrtc_connected: bool,
         data_channel_open: bool,
     },
+    /// Automatic reconnection to a peer gave up after `attempts` tries
+    WebRTCReconnectFailed {
+        device_id: String,
+        attempts: u32,
+    },
     UpdateMeshStatus {
         ready_count: usize,
         total_count: usize,
@@ -72,11 +77,20 @@ pub enum Message {
     },
     DKGComplete { result: DKGResult },
     DKGFailed { error: String },
+    DKGRoundTimedOut { round: u8, missing: Vec<String> },  // Round deadline passed; retriable
     CancelDKG,
+    /// Check reachability of `participants` before committing to a DKG
+    PreflightDKG { participants: Vec<String> },
+    DKGPreflightReport { report: crate::webrtc::PreflightReport<String> },
     StartDKGProtocol,  // Trigger the actual DKG protocol when mesh is ready
     ProcessDKGRound1 { from_device: String, package_bytes: Vec<u8> },  // Process received DKG Round 1 package
     ProcessDKGRound2 { from_device: String, package_bytes: Vec<u8> },  // Process received DKG Round 2 package
     DKGKeyGenerated { group_pubkey_hex: String },                      // Final FROST key ready
+    /// DKG progress as reported to `UICallback::update_dkg_status`; `round`
+    /// is the last round completed
+    DkgStatus { active: bool, round: u8, progress: f32 },
+    /// DKG participants as reported to `UICallback::update_dkg_participants`
+    DkgParticipants { participants: Vec<String> },
     
     // Signing operations
     InitiateSigning { request: SigningRequest },
@@ -86,6 +100,10 @@ pub enum Message {
     UpdateSigningProgress { request_id: String, progress: f32 },
     SigningComplete { request_id: String, signature: Vec<u8> },
     SigningFailed { request_id: String, error: String },
+    /// Look up a transaction by hash on the chain's configured RPC endpoint
+    FetchTransaction { chain: String, tx_hash: String },
+    /// Fetched and parsed; waits for the user to confirm signing it with `wallet_id`
+    TransactionFetched { wallet_id: String, chain: String, tx_hash: String, transaction_data: Vec<u8>, summary: String },
     
     // Network events
     WebSocketConnected,
@@ -139,7 +157,12 @@ pub enum Message {
     UpdateDeviceId { device_id: String },
     SaveSettings,
     LoadSettings,
-    SettingsLoaded { websocket_url: String, device_id: String },
+    SettingsLoaded {
+        websocket_url: String,
+        device_id: String,
+        stun_turn: crate::network::ice_config::StunTurnConfig,
+        address_settings: crate::blockchain_config::AddressSettings,
+    },
     
     // System messages
     Initialize,
@@ -228,6 +251,7 @@ impl Message {
             self,
             Message::Error { .. }
             | Message::DKGFailed { .. }
+            | Message::DKGRoundTimedOut { .. }
             | Message::SigningFailed { .. }
             | Message::WebSocketError { .. }
             | Message::KeystoreError { .. }
@@ -13,6 +13,7 @@ pub mod components;
 pub mod provider;
 pub mod webrtc_signaling;
 pub mod ws_runtime;
+pub mod ui_callback;
 
 pub use model::{Model, Screen, UIState, WalletState, NetworkState};
 pub use message::Message;
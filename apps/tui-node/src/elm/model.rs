@@ -99,6 +99,9 @@ pub struct WalletState {
     /// memory any longer than necessary. `None` outside the wallet-creation
     /// window.
     pub pending_password: Option<String>,
+    /// Address formats for wallets created from now on, as shown on the
+    /// Settings screen
+    pub address_settings: crate::blockchain_config::AddressSettings,
 }
 
 // Manual Debug implementation for WalletState
@@ -116,6 +119,7 @@ impl std::fmt::Debug for WalletState {
             // Never log the actual password, even at debug level — just
             // report whether one is currently staged.
             .field("pending_password", &self.pending_password.as_ref().map(|_| "<redacted>"))
+            .field("address_settings", &self.address_settings)
             .finish()
     }
 }
@@ -131,6 +135,7 @@ pub struct NetworkState {
     pub reconnect_attempts: u32,
     pub max_reconnect_attempts: u32,
     pub participant_webrtc_status: std::collections::HashMap<String, (bool, bool)>, // (webrtc_connected, data_channel_open)
+    pub stun_turn: crate::network::ice_config::StunTurnConfig,
 }
 
 impl Default for NetworkState {
@@ -144,6 +149,7 @@ impl Default for NetworkState {
             reconnect_attempts: 0,
             max_reconnect_attempts: 5,
             participant_webrtc_status: std::collections::HashMap::new(),
+            stun_turn: Default::default(),
         }
     }
 }
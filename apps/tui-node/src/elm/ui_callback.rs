@@ -0,0 +1,68 @@
+//! `UICallback` for the Elm TUI
+//!
+//! The shared core managers (`DkgManager`, ...) report progress through
+//! `UICallback`, the same way they drive the native UI. This implementation
+//! turns those calls into `Message`s for the Elm update loop, so both UIs
+//! render the same progress from the same calls.
+
+use async_trait::async_trait;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::core::{
+    ConnectionInfo, OperationMode, ParticipantInfo, SDCardOperation, SessionInfo, UICallback, WalletInfo,
+};
+use crate::elm::message::Message;
+
+/// Forwards core manager updates to the Elm update loop
+pub struct ElmUICallback {
+    tx: UnboundedSender<Message>,
+}
+
+impl ElmUICallback {
+    pub fn new(tx: UnboundedSender<Message>) -> Self {
+        Self { tx }
+    }
+}
+
+#[async_trait]
+impl UICallback for ElmUICallback {
+    // The Elm model learns about connections, wallets, sessions and offline
+    // mode from its own messages; only DKG progress and notices come from
+    // the core managers.
+    async fn update_connection_status(&self, _websocket: bool, _webrtc: bool) {}
+    async fn update_mesh_connections(&self, _connections: Vec<ConnectionInfo>) {}
+    async fn update_operation_mode(&self, _mode: OperationMode) {}
+    async fn update_wallets(&self, _wallets: Vec<WalletInfo>) {}
+    async fn update_active_wallet(&self, _index: usize) {}
+    async fn update_available_sessions(&self, _sessions: Vec<SessionInfo>) {}
+    async fn update_active_session(&self, _session: Option<SessionInfo>) {}
+
+    async fn update_dkg_status(&self, active: bool, round: u8, progress: f32) {
+        let _ = self.tx.send(Message::DkgStatus { active, round, progress });
+    }
+
+    async fn update_dkg_participants(&self, participants: Vec<ParticipantInfo>) {
+        let _ = self.tx.send(Message::DkgParticipants {
+            participants: participants.into_iter().map(|p| p.name).collect(),
+        });
+    }
+
+    async fn update_offline_status(&self, _enabled: bool, _sd_card_detected: bool) {}
+    async fn update_sd_operations(&self, _operations: Vec<SDCardOperation>) {}
+
+    async fn show_message(&self, message: String, is_error: bool) {
+        let _ = self.tx.send(if is_error {
+            Message::Error { message }
+        } else {
+            Message::Info { message }
+        });
+    }
+
+    async fn show_progress(&self, _title: String, _progress: f32) {}
+
+    /// The Elm loop asks its own questions through modals; nothing in the
+    /// core managers may proceed on an unanswered prompt
+    async fn request_confirmation(&self, _message: String) -> bool {
+        false
+    }
+}
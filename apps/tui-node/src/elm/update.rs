@@ -29,6 +29,31 @@ fn enter_round1(model: &mut Model) {
     }
 }
 
+/// Reconnect attempt `n` waits about `n` times this long
+const RECONNECT_BASE_DELAY_MS: u64 = 2000;
+
+/// Fraction of the reconnect delay randomized either way, so nodes dropped
+/// by the same outage don't redial and rejoin the server in lockstep
+const RECONNECT_JITTER: f64 = 0.5;
+
+/// Delay before reconnect attempt `attempt` (1-based) for a uniform `sample`
+/// in [0, 1): the linear backoff scaled by 1 ± `RECONNECT_JITTER`
+fn reconnect_delay_ms(attempt: u32, sample: f64) -> u64 {
+    let base = RECONNECT_BASE_DELAY_MS * attempt as u64;
+    let scale = 1.0 + RECONNECT_JITTER * (2.0 * sample.clamp(0.0, 1.0) - 1.0);
+    (base as f64 * scale) as u64
+}
+
+/// Uniform sample in [0, 1) from the OS RNG. Jitter only spreads load, so if
+/// the RNG fails we fall back to the unjittered midpoint.
+fn jitter_sample() -> f64 {
+    let mut bytes = [0u8; 8];
+    if getrandom::fill(&mut bytes).is_err() {
+        return 0.5;
+    }
+    (u64::from_le_bytes(bytes) >> 11) as f64 / (1u64 << 53) as f64
+}
+
 /// The main update function that handles all state transitions
 pub fn update(model: &mut Model, msg: Message) -> Option<Command> {
     debug!("Processing message: {:?}", msg);
@@ -211,6 +236,7 @@ pub fn update(model: &mut Model, msg: Message) -> Option<Command> {
                 session_type: SessionType::DKG,
                 curve_type: "unified".to_string(),
                 coordination_type: "online".to_string(),
+                participant_indices: Default::default(),
             });
             
             // Navigate to DKG Progress screen with placeholder
@@ -390,6 +416,21 @@ pub fn update(model: &mut Model, msg: Message) -> Option<Command> {
             }
         }
 
+        Message::WebRTCReconnectFailed { device_id, attempts } => {
+            error!("Giving up on WebRTC reconnection to {} after {} attempts", device_id, attempts);
+
+            model.network_state.participant_webrtc_status
+                .insert(device_id.clone(), (false, false));
+            model.ui_state.modal = Some(Modal::Error {
+                title: "Connection Lost".to_string(),
+                message: format!(
+                    "Could not reconnect to {} after {} attempts. Rejoin the session to continue.",
+                    device_id, attempts
+                ),
+            });
+            Some(Command::SendMessage(Message::ForceRemount))
+        }
+
         Message::UpdateMeshStatus { ready_count, total_count, all_connected } => {
             info!("Mesh status update: {}/{} ready, all_connected={}",
                  ready_count, total_count, all_connected);
@@ -466,7 +507,24 @@ pub fn update(model: &mut Model, msg: Message) -> Option<Command> {
 
             None
         }
-        
+
+        Message::DKGRoundTimedOut { round, missing } => {
+            warn!("DKG round {} timed out waiting for {:?}", round, missing);
+
+            // The session is kept, so retrying resumes it rather than starting over
+            model.ui_state.modal = Some(Modal::Confirm {
+                title: format!("DKG Round {} Timed Out", round),
+                message: format!(
+                    "No round {} package from: {}\n\nRetry to reconnect and resend, or cancel the DKG.",
+                    round,
+                    missing.join(", ")
+                ),
+                on_confirm: Box::new(Message::TriggerReconnect),
+                on_cancel: Box::new(Message::CancelDKG),
+            });
+            None
+        }
+
         Message::CancelDKG => {
             info!("🛑 CancelDKG requested by user");
 
@@ -485,6 +543,28 @@ pub fn update(model: &mut Model, msg: Message) -> Option<Command> {
             Some(Command::CancelDKG)
         }
 
+        Message::PreflightDKG { participants } => {
+            info!("🩺 DKG preflight requested for {:?}", participants);
+            Some(Command::PreflightDKG { participants })
+        }
+
+        Message::DKGPreflightReport { report } => {
+            let kind = if report.is_ready() {
+                NotificationKind::Success
+            } else {
+                warn!("DKG preflight found unreachable participants: {:?}", report.unreachable());
+                NotificationKind::Warning
+            };
+            model.ui_state.notifications.push(Notification {
+                id: Uuid::new_v4().to_string(),
+                text: format!("DKG preflight: {}", report),
+                kind,
+                timestamp: Utc::now(),
+                dismissible: true,
+            });
+            None
+        }
+
         Message::StartDKGProtocol => {
             // Fired by the WebRTC layer in two places:
             //   1. `mesh_ready` control-frame handler (network/webrtc.rs) once
@@ -562,6 +642,52 @@ pub fn update(model: &mut Model, msg: Message) -> Option<Command> {
             }
         }
 
+        Message::DkgParticipants { participants } => {
+            if let Some(ref mut session) = model.active_session {
+                for participant in participants {
+                    if !session.participants.contains(&participant) {
+                        session.participants.push(participant);
+                    }
+                }
+            }
+            if matches!(model.current_screen, Screen::DKGProgress { .. }) {
+                Some(Command::SendMessage(Message::ForceRemount))
+            } else {
+                None
+            }
+        }
+
+        Message::DkgStatus { active, round, progress } => {
+            info!("DKG status: active={} round={} progress={:.2}", active, round, progress);
+            // Each milestone only moves the progress forward, so a late
+            // or repeated status can't roll the screen back
+            match round {
+                1 => {
+                    if matches!(
+                        model.wallet_state.dkg_round,
+                        DKGRound::Initialization | DKGRound::WaitingForParticipants | DKGRound::Round1
+                    ) {
+                        model.wallet_state.dkg_round = DKGRound::Round2;
+                    }
+                }
+                2 => {
+                    if model.wallet_state.dkg_round != DKGRound::Complete {
+                        model.wallet_state.dkg_round = DKGRound::Finalization;
+                    }
+                }
+                _ if !active => {
+                    model.wallet_state.dkg_round = DKGRound::Complete;
+                    model.wallet_state.dkg_in_progress = false;
+                }
+                _ => {}
+            }
+            if matches!(model.current_screen, Screen::DKGProgress { .. }) {
+                Some(Command::SendMessage(Message::ForceRemount))
+            } else {
+                None
+            }
+        }
+
         Message::DKGKeyGenerated { group_pubkey_hex } => {
             info!("🎉 DKG finalised. Group verifying key: {}", group_pubkey_hex);
             // Terminal UI state: 100% and a "done" label. Previously we set
@@ -709,15 +835,17 @@ pub fn update(model: &mut Model, msg: Message) -> Option<Command> {
                 None
             };
 
-            // Attempt reconnection with exponential backoff
+            // Attempt reconnection (and, mid-DKG, rejoin the session) with a
+            // jittered linear backoff
             let reconnect_cmd = if model.network_state.reconnect_attempts
                 <= model.network_state.max_reconnect_attempts
             {
-                let delay = 2000 * model.network_state.reconnect_attempts as u64;
+                let delay = reconnect_delay_ms(model.network_state.reconnect_attempts, jitter_sample());
                 warn!(
                     "Scheduling reconnect attempt {} in {}ms",
                     model.network_state.reconnect_attempts, delay
                 );
+                model.network_state.connection_status = ConnectionStatus::Reconnecting;
                 Some(Command::ScheduleMessage {
                     delay_ms: delay,
                     message: Box::new(Message::TriggerReconnect),
@@ -741,7 +869,13 @@ pub fn update(model: &mut Model, msg: Message) -> Option<Command> {
 
         Message::TriggerReconnect => {
             model.network_state.connection_status = ConnectionStatus::Reconnecting;
-            Some(Command::ReconnectWebSocket)
+            // Mid-DKG, a plain redial would leave the mesh and round packages behind
+            match &model.active_session {
+                Some(session) if model.wallet_state.dkg_in_progress && session.session_id != "pending" => {
+                    Some(Command::ResumeDKG { session_id: session.session_id.clone() })
+                }
+                _ => Some(Command::ReconnectWebSocket),
+            }
         }
         
         // ============= UI Events =============
@@ -1137,7 +1271,7 @@ pub fn update(model: &mut Model, msg: Message) -> Option<Command> {
                             // Settings (when no wallets)
                             info!("Navigating to Settings");
                             model.push_screen(Screen::Settings);
-                            None
+                            Some(Command::LoadSettings)
                         }
                         (2, true) => {
                             // Manage Wallets (when wallets exist)
@@ -1167,7 +1301,7 @@ pub fn update(model: &mut Model, msg: Message) -> Option<Command> {
                             // Settings (when wallets exist)
                             info!("Navigating to Settings");
                             model.push_screen(Screen::Settings);
-                            None
+                            Some(Command::LoadSettings)
                         }
                         (5, true) => {
                             // Exit (when wallets exist)
@@ -1343,7 +1477,20 @@ pub fn update(model: &mut Model, msg: Message) -> Option<Command> {
                         .collect();
                     
                     // Get the session from the filtered list
-                    if let Some(session) = filtered_sessions.get(selected_idx).cloned() {
+                    let full = filtered_sessions.get(selected_idx).filter(|s| {
+                        s.participants.len() >= s.total as usize && !s.participants.contains(&model.device_id)
+                    });
+                    if let Some(session) = full {
+                        warn!("Not joining full session {}", session.session_id);
+                        Some(Command::SendMessage(Message::Error {
+                            message: format!(
+                                "Session {} is full ({}/{} participants)",
+                                session.session_id,
+                                session.participants.len(),
+                                session.total
+                            ),
+                        }))
+                    } else if let Some(session) = filtered_sessions.get(selected_idx).cloned() {
                         info!("Joining DKG session: {}", session.session_id);
                         
                         let session_id = session.session_id.clone();
@@ -1412,10 +1559,28 @@ pub fn update(model: &mut Model, msg: Message) -> Option<Command> {
                         None
                     }
                 }
+                // Enter saves the settings shown on the screen
+                Screen::Settings => Some(Command::SendMessage(Message::SaveSettings)),
                 _ => None,
             }
         }
 
+        // ============= Settings =============
+        Message::SettingsLoaded { websocket_url, device_id, stun_turn, address_settings } => {
+            debug!("Settings loaded for {}", device_id);
+            model.network_state.websocket_url = websocket_url;
+            model.network_state.stun_turn = stun_turn;
+            model.wallet_state.address_settings = address_settings;
+            None
+        }
+
+        Message::SaveSettings => Some(Command::SaveSettings {
+            websocket_url: model.network_state.websocket_url.clone(),
+            device_id: model.device_id.clone(),
+            stun_turn: model.network_state.stun_turn.clone(),
+            address_settings: model.wallet_state.address_settings,
+        }),
+
         // ============= Modal Management =============
         Message::ShowModal(modal) => {
             model.ui_state.modal = Some(modal);
@@ -1542,6 +1707,34 @@ pub fn update(model: &mut Model, msg: Message) -> Option<Command> {
             None
         }
         
+        Message::FetchTransaction { chain, tx_hash } => {
+            // The fetched transaction must come from the wallet that will sign it
+            match model.wallet_state.selected_wallet.clone().or_else(|| model.selected_wallet.clone()) {
+                Some(wallet_id) => Some(Command::FetchTransaction { wallet_id, chain, tx_hash }),
+                None => Some(Command::SendMessage(Message::Error {
+                    message: "Select a wallet before fetching a transaction".to_string(),
+                })),
+            }
+        }
+
+        Message::TransactionFetched { wallet_id, chain, tx_hash, transaction_data, summary } => {
+            info!("Fetched {} transaction {}", chain, tx_hash);
+            model.ui_state.modal = Some(Modal::Confirm {
+                title: "Sign Transaction?".to_string(),
+                message: format!("{}\n\n{}", tx_hash, summary),
+                on_confirm: Box::new(Message::InitiateSigning {
+                    request: crate::elm::message::SigningRequest {
+                        wallet_id,
+                        transaction_data,
+                        chain,
+                        metadata: Some(summary),
+                    },
+                }),
+                on_cancel: Box::new(Message::CloseModal),
+            });
+            None
+        }
+
         Message::Success { message } => {
             info!("Success: {}", message);
             model.ui_state.success_message = Some(message.clone());
@@ -1643,9 +1836,73 @@ pub fn update(model: &mut Model, msg: Message) -> Option<Command> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::dkg_manager::DkgEvent;
     use crate::elm::model::WalletMode;
     use crossterm::event::KeyEvent;
     
+    #[test]
+    fn test_reconnect_delay_is_jittered_around_linear_backoff() {
+        for attempt in 1..=5u32 {
+            let base = RECONNECT_BASE_DELAY_MS * attempt as u64;
+            let low = (base as f64 * (1.0 - RECONNECT_JITTER)) as u64;
+            let high = (base as f64 * (1.0 + RECONNECT_JITTER)) as u64;
+            assert_eq!(reconnect_delay_ms(attempt, 0.0), low);
+            assert_eq!(reconnect_delay_ms(attempt, 0.5), base);
+            assert_eq!(reconnect_delay_ms(attempt, 1.0), high);
+            for _ in 0..100 {
+                let delay = reconnect_delay_ms(attempt, jitter_sample());
+                assert!((low..=high).contains(&delay), "attempt {}: {}ms", attempt, delay);
+            }
+        }
+        // Samples spread out rather than repeating
+        let delays: std::collections::HashSet<_> = (0..20).map(|_| reconnect_delay_ms(3, jitter_sample())).collect();
+        assert!(delays.len() > 1);
+    }
+
+    #[test]
+    fn test_dkg_events_advance_progress_only_forward() {
+        let mut model = Model::new("test".to_string());
+        model.wallet_state.dkg_in_progress = true;
+        let stages = [
+            (DkgEvent::Round1Complete, DKGRound::Round2),
+            (DkgEvent::Round2Complete, DKGRound::Finalization),
+            (DkgEvent::Finalized, DKGRound::Complete),
+            (DkgEvent::Round1Complete, DKGRound::Complete),
+        ];
+        for (event, round) in stages {
+            let (active, round_completed, progress) = event.status().unwrap();
+            update(&mut model, Message::DkgStatus { active, round: round_completed, progress });
+            assert_eq!(model.wallet_state.dkg_round, round);
+        }
+        assert!(!model.wallet_state.dkg_in_progress);
+    }
+
+    #[test]
+    fn test_settings_screen_saves_loaded_settings() {
+        let mut model = Model::new("test".to_string());
+        model.current_screen = Screen::Settings;
+        let stun_turn = crate::network::ice_config::StunTurnConfig::default();
+        let address_settings = crate::blockchain_config::AddressSettings::default();
+        update(&mut model, Message::SettingsLoaded {
+            websocket_url: "wss://signal.example".to_string(),
+            device_id: "test".to_string(),
+            stun_turn: stun_turn.clone(),
+            address_settings,
+        });
+
+        let cmd = update(&mut model, Message::SelectItem { index: 0 });
+        assert!(matches!(cmd, Some(Command::SendMessage(Message::SaveSettings))));
+        match update(&mut model, Message::SaveSettings) {
+            Some(Command::SaveSettings { websocket_url, device_id, stun_turn: saved, address_settings: saved_addresses }) => {
+                assert_eq!(websocket_url, "wss://signal.example");
+                assert_eq!(device_id, "test");
+                assert_eq!(saved, stun_turn);
+                assert_eq!(saved_addresses, address_settings);
+            }
+            other => panic!("expected SaveSettings, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_navigate_back() {
         let mut model = Model::new("test".to_string());
@@ -8,11 +8,12 @@
 //! Now both drivers just forward each `ServerMsg::Relay { from, data }` here.
 
 use crate::elm::message::Message;
+use crate::protocal::signal::WebSocketMessage;
 use crate::utils::appstate_compat::AppState;
 use frost_core::{Ciphersuite, Field, Group};
 use std::sync::Arc;
 use tokio::sync::{mpsc::UnboundedSender, Mutex};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 /// Process a `ServerMsg::Relay` frame.
 ///
@@ -32,12 +33,23 @@ pub(crate) async fn handle_relay<C>(
     <<C as Ciphersuite>::Group as Group>::Element: Send + Sync,
     <<<C as Ciphersuite>::Group as Group>::Field as Field>::Scalar: Send + Sync,
 {
+    // A chunked payload is only handled once its last chunk arrives
+    let Some(data) = app_state.lock().await.relay_reassembler.accept(&from, data) else {
+        return;
+    };
     let _ = tx_msg.send(Message::Info {
         message: format!("📨 Received relay from {}", from),
     });
 
     if from != "server" {
-        handle_webrtc_signal(from, data, app_state, tx_msg, self_device_id).await;
+        let session_manager = app_state.lock().await.session_manager.clone();
+        if let Some(manager) = session_manager {
+            manager.record_activity(&from).await;
+        }
+        match data.get("websocket_msg_type").and_then(|v| v.as_str()) {
+            Some("DKGAbort") => handle_dkg_abort(from, data, app_state, tx_msg).await,
+            _ => handle_webrtc_signal(from, data, app_state, tx_msg, self_device_id).await,
+        }
     } else {
         // Server-originated frame (currently only `participant_update`).
         handle_server_frame(
@@ -51,6 +63,77 @@ pub(crate) async fn handle_relay<C>(
     }
 }
 
+/// A peer cancelled the DKG. Tear our side down too instead of waiting for
+/// packages that will never arrive. Only a participant of the session may
+/// abort it; anyone else on the signal server is ignored.
+async fn handle_dkg_abort<C>(
+    from: String,
+    data: serde_json::Value,
+    app_state: Arc<Mutex<AppState<C>>>,
+    tx_msg: UnboundedSender<Message>,
+) where
+    C: Ciphersuite + Send + Sync + 'static,
+{
+    let Ok(WebSocketMessage::DKGAbort { session_id, reason }) = serde_json::from_value(data) else {
+        error!("❌ Malformed DKGAbort from {}", from);
+        return;
+    };
+    let from_participant = app_state.lock().await.session.as_ref().is_some_and(|session| {
+        session.session_id == session_id && session.participants.contains(&from)
+    });
+    if !from_participant {
+        warn!("Ignoring DKGAbort for {} from non-participant {}", session_id, from);
+        return;
+    }
+    info!("🛑 Received DKGAbort for {} from {}: {}", session_id, from, reason);
+    if crate::protocal::dkg::abort_dkg_session(app_state, &session_id, &reason).await {
+        let _ = tx_msg.send(Message::DKGFailed {
+            error: format!("DKG aborted by {}: {}", from, reason),
+        });
+    }
+}
+
+/// Forward every `Relay` frame on a fresh broadcast subscription to
+/// `handle_relay`. The creator/joiner drivers subscribe to the broadcast that
+/// existed when they started; after a reconnect that channel is replaced, so
+/// a resumed session needs its own forwarder on the new one.
+pub(crate) fn spawn_relay_forwarder<C>(
+    mut broadcast_rx: tokio::sync::broadcast::Receiver<Arc<webrtc_signal_server::ServerMsg>>,
+    app_state: Arc<Mutex<AppState<C>>>,
+    tx_msg: UnboundedSender<Message>,
+    self_device_id: String,
+    session_id: String,
+) where
+    C: Ciphersuite + Send + Sync + 'static,
+    <<C as Ciphersuite>::Group as Group>::Element: Send + Sync,
+    <<<C as Ciphersuite>::Group as Group>::Field as Field>::Scalar: Send + Sync,
+{
+    tokio::spawn(async move {
+        loop {
+            let shared = match broadcast_rx.recv().await {
+                Ok(m) => m,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                    info!("Relay forwarder lagged {} messages; continuing", n);
+                    continue;
+                }
+                // Replaced by the next reconnect, which starts its own forwarder
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            };
+            if let webrtc_signal_server::ServerMsg::Relay { from, data } = &*shared {
+                handle_relay(
+                    from.clone(),
+                    data.clone(),
+                    app_state.clone(),
+                    tx_msg.clone(),
+                    self_device_id.clone(),
+                    Some(session_id.clone()),
+                )
+                .await;
+            }
+        }
+    });
+}
+
 /// Handle a peer-originated WebRTC offer / answer / ICE candidate.
 async fn handle_webrtc_signal<C>(
     from: String,
@@ -136,17 +219,46 @@ async fn handle_server_frame<C>(
         return;
     }
 
-    let new_participants: Vec<String> = session_info
-        .get("participants")
-        .and_then(|v| v.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str())
-                .filter(|&p| p != self_device_id)
-                .map(String::from)
-                .collect()
-        })
-        .unwrap_or_default();
+    let mut roster = session_roster(session_info);
+    if !roster.contains(&self_device_id) {
+        roster.push(self_device_id.clone());
+    }
+    let session_total_opt = session_info.get("total").and_then(|v| v.as_u64());
+    let (admitted, rejected) = match session_total_opt {
+        Some(total) => split_at_capacity(&roster, total as usize),
+        None => (roster, Vec::new()),
+    };
+    if rejected.contains(&self_device_id) {
+        let _ = tx_msg.send(Message::DKGFailed {
+            error: format!(
+                "Session {} is already full ({} participants); not joining",
+                session_id,
+                admitted.len()
+            ),
+        });
+        return;
+    }
+    if !rejected.is_empty() {
+        let _ = tx_msg.send(Message::Error {
+            message: format!(
+                "Session {} is full; ignoring extra joiner(s) {:?}",
+                session_id, rejected
+            ),
+        });
+        // The admitted set is unchanged, so the mesh already covers it
+        let state = app_state.lock().await;
+        if let Some(session) = &state.session
+            && session.participants.len() == admitted.len()
+            && admitted.iter().all(|p| session.participants.contains(p))
+        {
+            return;
+        }
+    }
+
+    let new_participants: Vec<String> = admitted
+        .into_iter()
+        .filter(|p| *p != self_device_id)
+        .collect();
     if new_participants.is_empty() {
         return;
     }
@@ -161,7 +273,6 @@ async fn handle_server_frame<C>(
     // against a 3-person session. The second run regenerates the Round-1
     // secret and the packages stop matching. Gate on `len >= session.total`
     // so FROST only starts once, against the finalized participant set.
-    let session_total_opt = session_info.get("total").and_then(|v| v.as_u64());
     let participants_len = new_participants.len() + 1; // include self
     if let Some(total) = session_total_opt {
         if (participants_len as u64) < total {
@@ -198,7 +309,18 @@ async fn handle_server_frame<C>(
         let mut all_parts = new_participants.clone();
         all_parts.push(self_device_id.clone());
         if let Some(ref mut session) = state.session {
-            session.participants = all_parts.clone();
+            // A full roster fixes each device's index; a device that left and
+            // came back must land on the index it had before
+            let mut updated = session.clone();
+            updated.participants = all_parts.clone();
+            if let Err(e) = updated.bind_participant_indices() {
+                drop(state);
+                let _ = tx_msg.send(Message::Error {
+                    message: format!("Rejected roster for session {}: {}", session_id, e),
+                });
+                return;
+            }
+            *session = updated;
             info!("✅ Updated session participants: {:?}", all_parts);
         }
         all_parts
@@ -221,6 +343,62 @@ async fn handle_server_frame<C>(
     });
 }
 
+/// Device ids in a `session_info`'s `participants`, in join order and
+/// without duplicates
+pub(crate) fn session_roster(session_info: &serde_json::Value) -> Vec<String> {
+    let mut roster: Vec<String> = Vec::new();
+    for device in session_info
+        .get("participants")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+    {
+        if !roster.iter().any(|seen| seen == device) {
+            roster.push(device.to_string());
+        }
+    }
+    roster
+}
+
+/// The roster and total of `session_id` carried by a server
+/// `participant_update` relay, if `data` is one
+pub(crate) fn participant_update_roster(
+    data: &serde_json::Value,
+    session_id: &str,
+) -> Option<(Vec<String>, Option<u16>)> {
+    if data.get("type").and_then(|v| v.as_str()) != Some("participant_update")
+        || data.get("session_id").and_then(|v| v.as_str()) != Some(session_id)
+    {
+        return None;
+    }
+    let session_info = data.get("session_info")?;
+    let total = session_info
+        .get("total")
+        .and_then(|v| v.as_u64())
+        .and_then(|total| u16::try_from(total).ok());
+    Some((session_roster(session_info), total))
+}
+
+/// Split a session roster, in join order and without duplicates, into the
+/// first `total` devices and the extras that joined after it was full. A
+/// device beyond `total` would get an identifier the DKG has no slot for.
+pub(crate) fn split_at_capacity(roster: &[String], total: usize) -> (Vec<String>, Vec<String>) {
+    let mut admitted: Vec<String> = Vec::new();
+    let mut rejected = Vec::new();
+    for device in roster {
+        if admitted.contains(device) || rejected.contains(device) {
+            continue;
+        }
+        if admitted.len() < total {
+            admitted.push(device.clone());
+        } else {
+            rejected.push(device.clone());
+        }
+    }
+    (admitted, rejected)
+}
+
 /// Spawn a task to accept a remote WebRTC offer: create peer connection, set
 /// remote + local SDP, send answer back through the shared WebSocket channel.
 fn spawn_offer_handler<C>(
@@ -375,9 +553,10 @@ where
     <<C as Ciphersuite>::Group as Group>::Element: Send + Sync,
     <<<C as Ciphersuite>::Group as Group>::Field as Field>::Scalar: Send + Sync,
 {
-    let device_connections = {
+    let (device_connections, config) = {
         let state = app_state.lock().await;
-        state.device_connections.clone()
+        let relay_only = state.turn_relay_peers.contains(device_id);
+        (state.device_connections.clone(), state.stun_turn_config.rtc_configuration(relay_only))
     };
     let mut conns = device_connections.lock().await;
     if let Some(existing) = conns.get(device_id) {
@@ -385,10 +564,6 @@ where
     }
 
     info!("📱 Creating peer connection for {} (to handle offer)", device_id);
-    let config = webrtc::peer_connection::configuration::RTCConfiguration {
-        ice_servers: vec![],
-        ..Default::default()
-    };
     let pc = match webrtc::api::APIBuilder::new()
         .build()
         .new_peer_connection(config)
@@ -441,6 +616,11 @@ fn attach_data_channel_handler<C>(
                     {
                         let mut state = app_state.lock().await;
                         state.data_channels.insert(device_id.clone(), dc.clone());
+                        state.dc_frame_buffers.reset(&device_id);
+                        state.connection_pool.remove(&device_id);
+                        if let Err(e) = state.connection_pool.get_or_create(&device_id).await {
+                            warn!("Not health-checking the channel to {}: {}", device_id, e);
+                        }
                         info!("📦 Stored incoming data channel for {} in AppState", device_id);
                     }
                     let _ = tx_msg.send(Message::UpdateParticipantWebRTCStatus {
@@ -464,8 +644,8 @@ fn attach_data_channel_handler<C>(
                 let app_state_msg = app_state_msg.clone();
                 let tx_for_msg = tx_for_msg.clone();
                 Box::pin(async move {
-                    crate::network::webrtc::dispatch_data_channel_msg::<C>(
-                        msg.data.to_vec(),
+                    crate::network::webrtc::receive_data_channel_msg::<C>(
+                        msg,
                         device_id_recv,
                         app_state_msg,
                         Some(tx_for_msg),
@@ -563,18 +743,182 @@ fn send_answer(from_device: &str, sdp: String, ws_tx: &UnboundedSender<String>)
         error!("❌ Failed to serialize WebRTC answer wrapper for {}", from_device);
         return;
     };
-    let relay = webrtc_signal_server::ClientMsg::Relay {
-        to: from_device.to_string(),
-        data: payload,
-    };
-    let Ok(json) = serde_json::to_string(&relay) else {
-        error!("❌ Failed to serialize Relay(Answer) for {}", from_device);
-        return;
-    };
     info!("📤 Sending WebRTC answer to {} via WebSocket", from_device);
-    if let Err(e) = ws_tx.send(json) {
+    if let Err(e) = crate::network::webrtc::send_relay(ws_tx, from_device, payload) {
         error!("❌ Failed to enqueue answer for {}: {}", from_device, e);
     } else {
         info!("✅ WebRTC answer sent to {}", from_device);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocal::signal::{SessionInfo, SessionType};
+    use frost_secp256k1::Secp256K1Sha256;
+
+    fn roster(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_participant_update_roster_reads_only_our_session() {
+        let frame = serde_json::json!({
+            "type": "participant_update",
+            "session_id": "dkg-1",
+            "session_info": { "total": 3, "participants": ["mpc-1", "mpc-2", "mpc-1"] },
+        });
+        assert_eq!(
+            participant_update_roster(&frame, "dkg-1"),
+            Some((roster(&["mpc-1", "mpc-2"]), Some(3)))
+        );
+        assert_eq!(participant_update_roster(&frame, "dkg-2"), None);
+        let other = serde_json::json!({ "type": "session_removed", "session_id": "dkg-1" });
+        assert_eq!(participant_update_roster(&other, "dkg-1"), None);
+    }
+
+    #[test]
+    fn test_split_at_capacity_keeps_join_order() {
+        let (admitted, rejected) = split_at_capacity(&roster(&["mpc-1", "mpc-2", "mpc-2", "stale", "mpc-3"]), 3);
+        assert_eq!(admitted, roster(&["mpc-1", "mpc-2", "stale"]));
+        assert_eq!(rejected, roster(&["mpc-3"]));
+    }
+
+    #[tokio::test]
+    async fn test_extra_joiner_is_rejected() {
+        let mut state = AppState::<Secp256K1Sha256>::with_device_id_and_server("mpc-1".to_string(), String::new());
+        state.session = Some(SessionInfo {
+            session_id: "dkg-full".to_string(),
+            proposer_id: "mpc-1".to_string(),
+            participants: roster(&["mpc-1"]),
+            threshold: 2,
+            total: 3,
+            session_type: SessionType::DKG,
+            curve_type: "secp256k1".to_string(),
+            coordination_type: "Network".to_string(),
+            participant_indices: Default::default(),
+        });
+        let app_state = Arc::new(Mutex::new(state));
+        let update = |participants: &[&str]| {
+            serde_json::json!({
+                "type": "participant_update",
+                "session_id": "dkg-full",
+                "session_info": { "total": 3, "participants": participants },
+            })
+        };
+
+        // A fourth device joined before the creator saw the third
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let frame = update(&["mpc-1", "mpc-2", "mpc-3", "mpc-4"]);
+        handle_server_frame(frame, app_state.clone(), tx, "mpc-1".to_string(), Some("dkg-full".to_string())).await;
+        let mut mesh = None;
+        let mut rejected = false;
+        while let Ok(message) = rx.try_recv() {
+            match message {
+                Message::Error { message } => rejected |= message.contains("mpc-4"),
+                Message::InitiateWebRTCWithParticipants { participants } => mesh = Some(participants),
+                _ => {}
+            }
+        }
+        assert!(rejected);
+        let mut mesh = mesh.expect("full session starts the mesh");
+        mesh.sort();
+        assert_eq!(mesh, roster(&["mpc-1", "mpc-2", "mpc-3"]));
+        assert_eq!(app_state.lock().await.session.as_ref().unwrap().participants.len(), 3);
+
+        // Seen from the extra device, the session is full
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let frame = update(&["mpc-1", "mpc-2", "mpc-3", "mpc-4"]);
+        handle_server_frame(frame, app_state.clone(), tx, "mpc-4".to_string(), Some("dkg-full".to_string())).await;
+        assert!(matches!(rx.try_recv(), Ok(Message::DKGFailed { .. })));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_abort_from_non_participant_is_ignored() {
+        let mut state = AppState::<Secp256K1Sha256>::with_device_id_and_server("mpc-1".to_string(), String::new());
+        state.session = Some(SessionInfo {
+            session_id: "dkg-abort".to_string(),
+            proposer_id: "mpc-1".to_string(),
+            participants: roster(&["mpc-1", "mpc-2"]),
+            threshold: 2,
+            total: 2,
+            session_type: SessionType::DKG,
+            curve_type: "secp256k1".to_string(),
+            coordination_type: "Network".to_string(),
+            participant_indices: Default::default(),
+        });
+        let app_state = Arc::new(Mutex::new(state));
+        let abort = serde_json::to_value(WebSocketMessage::DKGAbort {
+            session_id: "dkg-abort".to_string(),
+            reason: "cancelled".to_string(),
+        })
+        .unwrap();
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        handle_dkg_abort("mallory".to_string(), abort.clone(), app_state.clone(), tx).await;
+        assert!(rx.try_recv().is_err());
+        assert!(app_state.lock().await.session.is_some());
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        handle_dkg_abort("mpc-2".to_string(), abort, app_state.clone(), tx).await;
+        assert!(matches!(rx.try_recv(), Ok(Message::DKGFailed { .. })));
+        assert!(app_state.lock().await.session.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rejoining_device_keeps_its_index() {
+        let mut state = AppState::<Secp256K1Sha256>::with_device_id_and_server("mpc-1".to_string(), String::new());
+        state.session = Some(SessionInfo {
+            session_id: "dkg-rejoin".to_string(),
+            proposer_id: "mpc-1".to_string(),
+            participants: roster(&["mpc-1"]),
+            threshold: 2,
+            total: 3,
+            session_type: SessionType::DKG,
+            curve_type: "secp256k1".to_string(),
+            coordination_type: "Network".to_string(),
+            participant_indices: Default::default(),
+        });
+        let app_state = Arc::new(Mutex::new(state));
+        let receive = |participants: &[&str]| {
+            let frame = serde_json::json!({
+                "type": "participant_update",
+                "session_id": "dkg-rejoin",
+                "session_info": { "total": 3, "participants": participants },
+            });
+            let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+            let app_state = app_state.clone();
+            async move {
+                handle_server_frame(frame, app_state, tx, "mpc-1".to_string(), Some("dkg-rejoin".to_string())).await;
+                let mut errors = Vec::new();
+                while let Ok(message) = rx.try_recv() {
+                    if let Message::Error { message } = message {
+                        errors.push(message);
+                    }
+                }
+                errors
+            }
+        };
+        let index_of = |device: &'static str| {
+            let app_state = app_state.clone();
+            async move { app_state.lock().await.session.as_ref().unwrap().participant_index(device) }
+        };
+
+        assert!(receive(&["mpc-1", "mpc-2", "mpc-3"]).await.is_empty());
+        assert_eq!(index_of("mpc-2").await, Some(2));
+
+        // mpc-2 drops and comes back after mpc-3
+        assert!(receive(&["mpc-1", "mpc-3"]).await.is_empty());
+        assert!(receive(&["mpc-1", "mpc-3", "mpc-2"]).await.is_empty());
+        assert_eq!(index_of("mpc-2").await, Some(2));
+        assert_eq!(index_of("mpc-3").await, Some(3));
+
+        // A newcomer in mpc-2's place would shift mpc-3
+        receive(&["mpc-1", "mpc-3"]).await;
+        let errors = receive(&["mpc-1", "mpc-3", "mpc-4"]).await;
+        assert_eq!(errors, ["Rejected roster for session dkg-rejoin: mpc-3 is bound to participant 3 and cannot move to 2"]);
+        assert_eq!(index_of("mpc-3").await, Some(3));
+        assert_eq!(index_of("mpc-4").await, None);
+    }
+}
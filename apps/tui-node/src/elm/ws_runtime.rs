@@ -8,7 +8,9 @@
 //!
 //! Lifetime: there's exactly one primary WebSocket per process. On reconnect,
 //! the old channels in `AppState` get replaced before the new socket's tasks
-//! are spawned, so stale senders just fail silently.
+//! are spawned, so stale senders just fail silently. Whatever the old sender
+//! task still had queued when its socket died is parked in the replay queue
+//! and sent on the new socket right after `Register`.
 //!
 //! **Ownership:** the new `mpsc` (outbound) and `broadcast` (inbound) channels
 //! are minted here and stashed in `AppState`. Subsystems (DKG driver, Elm loop)
@@ -20,6 +22,7 @@ use crate::utils::appstate_compat::AppState;
 use frost_core::{Ciphersuite, Field, Group};
 use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, StreamExt};
+use std::collections::VecDeque;
 use std::sync::Arc;
 use tokio::net::TcpStream;
 use tokio::sync::{broadcast, mpsc, Mutex};
@@ -32,6 +35,10 @@ pub(crate) type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 pub(crate) type WsSink = SplitSink<WsStream, WsMessage>;
 pub(crate) type WsRx = SplitStream<WsStream>;
 
+/// Outbound payloads left unsent by a dead socket, oldest first. A std mutex:
+/// it is only held to push or pop, never across an await.
+pub type ReplayQueue = Arc<std::sync::Mutex<VecDeque<String>>>;
+
 /// Parameters captured from `AppState` before we dial. Also flags the state as
 /// "connecting" and drops the stale outbound channel so no caller sends onto
 /// a dead socket between now and `install_handles`.
@@ -39,6 +46,7 @@ pub(crate) struct ConnectParams {
     pub url: String,
     pub device_id: String,
     pub existing_session: Option<SessionInfo>,
+    pub replay: ReplayQueue,
 }
 
 pub(crate) async fn read_connect_params<C>(
@@ -57,6 +65,7 @@ where
         url: state.signal_server_url.clone(),
         device_id: state.device_id.clone(),
         existing_session: state.session.clone(),
+        replay: state.websocket_replay_queue.clone(),
     }
 }
 
@@ -110,6 +119,7 @@ where
 pub(crate) async fn send_register(sink: &mut WsSink, device_id: &str) {
     let msg = webrtc_signal_server::ClientMsg::Register {
         device_id: device_id.to_string(),
+        include_sessions: false,
     };
     match serde_json::to_string(&msg) {
         Ok(json) => {
@@ -157,12 +167,49 @@ pub(crate) async fn send_reannounce(
     }
 }
 
+/// Send what the previous socket left unsent, in order. Stops at the first
+/// failure and keeps the rest queued for the next attempt. Returns how many
+/// payloads went out.
+pub(crate) async fn replay_unsent(sink: &mut WsSink, replay: &ReplayQueue) -> usize {
+    let mut sent = 0;
+    loop {
+        let Some(payload) = replay.lock().unwrap().pop_front() else {
+            return sent;
+        };
+        if let Err(e) = sink.send(WsMessage::text(payload.clone())).await {
+            error!("Failed to replay queued message: {}", e);
+            replay.lock().unwrap().push_front(payload);
+            return sent;
+        }
+        sent += 1;
+    }
+}
+
+/// Park the payload that failed plus everything still queued behind it
+fn park_unsent(
+    failed: Option<String>,
+    rx: &mut mpsc::UnboundedReceiver<String>,
+    replay: &ReplayQueue,
+) {
+    rx.close();
+    let mut queue = replay.lock().unwrap();
+    queue.extend(failed);
+    while let Ok(payload) = rx.try_recv() {
+        queue.push_back(payload);
+    }
+    if !queue.is_empty() {
+        warn!("WS sender: {} message(s) queued for replay on reconnect", queue.len());
+    }
+}
+
 /// Drain the outbound `mpsc` into the socket, with a 30s ping to keep
 /// idle connections alive (Cloudflare Workers otherwise idle-close after
-/// ~100s). Exits when either the channel closes or a send fails.
+/// ~100s). Exits when either the channel closes or a send fails; on a
+/// failure the unsent payloads move to `replay`.
 pub(crate) fn spawn_sender_task(
     mut sink: WsSink,
     mut rx: mpsc::UnboundedReceiver<String>,
+    replay: ReplayQueue,
 ) {
     tokio::spawn(async move {
         let mut ping_interval =
@@ -172,8 +219,9 @@ pub(crate) fn spawn_sender_task(
             tokio::select! {
                 msg = rx.recv() => match msg {
                     Some(payload) => {
-                        if let Err(e) = sink.send(WsMessage::text(payload)).await {
+                        if let Err(e) = sink.send(WsMessage::text(payload.clone())).await {
                             error!("❌ WS sender: send failed: {}", e);
+                            park_unsent(Some(payload), &mut rx, &replay);
                             break;
                         }
                     }
@@ -182,6 +230,7 @@ pub(crate) fn spawn_sender_task(
                 _ = ping_interval.tick() => {
                     if let Err(e) = sink.send(WsMessage::Ping(vec![].into())).await {
                         error!("❌ WS sender: ping failed: {}", e);
+                        park_unsent(None, &mut rx, &replay);
                         break;
                     }
                 }
@@ -269,6 +318,39 @@ fn dispatch_frame(
     }
 }
 
+/// Connect to an in-memory signal server instead of dialing a socket, with
+/// the same channels and frame dispatch as a real connection. Returns the
+/// inbound broadcast so callers can subscribe before registering.
+#[cfg(test)]
+pub(crate) async fn connect_in_memory<C>(
+    app_state: &Arc<Mutex<AppState<C>>>,
+    server: &webrtc_signal_server::memory::InMemorySignalServer,
+    tx_elm: mpsc::UnboundedSender<Message>,
+) -> broadcast::Sender<Arc<webrtc_signal_server::ServerMsg>>
+where
+    C: Ciphersuite + Send + Sync + 'static,
+    <<C as Ciphersuite>::Group as Group>::Element: Send + Sync,
+    <<<C as Ciphersuite>::Group as Group>::Field as Field>::Scalar: Send + Sync,
+{
+    let webrtc_signal_server::memory::InMemoryConnection { tx, mut rx } = server.connect();
+    let InstalledChannels { mut ws_msg_rx, broadcast_tx } = install_handles(app_state).await;
+    tokio::spawn(async move {
+        while let Some(payload) = ws_msg_rx.recv().await {
+            if tx.send(payload).is_err() {
+                break;
+            }
+        }
+    });
+    let reader_broadcast = broadcast_tx.clone();
+    tokio::spawn(async move {
+        while let Some(txt) = rx.recv().await {
+            dispatch_frame(&txt, &tx_elm, &reader_broadcast);
+        }
+        let _ = tx_elm.send(Message::WebSocketDisconnected);
+    });
+    broadcast_tx
+}
+
 /// Handle the dial failure: mark state disconnected and tell Elm.
 pub(crate) async fn handle_dial_failure<C>(
     err: tokio_tungstenite::tungstenite::Error,
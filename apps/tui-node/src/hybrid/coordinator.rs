@@ -1,8 +1,17 @@
 //! Hybrid mode coordinator for managing mixed online/offline participants
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex};
+use frost_secp256k1::keys::PublicKeyPackage;
+use frost_secp256k1::round1::SigningCommitments;
+use frost_secp256k1::round2::SignatureShare;
+use frost_secp256k1::{Identifier, Signature, SigningPackage};
 use serde::{Serialize, Deserialize};
+use super::transport::HybridTransport;
+use crate::core::session_manager::SessionManager;
+
+/// Participant id of a coordinator that doesn't sign itself
+pub const COORDINATOR_ID: u16 = 0;
 
 /// Participant operational mode
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -35,6 +44,35 @@ pub enum HybridMessage {
     SignatureShare(Vec<u8>),
     /// Transaction to sign
     Transaction(Vec<u8>),
+    /// Signing package sent back to the chosen signers
+    SigningPackage(Vec<u8>),
+}
+
+/// Payload of a signing message: the sender's participant id plus the
+/// JSON-encoded FROST object, so the coordinator can key it by identifier
+pub fn signing_payload<T: Serialize>(from: u16, value: &T) -> Result<Vec<u8>, String> {
+    serde_json::to_vec(&(from, value)).map_err(|e| format!("Failed to serialize: {}", e))
+}
+
+/// Inverse of `signing_payload`
+pub fn parse_signing_payload<T: serde::de::DeserializeOwned>(data: &[u8]) -> Result<(u16, T), String> {
+    serde_json::from_slice(data).map_err(|e| format!("Failed to deserialize: {}", e))
+}
+
+/// SD card filename for a signing message of `kind` from or for `participant`
+/// (`signing_commitment`, `signing_package` or `signature_share`)
+pub fn sd_filename(kind: &str, participant: u16) -> String {
+    format!("{}_p{}.json", kind, participant)
+}
+
+/// State of the signing round driven by `collect_shares`
+#[derive(Default)]
+struct SigningRound {
+    /// Whose mailbox the round collects from
+    coordinator: u16,
+    commitments: BTreeMap<Identifier, SigningCommitments>,
+    package: Option<SigningPackage>,
+    shares: BTreeMap<Identifier, SignatureShare>,
 }
 
 /// Hybrid coordinator for managing mixed online/offline participants
@@ -50,6 +88,9 @@ pub struct HybridCoordinator {
     
     /// Current round for coordination
     current_round: u8,
+
+    /// Signing round in progress
+    signing: SigningRound,
 }
 
 impl HybridCoordinator {
@@ -60,6 +101,7 @@ impl HybridCoordinator {
             online_queue: Arc::new(Mutex::new(HashMap::new())),
             offline_storage: Arc::new(Mutex::new(HashMap::new())),
             current_round: 0,
+            signing: SigningRound::default(),
         }
     }
     
@@ -256,6 +298,165 @@ impl HybridCoordinator {
     }
 }
 
+/// Signing across both transports: online signers send to the coordinator
+/// over `OnlineTransport`, offline signers leave `sd_filename` files on the
+/// SD card. The coordinator doesn't care which mode a signer is in.
+impl HybridCoordinator {
+    /// Participant id of the session's elected signing coordinator (see
+    /// `session_manager::elect_coordinator`), where signers send their
+    /// commitments and shares
+    pub async fn signing_coordinator(&self, sessions: &SessionManager) -> Option<u16> {
+        let name = sessions.signing_coordinator().await?;
+        self.participants.values().find(|p| p.name == name).map(|p| p.id)
+    }
+
+    /// Drive the signing round if `sessions` elects this node as coordinator;
+    /// every other node only signs. Returns `Ok(None)` while waiting or while
+    /// another node coordinates. When a dropout hands coordination to this
+    /// node, the round starts over: the signing package the previous
+    /// coordinator sent is void and signers commit afresh.
+    pub async fn coordinate_signing(
+        &mut self,
+        sessions: &SessionManager,
+        transport: &HybridTransport,
+        message: &[u8],
+        threshold: usize,
+        public_key_package: &PublicKeyPackage,
+    ) -> Result<Option<Signature>, String> {
+        if !sessions.is_signing_coordinator().await {
+            return Ok(None);
+        }
+        let coordinator = self
+            .signing_coordinator(sessions)
+            .await
+            .ok_or("Elected coordinator is not a registered participant")?;
+        self.collect_shares(coordinator, transport, message, threshold, public_key_package)
+    }
+
+    /// The package of the round this node coordinates, for signing its own share
+    pub fn signing_package(&self) -> Option<&SigningPackage> {
+        self.signing.package.as_ref()
+    }
+
+    /// Poll both transports for the signing round collected in
+    /// `coordinator`'s mailbox. Once `threshold` commitments are in, the
+    /// signing package goes out to those signers; once all of them have
+    /// returned a share, the signature is aggregated and returned. Returns
+    /// `Ok(None)` while still waiting.
+    pub fn collect_shares(
+        &mut self,
+        coordinator: u16,
+        transport: &HybridTransport,
+        message: &[u8],
+        threshold: usize,
+        public_key_package: &PublicKeyPackage,
+    ) -> Result<Option<Signature>, String> {
+        if self.signing.coordinator != coordinator {
+            self.signing = SigningRound { coordinator, ..SigningRound::default() };
+        }
+        let mut incoming = transport.online.receive(coordinator);
+        let files = transport.offline.list_sd_files();
+        for participant in self.get_offline_participants() {
+            for kind in ["signing_commitment", "signature_share"] {
+                let filename = sd_filename(kind, participant.id);
+                if files.contains(&filename) {
+                    incoming.push(transport.offline.import_from_sd(coordinator, &filename)?);
+                }
+            }
+        }
+        for data in incoming {
+            let message: HybridMessage = serde_json::from_slice(&data)
+                .map_err(|e| format!("Failed to deserialize: {}", e))?;
+            self.record_signing_message(message)?;
+        }
+
+        if self.signing.package.is_none() && self.signing.commitments.len() >= threshold {
+            let commitments: BTreeMap<_, _> = self.signing.commitments.iter()
+                .take(threshold)
+                .map(|(id, c)| (*id, *c))
+                .collect();
+            let package = SigningPackage::new(commitments, message);
+            self.dispatch_signing_package(transport, &package)?;
+            self.signing.package = Some(package);
+        }
+
+        let Some(package) = &self.signing.package else {
+            return Ok(None);
+        };
+        let signers = package.signing_commitments();
+        if !signers.keys().all(|id| self.signing.shares.contains_key(id)) {
+            return Ok(None);
+        }
+        let shares: BTreeMap<_, _> = self.signing.shares.iter()
+            .filter(|(id, _)| signers.contains_key(id))
+            .map(|(id, share)| (*id, *share))
+            .collect();
+        mpc_wallet_frost_core::validate_contributions(package, &shares, public_key_package)
+            .map_err(|e| e.to_string())?;
+        let signature = frost_secp256k1::aggregate(package, &shares, public_key_package)
+            .map_err(|e| format!("Failed to aggregate: {}", e))?;
+        println!("  ✍️ Aggregated signature from {} signers", shares.len());
+        self.signing = SigningRound { coordinator, ..SigningRound::default() };
+        Ok(Some(signature))
+    }
+
+    /// Store a commitment or share under the sender's FROST identifier
+    fn record_signing_message(&mut self, message: HybridMessage) -> Result<(), String> {
+        match message {
+            HybridMessage::SigningCommitment(data) => {
+                let (from, commitments) = parse_signing_payload(&data)?;
+                let identifier = self.identifier_of(from)?;
+                self.signing.commitments.insert(identifier, commitments);
+            }
+            HybridMessage::SignatureShare(data) => {
+                let (from, share) = parse_signing_payload(&data)?;
+                let identifier = self.identifier_of(from)?;
+                self.signing.shares.insert(identifier, share);
+            }
+            other => println!("  ⚠️ Ignoring {:?} during signing", other),
+        }
+        Ok(())
+    }
+
+    /// Send the package to each chosen signer over that signer's transport
+    fn dispatch_signing_package(
+        &self,
+        transport: &HybridTransport,
+        package: &SigningPackage,
+    ) -> Result<(), String> {
+        let coordinator = self.signing.coordinator;
+        let data = serde_json::to_vec(&HybridMessage::SigningPackage(
+            signing_payload(coordinator, package)?,
+        ))
+        .map_err(|e| format!("Failed to serialize: {}", e))?;
+
+        for participant in self.participants.values() {
+            // The coordinator signs from `signing_package` instead
+            if participant.id == coordinator
+                || !package.signing_commitments().contains_key(&participant.identifier)
+            {
+                continue;
+            }
+            match participant.mode {
+                ParticipantMode::Online => transport.online.send(participant.id, data.clone())?,
+                ParticipantMode::Offline => transport.offline.export_to_sd(
+                    coordinator,
+                    &sd_filename("signing_package", participant.id),
+                    data.clone(),
+                )?,
+            }
+        }
+        Ok(())
+    }
+
+    fn identifier_of(&self, participant_id: u16) -> Result<Identifier, String> {
+        self.participants
+            .get(&participant_id)
+            .map(|p| p.identifier)
+            .ok_or_else(|| format!("Participant {} not found", participant_id))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -300,4 +501,177 @@ mod tests {
         coordinator.restore_network(vec![1, 2]);
         assert_eq!(coordinator.get_online_participants().len(), 2);
     }
-}
\ No newline at end of file
+    
+    /// Sign whatever package is waiting for `id` and return the share message
+    fn sign_package(id: u16, package_message: &[u8], key_package: &frost_secp256k1::keys::KeyPackage,
+                    nonces: &frost_secp256k1::round1::SigningNonces) -> Vec<u8> {
+        let HybridMessage::SigningPackage(data) = serde_json::from_slice(package_message).unwrap() else {
+            panic!("expected a signing package");
+        };
+        let (_, package): (u16, SigningPackage) = parse_signing_payload(&data).unwrap();
+        let share = frost_secp256k1::round2::sign(&package, nonces, key_package).unwrap();
+        serde_json::to_vec(&HybridMessage::SignatureShare(signing_payload(id, &share).unwrap())).unwrap()
+    }
+
+    #[test]
+    fn test_online_and_offline_signers_complete_2_of_2() {
+        let mut rng = frost_secp256k1::rand_core::OsRng;
+        let (shares, public_key_package) = frost_secp256k1::keys::generate_with_dealer(
+            2, 2, frost_secp256k1::keys::IdentifierList::Default, rng,
+        ).unwrap();
+        let key_package = |id: u16| {
+            let identifier = Identifier::try_from(id).unwrap();
+            frost_secp256k1::keys::KeyPackage::try_from(shares[&identifier].clone()).unwrap()
+        };
+        let (alice_key, bob_key) = (key_package(1), key_package(2));
+
+        let mut coordinator = HybridCoordinator::new();
+        coordinator.register_participant(1, "Alice", ParticipantMode::Online);
+        coordinator.register_participant(2, "Bob", ParticipantMode::Offline);
+        let transport = HybridTransport::new(0);
+        transport.online.connect(COORDINATOR_ID);
+        transport.online.connect(1);
+        let message = b"hybrid 2-of-2";
+
+        // Alice commits over the network, Bob via the SD card
+        let (alice_nonces, alice_commitments) = frost_secp256k1::round1::commit(alice_key.signing_share(), &mut rng);
+        let (bob_nonces, bob_commitments) = frost_secp256k1::round1::commit(bob_key.signing_share(), &mut rng);
+        let commitment = |id, c: &SigningCommitments| {
+            serde_json::to_vec(&HybridMessage::SigningCommitment(signing_payload(id, c).unwrap())).unwrap()
+        };
+        transport.online.send(COORDINATOR_ID, commitment(1, &alice_commitments)).unwrap();
+        transport.offline.export_to_sd(2, &sd_filename("signing_commitment", 2), commitment(2, &bob_commitments)).unwrap();
+
+        assert!(coordinator.collect_shares(COORDINATOR_ID, &transport, message, 2, &public_key_package).unwrap().is_none());
+
+        let alice_package = transport.online.receive(1).pop().unwrap();
+        let alice_share = sign_package(1, &alice_package, &alice_key, &alice_nonces);
+        transport.online.send(COORDINATOR_ID, alice_share).unwrap();
+        let bob_package = transport.offline.import_from_sd(2, &sd_filename("signing_package", 2)).unwrap();
+        let bob_share = sign_package(2, &bob_package, &bob_key, &bob_nonces);
+        transport.offline.export_to_sd(2, &sd_filename("signature_share", 2), bob_share).unwrap();
+
+        let signature = coordinator.collect_shares(COORDINATOR_ID, &transport, message, 2, &public_key_package)
+            .unwrap()
+            .expect("both shares are in");
+        assert!(public_key_package.verifying_key().verify(message, &signature).is_ok());
+    }
+
+    struct NoopUi;
+
+    #[async_trait::async_trait]
+    impl crate::core::UICallback for NoopUi {
+        async fn update_connection_status(&self, _: bool, _: bool) {}
+        async fn update_mesh_connections(&self, _: Vec<crate::core::ConnectionInfo>) {}
+        async fn update_operation_mode(&self, _: crate::core::OperationMode) {}
+        async fn update_wallets(&self, _: Vec<crate::core::WalletInfo>) {}
+        async fn update_active_wallet(&self, _: usize) {}
+        async fn update_available_sessions(&self, _: Vec<crate::core::SessionInfo>) {}
+        async fn update_active_session(&self, _: Option<crate::core::SessionInfo>) {}
+        async fn update_dkg_status(&self, _: bool, _: u8, _: f32) {}
+        async fn update_dkg_participants(&self, _: Vec<crate::core::ParticipantInfo>) {}
+        async fn update_offline_status(&self, _: bool, _: bool) {}
+        async fn update_sd_operations(&self, _: Vec<crate::core::SDCardOperation>) {}
+        async fn show_message(&self, _: String, _: bool) {}
+        async fn show_progress(&self, _: String, _: f32) {}
+        async fn request_confirmation(&self, _: String) -> bool { true }
+    }
+
+    /// One node of a 2-of-3 group: its coordinator view and liveness tracking
+    async fn node(local: &str, timeout: std::time::Duration) -> (HybridCoordinator, SessionManager) {
+        let mut coordinator = HybridCoordinator::new();
+        for (id, name) in [(1, "alice"), (2, "bob"), (3, "carol")] {
+            coordinator.register_participant(id, name, ParticipantMode::Online);
+        }
+        let sessions = SessionManager::new(Arc::new(crate::core::CoreState::new()), Arc::new(NoopUi))
+            .with_liveness_timeout(timeout);
+        sessions
+            .track_session(
+                crate::core::SessionInfo {
+                    session_id: "signing".to_string(),
+                    initiator: "alice".to_string(),
+                    participants: vec!["alice".to_string(), "bob".to_string(), "carol".to_string()],
+                    threshold: (2, 3),
+                    status: crate::core::SessionStatus::InProgress,
+                    created_at: String::new(),
+                },
+                local,
+            )
+            .await;
+        (coordinator, sessions)
+    }
+
+    /// The signing package in `id`'s mailbox that came from `coordinator`
+    fn package_from(transport: &HybridTransport, id: u16, coordinator: u16) -> SigningPackage {
+        transport.online.receive(id).iter()
+            .filter_map(|data| match serde_json::from_slice(data).unwrap() {
+                HybridMessage::SigningPackage(payload) => Some(parse_signing_payload(&payload).unwrap()),
+                _ => None,
+            })
+            .find_map(|(from, package): (u16, SigningPackage)| (from == coordinator).then_some(package))
+            .expect("no signing package from the coordinator")
+    }
+
+    #[tokio::test]
+    async fn test_elected_successor_completes_signing_after_coordinator_drops() {
+        use std::time::Duration;
+
+        let mut rng = frost_secp256k1::rand_core::OsRng;
+        let (shares, public_key_package) = frost_secp256k1::keys::generate_with_dealer(
+            3, 2, frost_secp256k1::keys::IdentifierList::Default, rng,
+        ).unwrap();
+        let key_package = |id: u16| {
+            let identifier = Identifier::try_from(id).unwrap();
+            frost_secp256k1::keys::KeyPackage::try_from(shares[&identifier].clone()).unwrap()
+        };
+        let transport = HybridTransport::new(0);
+        for id in 1..=3 {
+            transport.online.connect(id);
+        }
+        let timeout = Duration::from_millis(50);
+        let (mut alice, alice_sessions) = node("alice", timeout).await;
+        let (mut bob, bob_sessions) = node("bob", timeout).await;
+        let message = b"coordinator handover";
+        let commitment = |id, c: &SigningCommitments| {
+            serde_json::to_vec(&HybridMessage::SigningCommitment(signing_payload(id, c).unwrap())).unwrap()
+        };
+
+        // Alice has the lowest index and collects Bob's and Carol's commitments
+        assert_eq!(bob.signing_coordinator(&bob_sessions).await, Some(1));
+        for id in [2, 3] {
+            let (_, commitments) = frost_secp256k1::round1::commit(key_package(id).signing_share(), &mut rng);
+            transport.online.send(1, commitment(id, &commitments)).unwrap();
+        }
+        assert!(bob.coordinate_signing(&bob_sessions, &transport, message, 2, &public_key_package).await.unwrap().is_none());
+        assert!(alice.coordinate_signing(&alice_sessions, &transport, message, 2, &public_key_package).await.unwrap().is_none());
+        assert!(alice.signing_package().is_some());
+
+        // Alice goes quiet before any share reaches her; Bob has the next index
+        bob_sessions.check_liveness().await;
+        tokio::time::sleep(timeout * 2).await;
+        bob_sessions.record_activity("carol").await;
+        bob_sessions.check_liveness().await;
+        assert_eq!(bob.signing_coordinator(&bob_sessions).await, Some(2));
+
+        // Signers commit afresh to the successor, who drives the round to the end
+        let (bob_nonces, bob_commitments) = frost_secp256k1::round1::commit(key_package(2).signing_share(), &mut rng);
+        let (carol_nonces, carol_commitments) = frost_secp256k1::round1::commit(key_package(3).signing_share(), &mut rng);
+        transport.online.send(2, commitment(2, &bob_commitments)).unwrap();
+        transport.online.send(2, commitment(3, &carol_commitments)).unwrap();
+        assert!(bob.coordinate_signing(&bob_sessions, &transport, message, 2, &public_key_package).await.unwrap().is_none());
+
+        let package = bob.signing_package().unwrap().clone();
+        let bob_share = frost_secp256k1::round2::sign(&package, &bob_nonces, &key_package(2)).unwrap();
+        let carol_package = package_from(&transport, 3, 2);
+        let carol_share = frost_secp256k1::round2::sign(&carol_package, &carol_nonces, &key_package(3)).unwrap();
+        for (id, share) in [(2, bob_share), (3, carol_share)] {
+            let data = serde_json::to_vec(&HybridMessage::SignatureShare(signing_payload(id, &share).unwrap())).unwrap();
+            transport.online.send(2, data).unwrap();
+        }
+        let signature = bob.coordinate_signing(&bob_sessions, &transport, message, 2, &public_key_package)
+            .await
+            .unwrap()
+            .expect("both shares are in");
+        assert!(public_key_package.verifying_key().verify(message, &signature).is_ok());
+    }
+}
@@ -0,0 +1,115 @@
+//! Storage backends for the keystore.
+//!
+//! The keystore addresses its files by `/`-separated keys such as
+//! `<device_id>/<curve>/<wallet_id>.json`. A backend maps those keys onto
+//! whatever actually holds the bytes; the filesystem is the default.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+use super::{KeystoreError, Result};
+
+/// Byte store the keystore reads and writes its files through
+pub trait KeystoreBackend: Send + Sync {
+    /// Contents stored under `key`, or `None` if there is nothing there
+    fn read(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Stores `data` under `key`, replacing anything already there
+    fn write(&self, key: &str, data: &[u8]) -> Result<()>;
+
+    /// Keys stored directly under `prefix`, i.e. `<prefix>/<name>`
+    fn list(&self, prefix: &str) -> Result<Vec<String>>;
+
+    /// Removes `key`; removing a missing key is not an error
+    fn delete(&self, key: &str) -> Result<()>;
+
+    /// Adds `data` to the end of `key`, creating it if needed
+    fn append(&self, key: &str, data: &[u8]) -> Result<()> {
+        let mut contents = self.read(key)?.unwrap_or_default();
+        contents.extend_from_slice(data);
+        self.write(key, &contents)
+    }
+}
+
+/// Keeps every key as a file under a root directory
+pub struct FilesystemBackend {
+    root: PathBuf,
+}
+
+impl FilesystemBackend {
+    /// Uses `root` as the keystore directory, creating it if needed
+    pub fn new(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    /// Creates the directory `key` so it exists even before anything is written to it
+    pub fn create_dir(&self, key: &str) -> Result<()> {
+        fs::create_dir_all(self.path(key)?)?;
+        Ok(())
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        let relative = Path::new(key);
+        if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(KeystoreError::General(format!("Invalid keystore key '{}'", key)));
+        }
+        Ok(self.root.join(relative))
+    }
+
+    /// Path of `key`, with its directory created
+    fn path_with_parent(&self, key: &str) -> Result<PathBuf> {
+        let path = self.path(key)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        Ok(path)
+    }
+}
+
+impl KeystoreBackend for FilesystemBackend {
+    fn read(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match fs::read(self.path(key)?) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write(&self, key: &str, data: &[u8]) -> Result<()> {
+        fs::write(self.path_with_parent(key)?, data)?;
+        Ok(())
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let dir = self.path(prefix)?;
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut keys = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file()
+                && let Some(name) = entry.file_name().to_str()
+            {
+                keys.push(format!("{}/{}", prefix, name));
+            }
+        }
+        Ok(keys)
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        match fs::remove_file(self.path(key)?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn append(&self, key: &str, data: &[u8]) -> Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(self.path_with_parent(key)?)?;
+        file.write_all(data)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,384 @@
+//! Compressed and chunked keystore backups
+//!
+//! By default a backup is a single uncompressed JSON file, as before. For
+//! large backups moved over constrained channels (QR codes, small removable
+//! media) the payload can be gzip-compressed and split into numbered chunks
+//! described by a manifest. Restoring checks that every chunk is present and
+//! matches its checksum before reassembling.
+
+use super::{ExtensionKeystoreBackup, KeystoreError, Result};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Current manifest format version
+pub const BACKUP_MANIFEST_VERSION: u8 = 1;
+
+/// Largest backup restored, compressed or not. Manifests and gzip streams
+/// come from outside, so neither may size an allocation on its own.
+pub const MAX_BACKUP_SIZE: usize = 64 * 1024 * 1024;
+
+/// How a backup is written
+#[derive(Debug, Clone, Default)]
+pub struct BackupOptions {
+    /// Gzip-compress the serialized backup
+    pub compress: bool,
+    /// Split the (possibly compressed) payload into chunks of at most this many bytes
+    pub chunk_size: Option<usize>,
+}
+
+/// Describes a chunked backup so it can be reassembled and validated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub version: u8,
+    pub compressed: bool,
+    /// Size of the payload before chunking
+    pub total_size: usize,
+    /// SHA-256 of the full payload (hex)
+    pub sha256: String,
+    pub chunks: Vec<BackupChunkInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupChunkInfo {
+    pub index: usize,
+    pub size: usize,
+    /// SHA-256 of this chunk (hex)
+    pub sha256: String,
+}
+
+/// Serialized backup, ready to be written out
+#[derive(Debug, Clone)]
+pub enum BackupPayload {
+    /// One file; gzip-compressed when `compressed` is set
+    Single { data: Vec<u8>, compressed: bool },
+    /// Manifest plus numbered chunks, in index order
+    Chunked {
+        manifest: BackupManifest,
+        chunks: Vec<Vec<u8>>,
+    },
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+fn gzip(data: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+/// Decompress at most `limit` bytes; a stream that inflates past it fails
+fn gunzip(data: &[u8], limit: usize) -> Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    GzDecoder::new(data)
+        .take(limit as u64 + 1)
+        .read_to_end(&mut decoded)
+        .map_err(|e| KeystoreError::General(format!("Failed to decompress backup: {}", e)))?;
+    if decoded.len() > limit {
+        return Err(KeystoreError::General(format!(
+            "Backup exceeds {} bytes when decompressed",
+            limit
+        )));
+    }
+    Ok(decoded)
+}
+
+fn parse_backup(data: &[u8], compressed: bool) -> Result<ExtensionKeystoreBackup> {
+    let json = if compressed { gunzip(data, MAX_BACKUP_SIZE)? } else { data.to_vec() };
+    serde_json::from_slice(&json).map_err(|e| KeystoreError::SerializationError(e.to_string()))
+}
+
+/// Serialize a backup according to `options`
+pub fn export_backup(
+    backup: &ExtensionKeystoreBackup,
+    options: &BackupOptions,
+) -> Result<BackupPayload> {
+    let json = serde_json::to_vec(backup)
+        .map_err(|e| KeystoreError::SerializationError(e.to_string()))?;
+    let data = if options.compress { gzip(&json)? } else { json };
+
+    let chunk_size = match options.chunk_size {
+        None => {
+            return Ok(BackupPayload::Single {
+                data,
+                compressed: options.compress,
+            });
+        }
+        Some(0) => return Err(KeystoreError::General("Chunk size must be greater than zero".to_string())),
+        Some(size) => size,
+    };
+
+    let chunks: Vec<Vec<u8>> = data.chunks(chunk_size).map(<[u8]>::to_vec).collect();
+    let manifest = BackupManifest {
+        version: BACKUP_MANIFEST_VERSION,
+        compressed: options.compress,
+        total_size: data.len(),
+        sha256: sha256_hex(&data),
+        chunks: chunks
+            .iter()
+            .enumerate()
+            .map(|(index, chunk)| BackupChunkInfo {
+                index,
+                size: chunk.len(),
+                sha256: sha256_hex(chunk),
+            })
+            .collect(),
+    };
+    Ok(BackupPayload::Chunked { manifest, chunks })
+}
+
+/// Restore a backup from its serialized form
+pub fn restore_backup(payload: &BackupPayload) -> Result<ExtensionKeystoreBackup> {
+    match payload {
+        BackupPayload::Single { data, compressed } => parse_backup(data, *compressed),
+        BackupPayload::Chunked { manifest, chunks } => restore_chunked(manifest, chunks),
+    }
+}
+
+/// Reassemble a chunked backup, checking that every chunk is present and intact
+pub fn restore_chunked(manifest: &BackupManifest, chunks: &[Vec<u8>]) -> Result<ExtensionKeystoreBackup> {
+    if manifest.version != BACKUP_MANIFEST_VERSION {
+        return Err(KeystoreError::General(format!(
+            "Unsupported backup manifest version {}",
+            manifest.version
+        )));
+    }
+    if chunks.len() != manifest.chunks.len() {
+        return Err(KeystoreError::General(format!(
+            "Backup incomplete: expected {} chunks, got {}",
+            manifest.chunks.len(),
+            chunks.len()
+        )));
+    }
+
+    // Only reserve what the chunks actually hold, however large the
+    // manifest claims the backup is
+    let chunk_bytes: usize = chunks.iter().map(Vec::len).sum();
+    if manifest.total_size > MAX_BACKUP_SIZE || chunk_bytes != manifest.total_size {
+        return Err(KeystoreError::General("Reassembled backup does not match manifest".to_string()));
+    }
+
+    let mut data = Vec::with_capacity(manifest.total_size);
+    for (info, chunk) in manifest.chunks.iter().zip(chunks) {
+        if chunk.len() != info.size || sha256_hex(chunk) != info.sha256 {
+            return Err(KeystoreError::General(format!(
+                "Backup chunk {} is corrupted",
+                info.index
+            )));
+        }
+        data.extend_from_slice(chunk);
+    }
+    if data.len() != manifest.total_size || sha256_hex(&data) != manifest.sha256 {
+        return Err(KeystoreError::General("Reassembled backup does not match manifest".to_string()));
+    }
+
+    parse_backup(&data, manifest.compressed)
+}
+
+fn manifest_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.manifest.json", name))
+}
+
+fn chunk_path(dir: &Path, name: &str, index: usize) -> PathBuf {
+    dir.join(format!("{}.part{:04}", name, index))
+}
+
+/// Write a backup to `dir`, returning the files created
+///
+/// A single backup is written to `<name>.json` (or `<name>.json.gz`); a chunked
+/// backup to `<name>.manifest.json` plus `<name>.part0000`, `<name>.part0001`, ...
+pub fn write_backup_files(
+    dir: impl AsRef<Path>,
+    name: &str,
+    backup: &ExtensionKeystoreBackup,
+    options: &BackupOptions,
+) -> Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+
+    match export_backup(backup, options)? {
+        BackupPayload::Single { data, compressed } => {
+            let extension = if compressed { "json.gz" } else { "json" };
+            let path = dir.join(format!("{}.{}", name, extension));
+            fs::write(&path, data)?;
+            Ok(vec![path])
+        }
+        BackupPayload::Chunked { manifest, chunks } => {
+            let mut paths = Vec::with_capacity(chunks.len() + 1);
+            let path = manifest_path(dir, name);
+            let manifest_json = serde_json::to_vec_pretty(&manifest)
+                .map_err(|e| KeystoreError::SerializationError(e.to_string()))?;
+            fs::write(&path, manifest_json)?;
+            paths.push(path);
+
+            for (index, chunk) in chunks.iter().enumerate() {
+                let path = chunk_path(dir, name, index);
+                fs::write(&path, chunk)?;
+                paths.push(path);
+            }
+            Ok(paths)
+        }
+    }
+}
+
+/// Read a backup written by [`write_backup_files`]
+///
+/// `path` is either a single backup file or a `.manifest.json`, in which case
+/// the chunks are read from alongside it.
+pub fn read_backup_files(path: impl AsRef<Path>) -> Result<ExtensionKeystoreBackup> {
+    let path = path.as_ref();
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| KeystoreError::General(format!("Invalid backup path: {}", path.display())))?;
+
+    if let Some(name) = file_name.strip_suffix(".manifest.json") {
+        let manifest: BackupManifest = serde_json::from_slice(&fs::read(path)?)
+            .map_err(|e| KeystoreError::SerializationError(e.to_string()))?;
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+
+        let mut chunks = Vec::with_capacity(manifest.chunks.len());
+        for info in &manifest.chunks {
+            let chunk_file = chunk_path(dir, name, info.index);
+            let chunk = fs::read(&chunk_file).map_err(|_| {
+                KeystoreError::General(format!(
+                    "Backup incomplete: missing chunk {} ({})",
+                    info.index,
+                    chunk_file.display()
+                ))
+            })?;
+            chunks.push(chunk);
+        }
+        restore_chunked(&manifest, &chunks)
+    } else {
+        parse_backup(&fs::read(path)?, file_name.ends_with(".gz"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keystore::{ExtensionBackupWallet, ExtensionWalletMetadata};
+    use crate::keystore::extension_compat::ExtensionEncryptedKeyShare;
+    use tempfile::TempDir;
+
+    fn large_backup() -> ExtensionKeystoreBackup {
+        let wallets = (0..200)
+            .map(|i| ExtensionBackupWallet {
+                metadata: ExtensionWalletMetadata {
+                    id: format!("wallet-{}", i),
+                    name: format!("Wallet {}", i),
+                    blockchain: if i % 2 == 0 { "ethereum" } else { "solana" }.to_string(),
+                    address: format!("0x{:040x}", i),
+                    session_id: format!("session-{}", i),
+                    is_active: i % 3 == 0,
+                    has_backup: true,
+                },
+                encrypted_share: ExtensionEncryptedKeyShare {
+                    wallet_id: format!("wallet-{}", i),
+                    algorithm: "AES-GCM".to_string(),
+                    salt: "c2FsdHNhbHRzYWx0c2FsdA==".to_string(),
+                    iv: "aXZpdml2aXZpdml2".to_string(),
+                    ciphertext: "Y2lwaGVydGV4dA==".repeat(40),
+                    auth_tag: None,
+                },
+            })
+            .collect();
+
+        ExtensionKeystoreBackup {
+            version: "1.0.0".to_string(),
+            device_id: "device-1".to_string(),
+            exported_at: 1_700_000_000_000,
+            wallets,
+        }
+    }
+
+    fn assert_same(a: &ExtensionKeystoreBackup, b: &ExtensionKeystoreBackup) {
+        assert_eq!(serde_json::to_value(a).unwrap(), serde_json::to_value(b).unwrap());
+    }
+
+    #[test]
+    fn test_default_is_single_uncompressed() {
+        let backup = large_backup();
+        let payload = export_backup(&backup, &BackupOptions::default()).unwrap();
+        match &payload {
+            BackupPayload::Single { data, compressed } => {
+                assert!(!compressed);
+                assert_eq!(data, &serde_json::to_vec(&backup).unwrap());
+            }
+            BackupPayload::Chunked { .. } => panic!("default export should not be chunked"),
+        }
+        assert_same(&restore_backup(&payload).unwrap(), &backup);
+    }
+
+    #[test]
+    fn test_compressed_chunked_roundtrip() {
+        let backup = large_backup();
+        let options = BackupOptions {
+            compress: true,
+            chunk_size: Some(1024),
+        };
+        let uncompressed_size = serde_json::to_vec(&backup).unwrap().len();
+
+        let temp_dir = TempDir::new().unwrap();
+        let paths = write_backup_files(temp_dir.path(), "backup", &backup, &options).unwrap();
+        assert!(paths.len() > 2);
+
+        let manifest: BackupManifest = serde_json::from_slice(&fs::read(&paths[0]).unwrap()).unwrap();
+        assert!(manifest.compressed);
+        assert!(manifest.total_size < uncompressed_size);
+        assert_eq!(manifest.chunks.len(), paths.len() - 1);
+
+        let restored = read_backup_files(&paths[0]).unwrap();
+        assert_same(&restored, &backup);
+
+        // A missing chunk is reported instead of producing a truncated backup
+        fs::remove_file(&paths[2]).unwrap();
+        let err = read_backup_files(&paths[0]).unwrap_err();
+        assert!(err.to_string().contains("missing chunk 1"));
+    }
+
+    #[test]
+    fn test_corrupted_chunk_rejected() {
+        let backup = large_backup();
+        let options = BackupOptions {
+            compress: false,
+            chunk_size: Some(4096),
+        };
+        let BackupPayload::Chunked { manifest, mut chunks } = export_backup(&backup, &options).unwrap() else {
+            panic!("expected a chunked backup");
+        };
+        chunks[0][0] ^= 0xff;
+        assert!(restore_chunked(&manifest, &chunks).is_err());
+        assert!(restore_chunked(&manifest, &chunks[1..]).is_err());
+    }
+
+    #[test]
+    fn test_oversized_backups_rejected() {
+        let backup = large_backup();
+        let options = BackupOptions {
+            compress: false,
+            chunk_size: Some(4096),
+        };
+        let BackupPayload::Chunked { mut manifest, chunks } = export_backup(&backup, &options).unwrap() else {
+            panic!("expected a chunked backup");
+        };
+        // A manifest claiming an absurd size is refused before allocating
+        manifest.total_size = usize::MAX;
+        let err = restore_chunked(&manifest, &chunks).unwrap_err();
+        assert!(err.to_string().contains("does not match manifest"));
+
+        // Gzip streams are cut off at the limit instead of inflating fully
+        let bomb = gzip(&vec![0; 4096]).unwrap();
+        assert_eq!(gunzip(&bomb, 4096).unwrap().len(), 4096);
+        let err = gunzip(&bomb, 1024).unwrap_err();
+        assert!(err.to_string().contains("exceeds 1024 bytes"));
+    }
+}
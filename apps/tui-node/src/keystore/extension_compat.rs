@@ -274,7 +274,14 @@ impl ExtensionKeyShareData {
                 .map(|b| b.blockchain.as_str())
         } else {
             wallet_metadata.blockchain.as_deref()
-        }.ok_or_else(|| KeystoreError::General("No blockchain specified".into()))?;
+        }
+        // Current wallets store no chains; their address comes from the curve
+        .or(match wallet_metadata.curve_type.as_str() {
+            "secp256k1" => Some("ethereum"),
+            "ed25519" => Some("solana"),
+            _ => None,
+        })
+        .ok_or_else(|| KeystoreError::General("No blockchain specified".into()))?;
         
         let (key_package_base64, public_key_base64, curve, ethereum_address, solana_address) = 
             match primary_blockchain {
@@ -418,7 +425,7 @@ impl ExtensionKeyShareData {
         
         let wallet_info = super::models::WalletInfo::new(
             self.session_id.clone(),
-            format!("Imported Wallet {}", &self.session_id[..8]),
+            format!("Imported Wallet {}", self.session_id.chars().take(8).collect::<String>()),
             curve_type.to_string(),
             blockchain.to_string(),
             address.clone(),
@@ -444,6 +451,17 @@ pub fn encrypt_for_extension(
     data: &ExtensionKeyShareData,
     password: &str,
     wallet_id: &str,
+) -> Result<ExtensionEncryptedKeyShare> {
+    let plaintext = serde_json::to_vec(data)
+        .map_err(|e| KeystoreError::SerializationError(e.to_string()))?;
+    encrypt_bytes_for_extension(&plaintext, password, wallet_id)
+}
+
+/// Encrypt raw bytes with the extension's PBKDF2 + AES-GCM scheme
+pub(super) fn encrypt_bytes_for_extension(
+    plaintext: &[u8],
+    password: &str,
+    wallet_id: &str,
 ) -> Result<ExtensionEncryptedKeyShare> {
     use aes_gcm::{
         aead::{Aead, KeyInit},
@@ -467,14 +485,10 @@ pub fn encrypt_for_extension(
         &mut key,
     );
     
-    // Serialize and encrypt
-    let plaintext = serde_json::to_vec(data)
-        .map_err(|e| KeystoreError::SerializationError(e.to_string()))?;
-    
     let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
     let nonce = Nonce::from_slice(&iv);
     
-    let ciphertext = cipher.encrypt(nonce, plaintext.as_ref())
+    let ciphertext = cipher.encrypt(nonce, plaintext)
         .map_err(|e| KeystoreError::EncryptionError(e.to_string()))?;
     
     Ok(ExtensionEncryptedKeyShare {
@@ -492,6 +506,19 @@ pub fn decrypt_from_extension(
     encrypted: &ExtensionEncryptedKeyShare,
     password: &str,
 ) -> Result<ExtensionKeyShareData> {
+    let plaintext = decrypt_bytes_from_extension(encrypted, password)?;
+    
+    let data: ExtensionKeyShareData = serde_json::from_slice(&plaintext)
+        .map_err(|e| KeystoreError::DecryptionError(e.to_string()))?;
+    
+    Ok(data)
+}
+
+/// Decrypt raw bytes sealed by [`encrypt_bytes_for_extension`]
+pub(super) fn decrypt_bytes_from_extension(
+    encrypted: &ExtensionEncryptedKeyShare,
+    password: &str,
+) -> Result<Vec<u8>> {
     use aes_gcm::{
         aead::{Aead, KeyInit},
         Aes256Gcm, Key, Nonce
@@ -520,13 +547,8 @@ pub fn decrypt_from_extension(
     let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
     let nonce = Nonce::from_slice(&iv);
     
-    let plaintext = cipher.decrypt(nonce, ciphertext.as_ref())
-        .map_err(|e| KeystoreError::DecryptionError(e.to_string()))?;
-    
-    let data: ExtensionKeyShareData = serde_json::from_slice(&plaintext)
-        .map_err(|e| KeystoreError::DecryptionError(e.to_string()))?;
-    
-    Ok(data)
+    cipher.decrypt(nonce, ciphertext.as_ref())
+        .map_err(|e| KeystoreError::DecryptionError(e.to_string()))
 }
 
 
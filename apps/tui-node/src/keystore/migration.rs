@@ -0,0 +1,115 @@
+//! Upgrades wallet files written by older versions of the keystore.
+//!
+//! Each wallet file records the `KEYSTORE_VERSION` it was written with in
+//! `keystore_version`; files from before that field existed are version 0.
+//! On load the raw JSON is brought up to the current version one step at a
+//! time, so a format change only needs a new step here.
+
+use super::models::WalletFile;
+use super::{KEYSTORE_VERSION, KeystoreError, Result};
+
+/// Upgrades a wallet file's JSON from `from_version` to `KEYSTORE_VERSION`
+pub fn migrate_keystore(mut data: serde_json::Value, from_version: u8) -> Result<serde_json::Value> {
+    if from_version > KEYSTORE_VERSION {
+        return Err(KeystoreError::General(format!(
+            "Wallet file version {} is newer than this build supports ({})",
+            from_version, KEYSTORE_VERSION
+        )));
+    }
+    for version in from_version..KEYSTORE_VERSION {
+        match version {
+            0 => migrate_v0_to_v1(&mut data)?,
+            _ => unreachable!("no migration from keystore version {}", version),
+        }
+    }
+    if let Some(file) = data.as_object_mut() {
+        file.insert("keystore_version".to_string(), KEYSTORE_VERSION.into());
+    }
+    Ok(data)
+}
+
+/// Version 0 files may lack `tags` and spell the curve as e.g. "Secp256k1",
+/// which misses the lowercase curve directory the file lives in
+fn migrate_v0_to_v1(data: &mut serde_json::Value) -> Result<()> {
+    let metadata = data
+        .get_mut("metadata")
+        .and_then(|m| m.as_object_mut())
+        .ok_or_else(|| KeystoreError::General("Wallet file has no metadata".to_string()))?;
+    metadata.entry("tags").or_insert_with(|| serde_json::json!([]));
+    if let Some(curve) = metadata.get_mut("curve_type")
+        && let Some(name) = curve.as_str()
+    {
+        *curve = name.to_lowercase().into();
+    }
+    Ok(())
+}
+
+/// Parses a stored wallet file, migrating it first if it is older than
+/// `KEYSTORE_VERSION`
+pub fn parse_wallet_file(bytes: &[u8]) -> Result<WalletFile> {
+    let data: serde_json::Value = serde_json::from_slice(bytes)
+        .map_err(|e| KeystoreError::General(format!("Failed to parse wallet JSON: {}", e)))?;
+    wallet_file_from_value(data)
+}
+
+/// `parse_wallet_file` for JSON that is already parsed, e.g. from a backup archive
+pub fn wallet_file_from_value(data: serde_json::Value) -> Result<WalletFile> {
+    let from_version = data
+        .get("keystore_version")
+        .and_then(|v| v.as_u64())
+        .map_or(0, |v| u8::try_from(v).unwrap_or(u8::MAX));
+    let data = migrate_keystore(data, from_version)?;
+    serde_json::from_value(data)
+        .map_err(|e| KeystoreError::General(format!("Failed to parse wallet JSON: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v0_blob() -> serde_json::Value {
+        serde_json::json!({
+            "version": "2.0",
+            "encrypted": true,
+            "algorithm": "AES-256-GCM",
+            "data": "AAEC",
+            "metadata": {
+                "wallet_id": "legacy-wallet",
+                "device_id": "alice",
+                "curve_type": "Secp256k1",
+                "threshold": 2,
+                "total_participants": 3,
+                "participant_index": 1,
+                "group_public_key": "02ab",
+                "created_at": "2024-01-01T00:00:00Z",
+                "last_modified": "2024-01-01T00:00:00Z"
+            }
+        })
+    }
+
+    #[test]
+    fn test_v0_blob_is_migrated_and_loads() {
+        let migrated = migrate_keystore(v0_blob(), 0).unwrap();
+        assert_eq!(migrated["keystore_version"], KEYSTORE_VERSION);
+        assert_eq!(migrated["metadata"]["tags"], serde_json::json!([]));
+        assert_eq!(migrated["metadata"]["curve_type"], "secp256k1");
+
+        let wallet = parse_wallet_file(&serde_json::to_vec(&v0_blob()).unwrap()).unwrap();
+        assert_eq!(wallet.keystore_version, KEYSTORE_VERSION);
+        assert_eq!(wallet.metadata.session_id, "legacy-wallet");
+        assert_eq!(wallet.metadata.curve_type, "secp256k1");
+        assert!(wallet.metadata.tags.is_empty());
+
+        // Current files pass through untouched
+        let current = serde_json::to_value(&wallet).unwrap();
+        assert_eq!(migrate_keystore(current.clone(), KEYSTORE_VERSION).unwrap(), current);
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let mut blob = v0_blob();
+        blob["keystore_version"] = (KEYSTORE_VERSION + 1).into();
+        let error = parse_wallet_file(&serde_json::to_vec(&blob).unwrap()).unwrap_err();
+        assert!(error.to_string().contains("newer than this build supports"));
+    }
+}
@@ -4,20 +4,29 @@
 //! across multiple devices and wallets. It supports encryption, backup, and recovery
 //! mechanisms in line with the threshold security model.
 
+mod backend;
 mod encryption;
+mod migration;
 mod models;
 mod storage;
 mod extension_compat;
+pub mod backup;
 pub mod frost_keystore;
 
 pub use storage::Keystore;
-pub use models::{DeviceInfo, BlockchainInfo, WalletMetadata};
+pub use backend::{KeystoreBackend, FilesystemBackend};
+pub use migration::migrate_keystore;
+pub use models::{
+    DeviceInfo, BlockchainInfo, WalletMetadata, SigningPolicy, PolicyViolation, AuditAction, AuditEntry,
+    KeystoreSummary,
+};
 pub use extension_compat::{
     ExtensionKeyShareData, ExtensionWalletMetadata,
     ExtensionKeystoreBackup, ExtensionBackupWallet,
     encrypt_for_extension, decrypt_from_extension, WalletData
 };
 pub use frost_keystore::{FrostKeystoreManager, FrostKeystore, FrostMetadata};
+pub use backup::{BackupOptions, BackupManifest, BackupPayload};
 
 /// Error types that can occur during keystore operations
 #[derive(Debug, thiserror::Error)]
@@ -56,3 +65,8 @@ pub type Result<T> = std::result::Result<T, KeystoreError>;
 /// Current keystore file format version
 pub const KEYSTORE_VERSION: u8 = 1;
 
+/// Current multi-wallet backup archive version
+pub const KEYSTORE_BACKUP_VERSION: u8 = 1;
+
+/// `version` of the [`ExtensionKeystoreBackup`]s the browser extension reads
+pub const EXTENSION_BACKUP_VERSION: &str = "1.0.0";
@@ -4,6 +4,7 @@
 //! wallet information, device metadata, and key packages.
 
 use std::time::{SystemTime, UNIX_EPOCH};
+use mpc_wallet_blockchain::ParsedTransaction;
 
 
 /// Gets the current Unix timestamp in seconds
@@ -216,6 +217,183 @@ impl DeviceInfo {
 
 }
 
+/// Operator-set limits on what a wallet may sign. Values are in the
+/// chain's base unit (satoshis, wei, token base units).
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SigningPolicy {
+    /// Largest amount a single transfer may move
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_value: Option<u128>,
+
+    /// Destinations transfers may go to; empty allows any
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_destinations: Vec<String>,
+}
+
+/// Why a signing policy refused a transaction
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PolicyViolation {
+    #[error("Transfer of {amount} to {destination} exceeds the limit of {limit}")]
+    ValueExceedsLimit { destination: String, amount: u128, limit: u128 },
+
+    #[error("Destination {0} is not on the wallet's allowlist")]
+    DestinationNotAllowed(String),
+
+    #[error("Cannot determine the transfers in transaction {0} to check them against the policy")]
+    UnknownTransfers(String),
+}
+
+impl SigningPolicy {
+    /// Checks every transfer in `tx` from the wallet with `group_public_key`
+    /// (hex). A transaction whose transfers the chain handler didn't decode
+    /// is refused rather than waved through.
+    pub fn check(&self, tx: &ParsedTransaction, group_public_key: &str) -> Result<(), PolicyViolation> {
+        if self.max_value.is_none() && self.allowed_destinations.is_empty() {
+            return Ok(());
+        }
+        let transfers = policy_transfers(tx, group_public_key)
+            .ok_or_else(|| PolicyViolation::UnknownTransfers(tx.hash.clone()))?;
+        for (destination, amount) in transfers {
+            if !self.allowed_destinations.is_empty()
+                && !self.allowed_destinations.iter().any(|allowed| allowed.eq_ignore_ascii_case(&destination))
+            {
+                return Err(PolicyViolation::DestinationNotAllowed(destination));
+            }
+            if let Some(limit) = self.max_value
+                && amount > limit
+            {
+                return Err(PolicyViolation::ValueExceedsLimit { destination, amount, limit });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `(destination, amount)` for each outgoing transfer the handler decoded:
+/// Bitcoin outputs other than verified change, Solana token transfers, and
+/// the value an Ethereum transaction sends to `to`. `None` when something in
+/// the transaction could move funds undecoded, such as a Solana instruction
+/// other than a token transfer or nonce advance, or Ethereum calldata.
+fn policy_transfers(tx: &ParsedTransaction, group_public_key: &str) -> Option<Vec<(String, u128)>> {
+    if let Some(to) = tx.metadata.get("to") {
+        // A contract creation has no destination, and a call can move
+        // tokens whatever its value
+        if tx.metadata.get("data")?.as_str()? != "0x" {
+            return None;
+        }
+        let destination = to.as_str()?.to_string();
+        return Some(vec![(destination, tx.metadata.get("value")?.as_str()?.parse().ok()?)]);
+    }
+    if let Some(outputs) = tx.metadata.get("outputs").and_then(|v| v.as_array()) {
+        let network = tx.metadata.get("network").and_then(|v| v.as_str());
+        return outputs
+            .iter()
+            .filter(|output| !is_wallet_change(output, network, group_public_key))
+            .map(|output| {
+                let destination = output.get("address")?.as_str()?.to_string();
+                Some((destination, output.get("value_sat")?.as_u64()? as u128))
+            })
+            .collect();
+    }
+    if let Some(transfers) = tx.metadata.get("token_transfers").and_then(|v| v.as_array()) {
+        let nonce_advance = usize::from(!tx.metadata["durable_nonce"].is_null());
+        let instructions = tx.metadata.get("instructions")?.as_u64()? as usize;
+        if transfers.len() + nonce_advance != instructions {
+            return None;
+        }
+        return transfers
+            .iter()
+            .map(|transfer| {
+                let destination = transfer.get("destination")?.as_str()?.to_string();
+                Some((destination, transfer.get("amount")?.as_str()?.parse().ok()?))
+            })
+            .collect();
+    }
+    None
+}
+
+/// Whether a decoded Bitcoin output provably pays back to the wallet. The
+/// PSBT's `change` flag is only a claim, so one of the output's key origins
+/// must re-derive from `group_public_key` along its path to the claimed key,
+/// and the output's address must be one of that key's addresses.
+fn is_wallet_change(output: &serde_json::Value, network: Option<&str>, group_public_key: &str) -> bool {
+    use mpc_wallet_blockchain::bitcoin::{BitcoinAddressType, BitcoinHandler};
+    use mpc_wallet_frost_core::{ChainCode, DerivationPath, derive_child_verifying_key};
+
+    let Some(address) = output.get("address").and_then(|v| v.as_str()) else {
+        return false;
+    };
+    let Some(origins) = output.get("key_origins").and_then(|v| v.as_array()) else {
+        return false;
+    };
+    let Ok(group_key_bytes) = hex::decode(group_public_key) else {
+        return false;
+    };
+    let Ok(group_key) = frost_secp256k1::VerifyingKey::deserialize(&group_key_bytes) else {
+        return false;
+    };
+    let handler = match network {
+        Some("testnet") => BitcoinHandler::new_testnet(),
+        _ => BitcoinHandler::new(),
+    };
+    let chain_code = ChainCode::from_group_key(&group_key_bytes);
+
+    origins.iter().any(|origin| {
+        let claimed = origin.get("pubkey").and_then(|v| v.as_str()).and_then(|key| hex::decode(key).ok());
+        let path = origin.get("path").and_then(|v| v.as_str()).and_then(|path| DerivationPath::parse(path).ok());
+        let (Some(claimed), Some(path)) = (claimed, path) else {
+            return false;
+        };
+        let Ok(child) = derive_child_verifying_key(&group_key, &chain_code, &path) else {
+            return false;
+        };
+        let Ok(child) = child.serialize() else {
+            return false;
+        };
+        // Taproot origins name the x-only key
+        if claimed != child && claimed[..] != child[1..] {
+            return false;
+        }
+        [BitcoinAddressType::P2wpkh, BitcoinAddressType::P2shP2wpkh, BitcoinAddressType::P2tr]
+            .into_iter()
+            .any(|address_type| handler.derive_address(&child, address_type).is_ok_and(|derived| derived == address))
+    })
+}
+
+/// What this device did in a signing session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// Produced a signature share
+    SignatureShare,
+    /// Aggregated the shares into the final signature
+    AggregatedSignature,
+}
+
+/// One record in a wallet's append-only signing audit log
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AuditEntry {
+    /// When the signing step completed (RFC 3339)
+    pub timestamp: String,
+
+    /// Wallet that signed
+    pub wallet_id: String,
+
+    pub action: AuditAction,
+
+    /// Hex of the message the signers signed
+    pub message_hash: String,
+
+    /// Devices whose commitments went into the signing package
+    pub participants: Vec<String>,
+
+    /// Chain the transaction was for
+    pub chain: String,
+
+    /// The blockchain handler's one-line description of the transaction
+    pub summary: String,
+}
+
 /// Simplified wallet metadata - KISS and Orthogonal
 /// All blockchain addresses can be derived from group_public_key + curve_type
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -248,6 +426,19 @@ pub struct WalletMetadata {
     /// ISO 8601 timestamp when last modified  
     pub last_modified: String,
     
+    /// User-defined tags for finding wallets; absent in older wallet files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    
+    /// Addresses derived from `group_public_key`, keyed by chain; filled in
+    /// the first time the wallet's addresses are listed
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub addresses: std::collections::BTreeMap<String, String>,
+    
+    /// Limits this node enforces before contributing a signature share
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_policy: Option<SigningPolicy>,
+    
     // === Legacy fields for backward compatibility (will be removed in v3.0) ===
     
     /// User-friendly device name (deprecated, use device_id)
@@ -270,10 +461,6 @@ pub struct WalletMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub identifier: Option<String>,
     
-    /// User-defined tags (deprecated)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub tags: Option<Vec<String>>,
-    
     /// Optional description (deprecated)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
@@ -301,13 +488,15 @@ impl WalletMetadata {
             group_public_key,
             created_at: now.clone(),
             last_modified: now,
+            tags: Vec::new(),
+            addresses: Default::default(),
+            signing_policy: None,
             // All legacy fields set to None
             device_name: None,
             blockchains: Vec::new(),
             blockchain: None,
             public_address: None,
             identifier: None,
-            tags: None,
             description: None,
         }
     }
@@ -397,6 +586,11 @@ impl WalletMetadata {
 pub struct WalletFile {
     /// Format version
     pub version: String,
+
+    /// `KEYSTORE_VERSION` the metadata was written with; 0 for files that
+    /// predate the field
+    #[serde(default)]
+    pub keystore_version: u8,
     
     /// Whether the data is encrypted
     pub encrypted: bool,
@@ -411,6 +605,41 @@ pub struct WalletFile {
     pub metadata: WalletMetadata,
 }
 
+/// Public details of a wallet file, readable without its password
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct KeystoreSummary {
+    pub wallet_id: String,
+    pub device_id: String,
+    pub curve_type: String,
+    pub threshold: u16,
+    pub total_participants: u16,
+    pub participant_index: u16,
+    pub group_public_key: String,
+    /// Keystore version the file was written with, before any migration
+    pub keystore_version: u8,
+    /// Encryption algorithm protecting the key share
+    pub algorithm: String,
+    /// Chain the address is for ("ethereum" or "solana")
+    pub chain: String,
+    pub address: String,
+}
+
+/// Password-encrypted archive of every wallet file in a keystore
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct KeystoreBackupArchive {
+    /// Archive format version
+    pub version: u8,
+
+    /// Device the backup was taken on
+    pub device_id: String,
+
+    /// Unix timestamp in milliseconds
+    pub exported_at: i64,
+
+    /// JSON list of `WalletFile`s, sealed with the backup password
+    pub wallets: super::extension_compat::ExtensionEncryptedKeyShare,
+}
+
 /// Master index of all wallets and devices (legacy - for migration only)
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct KeystoreIndex {
@@ -1,22 +1,29 @@
 //! Storage functionality for the keystore module.
 //!
-//! This module provides functions for saving and loading keystore data to disk,
-//! including encrypted wallet files and the keystore index.
+//! This module provides functions for saving and loading keystore data through a
+//! [`KeystoreBackend`], including encrypted wallet files and the keystore index.
 
-use std::fs::{self, File};
-use std::io::Read;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 use super::{
-    KeystoreError, Result,
+    EXTENSION_BACKUP_VERSION, KEYSTORE_BACKUP_VERSION, KEYSTORE_VERSION, KeystoreError, Result,
+    backend::{FilesystemBackend, KeystoreBackend},
     encryption::decrypt_data,
-    models::{DeviceInfo, KeystoreIndex, WalletFile, WalletMetadata},
+    extension_compat::{
+        ExtensionBackupWallet, ExtensionKeyShareData, ExtensionKeystoreBackup, ExtensionWalletMetadata, WalletData,
+        decrypt_bytes_from_extension, decrypt_from_extension, encrypt_bytes_for_extension, encrypt_for_extension,
+    },
+    migration::{parse_wallet_file, wallet_file_from_value},
+    models::{
+        AuditEntry, DeviceInfo, KeystoreBackupArchive, KeystoreIndex, KeystoreSummary, PolicyViolation, SigningPolicy,
+        WalletFile, WalletMetadata,
+    },
 };
 
 /// Main keystore interface
 pub struct Keystore {
-    /// Base path for keystore files
-    base_path: PathBuf,
+    /// Where the keystore files are kept
+    backend: Box<dyn KeystoreBackend>,
 
     /// Unique identifier for this device
     device_id: String,
@@ -32,21 +39,22 @@ impl Keystore {
 
     /// Creates a new keystore at the specified path with the given device name.
     pub fn new(base_path: impl AsRef<Path>, device_name: &str) -> Result<Self> {
-        let base_path = base_path.as_ref().to_path_buf();
-        let device_id = device_name.to_string();
-        let device_name = device_name.to_string();
-
-        // Create directory structure if it doesn't exist
-        fs::create_dir_all(&base_path)?;
+        let backend = FilesystemBackend::new(base_path)?;
 
         // Create the device-specific wallet directory with curve subdirectories
-        let device_wallet_dir = base_path.join(&device_id);
-        fs::create_dir_all(&device_wallet_dir)?;
-        fs::create_dir_all(device_wallet_dir.join("ed25519"))?;
-        fs::create_dir_all(device_wallet_dir.join("secp256k1"))?;
+        backend.create_dir(&format!("{}/ed25519", device_name))?;
+        backend.create_dir(&format!("{}/secp256k1", device_name))?;
+
+        Self::with_backend(backend, device_name)
+    }
+
+    /// Creates a keystore for `device_name` whose files live in `backend`
+    pub fn with_backend(backend: impl KeystoreBackend + 'static, device_name: &str) -> Result<Self> {
+        let device_id = device_name.to_string();
+        let device_name = device_name.to_string();
 
         let mut keystore = Self {
-            base_path,
+            backend: Box::new(backend),
             device_id,
             device_name,
             wallet_cache: Vec::new(),
@@ -65,27 +73,16 @@ impl Keystore {
     fn reload_wallet_cache(&mut self) -> Result<()> {
         self.wallet_cache.clear();
         
-        let device_dir = self.base_path.join(&self.device_id);
-        
         // Scan both curve directories
         for curve_type in &["ed25519", "secp256k1"] {
-            let curve_dir = device_dir.join(curve_type);
-            if !curve_dir.exists() {
-                continue;
-            }
-            
             // Read all .json files in the directory
-            for entry in fs::read_dir(&curve_dir)? {
-                let entry = entry?;
-                let path = entry.path();
-                
-                if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                    // Try to read the wallet metadata
-                    if let Ok(file) = File::open(&path) {
-                        if let Ok(wallet_file) = serde_json::from_reader::<_, WalletFile>(file) {
-                            self.wallet_cache.push(wallet_file.metadata);
-                        }
-                    }
+            for key in self.backend.list(&format!("{}/{}", self.device_id, curve_type))? {
+                // Try to read the wallet metadata
+                if key.ends_with(".json")
+                    && let Ok(Some(data)) = self.backend.read(&key)
+                    && let Ok(wallet_file) = parse_wallet_file(&data)
+                {
+                    self.wallet_cache.push(wallet_file.metadata);
                 }
             }
         }
@@ -108,6 +105,147 @@ impl Keystore {
         self.wallet_cache.iter().find(|w| w.session_id == wallet_id)
     }
 
+    /// Lists the wallets carrying `tag`
+    pub fn find_wallets_by_tag(&self, tag: &str) -> Vec<&WalletMetadata> {
+        self.wallet_cache
+            .iter()
+            .filter(|w| w.tags.iter().any(|t| t == tag))
+            .collect()
+    }
+
+    /// Tags a wallet; tagging it twice with the same tag is a no-op
+    pub fn add_tag(&mut self, wallet_id: &str, tag: &str) -> Result<()> {
+        if self.get_wallet(wallet_id).is_some_and(|w| w.tags.iter().any(|t| t == tag)) {
+            return Ok(());
+        }
+        self.update_wallet_metadata(wallet_id, |metadata| metadata.tags.push(tag.to_string()))
+    }
+
+    /// Removes a tag from a wallet, if it has it
+    pub fn remove_tag(&mut self, wallet_id: &str, tag: &str) -> Result<()> {
+        if self.get_wallet(wallet_id).is_some_and(|w| !w.tags.iter().any(|t| t == tag)) {
+            return Ok(());
+        }
+        self.update_wallet_metadata(wallet_id, |metadata| metadata.tags.retain(|t| t != tag))
+    }
+
+    /// Sets or, with `None`, removes the wallet's signing policy
+    pub fn set_signing_policy(&mut self, wallet_id: &str, policy: Option<SigningPolicy>) -> Result<()> {
+        self.update_wallet_metadata(wallet_id, |metadata| metadata.signing_policy = policy)
+    }
+
+    /// The wallet's signing policy, if it has one
+    pub fn signing_policy(&self, wallet_id: &str) -> Option<&SigningPolicy> {
+        self.get_wallet(wallet_id)?.signing_policy.as_ref()
+    }
+
+    /// Checks `tx` against the wallet's signing policy; call before
+    /// generating a signature share for it. A wallet without a policy
+    /// allows everything.
+    pub fn check_policy(
+        &self,
+        wallet_id: &str,
+        tx: &mpc_wallet_blockchain::ParsedTransaction,
+    ) -> std::result::Result<(), PolicyViolation> {
+        match self.get_wallet(wallet_id) {
+            Some(WalletMetadata { signing_policy: Some(policy), group_public_key, .. }) => {
+                policy.check(tx, group_public_key)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Appends `entry` to its wallet's audit log. Entries are never
+    /// rewritten or removed.
+    pub fn append_audit_entry(&self, entry: &AuditEntry) -> Result<()> {
+        let key = self.audit_log_key(&entry.wallet_id)?;
+        let line = serde_json::to_string(entry)
+            .map_err(|e| KeystoreError::SerializationError(e.to_string()))?;
+        self.backend.append(&key, format!("{}\n", line).as_bytes())
+    }
+
+    /// The wallet's signing audit log, oldest entry first
+    pub fn get_audit_log(&self, wallet_id: &str) -> Result<Vec<AuditEntry>> {
+        let Some(data) = self.backend.read(&self.audit_log_key(wallet_id)?)? else {
+            return Ok(Vec::new());
+        };
+        String::from_utf8_lossy(&data)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line)
+                    .map_err(|e| KeystoreError::SerializationError(format!("Corrupt audit entry: {}", e)))
+            })
+            .collect()
+    }
+
+    /// Every address this device controls, as `(wallet_id, chain, address)`:
+    /// Ethereum for secp256k1 wallets and Solana for ed25519 ones. Derived
+    /// addresses are cached in the wallet metadata; wallets whose group key
+    /// can't be turned into an address are skipped.
+    pub fn list_addresses(&mut self) -> Vec<(String, String, String)> {
+        let wallets: Vec<(String, &'static str, String, String)> = self
+            .wallet_cache
+            .iter()
+            .filter_map(|w| {
+                let chain = address_chain(&w.curve_type)?;
+                Some((w.session_id.clone(), chain, w.curve_type.clone(), w.group_public_key.clone()))
+            })
+            .collect();
+
+        let mut addresses = Vec::new();
+        for (wallet_id, chain, curve_type, group_public_key) in wallets {
+            let cached = self.get_wallet(&wallet_id).and_then(|w| w.addresses.get(chain).cloned());
+            let address = match cached {
+                Some(address) => address,
+                None => match derive_address(&curve_type, chain, &group_public_key) {
+                    Ok(address) => {
+                        let cache = self.update_wallet_metadata(&wallet_id, |metadata| {
+                            metadata.addresses.insert(chain.to_string(), address.clone());
+                        });
+                        if let Err(e) = cache {
+                            tracing::warn!("Failed to cache address for wallet {}: {}", wallet_id, e);
+                        }
+                        address
+                    }
+                    Err(e) => {
+                        tracing::warn!("No {} address for wallet {}: {}", chain, wallet_id, e);
+                        continue;
+                    }
+                },
+            };
+            addresses.push((wallet_id, chain.to_string(), address));
+        }
+        addresses
+    }
+
+    /// Address of `wallet_id` on `chain`, from the cache or derived from its group key
+    pub fn wallet_address(&self, wallet_id: &str, chain: &str) -> std::result::Result<String, String> {
+        let wallet = self.get_wallet(wallet_id).ok_or_else(|| format!("wallet {} not found", wallet_id))?;
+        match wallet.addresses.get(chain) {
+            Some(address) => Ok(address.clone()),
+            None => derive_address(&wallet.curve_type, chain, &wallet.group_public_key),
+        }
+    }
+
+    /// Rewrites a wallet file's embedded metadata, leaving the encrypted share as is
+    fn update_wallet_metadata(&mut self, wallet_id: &str, update: impl FnOnce(&mut WalletMetadata)) -> Result<()> {
+        let index = self
+            .wallet_cache
+            .iter()
+            .position(|w| w.session_id == wallet_id)
+            .ok_or_else(|| KeystoreError::WalletNotFound(wallet_id.to_string()))?;
+        let key = self.wallet_key(&self.wallet_cache[index].curve_type, wallet_id);
+
+        let mut wallet_file = self.read_wallet_file(&key)?;
+        update(&mut wallet_file.metadata);
+        wallet_file.metadata.last_modified = chrono::Utc::now().to_rfc3339();
+        self.write_wallet_file(&key, &wallet_file)?;
+
+        self.wallet_cache[index] = wallet_file.metadata;
+        Ok(())
+    }
+
     /// Gets this device's info (for compatibility)
     pub fn get_this_device(&self) -> Option<DeviceInfo> {
         Some(DeviceInfo::new(
@@ -130,7 +268,7 @@ impl Keystore {
         group_public_key: &str,
         key_share_data: &[u8],
         password: &str,
-        _tags: Vec<String>, // Deprecated parameter
+        tags: Vec<String>,
         _description: Option<String>, // Deprecated parameter
         participant_index: u16,
     ) -> Result<String> {
@@ -146,7 +284,7 @@ impl Keystore {
         }
 
         // Create simplified wallet metadata - no blockchain info stored
-        let metadata = WalletMetadata::new(
+        let mut metadata = WalletMetadata::new(
             wallet_id.clone(),
             self.device_id.clone(),
             curve_type.to_string(),
@@ -155,6 +293,7 @@ impl Keystore {
             participant_index,
             group_public_key.to_string(),
         );
+        metadata.tags = tags;
 
         // Save the wallet with embedded metadata
         self.save_wallet_file_v2(&wallet_id, key_share_data, password, &metadata)?;
@@ -205,15 +344,6 @@ impl Keystore {
 
     /// Saves encrypted wallet data to a file with embedded metadata (v2 format) using specified encryption method
     fn save_wallet_file_v2_with_method(&self, wallet_id: &str, data: &[u8], password: &str, metadata: &WalletMetadata, method: crate::keystore::encryption::KeyDerivation) -> Result<()> {
-        // Create device-specific wallet directory with curve type
-        let wallet_dir = self.base_path.join(&self.device_id).join(&metadata.curve_type);
-
-        // Create the directory structure if it doesn't exist
-        fs::create_dir_all(&wallet_dir)?;
-
-        // Define wallet file path
-        let wallet_path = wallet_dir.join(format!("{}.json", wallet_id));
-
         // Encrypt the wallet data using the specified method
         let encrypted_data = crate::keystore::encryption::encrypt_data_with_method(data, password, method)?;
 
@@ -224,18 +354,15 @@ impl Keystore {
         // Create the wallet file with embedded metadata
         let wallet_file = WalletFile {
             version: "2.0".to_string(),
+            keystore_version: KEYSTORE_VERSION,
             encrypted: true,
             algorithm: method.algorithm_string().to_string(),
             data: base64_encrypted,
             metadata: metadata.clone(),
         };
 
-        // Write JSON to file with pretty formatting
-        let mut file = File::create(wallet_path)?;
-        serde_json::to_writer_pretty(&mut file, &wallet_file)
-            .map_err(|e| KeystoreError::General(format!("Failed to write wallet JSON: {}", e)))?;
-
-        Ok(())
+        // Device-specific wallet file under its curve type
+        self.write_wallet_file(&self.wallet_key(&metadata.curve_type, wallet_id), &wallet_file)
     }
 
     /// Loads encrypted wallet data from a file
@@ -245,25 +372,14 @@ impl Keystore {
             .ok_or_else(|| KeystoreError::WalletNotFound(wallet_id.to_string()))?;
         
         // Device-specific wallet path with curve type
-        let wallet_dir = self
-            .base_path
-            .join(&self.device_id)
-            .join(&wallet.curve_type);
-            
-        let json_path = wallet_dir.join(format!("{}.json", wallet_id));
-        
-        if !json_path.exists() {
-            return Err(KeystoreError::General(format!(
-                "Wallet file not found for {}", wallet_id
-            )));
-        }
+        let data = self
+            .backend
+            .read(&self.wallet_key(&wallet.curve_type, wallet_id))
+            .map_err(|e| KeystoreError::General(format!("Failed to open wallet file: {}", e)))?
+            .ok_or_else(|| KeystoreError::General(format!("Wallet file not found for {}", wallet_id)))?;
         
         // Read JSON format
-        let file = File::open(&json_path)
-            .map_err(|e| KeystoreError::General(format!("Failed to open wallet file: {}", e)))?;
-        
-        let wallet_file: WalletFile = serde_json::from_reader(file)
-            .map_err(|e| KeystoreError::General(format!("Failed to parse wallet JSON: {}", e)))?;
+        let wallet_file = parse_wallet_file(&data)?;
         
         // Decode from base64
         use base64::{Engine as _, engine::general_purpose};
@@ -276,24 +392,248 @@ impl Keystore {
         Ok(decrypted_data)
     }
 
+    /// Reads the public metadata of an exported wallet file and derives its
+    /// address, without a password and without adding it to any keystore.
+    /// Fails if the file is corrupt or its metadata is unusable.
+    pub fn inspect(data: impl AsRef<[u8]>) -> Result<KeystoreSummary> {
+        let data: serde_json::Value = serde_json::from_slice(data.as_ref())
+            .map_err(|e| KeystoreError::General(format!("Failed to parse wallet JSON: {}", e)))?;
+        let keystore_version = data
+            .get("keystore_version")
+            .and_then(|v| v.as_u64())
+            .map_or(0, |v| u8::try_from(v).unwrap_or(u8::MAX));
+        let wallet_file = wallet_file_from_value(data)?;
+        let invalid = |reason: String| KeystoreError::General(format!("Keystore file is invalid: {}", reason));
+        validate_backup_wallet(&wallet_file).map_err(invalid)?;
+
+        let metadata = wallet_file.metadata;
+        let chain = address_chain(&metadata.curve_type)
+            .ok_or_else(|| invalid(format!("unsupported curve '{}'", metadata.curve_type)))?;
+        let address = derive_address(&metadata.curve_type, chain, &metadata.group_public_key).map_err(invalid)?;
+        Ok(KeystoreSummary {
+            wallet_id: metadata.session_id,
+            device_id: metadata.device_id,
+            curve_type: metadata.curve_type,
+            threshold: metadata.threshold,
+            total_participants: metadata.total_participants,
+            participant_index: metadata.participant_index,
+            group_public_key: metadata.group_public_key,
+            keystore_version,
+            algorithm: wallet_file.algorithm,
+            chain: chain.to_string(),
+            address,
+        })
+    }
+
+    /// Exports every wallet into one archive encrypted with `password`
+    ///
+    /// Each wallet keeps its own encrypted share, so restoring still needs the
+    /// wallet passwords to sign.
+    pub fn export_backup(&self, password: &str) -> Result<Vec<u8>> {
+        let mut wallets = Vec::with_capacity(self.wallet_cache.len());
+        for wallet in &self.wallet_cache {
+            let key = self.wallet_key(&wallet.curve_type, &wallet.session_id);
+            wallets.push(self.read_wallet_file(&key)?);
+        }
+
+        let plaintext = serde_json::to_vec(&wallets)
+            .map_err(|e| KeystoreError::SerializationError(e.to_string()))?;
+        let archive = KeystoreBackupArchive {
+            version: KEYSTORE_BACKUP_VERSION,
+            device_id: self.device_id.clone(),
+            exported_at: chrono::Utc::now().timestamp_millis(),
+            wallets: encrypt_bytes_for_extension(&plaintext, password, "keystore-backup")?,
+        };
+        serde_json::to_vec(&archive).map_err(|e| KeystoreError::SerializationError(e.to_string()))
+    }
+
+    /// Restores wallets from an [`export_backup`](Self::export_backup) archive
+    ///
+    /// Wallets already in the keystore are left untouched. Every wallet in the
+    /// archive is validated before anything is written, so a corrupt entry
+    /// fails the whole import and leaves the keystore as it was. Returns the
+    /// IDs of the wallets that were restored.
+    pub fn import_backup(&mut self, bytes: &[u8], password: &str) -> Result<Vec<String>> {
+        let archive: KeystoreBackupArchive = serde_json::from_slice(bytes)
+            .map_err(|e| KeystoreError::SerializationError(e.to_string()))?;
+        if archive.version != KEYSTORE_BACKUP_VERSION {
+            return Err(KeystoreError::General(format!(
+                "Unsupported keystore backup version {}", archive.version
+            )));
+        }
+
+        let plaintext = decrypt_bytes_from_extension(&archive.wallets, password)
+            .map_err(|_| KeystoreError::InvalidPassword)?;
+        let entries: Vec<serde_json::Value> = serde_json::from_slice(&plaintext)
+            .map_err(|e| KeystoreError::SerializationError(e.to_string()))?;
+
+        // Stage and validate everything before touching storage
+        let mut staged: Vec<WalletFile> = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        for (position, entry) in entries.into_iter().enumerate() {
+            let name = entry
+                .pointer("/metadata/session_id")
+                .and_then(|id| id.as_str())
+                .map_or_else(|| format!("#{}", position + 1), |id| format!("'{}'", id));
+            let invalid = |reason: String| {
+                KeystoreError::General(format!("Wallet {} in backup is invalid: {}", name, reason))
+            };
+            let wallet_file = wallet_file_from_value(entry).map_err(|e| match e {
+                KeystoreError::General(reason) => invalid(reason),
+                other => other,
+            })?;
+            validate_backup_wallet(&wallet_file).map_err(invalid)?;
+            if !seen.insert(wallet_file.metadata.session_id.clone()) {
+                return Err(invalid("it appears more than once".to_string()));
+            }
+            if self.get_wallet(&wallet_file.metadata.session_id).is_none() {
+                staged.push(wallet_file);
+            }
+        }
+
+        let mut written: Vec<String> = Vec::new();
+        for wallet_file in &staged {
+            let key = self.wallet_key(&wallet_file.metadata.curve_type, &wallet_file.metadata.session_id);
+            if let Err(e) = self.write_wallet_file(&key, wallet_file) {
+                // Roll back so a failed import leaves no partial state
+                for key in &written {
+                    let _ = self.backend.delete(key);
+                }
+                return Err(e);
+            }
+            written.push(key);
+        }
+
+        let restored = staged.iter().map(|w| w.metadata.session_id.clone()).collect();
+        self.wallet_cache.extend(staged.into_iter().map(|w| w.metadata));
+        Ok(restored)
+    }
+
+    /// Exports every wallet as the [`ExtensionKeystoreBackup`] the browser
+    /// extension imports, each key share sealed with `password` by
+    /// [`encrypt_for_extension`]
+    ///
+    /// `password` must also open each wallet's key share here, which has to
+    /// be the JSON [`WalletData`] holding its FROST key packages.
+    pub fn export_for_extension(&self, password: &str) -> Result<ExtensionKeystoreBackup> {
+        let device = self
+            .get_this_device()
+            .ok_or_else(|| KeystoreError::DeviceNotFound(self.device_id.clone()))?;
+        let mut wallets = Vec::with_capacity(self.wallet_cache.len());
+        for wallet in &self.wallet_cache {
+            let failed = |reason: String| {
+                KeystoreError::General(format!("Cannot export wallet '{}': {}", wallet.session_id, reason))
+            };
+            let wallet_data: WalletData = serde_json::from_slice(&self.load_wallet_file(&wallet.session_id, password)?)
+                .map_err(|e| failed(format!("key share is not FROST wallet data ({})", e)))?;
+            let chain = address_chain(&wallet.curve_type)
+                .ok_or_else(|| failed(format!("unsupported curve '{}'", wallet.curve_type)))?;
+            let address = derive_address(&wallet.curve_type, chain, &wallet.group_public_key).map_err(failed)?;
 
+            let mut share = ExtensionKeyShareData::from_cli_wallet_metadata(&wallet_data, wallet, &device)?;
+            match chain {
+                "ethereum" => share.ethereum_address = Some(address.clone()),
+                _ => share.solana_address = Some(address.clone()),
+            }
+            wallets.push(ExtensionBackupWallet {
+                metadata: ExtensionWalletMetadata {
+                    id: wallet.session_id.clone(),
+                    name: wallet.session_id.clone(),
+                    blockchain: chain.to_string(),
+                    address,
+                    session_id: share.session_id.clone(),
+                    is_active: false,
+                    has_backup: true,
+                },
+                encrypted_share: encrypt_for_extension(&share, password, &wallet.session_id)?,
+            });
+        }
+
+        Ok(ExtensionKeystoreBackup {
+            version: EXTENSION_BACKUP_VERSION.to_string(),
+            device_id: self.device_id.clone(),
+            exported_at: chrono::Utc::now().timestamp_millis(),
+            wallets,
+        })
+    }
+
+    /// Restores wallets from an extension backup, such as one made by
+    /// [`export_for_extension`](Self::export_for_extension)
+    ///
+    /// Each key share is decrypted with `password` and stored under it again.
+    /// Wallets already in the keystore are skipped, and nothing is written
+    /// unless every share decrypts. Returns the IDs of the restored wallets.
+    pub fn import_from_extension(&mut self, backup: &ExtensionKeystoreBackup, password: &str) -> Result<Vec<String>> {
+        let mut staged = Vec::new();
+        for wallet in &backup.wallets {
+            let share = decrypt_from_extension(&wallet.encrypted_share, password)
+                .map_err(|_| KeystoreError::InvalidPassword)?;
+            let (wallet_data, _) = share.to_cli_wallet()?;
+            let data = serde_json::to_vec(&wallet_data)
+                .map_err(|e| KeystoreError::SerializationError(e.to_string()))?;
+            if self.get_wallet(&wallet.metadata.id).is_none() {
+                staged.push((wallet.metadata.id.clone(), share, data));
+            }
+        }
+
+        let mut restored = Vec::with_capacity(staged.len());
+        for (wallet_id, share, data) in staged {
+            restored.push(self.create_wallet_multi_chain(
+                &wallet_id,
+                &share.curve,
+                Vec::new(),
+                share.threshold,
+                share.total_participants,
+                &share.group_public_key,
+                &data,
+                password,
+                Vec::new(),
+                None,
+                share.participant_index,
+            )?);
+        }
+        Ok(restored)
+    }
+
+    /// The audit log lives next to the wallet file, as one JSON entry per line
+    fn audit_log_key(&self, wallet_id: &str) -> Result<String> {
+        let wallet = self
+            .get_wallet(wallet_id)
+            .ok_or_else(|| KeystoreError::WalletNotFound(wallet_id.to_string()))?;
+        Ok(format!("{}/{}/{}.audit.jsonl", self.device_id, wallet.curve_type, wallet_id))
+    }
+
+    /// Key of a wallet file in this device's directory
+    fn wallet_key(&self, curve_type: &str, wallet_id: &str) -> String {
+        format!("{}/{}/{}.json", self.device_id, curve_type, wallet_id)
+    }
+
+    fn read_wallet_file(&self, key: &str) -> Result<WalletFile> {
+        let data = self
+            .backend
+            .read(key)?
+            .ok_or_else(|| KeystoreError::General(format!("Wallet file {} not found", key)))?;
+        parse_wallet_file(&data)
+    }
+
+    fn write_wallet_file(&self, key: &str, wallet_file: &WalletFile) -> Result<()> {
+        let data = serde_json::to_vec_pretty(wallet_file)
+            .map_err(|e| KeystoreError::General(format!("Failed to write wallet JSON: {}", e)))?;
+        self.backend.write(key, &data)
+    }
     
     /// Migrates legacy files to the new self-contained format
     fn migrate_legacy_files(&mut self) -> Result<()> {
         // Check if legacy index.json exists
-        let index_path = self.base_path.join("index.json");
-        let device_id_path = self.base_path.join("device_id");
-        
-        if !index_path.exists() {
+        let Some(index_data) = self.backend.read("index.json")? else {
             // No legacy files to migrate
             return Ok(());
-        }
+        };
         
         println!("Found legacy index.json, migrating to new format...");
         
         // Load the legacy index
-        let index_file = File::open(&index_path)?;
-        let legacy_index: KeystoreIndex = serde_json::from_reader(index_file)
+        let legacy_index: KeystoreIndex = serde_json::from_slice(&index_data)
             .map_err(|e| KeystoreError::General(format!("Failed to read legacy index: {}", e)))?;
         
         // Migrate each wallet that belongs to this device
@@ -301,24 +641,20 @@ impl Keystore {
             // Check if this device has a share for this wallet
             if wallet_info.devices.iter().any(|d| d.device_id == self.device_id) {
                 // Try to find the wallet file
-                let wallet_dir = self.base_path.join(&self.device_id).join(&wallet_info.curve_type);
-                let json_path = wallet_dir.join(format!("{}.json", wallet_info.wallet_id));
-                let dat_path = wallet_dir.join(format!("{}.dat", wallet_info.wallet_id));
+                let json_key = self.wallet_key(&wallet_info.curve_type, &wallet_info.wallet_id);
+                let dat_key = format!("{}/{}/{}.dat", self.device_id, wallet_info.curve_type, wallet_info.wallet_id);
                 
-                if json_path.exists() {
+                if let Some(json_data) = self.backend.read(&json_key)? {
                     // Check if it's already v2 format
-                    if let Ok(file) = File::open(&json_path) {
-                        if let Ok(wallet_file) = serde_json::from_reader::<_, WalletFile>(file) {
-                            if wallet_file.version == "2.0" {
-                                // Already migrated
-                                continue;
-                            }
-                        }
+                    if let Ok(wallet_file) = serde_json::from_slice::<WalletFile>(&json_data)
+                        && wallet_file.version == "2.0"
+                    {
+                        // Already migrated
+                        continue;
                     }
                     
                     // Read v1 JSON file
-                    let file = File::open(&json_path)?;
-                    let v1_json: serde_json::Value = serde_json::from_reader(file)
+                    let v1_json: serde_json::Value = serde_json::from_slice(&json_data)
                         .map_err(|e| KeystoreError::General(format!("Failed to parse v1 JSON: {}", e)))?;
                     
                     // Find participant index for this device
@@ -361,13 +697,16 @@ impl Keystore {
                             .unwrap_or_default()
                             .to_rfc3339(),
                         last_modified: chrono::Utc::now().to_rfc3339(),
-                        tags: None, // Deprecated field
+                        tags: Vec::new(),
+                        addresses: Default::default(),
+                        signing_policy: None,
                         description: None, // Deprecated field
                     };
                     
                     // Create v2 wallet file
                     let wallet_file = WalletFile {
                         version: "2.0".to_string(),
+                        keystore_version: KEYSTORE_VERSION,
                         encrypted: v1_json.get("encrypted").and_then(|v| v.as_bool()).unwrap_or(true),
                         algorithm: v1_json.get("algorithm").and_then(|v| v.as_str()).unwrap_or("AES-256-GCM").to_string(),
                         data: v1_json.get("data").and_then(|v| v.as_str()).unwrap_or("").to_string(),
@@ -375,16 +714,13 @@ impl Keystore {
                     };
                     
                     // Write v2 file
-                    let file = File::create(&json_path)?;
-                    serde_json::to_writer_pretty(file, &wallet_file)
+                    let data = serde_json::to_vec_pretty(&wallet_file)
                         .map_err(|e| KeystoreError::General(format!("Failed to write v2 JSON: {}", e)))?;
+                    self.backend.write(&json_key, &data)?;
                     
                     println!("Migrated wallet {} to v2 format", wallet_info.wallet_id);
-                } else if dat_path.exists() {
+                } else if let Some(encrypted_data) = self.backend.read(&dat_key)? {
                     // Convert .dat to v2 JSON
-                    let mut file = File::open(&dat_path)?;
-                    let mut encrypted_data = Vec::new();
-                    file.read_to_end(&mut encrypted_data)?;
                     
                     use base64::{Engine as _, engine::general_purpose};
                     let base64_encrypted = general_purpose::STANDARD.encode(&encrypted_data);
@@ -429,13 +765,16 @@ impl Keystore {
                             .unwrap_or_default()
                             .to_rfc3339(),
                         last_modified: chrono::Utc::now().to_rfc3339(),
-                        tags: None, // Deprecated field
+                        tags: Vec::new(),
+                        addresses: Default::default(),
+                        signing_policy: None,
                         description: None, // Deprecated field
                     };
                     
                     // Create v2 wallet file
                     let wallet_file = WalletFile {
                         version: "2.0".to_string(),
+                        keystore_version: KEYSTORE_VERSION,
                         encrypted: true,
                         algorithm: "AES-256-GCM".to_string(),
                         data: base64_encrypted,
@@ -443,12 +782,12 @@ impl Keystore {
                     };
                     
                     // Write v2 JSON file
-                    let json_file = File::create(&json_path)?;
-                    serde_json::to_writer_pretty(json_file, &wallet_file)
+                    let data = serde_json::to_vec_pretty(&wallet_file)
                         .map_err(|e| KeystoreError::General(format!("Failed to write v2 JSON: {}", e)))?;
+                    self.backend.write(&json_key, &data)?;
                     
                     // Delete old .dat file
-                    fs::remove_file(&dat_path)?;
+                    self.backend.delete(&dat_key)?;
                     
                     println!("Converted wallet {} from .dat to v2 JSON format", wallet_info.wallet_id);
                 }
@@ -456,14 +795,12 @@ impl Keystore {
         }
         
         // After successful migration, rename legacy files (don't delete in case something goes wrong)
-        if let Err(_e) = fs::rename(&index_path, self.base_path.join("index.json.legacy")) {
+        if let Err(_e) = self.rename("index.json", "index.json.legacy") {
             eprintln!("Warning: Failed to rename legacy index.json: {}", _e);
         }
         
-        if device_id_path.exists() {
-            if let Err(_e) = fs::rename(&device_id_path, self.base_path.join("device_id.legacy")) {
-                eprintln!("Warning: Failed to rename legacy device_id file: {}", _e);
-            }
+        if let Err(_e) = self.rename("device_id", "device_id.legacy") {
+            eprintln!("Warning: Failed to rename legacy device_id file: {}", _e);
         }
         
         // Reload the wallet cache
@@ -472,8 +809,537 @@ impl Keystore {
         println!("Migration to v2 format completed successfully");
         Ok(())
     }
+
+    /// Moves `from` to `to`; a missing `from` is left alone
+    fn rename(&self, from: &str, to: &str) -> Result<()> {
+        if let Some(data) = self.backend.read(from)? {
+            self.backend.write(to, &data)?;
+            self.backend.delete(from)?;
+        }
+        Ok(())
+    }
+}
+
+/// Chain whose address `list_addresses` reports for a curve
+fn address_chain(curve_type: &str) -> Option<&'static str> {
+    match curve_type {
+        "secp256k1" => Some("ethereum"),
+        "ed25519" => Some("solana"),
+        _ => None,
+    }
+}
+
+/// Address on `chain` for a hex-encoded FROST group verifying key
+fn derive_address(curve_type: &str, chain: &str, group_public_key: &str) -> std::result::Result<String, String> {
+    use mpc_wallet_frost_core::{Ed25519Curve, FrostCurve, Secp256k1Curve};
+
+    let key_bytes = hex::decode(group_public_key.trim_start_matches("0x"))
+        .map_err(|e| format!("group public key is not hex: {}", e))?;
+    match curve_type {
+        "secp256k1" => {
+            let key = frost_secp256k1::VerifyingKey::deserialize(&key_bytes).map_err(|e| e.to_string())?;
+            Secp256k1Curve::get_address_for_chain(&key, chain).map_err(|e| e.to_string())
+        }
+        "ed25519" => {
+            let key = frost_ed25519::VerifyingKey::deserialize(&key_bytes).map_err(|e| e.to_string())?;
+            Ed25519Curve::get_address_for_chain(&key, chain).map_err(|e| e.to_string())
+        }
+        other => Err(format!("unsupported curve {}", other)),
+    }
+}
+
+/// Checks a wallet from a backup archive without decrypting its key share
+fn validate_backup_wallet(wallet_file: &WalletFile) -> std::result::Result<(), String> {
+    let metadata = &wallet_file.metadata;
+    let wallet_id = &metadata.session_id;
+    if wallet_id.is_empty() || wallet_id.contains(['/', '\\', ':']) || wallet_id.starts_with('.') {
+        return Err(format!("unusable wallet ID '{}'", wallet_id));
+    }
+    if !matches!(metadata.curve_type.as_str(), "secp256k1" | "ed25519") {
+        return Err(format!("unsupported curve '{}'", metadata.curve_type));
+    }
+    if metadata.threshold == 0 || metadata.threshold > metadata.total_participants {
+        return Err(format!(
+            "threshold {} is not between 1 and {} participants",
+            metadata.threshold, metadata.total_participants
+        ));
+    }
+    if metadata.participant_index == 0 || metadata.participant_index > metadata.total_participants {
+        return Err(format!(
+            "participant index {} is not between 1 and {}",
+            metadata.participant_index, metadata.total_participants
+        ));
+    }
+    if hex::decode(&metadata.group_public_key).is_err() {
+        return Err("group public key is not hex".to_string());
+    }
+    use base64::{Engine as _, engine::general_purpose};
+    match general_purpose::STANDARD.decode(&wallet_file.data) {
+        Ok(data) if !data.is_empty() => Ok(()),
+        Ok(_) => Err("key share data is empty".to_string()),
+        Err(e) => Err(format!("key share data is not base64: {}", e)),
+    }
 }
 
 #[cfg(test)]
 
-mod tests { #[test] fn test_placeholder() { assert!(true); } }
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::sync::{Arc, Mutex};
+    use tempfile::TempDir;
+
+    /// Backend keeping every key in a shared map
+    #[derive(Clone, Default)]
+    struct MemoryBackend(Arc<Mutex<BTreeMap<String, Vec<u8>>>>);
+
+    impl KeystoreBackend for MemoryBackend {
+        fn read(&self, key: &str) -> Result<Option<Vec<u8>>> {
+            Ok(self.0.lock().unwrap().get(key).cloned())
+        }
+
+        fn write(&self, key: &str, data: &[u8]) -> Result<()> {
+            self.0.lock().unwrap().insert(key.to_string(), data.to_vec());
+            Ok(())
+        }
+
+        fn list(&self, prefix: &str) -> Result<Vec<String>> {
+            let dir = format!("{}/", prefix);
+            Ok(self.0.lock().unwrap().keys()
+                .filter(|key| key.strip_prefix(&dir).is_some_and(|name| !name.contains('/')))
+                .cloned()
+                .collect())
+        }
+
+        fn delete(&self, key: &str) -> Result<()> {
+            self.0.lock().unwrap().remove(key);
+            Ok(())
+        }
+    }
+
+    #[test] fn test_placeholder() { assert!(true); }
+
+    fn create(keystore: &mut Keystore, name: &str, curve: &str, share: &[u8]) -> String {
+        keystore
+            .create_wallet_multi_chain(name, curve, Vec::new(), 2, 3, "02abcd", share, "wallet-pass", Vec::new(), None, 1)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_in_memory_backend() {
+        let backend = MemoryBackend::default();
+        let mut keystore = Keystore::with_backend(backend.clone(), "device-1").unwrap();
+        let eth = create(&mut keystore, "eth-wallet", "secp256k1", b"eth share");
+        keystore.add_tag(&eth, "prod").unwrap();
+        keystore.append_audit_entry(&AuditEntry {
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+            wallet_id: eth.clone(),
+            action: crate::keystore::AuditAction::SignatureShare,
+            message_hash: "00".repeat(32),
+            participants: vec!["device-1".to_string(), "device-2".to_string()],
+            chain: "ethereum".to_string(),
+            summary: "Transfer".to_string(),
+        }).unwrap();
+
+        let mut keys: Vec<String> = backend.0.lock().unwrap().keys().cloned().collect();
+        keys.sort();
+        assert_eq!(keys, vec![
+            "device-1/secp256k1/eth-wallet.audit.jsonl".to_string(),
+            "device-1/secp256k1/eth-wallet.json".to_string(),
+        ]);
+
+        // A second keystore over the same backend sees everything
+        let mut reopened = Keystore::with_backend(backend.clone(), "device-1").unwrap();
+        assert_eq!(reopened.find_wallets_by_tag("prod").len(), 1);
+        assert_eq!(reopened.load_wallet_file(&eth, "wallet-pass").unwrap(), b"eth share");
+        assert_eq!(reopened.get_audit_log(&eth).unwrap().len(), 1);
+
+        let backup = reopened.export_backup("backup-pass").unwrap();
+        let mut other = Keystore::with_backend(MemoryBackend::default(), "device-1").unwrap();
+        assert_eq!(other.import_backup(&backup, "backup-pass").unwrap(), vec![eth.clone()]);
+        assert_eq!(other.load_wallet_file(&eth, "wallet-pass").unwrap(), b"eth share");
+        assert!(reopened.import_backup(&backup, "backup-pass").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_backup_restores_all_wallets() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut keystore = Keystore::new(temp_dir.path(), "device-1").unwrap();
+        let eth = create(&mut keystore, "eth-wallet", "secp256k1", b"eth share");
+        let sol = create(&mut keystore, "sol-wallet", "ed25519", b"sol share");
+
+        let backup = keystore.export_backup("backup-pass").unwrap();
+        assert!(matches!(
+            keystore.import_backup(&backup, "wrong-pass"),
+            Err(KeystoreError::InvalidPassword)
+        ));
+        // Nothing to restore while the wallets are still present
+        assert!(keystore.import_backup(&backup, "backup-pass").unwrap().is_empty());
+
+        fs::remove_dir_all(temp_dir.path()).unwrap();
+        let mut keystore = Keystore::new(temp_dir.path(), "device-1").unwrap();
+        assert!(keystore.list_wallets().is_empty());
+
+        let mut restored = keystore.import_backup(&backup, "backup-pass").unwrap();
+        restored.sort();
+        assert_eq!(restored, vec![eth.clone(), sol.clone()]);
+        assert_eq!(keystore.load_wallet_file(&eth, "wallet-pass").unwrap(), b"eth share");
+        assert_eq!(keystore.load_wallet_file(&sol, "wallet-pass").unwrap(), b"sol share");
+
+        // Restored files are picked up on the next open too
+        let reopened = Keystore::new(temp_dir.path(), "device-1").unwrap();
+        assert_eq!(reopened.list_wallets().len(), 2);
+    }
+
+    #[test]
+    fn test_backup_with_corrupt_wallet_imports_nothing() {
+        let source_dir = TempDir::new().unwrap();
+        let mut source = Keystore::new(source_dir.path(), "device-1").unwrap();
+        create(&mut source, "eth-wallet", "secp256k1", b"eth share");
+        create(&mut source, "sol-wallet", "ed25519", b"sol share");
+        create(&mut source, "cold-wallet", "secp256k1", b"cold share");
+
+        // Re-seal the backup with one wallet claiming an out-of-range index
+        let mut archive: KeystoreBackupArchive =
+            serde_json::from_slice(&source.export_backup("backup-pass").unwrap()).unwrap();
+        let plaintext = decrypt_bytes_from_extension(&archive.wallets, "backup-pass").unwrap();
+        let mut wallets: Vec<serde_json::Value> = serde_json::from_slice(&plaintext).unwrap();
+        let corrupt = wallets
+            .iter_mut()
+            .find(|w| w["metadata"]["session_id"] == "sol-wallet")
+            .unwrap();
+        corrupt["metadata"]["participant_index"] = serde_json::json!(9);
+        archive.wallets = encrypt_bytes_for_extension(&serde_json::to_vec(&wallets).unwrap(), "backup-pass", "keystore-backup").unwrap();
+        let backup = serde_json::to_vec(&archive).unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut keystore = Keystore::new(temp_dir.path(), "device-1").unwrap();
+        let error = keystore.import_backup(&backup, "backup-pass").unwrap_err().to_string();
+        assert!(error.contains("'sol-wallet'"), "{}", error);
+        assert!(error.contains("participant index 9"), "{}", error);
+
+        assert!(keystore.list_wallets().is_empty());
+        assert!(Keystore::new(temp_dir.path(), "device-1").unwrap().list_wallets().is_empty());
+    }
+
+    /// A `WalletData` key share for participant 1 of a fresh 2-of-3 group,
+    /// and the group's hex verifying key
+    fn frost_wallet(wallet_id: &str, curve: &str) -> (Vec<u8>, String) {
+        let rng = frost_secp256k1::rand_core::OsRng;
+        let mut wallet_data = WalletData {
+            secp256k1_key_package: None,
+            secp256k1_public_key: None,
+            ed25519_key_package: None,
+            ed25519_public_key: None,
+            session_id: wallet_id.to_string(),
+            device_id: "device-1".to_string(),
+        };
+        let group_key = if curve == "secp256k1" {
+            let (shares, public_key_package) = frost_secp256k1::keys::generate_with_dealer(
+                3, 2, frost_secp256k1::keys::IdentifierList::Default, rng,
+            ).unwrap();
+            let share = shares.into_values().next().unwrap();
+            wallet_data.secp256k1_key_package = Some(share.try_into().unwrap());
+            let group_key = public_key_package.verifying_key().serialize().unwrap();
+            wallet_data.secp256k1_public_key = Some(public_key_package);
+            group_key
+        } else {
+            let (shares, public_key_package) = frost_ed25519::keys::generate_with_dealer(
+                3, 2, frost_ed25519::keys::IdentifierList::Default, rng,
+            ).unwrap();
+            let share = shares.into_values().next().unwrap();
+            wallet_data.ed25519_key_package = Some(share.try_into().unwrap());
+            let group_key = public_key_package.verifying_key().serialize().unwrap();
+            wallet_data.ed25519_public_key = Some(public_key_package);
+            group_key
+        };
+        (serde_json::to_vec(&wallet_data).unwrap(), hex::encode(group_key))
+    }
+
+    #[test]
+    fn test_extension_export_round_trip() {
+        let mut keystore = Keystore::with_backend(MemoryBackend::default(), "device-1").unwrap();
+        for (wallet_id, curve) in [("eth-wallet", "secp256k1"), ("sol-wallet", "ed25519")] {
+            let (data, group_key) = frost_wallet(wallet_id, curve);
+            keystore
+                .create_wallet_multi_chain(wallet_id, curve, Vec::new(), 2, 3, &group_key, &data, "pw", Vec::new(), None, 1)
+                .unwrap();
+        }
+        let addresses: BTreeMap<String, String> = keystore
+            .list_addresses()
+            .into_iter()
+            .map(|(wallet_id, _, address)| (wallet_id, address))
+            .collect();
+
+        // What the extension receives is plain JSON with each share sealed
+        let exported = serde_json::to_string(&keystore.export_for_extension("pw").unwrap()).unwrap();
+        let backup: ExtensionKeystoreBackup = serde_json::from_str(&exported).unwrap();
+        assert_eq!(backup.version, EXTENSION_BACKUP_VERSION);
+        assert_eq!(backup.wallets.len(), 2);
+        for wallet in &backup.wallets {
+            assert_eq!(wallet.metadata.address, addresses[&wallet.metadata.id]);
+            assert!(decrypt_from_extension(&wallet.encrypted_share, "wrong").is_err());
+            let share = decrypt_from_extension(&wallet.encrypted_share, "pw").unwrap();
+            assert_eq!(share.session_id, wallet.metadata.id);
+            assert_eq!((share.threshold, share.total_participants, share.participant_index), (2, 3, 1));
+            let original: WalletData = serde_json::from_slice(
+                &keystore.load_wallet_file(&wallet.metadata.id, "pw").unwrap(),
+            ).unwrap();
+            let (restored, _) = share.to_cli_wallet().unwrap();
+            assert_eq!(serde_json::to_value(restored).unwrap(), serde_json::to_value(original).unwrap());
+        }
+
+        let mut other = Keystore::with_backend(MemoryBackend::default(), "device-1").unwrap();
+        assert!(matches!(other.import_from_extension(&backup, "wrong"), Err(KeystoreError::InvalidPassword)));
+        assert!(other.list_wallets().is_empty());
+        let mut restored = other.import_from_extension(&backup, "pw").unwrap();
+        restored.sort();
+        assert_eq!(restored, vec!["eth-wallet".to_string(), "sol-wallet".to_string()]);
+        assert_eq!(
+            other.load_wallet_file("eth-wallet", "pw").unwrap(),
+            keystore.load_wallet_file("eth-wallet", "pw").unwrap()
+        );
+        assert!(other.import_from_extension(&backup, "pw").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tags_persist_and_filter() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut keystore = Keystore::new(temp_dir.path(), "device-1").unwrap();
+        let treasury = create(&mut keystore, "treasury", "secp256k1", b"share 1");
+        let payroll = create(&mut keystore, "payroll", "secp256k1", b"share 2");
+        create(&mut keystore, "test", "ed25519", b"share 3");
+
+        keystore.add_tag(&treasury, "prod").unwrap();
+        keystore.add_tag(&treasury, "prod").unwrap();
+        keystore.add_tag(&payroll, "prod").unwrap();
+        keystore.add_tag(&payroll, "hr").unwrap();
+        assert!(matches!(keystore.add_tag("missing", "prod"), Err(KeystoreError::WalletNotFound(_))));
+
+        let ids = |wallets: Vec<&WalletMetadata>| {
+            let mut ids: Vec<String> = wallets.into_iter().map(|w| w.session_id.clone()).collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(ids(keystore.find_wallets_by_tag("prod")), vec![payroll.clone(), treasury.clone()]);
+        assert_eq!(keystore.get_wallet(&treasury).unwrap().tags, vec!["prod".to_string()]);
+
+        keystore.remove_tag(&payroll, "prod").unwrap();
+        assert_eq!(ids(keystore.find_wallets_by_tag("prod")), vec![treasury.clone()]);
+
+        // Tags survive a reload, and the shares are untouched
+        let reopened = Keystore::new(temp_dir.path(), "device-1").unwrap();
+        assert_eq!(ids(reopened.find_wallets_by_tag("hr")), vec![payroll.clone()]);
+        assert_eq!(ids(reopened.find_wallets_by_tag("prod")), vec![treasury.clone()]);
+        assert!(reopened.find_wallets_by_tag("staging").is_empty());
+        assert_eq!(reopened.load_wallet_file(&payroll, "wallet-pass").unwrap(), b"share 2");
+    }
+
+    #[test]
+    fn test_metadata_without_tags_loads_empty() {
+        let json = serde_json::to_value(WalletMetadata::new(
+            "legacy".to_string(),
+            "device-1".to_string(),
+            "ed25519".to_string(),
+            2,
+            3,
+            1,
+            "abcd".to_string(),
+        ))
+        .unwrap();
+        assert!(json.get("tags").is_none());
+        let metadata: WalletMetadata = serde_json::from_value(json).unwrap();
+        assert!(metadata.tags.is_empty());
+    }
+
+    #[test]
+    fn test_lists_addresses_across_curves() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut keystore = Keystore::new(temp_dir.path(), "device-1").unwrap();
+        // Group key = generator point, i.e. the key for secret scalar 1
+        let secp_key = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let ed_key = "5866666666666666666666666666666666666666666666666666666666666666";
+        keystore
+            .create_wallet_multi_chain("eth", "secp256k1", Vec::new(), 2, 3, secp_key, b"s1", "pw", Vec::new(), None, 1)
+            .unwrap();
+        keystore
+            .create_wallet_multi_chain("sol", "ed25519", Vec::new(), 2, 3, ed_key, b"s2", "pw", Vec::new(), None, 1)
+            .unwrap();
+        create(&mut keystore, "broken", "secp256k1", b"s3");
+        // Derived on demand before anything is cached
+        assert_eq!(
+            keystore.wallet_address("eth", "ethereum").as_deref(),
+            Ok("0x7e5f4552091a69125d5dfcb7b8c2659029395bdf")
+        );
+        assert!(keystore.wallet_address("missing", "ethereum").is_err());
+
+        let mut addresses = keystore.list_addresses();
+        addresses.sort();
+        let sol_address = bs58::encode(hex::decode(ed_key).unwrap()).into_string();
+        let expected = vec![
+            ("eth".to_string(), "ethereum".to_string(), "0x7e5f4552091a69125d5dfcb7b8c2659029395bdf".to_string()),
+            ("sol".to_string(), "solana".to_string(), sol_address.clone()),
+        ];
+        assert_eq!(addresses, expected);
+
+        // Cached in the wallet files, so a fresh keystore reads them back
+        let mut reopened = Keystore::new(temp_dir.path(), "device-1").unwrap();
+        assert_eq!(reopened.get_wallet("sol").unwrap().addresses.get("solana"), Some(&sol_address));
+        let mut addresses = reopened.list_addresses();
+        addresses.sort();
+        assert_eq!(addresses, expected);
+    }
+
+    #[test]
+    fn test_inspect_summarizes_without_password() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut keystore = Keystore::new(temp_dir.path(), "device-1").unwrap();
+        let secp_key = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        keystore
+            .create_wallet_multi_chain("eth", "secp256k1", Vec::new(), 2, 3, secp_key, b"s1", "pw", Vec::new(), None, 1)
+            .unwrap();
+        let path = temp_dir.path().join("device-1/secp256k1/eth.json");
+        let before = fs::read(&path).unwrap();
+
+        let summary = Keystore::inspect(&before).unwrap();
+        assert_eq!(summary.wallet_id, "eth");
+        assert_eq!(summary.curve_type, "secp256k1");
+        assert_eq!((summary.threshold, summary.total_participants, summary.participant_index), (2, 3, 1));
+        assert_eq!(summary.keystore_version, KEYSTORE_VERSION);
+        assert_eq!(summary.chain, "ethereum");
+        assert_eq!(summary.address, "0x7e5f4552091a69125d5dfcb7b8c2659029395bdf");
+        // Inspecting neither caches the address nor touches the file
+        assert_eq!(fs::read(&path).unwrap(), before);
+        assert!(keystore.get_wallet("eth").unwrap().addresses.is_empty());
+    }
+
+    #[test]
+    fn test_inspect_rejects_corrupt_file() {
+        let error = Keystore::inspect("{\"version\": \"2.0\", \"encrypted\": tr").unwrap_err();
+        assert!(error.to_string().contains("Failed to parse wallet JSON"));
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut keystore = Keystore::new(temp_dir.path(), "device-1").unwrap();
+        create(&mut keystore, "broken", "secp256k1", b"s1");
+        let data = fs::read(temp_dir.path().join("device-1/secp256k1/broken.json")).unwrap();
+        let error = Keystore::inspect(data).unwrap_err();
+        assert!(error.to_string().contains("Keystore file is invalid"));
+    }
+
+    const TREASURY_COLD: &str = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+    const EXCHANGE: &str = "bc1qm34lsc65zpw79lxes69zkqmk6ee3ewf0j77s3h";
+    const TREASURY_KEY: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+    const CHANGE_PATH: &str = "m/84'/0'/0'/1/0";
+
+    /// The treasury's key at `CHANGE_PATH` and its P2WPKH address
+    fn treasury_change() -> (String, String) {
+        use mpc_wallet_frost_core::{ChainCode, DerivationPath, derive_child_verifying_key};
+        let group_key_bytes = hex::decode(TREASURY_KEY).unwrap();
+        let group_key = frost_secp256k1::VerifyingKey::deserialize(&group_key_bytes).unwrap();
+        let path = DerivationPath::parse(CHANGE_PATH).unwrap();
+        let child = derive_child_verifying_key(&group_key, &ChainCode::from_group_key(&group_key_bytes), &path)
+            .unwrap()
+            .serialize()
+            .unwrap();
+        let address = mpc_wallet_blockchain::bitcoin::BitcoinHandler::new()
+            .derive_address(&child, mpc_wallet_blockchain::bitcoin::BitcoinAddressType::P2wpkh)
+            .unwrap();
+        (hex::encode(child), address)
+    }
+
+    /// Bitcoin transaction paying `value_sat` to `destination`, plus change
+    /// back to the treasury
+    fn bitcoin_payment(destination: &str, value_sat: u64) -> mpc_wallet_blockchain::ParsedTransaction {
+        let (change_key, change_address) = treasury_change();
+        let change_output = serde_json::json!({
+            "address": change_address,
+            "value_sat": 900_000_000u64,
+            "change": true,
+            "key_origins": [{ "pubkey": change_key, "path": CHANGE_PATH }],
+        });
+        mpc_wallet_blockchain::ParsedTransaction {
+            raw_bytes: Vec::new(),
+            hash: "payment".to_string(),
+            summary: String::new(),
+            chain_id: None,
+            metadata: serde_json::json!({
+                "network": "mainnet",
+                "outputs": [
+                    { "address": destination, "value_sat": value_sat, "change": false, "key_origins": [] },
+                    change_output,
+                ],
+            }),
+        }
+    }
+
+    fn treasury_with_policy(temp_dir: &TempDir) -> (Keystore, String) {
+        let mut keystore = Keystore::new(temp_dir.path(), "device-1").unwrap();
+        let treasury = keystore
+            .create_wallet_multi_chain("treasury", "secp256k1", Vec::new(), 2, 3, TREASURY_KEY, b"share", "wallet-pass", Vec::new(), None, 1)
+            .unwrap();
+        keystore
+            .set_signing_policy(&treasury, Some(SigningPolicy {
+                max_value: Some(1_000_000),
+                allowed_destinations: vec![TREASURY_COLD.to_string()],
+            }))
+            .unwrap();
+        (keystore, treasury)
+    }
+
+    #[test]
+    fn test_policy_blocks_over_limit_transfer() {
+        let temp_dir = TempDir::new().unwrap();
+        let (keystore, treasury) = treasury_with_policy(&temp_dir);
+
+        assert!(keystore.check_policy(&treasury, &bitcoin_payment(TREASURY_COLD, 1_000_000)).is_ok());
+        // Change outputs don't count against the limit, but this payment does
+        assert_eq!(
+            keystore.check_policy(&treasury, &bitcoin_payment(TREASURY_COLD, 1_000_001)),
+            Err(PolicyViolation::ValueExceedsLimit {
+                destination: TREASURY_COLD.to_string(),
+                amount: 1_000_001,
+                limit: 1_000_000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_policy_blocks_disallowed_destination() {
+        let temp_dir = TempDir::new().unwrap();
+        let (_, treasury) = treasury_with_policy(&temp_dir);
+
+        // The policy is stored with the wallet
+        let keystore = Keystore::new(temp_dir.path(), "device-1").unwrap();
+        assert_eq!(
+            keystore.check_policy(&treasury, &bitcoin_payment(EXCHANGE, 10)),
+            Err(PolicyViolation::DestinationNotAllowed(EXCHANGE.to_string()))
+        );
+
+        // A change flag the wallet key doesn't back counts as a transfer
+        let (change_key, change_address) = treasury_change();
+        for (claimed_key, address) in [
+            // A key that doesn't sit at the claimed path
+            (TREASURY_KEY.to_string(), change_address),
+            // The right key, but the output pays elsewhere
+            (change_key, EXCHANGE.to_string()),
+        ] {
+            let mut payment = bitcoin_payment(TREASURY_COLD, 10);
+            let change = &mut payment.metadata["outputs"][1];
+            change["key_origins"] = serde_json::json!([{ "pubkey": claimed_key, "path": CHANGE_PATH }]);
+            change["address"] = serde_json::json!(address);
+            assert_eq!(
+                keystore.check_policy(&treasury, &payment),
+                Err(PolicyViolation::DestinationNotAllowed(address))
+            );
+        }
+
+        // A transaction whose transfers weren't decoded can't be checked
+        let mut opaque = bitcoin_payment(EXCHANGE, 10);
+        opaque.metadata = serde_json::json!({ "type": "legacy" });
+        assert_eq!(
+            keystore.check_policy(&treasury, &opaque),
+            Err(PolicyViolation::UnknownTransfers("payment".to_string()))
+        );
+    }
+}
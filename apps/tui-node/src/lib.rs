@@ -12,6 +12,8 @@ pub mod offline;
 pub mod elm;
 pub mod hybrid;
 pub mod webrtc;
+pub mod security;
+pub mod optimization;
 
 // Re-export commonly used types
 pub use keystore::{Keystore, DeviceInfo};
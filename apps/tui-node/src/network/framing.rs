@@ -0,0 +1,272 @@
+//! Length-prefixed framing for data-channel payloads
+//!
+//! A frame is a 4-byte big-endian length followed by the payload bytes. A
+//! frame may be cut across several data-channel messages, and one message
+//! may carry several frames; the receiver keeps a `FrameDecoder` per peer and
+//! hands on complete frames only.
+//!
+//! Frames travel as binary messages. A payload small enough for a single
+//! message is still sent as plain text, which every peer (including the
+//! browser extension) reads directly, and text messages are always passed
+//! through unchanged.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use webrtc::data_channel::RTCDataChannel;
+
+/// Bytes of the big-endian length prefix
+pub const FRAME_HEADER_LEN: usize = 4;
+
+/// Largest payload accepted in one frame unless `FrameLimits` says otherwise;
+/// a bigger prefix means a corrupt stream or a hostile peer
+pub const MAX_FRAME_LEN: usize = 1024 * 1024;
+
+/// Data-channel messages are kept to this size so SCTP never has to reject them
+pub const MAX_DATA_CHANNEL_MESSAGE_LEN: usize = 16 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FramingError {
+    FrameTooLarge { len: usize, max: usize },
+}
+
+impl fmt::Display for FramingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FramingError::FrameTooLarge { len, max } => {
+                write!(f, "frame of {} bytes exceeds the {} byte limit", len, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FramingError {}
+
+/// Prefix `payload` with its length
+pub fn encode_frame(payload: &[u8]) -> Result<Vec<u8>, FramingError> {
+    if payload.len() > MAX_FRAME_LEN {
+        return Err(FramingError::FrameTooLarge { len: payload.len(), max: MAX_FRAME_LEN });
+    }
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + payload.len());
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(payload);
+    Ok(frame)
+}
+
+/// Frame `payload` and cut it into data-channel messages of at most
+/// `max_message_len` bytes
+pub fn frame_messages(payload: &[u8], max_message_len: usize) -> Result<Vec<Vec<u8>>, FramingError> {
+    Ok(encode_frame(payload)?
+        .chunks(max_message_len.max(1))
+        .map(<[u8]>::to_vec)
+        .collect())
+}
+
+/// What a peer may send us on a data channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameLimits {
+    /// Largest payload accepted, whether framed or as a single text message
+    pub max_frame_len: usize,
+    /// Close the offending peer's connection rather than only dropping the payload
+    pub disconnect_on_oversize: bool,
+}
+
+impl Default for FrameLimits {
+    fn default() -> Self {
+        Self {
+            max_frame_len: MAX_FRAME_LEN,
+            disconnect_on_oversize: false,
+        }
+    }
+}
+
+impl FrameLimits {
+    /// Rejects an unframed (text) payload of `len` bytes over the limit
+    pub fn check(&self, len: usize) -> Result<(), FramingError> {
+        if len > self.max_frame_len {
+            return Err(FramingError::FrameTooLarge { len, max: self.max_frame_len });
+        }
+        Ok(())
+    }
+}
+
+/// Reassembles frames from one peer's data-channel messages
+#[derive(Debug)]
+pub struct FrameDecoder {
+    buffer: Vec<u8>,
+    max_frame_len: usize,
+}
+
+impl Default for FrameDecoder {
+    fn default() -> Self {
+        Self::new(MAX_FRAME_LEN)
+    }
+}
+
+impl FrameDecoder {
+    pub fn new(max_frame_len: usize) -> Self {
+        Self { buffer: Vec::new(), max_frame_len }
+    }
+
+    /// Buffer `bytes` and return every frame they complete, in order. Only
+    /// the frame in progress is ever held, and its length prefix is checked
+    /// before any of its payload is buffered, so a peer can't make us hold
+    /// more than `max_frame_len` bytes. On an oversized prefix the buffer is
+    /// dropped, since the stream can't be resynchronised.
+    pub fn push(&mut self, mut bytes: &[u8]) -> Result<Vec<Vec<u8>>, FramingError> {
+        let mut frames = Vec::new();
+        while !bytes.is_empty() {
+            if self.buffer.len() < FRAME_HEADER_LEN {
+                let take = (FRAME_HEADER_LEN - self.buffer.len()).min(bytes.len());
+                self.buffer.extend_from_slice(&bytes[..take]);
+                bytes = &bytes[take..];
+                if self.buffer.len() < FRAME_HEADER_LEN {
+                    break;
+                }
+            }
+            let header = &self.buffer[..FRAME_HEADER_LEN];
+            let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+            if len > self.max_frame_len {
+                self.buffer = Vec::new();
+                return Err(FramingError::FrameTooLarge { len, max: self.max_frame_len });
+            }
+            let take = (FRAME_HEADER_LEN + len - self.buffer.len()).min(bytes.len());
+            self.buffer.extend_from_slice(&bytes[..take]);
+            bytes = &bytes[take..];
+            if self.buffer.len() == FRAME_HEADER_LEN + len {
+                frames.push(self.buffer[FRAME_HEADER_LEN..].to_vec());
+                self.buffer.clear();
+            }
+        }
+        Ok(frames)
+    }
+
+    /// Bytes held for a frame that hasn't fully arrived
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+}
+
+/// A `FrameDecoder` per peer device, all sharing one set of limits
+#[derive(Debug, Default)]
+pub struct PeerFrameBuffers {
+    peers: HashMap<String, FrameDecoder>,
+    limits: FrameLimits,
+}
+
+impl PeerFrameBuffers {
+    pub fn new(limits: FrameLimits) -> Self {
+        Self { peers: HashMap::new(), limits }
+    }
+
+    pub fn limits(&self) -> FrameLimits {
+        self.limits
+    }
+
+    pub fn push(&mut self, peer: &str, bytes: &[u8]) -> Result<Vec<Vec<u8>>, FramingError> {
+        let max_frame_len = self.limits.max_frame_len;
+        self.peers
+            .entry(peer.to_string())
+            .or_insert_with(|| FrameDecoder::new(max_frame_len))
+            .push(bytes)
+    }
+
+    /// Forget a peer's partial frame, e.g. when its data channel is replaced
+    pub fn reset(&mut self, peer: &str) {
+        self.peers.remove(peer);
+    }
+
+    pub fn clear(&mut self) {
+        self.peers.clear();
+    }
+}
+
+/// Send `text` on `channel`: as a text message when it fits in one, otherwise
+/// as a frame split over binary messages
+pub async fn send_payload(channel: &RTCDataChannel, text: &str) -> Result<(), String> {
+    if text.len() <= MAX_DATA_CHANNEL_MESSAGE_LEN {
+        return channel.send_text(text).await.map(|_| ()).map_err(|e| e.to_string());
+    }
+    let messages = frame_messages(text.as_bytes(), MAX_DATA_CHANNEL_MESSAGE_LEN).map_err(|e| e.to_string())?;
+    for message in messages {
+        channel
+            .send(&bytes::Bytes::from(message))
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_split_across_messages() {
+        let payload = br#"{"webrtc_msg_type":"SimpleMessage","text":"DKG_ROUND1:AAAA"}"#;
+        let messages = frame_messages(payload, 7).unwrap();
+        assert!(messages.len() > 3);
+
+        let mut decoder = FrameDecoder::default();
+        let (last, rest) = messages.split_last().unwrap();
+        for message in rest {
+            assert!(decoder.push(message).unwrap().is_empty());
+        }
+        assert!(decoder.buffered() > 0);
+        assert_eq!(decoder.push(last).unwrap(), vec![payload.to_vec()]);
+        assert_eq!(decoder.buffered(), 0);
+    }
+
+    #[test]
+    fn test_coalesced_frames_in_one_message() {
+        let first = encode_frame(b"round1").unwrap();
+        let second = encode_frame(b"round2").unwrap();
+        let third = encode_frame(b"ack").unwrap();
+        let mut message = [first, second].concat();
+        message.extend_from_slice(&third[..5]);
+
+        let mut buffers = PeerFrameBuffers::default();
+        assert_eq!(
+            buffers.push("bob", &message).unwrap(),
+            vec![b"round1".to_vec(), b"round2".to_vec()]
+        );
+        // Another peer's bytes don't land in Bob's partial frame
+        assert_eq!(buffers.push("carol", &encode_frame(b"x").unwrap()).unwrap(), vec![b"x".to_vec()]);
+        assert_eq!(buffers.push("bob", &third[5..]).unwrap(), vec![b"ack".to_vec()]);
+    }
+
+    #[test]
+    fn test_oversized_prefix_is_rejected() {
+        let mut decoder = FrameDecoder::default();
+        let error = decoder.push(&u32::MAX.to_be_bytes()).unwrap_err();
+        assert!(matches!(error, FramingError::FrameTooLarge { .. }));
+        assert_eq!(decoder.buffered(), 0);
+        assert!(encode_frame(&vec![0; MAX_FRAME_LEN + 1]).is_err());
+    }
+
+    #[test]
+    fn test_configured_limit_bounds_buffering() {
+        let limits = FrameLimits { max_frame_len: 64, disconnect_on_oversize: true };
+        let mut buffers = PeerFrameBuffers::new(limits);
+
+        // A frame right at the limit still gets through, even byte by byte
+        let frame = encode_frame(&[7; 64]).unwrap();
+        let (last, rest) = frame.split_last().unwrap();
+        for byte in rest {
+            assert!(buffers.push("bob", &[*byte]).unwrap().is_empty());
+        }
+        assert_eq!(buffers.push("bob", &[*last]).unwrap(), vec![vec![7; 64]]);
+
+        // One byte more is refused from the prefix alone, before the payload
+        // is buffered, even when the whole oversized frame comes in one message
+        let oversized = encode_frame(&[7; 65]).unwrap();
+        let error = buffers.push("bob", &oversized).unwrap_err();
+        assert_eq!(error, FramingError::FrameTooLarge { len: 65, max: 64 });
+        assert_eq!(buffers.peers["bob"].buffered(), 0);
+        assert_eq!(buffers.peers["bob"].buffer.capacity(), 0);
+
+        // Unframed text payloads are held to the same limit
+        assert!(limits.check(64).is_ok());
+        assert!(limits.check(65).is_err());
+    }
+}
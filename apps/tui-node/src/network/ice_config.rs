@@ -0,0 +1,172 @@
+//! STUN/TURN server settings for WebRTC peer connections
+//!
+//! Without at least a STUN server, peers behind NAT only offer host candidates
+//! and never reach each other. Nodes behind symmetric NATs also need a TURN
+//! relay. The list is stored next to the keystore in `ice_servers.json`.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use webrtc::ice_transport::ice_server::RTCIceServer;
+use webrtc::peer_connection::configuration::RTCConfiguration;
+use webrtc::peer_connection::policy::ice_transport_policy::RTCIceTransportPolicy;
+
+/// Settings file name inside the keystore directory
+pub const ICE_CONFIG_FILE: &str = "ice_servers.json";
+
+/// A single STUN or TURN server entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IceServerEntry {
+    /// `stun:`, `turn:` or `turns:` URLs for this server
+    pub urls: Vec<String>,
+    /// TURN username
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// TURN credential
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential: Option<String>,
+}
+
+/// ICE servers used for every peer connection this node creates
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StunTurnConfig {
+    pub servers: Vec<IceServerEntry>,
+}
+
+impl Default for StunTurnConfig {
+    fn default() -> Self {
+        Self {
+            servers: vec![IceServerEntry {
+                urls: vec!["stun:stun.l.google.com:19302".to_string()],
+                username: None,
+                credential: None,
+            }],
+        }
+    }
+}
+
+impl StunTurnConfig {
+    /// `~/.frost_keystore/ice_servers.json`
+    pub fn default_path() -> PathBuf {
+        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        PathBuf::from(home).join(".frost_keystore").join(ICE_CONFIG_FILE)
+    }
+
+    /// Loads the config from `path`, falling back to the default when the
+    /// file is missing or unreadable
+    pub fn load_or_default(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid ICE config {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Writes the config to `path`, creating the parent directory
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        std::fs::write(path, json)
+    }
+
+    /// Entries in the form `RTCConfiguration.ice_servers` expects
+    pub fn to_ice_servers(&self) -> Vec<RTCIceServer> {
+        self.servers
+            .iter()
+            .map(|server| RTCIceServer {
+                urls: server.urls.clone(),
+                username: server.username.clone().unwrap_or_default(),
+                credential: server.credential.clone().unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// True if any entry is a `turn:` or `turns:` server
+    pub fn has_turn(&self) -> bool {
+        self.servers
+            .iter()
+            .flat_map(|server| &server.urls)
+            .any(|url| url.starts_with("turn:") || url.starts_with("turns:"))
+    }
+
+    /// Settings for a new peer connection. With `relay_only` ICE gathers
+    /// only TURN relay candidates, for peers no direct path reached.
+    pub fn rtc_configuration(&self, relay_only: bool) -> RTCConfiguration {
+        RTCConfiguration {
+            ice_servers: self.to_ice_servers(),
+            ice_transport_policy: if relay_only {
+                RTCIceTransportPolicy::Relay
+            } else {
+                RTCIceTransportPolicy::All
+            },
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maps_to_rtc_ice_servers() {
+        let config = StunTurnConfig {
+            servers: vec![
+                IceServerEntry {
+                    urls: vec!["stun:stun.example.org:3478".to_string()],
+                    username: None,
+                    credential: None,
+                },
+                IceServerEntry {
+                    urls: vec![
+                        "turn:turn.example.org:3478?transport=udp".to_string(),
+                        "turns:turn.example.org:5349".to_string(),
+                    ],
+                    username: Some("alice".to_string()),
+                    credential: Some("secret".to_string()),
+                },
+            ],
+        };
+
+        let servers = config.to_ice_servers();
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[0].urls, vec!["stun:stun.example.org:3478"]);
+        assert!(servers[0].username.is_empty() && servers[0].credential.is_empty());
+        assert_eq!(servers[1].urls.len(), 2);
+        assert_eq!(servers[1].username, "alice");
+        assert_eq!(servers[1].credential, "secret");
+        assert!(config.has_turn());
+        assert!(!StunTurnConfig::default().has_turn());
+    }
+
+    #[test]
+    fn test_relay_only_configuration_uses_relay_policy() {
+        let config = StunTurnConfig::default();
+        assert_eq!(config.rtc_configuration(false).ice_transport_policy, RTCIceTransportPolicy::All);
+        let relay = config.rtc_configuration(true);
+        assert_eq!(relay.ice_transport_policy, RTCIceTransportPolicy::Relay);
+        assert_eq!(relay.ice_servers, config.to_ice_servers());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join(ICE_CONFIG_FILE);
+        assert_eq!(StunTurnConfig::load_or_default(&path), StunTurnConfig::default());
+
+        let config = StunTurnConfig {
+            servers: vec![IceServerEntry {
+                urls: vec!["turn:relay.example.org".to_string()],
+                username: Some("bob".to_string()),
+                credential: Some("pw".to_string()),
+            }],
+        };
+        config.save(&path).unwrap();
+        assert_eq!(StunTurnConfig::load_or_default(&path), config);
+    }
+}
@@ -1 +1,4 @@
+pub mod framing;
+pub mod ice_config;
+pub mod rpc;
 pub mod webrtc;
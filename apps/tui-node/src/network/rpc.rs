@@ -0,0 +1,265 @@
+//! JSON-RPC transaction lookup, so a transaction can be signed by its hash
+//!
+//! Endpoints are configured per chain in `rpc_endpoints.json` next to the
+//! keystore. The fetched transaction is rebuilt as its unsigned encoding and
+//! run through the chain's `BlockchainHandler` like pasted raw hex would be.
+//! The endpoint is not trusted: the response must hash to the requested
+//! transaction, come from the signing wallet and target the configured chain.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::Transaction;
+use ethers_providers::{Http, Provider};
+use mpc_wallet_blockchain::{BlockchainHandler, BlockchainRegistry};
+use serde::{Deserialize, Serialize};
+
+/// Settings file name inside the keystore directory
+pub const RPC_CONFIG_FILE: &str = "rpc_endpoints.json";
+
+/// JSON-RPC endpoint per chain name (`"ethereum"` → URL)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RpcConfig {
+    pub endpoints: HashMap<String, String>,
+    /// EIP-155 chain id the endpoint must serve, per chain name. Ethereum
+    /// defaults to mainnet (1); set it for testnets.
+    #[serde(default)]
+    pub chain_ids: HashMap<String, u64>,
+}
+
+impl RpcConfig {
+    /// `~/.frost_keystore/rpc_endpoints.json`
+    pub fn default_path() -> PathBuf {
+        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        PathBuf::from(home).join(".frost_keystore").join(RPC_CONFIG_FILE)
+    }
+
+    /// Loads the config from `path`; no endpoints when the file is missing
+    /// or unreadable
+    pub fn load_or_default(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid RPC config {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Chain id fetched transactions on `chain` must carry
+    pub fn chain_id(&self, chain: &str) -> Option<u64> {
+        self.chain_ids.get(chain).copied().or(match chain {
+            "ethereum" => Some(1),
+            _ => None,
+        })
+    }
+}
+
+/// A fetched transaction ready for confirmation
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchedTransaction {
+    /// Unsigned transaction encoding, as the handler parsed it
+    pub raw_bytes: Vec<u8>,
+    /// One-line description for the confirmation prompt
+    pub summary: String,
+}
+
+/// Look up `tx_hash` on the endpoint configured for `chain`; it must be a
+/// transaction sent from `wallet_address`
+pub async fn fetch_transaction(
+    config: &RpcConfig,
+    chain: &str,
+    wallet_address: &str,
+    tx_hash: &str,
+) -> Result<FetchedTransaction, String> {
+    evm_handler(&BlockchainRegistry::new(), chain)?;
+    let chain_id = config
+        .chain_id(chain)
+        .ok_or_else(|| format!("No chain id configured for {}", chain))?;
+    let url = config.endpoints.get(chain).ok_or_else(|| {
+        format!(
+            "No RPC endpoint configured for {}; add one to {}",
+            chain,
+            RpcConfig::default_path().display()
+        )
+    })?;
+    let provider = Provider::<Http>::try_from(url.as_str())
+        .map_err(|e| format!("Invalid RPC endpoint {}: {}", url, e))?;
+    let response: serde_json::Value = provider
+        .request("eth_getTransactionByHash", [tx_hash])
+        .await
+        .map_err(|e| format!("RPC request to {} failed: {}", url, e))?;
+    parse_rpc_transaction(chain, chain_id, wallet_address, tx_hash, &response)
+}
+
+/// Turn an `eth_getTransactionByHash` result into the unsigned transaction
+/// and run it through the chain's handler. The hash is recomputed from the
+/// returned signed fields, so an endpoint can't substitute another
+/// transaction for the one requested.
+pub fn parse_rpc_transaction(
+    chain: &str,
+    chain_id: u64,
+    wallet_address: &str,
+    tx_hash: &str,
+    response: &serde_json::Value,
+) -> Result<FetchedTransaction, String> {
+    let registry = BlockchainRegistry::new();
+    let handler = evm_handler(&registry, chain)?;
+    if response.is_null() {
+        return Err(format!("Transaction {} not found on {}", tx_hash, chain));
+    }
+    let tx: Transaction = serde_json::from_value(response.clone())
+        .map_err(|e| format!("Unexpected RPC transaction format: {}", e))?;
+    let computed = format!("{:?}", tx.hash());
+    if !computed.eq_ignore_ascii_case(tx_hash) {
+        return Err(format!(
+            "RPC endpoint returned transaction {} instead of {}",
+            computed, tx_hash
+        ));
+    }
+    let from = format!("{:?}", tx.from);
+    if !from.eq_ignore_ascii_case(wallet_address) {
+        return Err(format!(
+            "Transaction is sent from {}, not this wallet's address {}",
+            from, wallet_address
+        ));
+    }
+    if tx.chain_id != Some(chain_id.into()) {
+        return Err(format!(
+            "Transaction targets chain id {}, expected {}",
+            tx.chain_id.map(|id| id.to_string()).unwrap_or_else(|| "none".to_string()),
+            chain_id
+        ));
+    }
+    let typed: TypedTransaction = (&tx).into();
+    let unsigned = typed.rlp();
+
+    let parsed = handler
+        .parse_transaction(&hex::encode(&unsigned))
+        .map_err(|e| format!("Failed to parse transaction: {}", e))?;
+
+    let to = tx.to.map(|to| format!("{:?}", to)).unwrap_or_else(|| "contract creation".to_string());
+    let summary = format!(
+        "Send {} ETH to {} (nonce {}, gas {}) - {}",
+        ethers_core::utils::format_ether(tx.value),
+        to,
+        tx.nonce,
+        tx.gas,
+        parsed.summary
+    );
+    Ok(FetchedTransaction { raw_bytes: parsed.raw_bytes, summary })
+}
+
+/// Handler for `chain`; only EVM chains expose `eth_getTransactionByHash`
+fn evm_handler<'a>(registry: &'a BlockchainRegistry, chain: &str) -> Result<&'a dyn BlockchainHandler, String> {
+    match registry.get(chain) {
+        None => Err(format!("Unknown chain: {}", chain)),
+        Some(handler) if handler.blockchain_id() != "ethereum" => {
+            Err(format!("Fetching transactions by hash is not supported for {}", chain))
+        }
+        Some(handler) => Ok(handler),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TX_HASH: &str = "0x30b4863a8bd9bb2cb2fae8934289490fecd5a877edb1fd69c4d483dbe7204c1b";
+    const WALLET: &str = "0x742d35cc6634c0532925a3b844bc9e7595f0beb1";
+
+    /// `eth_getTransactionByHash` result for a pending EIP-1559 transfer
+    fn rpc_response() -> serde_json::Value {
+        serde_json::json!({
+            "hash": TX_HASH,
+            "nonce": "0x7",
+            "blockHash": null,
+            "blockNumber": null,
+            "transactionIndex": null,
+            "from": WALLET,
+            "to": "0x3535353535353535353535353535353535353535",
+            "value": "0xde0b6b3a7640000",
+            "gas": "0x5208",
+            "maxFeePerGas": "0x4a817c800",
+            "maxPriorityFeePerGas": "0x3b9aca00",
+            "input": "0x",
+            "type": "0x2",
+            "chainId": "0x1",
+            "accessList": [],
+            "v": "0x0",
+            "r": "0x1",
+            "s": "0x1"
+        })
+    }
+
+    fn parse(chain: &str, response: &serde_json::Value) -> Result<FetchedTransaction, String> {
+        parse_rpc_transaction(chain, 1, WALLET, TX_HASH, response)
+    }
+
+    #[test]
+    fn test_parses_mocked_rpc_transaction() {
+        let fetched = parse("ethereum", &rpc_response()).unwrap();
+        // Unsigned EIP-1559 encoding
+        assert_eq!(fetched.raw_bytes[0], 0x02);
+        assert!(fetched.summary.starts_with("Send 1.000000000000000000 ETH to 0x3535"), "{}", fetched.summary);
+        assert!(fetched.summary.contains("nonce 7, gas 21000"), "{}", fetched.summary);
+        // Addresses and hashes compare case-insensitively
+        let checksummed = "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb1";
+        let upper_hash = format!("0x{}", TX_HASH[2..].to_uppercase());
+        assert!(parse_rpc_transaction("ethereum", 1, checksummed, &upper_hash, &rpc_response()).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_missing_transaction_and_unknown_chains() {
+        let missing = parse("ethereum", &serde_json::Value::Null).unwrap_err();
+        assert!(missing.contains("not found"));
+        let unknown = parse("dogecoin", &rpc_response()).unwrap_err();
+        assert_eq!(unknown, "Unknown chain: dogecoin");
+        let unsupported = parse("solana", &rpc_response()).unwrap_err();
+        assert!(unsupported.contains("not supported"));
+    }
+
+    #[test]
+    fn test_rejects_substituted_transaction() {
+        // Any changed signed field changes the hash, whatever `hash` claims
+        let mut swapped = rpc_response();
+        swapped["to"] = serde_json::json!("0x9999999999999999999999999999999999999999");
+        let error = parse("ethereum", &swapped).unwrap_err();
+        assert!(error.starts_with("RPC endpoint returned transaction 0x"), "{}", error);
+        assert!(error.ends_with(&format!("instead of {}", TX_HASH)), "{}", error);
+
+        let other_hash = "0x".to_string() + &"ab".repeat(32);
+        let error = parse_rpc_transaction("ethereum", 1, WALLET, &other_hash, &rpc_response()).unwrap_err();
+        assert!(error.contains("instead of"), "{}", error);
+    }
+
+    #[test]
+    fn test_rejects_foreign_sender_and_chain_id() {
+        // `from` isn't covered by the hash, so the endpoint could lie about it
+        let stranger = "0x1111111111111111111111111111111111111111";
+        let error = parse_rpc_transaction("ethereum", 1, stranger, TX_HASH, &rpc_response()).unwrap_err();
+        assert!(error.starts_with(&format!("Transaction is sent from {}", WALLET)), "{}", error);
+
+        let error = parse_rpc_transaction("ethereum", 11155111, WALLET, TX_HASH, &rpc_response()).unwrap_err();
+        assert_eq!(error, "Transaction targets chain id 1, expected 11155111");
+    }
+
+    #[test]
+    fn test_chain_id_defaults_to_mainnet() {
+        let mut config = RpcConfig::default();
+        assert_eq!(config.chain_id("ethereum"), Some(1));
+        assert_eq!(config.chain_id("solana"), None);
+        config.chain_ids.insert("ethereum".to_string(), 11155111);
+        assert_eq!(config.chain_id("ethereum"), Some(11155111));
+        // Configs written before chain ids existed still load
+        let old: RpcConfig = serde_json::from_str(r#"{"endpoints":{"ethereum":"http://localhost:8545"}}"#).unwrap();
+        assert_eq!(old.chain_id("ethereum"), Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_without_endpoint_fails_cleanly() {
+        let error = fetch_transaction(&RpcConfig::default(), "ethereum", WALLET, TX_HASH).await.unwrap_err();
+        assert!(error.starts_with("No RPC endpoint configured for ethereum"));
+    }
+}
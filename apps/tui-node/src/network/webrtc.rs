@@ -6,10 +6,73 @@ use std::collections::HashMap;
 use webrtc::peer_connection::RTCPeerConnection;
 use tracing::{info, error, warn};
 use crate::protocal::signal::{WebRTCSignal, SDPInfo, WebSocketMessage};
-use webrtc_signal_server::ClientMsg as SharedClientMsg;
 use crate::utils::appstate_compat::AppState;
 use serde_json;
 
+/// Entry point for every message on a session data channel. Binary messages
+/// carry length-prefixed frames (see `network::framing`) and go through the
+/// sender's reassembly buffer; text messages are unframed payloads from
+/// peers that predate framing. Each complete payload is handed to
+/// `dispatch_data_channel_msg`.
+pub async fn receive_data_channel_msg<C>(
+    msg: webrtc::data_channel::data_channel_message::DataChannelMessage,
+    device_id_recv: String,
+    app_state: Arc<Mutex<AppState<C>>>,
+    ui_msg_tx: Option<tokio::sync::mpsc::UnboundedSender<crate::elm::message::Message>>,
+) where
+    C: frost_core::Ciphersuite + Send + Sync + 'static,
+    <<C as frost_core::Ciphersuite>::Group as frost_core::Group>::Element: Send + Sync,
+    <<<C as frost_core::Ciphersuite>::Group as frost_core::Group>::Field as frost_core::Field>::Scalar: Send + Sync,
+{
+    let (payloads, limits, session_manager) = {
+        let mut state = app_state.lock().await;
+        state.connection_pool.record_activity(&device_id_recv);
+        let limits = state.dc_frame_buffers.limits();
+        let payloads = if msg.is_string {
+            limits.check(msg.data.len()).map(|()| vec![msg.data.to_vec()])
+        } else {
+            state.dc_frame_buffers.push(&device_id_recv, &msg.data)
+        };
+        (payloads, limits, state.session_manager.clone())
+    };
+    if let Some(manager) = session_manager {
+        manager.record_activity(&device_id_recv).await;
+    }
+    let payloads = match payloads {
+        Ok(payloads) => payloads,
+        Err(e) => {
+            warn!("Dropping oversized data-channel payload from {}: {}", device_id_recv, e);
+            if limits.disconnect_on_oversize {
+                disconnect_peer(&app_state, &device_id_recv, "oversized payload").await;
+            }
+            return;
+        }
+    };
+    for payload in payloads {
+        dispatch_data_channel_msg::<C>(payload, device_id_recv.clone(), app_state.clone(), ui_msg_tx.clone()).await;
+    }
+}
+
+/// Drops our data channel and peer connection to `device_id`, e.g. after it
+/// sent more than `FrameLimits` allows or stopped answering health checks
+async fn disconnect_peer<C>(app_state: &Arc<Mutex<AppState<C>>>, device_id: &str, reason: &str)
+where
+    C: frost_core::Ciphersuite,
+{
+    let device_connections = {
+        let mut state = app_state.lock().await;
+        state.data_channels.remove(device_id);
+        state.dc_frame_buffers.reset(device_id);
+        state.connection_pool.remove(device_id);
+        state.device_connections.clone()
+    };
+    let pc = device_connections.lock().await.remove(device_id);
+    if let Some(pc) = pc {
+        warn!("🔌 Closing connection to {} ({})", device_id, reason);
+        let _ = pc.close().await;
+    }
+}
+
 /// Parse and react to a single frame received on a WebRTC data channel.
 ///
 /// Both the initiator side (this file's `initiate_webrtc_with_channel`
@@ -129,15 +192,36 @@ pub async fn dispatch_data_channel_msg<C>(
                 }
                 return;
             }
+            if let Some(round) = msg_text.strip_prefix(crate::protocal::dkg::DKG_ACK_PREFIX) {
+                match round.parse::<u8>() {
+                    Ok(round) => {
+                        info!("✔️ {} acked our DKG Round {} package", device_id_recv, round);
+                        app_state.lock().await.dkg_acks.insert((round, device_id_recv.clone()));
+                    }
+                    Err(_) => warn!("Malformed DKG ack from {}: {}", device_id_recv, msg_text),
+                }
+                return;
+            }
             info!("📨 SimpleMessage from {}: {}", device_id_recv, msg_text);
             return;
         }
     }
 
-    // Control frames: `channel_open`, `mesh_ready`.
+    // Control frames: `channel_open`, `mesh_ready`, health-check `ping`/`pong`.
     if let Some(msg_type) = json_msg.get("type").and_then(|v| v.as_str()) {
         match msg_type {
             "channel_open" => info!("📂 Received channel_open from {}", device_id_recv),
+            "ping" => {
+                let dc = app_state.lock().await.data_channels.get(&device_id_recv).cloned();
+                if let Some(dc) = dc {
+                    let pong = serde_json::json!({ "type": "pong" }).to_string();
+                    if let Err(e) = crate::network::framing::send_payload(&dc, &pong).await {
+                        warn!("Failed to answer health-check ping from {}: {}", device_id_recv, e);
+                    }
+                }
+            }
+            // `receive_data_channel_msg` already recorded the reply
+            "pong" => {}
             "mesh_ready" => {
                 info!("✅ Received mesh_ready from {}", device_id_recv);
                 let mut state = app_state.lock().await;
@@ -164,6 +248,202 @@ pub async fn dispatch_data_channel_msg<C>(
     }
 }
 
+/// Re-negotiates a dropped connection to `device_id` with exponential backoff.
+///
+/// Only the offering side (lower device id) runs this; the answerer simply
+/// receives the fresh offer. Each attempt drops the stale peer connection and
+/// asks the Elm loop to re-initiate WebRTC, then waits for the data channel to
+/// reopen. `reconnection_tracker` keeps it to one task per peer.
+pub fn spawn_reconnect<C>(
+    device_id: String,
+    app_state: Arc<Mutex<AppState<C>>>,
+    ui_msg_tx: tokio::sync::mpsc::UnboundedSender<crate::elm::message::Message>,
+) where
+    C: frost_core::Ciphersuite + 'static + Send + Sync,
+    <<C as frost_core::Ciphersuite>::Group as frost_core::Group>::Element: Send + Sync,
+    <<<C as frost_core::Ciphersuite>::Group as frost_core::Group>::Field as frost_core::Field>::Scalar: Send + Sync,
+{
+    use crate::elm::message::Message;
+    use crate::webrtc::{reconnect_with_backoff, ReconnectPolicy, ReconnectStatus};
+
+    tokio::spawn(async move {
+        {
+            let mut state = app_state.lock().await;
+            if state.reconnection_tracker.contains_key(&device_id) {
+                return;
+            }
+            state.reconnection_tracker.insert(device_id.clone(), std::time::Instant::now());
+        }
+
+        let status = reconnect_with_backoff(
+            &ReconnectPolicy::default(),
+            |attempt| {
+                let device_id = device_id.clone();
+                let app_state = app_state.clone();
+                let ui_msg_tx = ui_msg_tx.clone();
+                async move {
+                    // A transient ICE disconnect may recover by itself
+                    if is_data_channel_open(&app_state, &device_id).await {
+                        return true;
+                    }
+                    info!("🔁 Re-negotiating with {} (attempt {})", device_id, attempt);
+
+                    let device_connections = {
+                        let mut state = app_state.lock().await;
+                        state.data_channels.remove(&device_id);
+                        state.connection_pool.remove(&device_id);
+                        state.device_connections.clone()
+                    };
+                    let stale = device_connections.lock().await.remove(&device_id);
+                    if let Some(pc) = stale {
+                        let _ = pc.close().await;
+                    }
+                    let _ = ui_msg_tx.send(Message::InitiateWebRTCWithParticipants {
+                        participants: vec![device_id.clone()],
+                    });
+
+                    for _ in 0..10 {
+                        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+                        if is_data_channel_open(&app_state, &device_id).await {
+                            return true;
+                        }
+                    }
+                    false
+                }
+            },
+            |status| {
+                let open = matches!(status, ReconnectStatus::Reconnected { .. });
+                let _ = ui_msg_tx.send(Message::UpdateParticipantWebRTCStatus {
+                    device_id: device_id.clone(),
+                    webrtc_connected: open,
+                    data_channel_open: open,
+                });
+            },
+        )
+        .await;
+
+        app_state.lock().await.reconnection_tracker.remove(&device_id);
+        match status {
+            ReconnectStatus::Failed { attempts } => {
+                error!("❌ Giving up on {} after {} reconnection attempts", device_id, attempts);
+                let _ = ui_msg_tx.send(Message::WebRTCReconnectFailed { device_id, attempts });
+            }
+            _ => info!("✅ Reconnected to {}", device_id),
+        }
+    });
+}
+
+/// Health-checks every open data channel on the pool's keep-alive interval.
+///
+/// Each pass pings the channels that answered the previous ping and closes
+/// those that didn't (or were marked dead), so a half-open connection is
+/// dropped rather than used for the next DKG or signing message. The peer
+/// answers with `pong` from `dispatch_data_channel_msg`; any inbound traffic
+/// counts as an answer.
+pub async fn spawn_connection_health_checks<C>(app_state: Arc<Mutex<AppState<C>>>) -> tokio::task::JoinHandle<()>
+where
+    C: frost_core::Ciphersuite + 'static + Send + Sync,
+    <<C as frost_core::Ciphersuite>::Group as frost_core::Group>::Element: Send + Sync,
+    <<<C as frost_core::Ciphersuite>::Group as frost_core::Group>::Field as frost_core::Field>::Scalar: Send + Sync,
+{
+    let pool = app_state.lock().await.connection_pool.clone();
+    let interval = pool.config().keep_alive_interval;
+    pool.spawn_health_checks(interval, move |check| {
+        let app_state = app_state.clone();
+        tokio::spawn(async move {
+            for device_id in &check.evicted {
+                disconnect_peer(&app_state, device_id, "health check unanswered").await;
+            }
+            let ping = serde_json::json!({ "type": "ping" }).to_string();
+            for device_id in &check.ping {
+                let dc = app_state.lock().await.data_channels.get(device_id).cloned();
+                if let Some(dc) = dc
+                    && let Err(e) = crate::network::framing::send_payload(&dc, &ping).await
+                {
+                    warn!("Failed to send health-check ping to {}: {}", device_id, e);
+                }
+            }
+        });
+    })
+}
+
+/// How long direct WebRTC negotiation with a peer may take before it is
+/// retried through a TURN relay
+pub const TURN_FALLBACK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(20);
+
+/// Falls back to a TURN relay for `device_id` if no data channel to it has
+/// opened after `TURN_FALLBACK_TIMEOUT`.
+///
+/// Only the offering side (lower device id) runs this, like `spawn_reconnect`:
+/// it drops the stalled peer connection and re-negotiates one that gathers
+/// only relay candidates, and the answerer simply receives the fresh offer.
+/// Messages still travel over the DTLS-encrypted data channel, so the protocol
+/// handlers never see the difference and the signal server never sees a
+/// package. Needs a TURN server in `ice_servers.json`.
+pub fn spawn_turn_fallback<C>(
+    device_id: String,
+    app_state: Arc<Mutex<AppState<C>>>,
+    ui_msg_tx: Option<tokio::sync::mpsc::UnboundedSender<crate::elm::message::Message>>,
+) where
+    C: frost_core::Ciphersuite + 'static + Send + Sync,
+    <<C as frost_core::Ciphersuite>::Group as frost_core::Group>::Element: Send + Sync,
+    <<<C as frost_core::Ciphersuite>::Group as frost_core::Group>::Field as frost_core::Field>::Scalar: Send + Sync,
+{
+    use crate::elm::message::Message;
+
+    tokio::spawn(async move {
+        tokio::time::sleep(TURN_FALLBACK_TIMEOUT).await;
+        if is_data_channel_open(&app_state, &device_id).await {
+            return;
+        }
+
+        let device_connections = {
+            let mut state = app_state.lock().await;
+            if !state.session.as_ref().is_some_and(|s| s.participants.contains(&device_id)) {
+                return;
+            }
+            if !state.stun_turn_config.has_turn() {
+                warn!("⏱️ WebRTC negotiation with {} timed out and no TURN server is configured", device_id);
+                if let Some(tx) = &ui_msg_tx {
+                    let _ = tx.send(Message::Error {
+                        message: format!("No direct connection to {}; add a TURN server to ice_servers.json", device_id),
+                    });
+                }
+                return;
+            }
+            if !state.turn_relay_peers.insert(device_id.clone()) {
+                return;
+            }
+            state.data_channels.remove(&device_id);
+            state.connection_pool.remove(&device_id);
+            state.device_connections.clone()
+        };
+
+        warn!("⏱️ WebRTC negotiation with {} timed out; retrying through TURN", device_id);
+        let stale = device_connections.lock().await.remove(&device_id);
+        if let Some(pc) = stale {
+            let _ = pc.close().await;
+        }
+        if let Some(tx) = &ui_msg_tx {
+            let _ = tx.send(Message::Info {
+                message: format!("🛰️ No direct connection to {}; retrying through TURN", device_id),
+            });
+            let _ = tx.send(Message::InitiateWebRTCWithParticipants {
+                participants: vec![device_id],
+            });
+        }
+    });
+}
+
+async fn is_data_channel_open<C>(app_state: &Arc<Mutex<AppState<C>>>, device_id: &str) -> bool
+where
+    C: frost_core::Ciphersuite,
+{
+    app_state.lock().await.data_channels.get(device_id).is_some_and(|dc| {
+        dc.ready_state() == webrtc::data_channel::data_channel_state::RTCDataChannelState::Open
+    })
+}
+
 /// WebRTC connection initiation using existing WebSocket channel
 pub async fn initiate_webrtc_with_channel<C>(
     self_device_id: String,
@@ -212,6 +492,10 @@ pub async fn initiate_webrtc_with_channel<C>(
         return;
     }
 
+    for participant in other_participants.iter().filter(|p| self_device_id < **p) {
+        spawn_turn_fallback(participant.clone(), app_state.clone(), ui_msg_tx.clone());
+    }
+
     // Pre-create PCs ONLY for peers we're going to initiate to (self_id < peer_id
     // in perfect-negotiation terms). For the "wait for offer" side we MUST NOT
     // create the PC here — if we do, the later offer arrives, `ensure_peer_connection`
@@ -239,9 +523,10 @@ pub async fn initiate_webrtc_with_channel<C>(
             info!("📱 [{}] Creating NEW peer connection for {}", self_device_id, participant);
 
             // Create a simple peer connection using webrtc crate directly
-            let config = webrtc::peer_connection::configuration::RTCConfiguration {
-                ice_servers: vec![],
-                ..Default::default()
+            let config = {
+                let state = app_state.lock().await;
+                let relay_only = state.turn_relay_peers.contains(participant);
+                state.stun_turn_config.rtc_configuration(relay_only)
             };
 
             match webrtc::api::APIBuilder::new()
@@ -308,14 +593,16 @@ pub async fn initiate_webrtc_with_channel<C>(
             // Set up connection state handler
             let device_id_state = device_id.clone();
             let ui_msg_tx_state = ui_msg_tx.clone();
+            let app_state_reconnect = app_state.clone();
             pc.on_peer_connection_state_change(Box::new(move |state: webrtc::peer_connection::peer_connection_state::RTCPeerConnectionState| {
                 let device_id_state = device_id_state.clone();
                 let ui_msg_tx_state = ui_msg_tx_state.clone();
+                let app_state_reconnect = app_state_reconnect.clone();
                 Box::pin(async move {
                     let is_connected = matches!(state, webrtc::peer_connection::peer_connection_state::RTCPeerConnectionState::Connected);
                     
                     // Send UI update
-                    if let Some(tx) = ui_msg_tx_state {
+                    if let Some(tx) = &ui_msg_tx_state {
                         let _ = tx.send(crate::elm::message::Message::UpdateParticipantWebRTCStatus {
                             device_id: device_id_state.clone(),
                             webrtc_connected: is_connected,
@@ -329,9 +616,15 @@ pub async fn initiate_webrtc_with_channel<C>(
                         }
                         webrtc::peer_connection::peer_connection_state::RTCPeerConnectionState::Failed => {
                             error!("❌ WebRTC connection FAILED with {}", device_id_state);
+                            if let Some(tx) = ui_msg_tx_state {
+                                spawn_reconnect(device_id_state.clone(), app_state_reconnect, tx);
+                            }
                         }
                         webrtc::peer_connection::peer_connection_state::RTCPeerConnectionState::Disconnected => {
                             warn!("⚠️ WebRTC connection DISCONNECTED from {}", device_id_state);
+                            if let Some(tx) = ui_msg_tx_state {
+                                spawn_reconnect(device_id_state.clone(), app_state_reconnect, tx);
+                            }
                         }
                         webrtc::peer_connection::peer_connection_state::RTCPeerConnectionState::Closed => {
                             info!("🔒 WebRTC connection CLOSED with {}", device_id_state);
@@ -409,6 +702,11 @@ pub async fn initiate_webrtc_with_channel<C>(
                             {
                                 let mut state = app_state_mesh.lock().await;
                                 state.data_channels.insert(device_id_open.clone(), dc_open.clone());
+                                state.dc_frame_buffers.reset(&device_id_open);
+                                state.connection_pool.remove(&device_id_open);
+                                if let Err(e) = state.connection_pool.get_or_create(&device_id_open).await {
+                                    warn!("Not health-checking the channel to {}: {}", device_id_open, e);
+                                }
                                 info!("📦 Stored data channel for {} in AppState", device_id_open);
                             }
                             
@@ -430,7 +728,7 @@ pub async fn initiate_webrtc_with_channel<C>(
                             });
                             
                             if let Ok(msg_str) = serde_json::to_string(&channel_open_msg) {
-                                let _ = dc_open.send_text(msg_str).await;
+                                let _ = crate::network::framing::send_payload(&dc_open, &msg_str).await;
                                 info!("📤 Sent channel_open message to {}", device_id_open);
                             }
                             
@@ -463,7 +761,7 @@ pub async fn initiate_webrtc_with_channel<C>(
                                 });
                                 
                                 if let Ok(msg_str) = serde_json::to_string(&mesh_ready_msg) {
-                                    let _ = dc_open.send_text(msg_str).await;
+                                    let _ = crate::network::framing::send_payload(&dc_open, &msg_str).await;
                                     info!("📤 Sent mesh_ready signal via data channel");
                                     
                                     // Mark as sent and check if all participants are ready
@@ -501,8 +799,8 @@ pub async fn initiate_webrtc_with_channel<C>(
                         let app_state_msg = app_state_for_msg.clone();
                         let ui_msg_tx_msg = ui_msg_tx_for_msg.clone();
                         Box::pin(async move {
-                            crate::network::webrtc::dispatch_data_channel_msg::<C>(
-                                msg.data.to_vec(),
+                            crate::network::webrtc::receive_data_channel_msg::<C>(
+                                msg,
                                 device_id_recv,
                                 app_state_msg,
                                 ui_msg_tx_msg,
@@ -531,24 +829,10 @@ pub async fn initiate_webrtc_with_channel<C>(
 
                                 match serde_json::to_value(websocket_message) {
                                     Ok(json_val) => {
-                                        let relay_msg = SharedClientMsg::Relay {
-                                            to: device_id.clone(),
-                                            data: json_val,
-                                        };
-
-                                        // Serialize the message immediately to avoid Send issues
-                                        match serde_json::to_string(&relay_msg) {
-                                            Ok(json) => {
-                                                info!("📤 Sending WebRTC offer to {} via WebSocket", device_id);
-                                                if let Err(e) = ws_msg_tx.send(json) {
-                                                    error!("❌ Failed to send offer to {}: {}", device_id, e);
-                                                } else {
-                                                    info!("✅ WebRTC offer sent to {} via WebSocket", device_id);
-                                                }
-                                            }
-                                            Err(e) => {
-                                                error!("❌ Failed to serialize relay message for {}: {}", device_id, e);
-                                            }
+                                        info!("📤 Sending WebRTC offer to {} via WebSocket", device_id);
+                                        match send_relay(&ws_msg_tx, &device_id, json_val) {
+                                            Ok(()) => info!("✅ WebRTC offer sent to {} via WebSocket", device_id),
+                                            Err(e) => error!("❌ Failed to send offer to {}: {}", device_id, e),
                                         }
                                     }
                                     Err(e) => {
@@ -570,4 +854,20 @@ pub async fn initiate_webrtc_with_channel<C>(
     }
 
     info!("✅ Simple WebRTC initiation complete");
-}
\ No newline at end of file
+}
+
+/// Queue a relay to `to` on the signal WebSocket, split into chunks when the
+/// payload is large (SDP with many candidates, DKG package bundles)
+pub(crate) fn send_relay(
+    ws_msg_tx: &tokio::sync::mpsc::UnboundedSender<String>,
+    to: &str,
+    data: serde_json::Value,
+) -> Result<(), String> {
+    use webrtc_signal_server::chunking::{DEFAULT_MAX_CHUNK_LEN, chunk_relay};
+
+    for relay in chunk_relay(to, data, DEFAULT_MAX_CHUNK_LEN) {
+        let json = serde_json::to_string(&relay).map_err(|e| e.to_string())?;
+        ws_msg_tx.send(json).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
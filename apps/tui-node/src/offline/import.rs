@@ -188,4 +188,34 @@ pub enum ImportResult {
     SigningPackage(SigningPackage),
     SignatureShare(SignatureShareData),
     AggregatedSignature(AggregatedSignature),
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::offline::export::export_commitments;
+
+    #[test]
+    fn test_flipped_byte_fails_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("offline_1_commitments__alice.json");
+        let commitments = CommitmentsData {
+            session_id: "offline_1".to_string(),
+            device_id: "alice".to_string(),
+            identifier: "01".to_string(),
+            hiding_nonce_commitment: "aa".repeat(33),
+            binding_nonce_commitment: "bb".repeat(33),
+        };
+        export_commitments(&commitments, &path, 60).unwrap();
+        let config = OfflineConfig::default();
+        assert!(import_commitments(&path, &config).is_ok());
+
+        // One corrupted nibble in the hiding commitment, still valid JSON
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::write(&path, contents.replacen("aaaa", "abaa", 1)).unwrap();
+        assert!(matches!(
+            import_commitments(&path, &config),
+            Err(OfflineError::InvalidFormat(msg)) if msg.contains("Checksum")
+        ));
+    }
+}
@@ -7,9 +7,11 @@ pub mod types;
 pub mod export;
 pub mod import;
 pub mod session;
+pub mod qr;
 
 pub use types::*;
 pub use session::OfflineSession;
+pub use qr::{chunk_for_qr, reassemble_from_qr};
 
 use std::path::Path;
 use std::fs;
@@ -49,6 +51,12 @@ pub enum OfflineError {
     #[error("Threshold not met: got {0}, need {1}")]
     ThresholdNotMet(usize, usize),
 
+    #[error("Signing policy violation: {0}")]
+    PolicyViolation(#[from] crate::keystore::PolicyViolation),
+
+    #[error("Keystore error: {0}")]
+    KeystoreError(#[from] crate::keystore::KeystoreError),
+
     #[error("General offline error: {0}")]
     General(String),
 }
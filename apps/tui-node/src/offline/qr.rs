@@ -0,0 +1,115 @@
+//! QR code chunking for air-gapped transfer
+//!
+//! Offline JSON blobs are too big for a single QR code, so they are split
+//! into chunks of the form `MPCQR:<index>/<total>/<session>:<base64>`.
+//! `index` is 1-based and `session` is a short SHA-256 tag of the whole
+//! payload, so chunks from two different transfers can't be mixed.
+
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use sha2::{Digest, Sha256};
+use super::{OfflineError, Result};
+
+/// Prefix identifying one of our QR chunks
+const QR_CHUNK_PREFIX: &str = "MPCQR:";
+
+/// Tag tying chunks to the payload they were cut from
+fn payload_tag(data: &[u8]) -> String {
+    hex::encode(&Sha256::digest(data)[..4])
+}
+
+/// Split `data` into QR chunks carrying at most `max_chunk` payload bytes each
+pub fn chunk_for_qr(data: &[u8], max_chunk: usize) -> Vec<String> {
+    let tag = payload_tag(data);
+    let pieces: Vec<&[u8]> = if data.is_empty() {
+        vec![data]
+    } else {
+        data.chunks(max_chunk.max(1)).collect()
+    };
+    let total = pieces.len();
+    pieces
+        .iter()
+        .enumerate()
+        .map(|(i, piece)| format!("{}{}/{}/{}:{}", QR_CHUNK_PREFIX, i + 1, total, tag, BASE64.encode(piece)))
+        .collect()
+}
+
+/// Reassemble chunks scanned in order. Fails on a missing, duplicated or
+/// out-of-order chunk, or on chunks from different payloads.
+pub fn reassemble_from_qr(chunks: Vec<String>) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut expected: Option<(usize, String)> = None;
+
+    for (position, chunk) in chunks.iter().enumerate() {
+        let (index, total, tag, payload) = parse_chunk(chunk)?;
+        let (expected_total, expected_tag) = expected.get_or_insert_with(|| (total, tag.to_string()));
+        if total != *expected_total || tag != expected_tag.as_str() {
+            return Err(OfflineError::InvalidFormat(format!(
+                "Chunk {} belongs to a different transfer", index
+            )));
+        }
+        if index != position + 1 {
+            return Err(OfflineError::InvalidFormat(format!(
+                "Expected chunk {} of {}, got chunk {}", position + 1, total, index
+            )));
+        }
+        data.extend(BASE64.decode(payload).map_err(|e| {
+            OfflineError::InvalidFormat(format!("Chunk {} is not valid base64: {}", index, e))
+        })?);
+    }
+
+    let Some((total, tag)) = expected else {
+        return Err(OfflineError::InvalidFormat("No QR chunks".to_string()));
+    };
+    if chunks.len() != total {
+        return Err(OfflineError::InvalidFormat(format!(
+            "Missing chunks: got {} of {}", chunks.len(), total
+        )));
+    }
+    if payload_tag(&data) != tag {
+        return Err(OfflineError::InvalidFormat("Reassembled payload does not match its tag".to_string()));
+    }
+    Ok(data)
+}
+
+/// Split `MPCQR:<index>/<total>/<session>:<base64>` into its parts
+fn parse_chunk(chunk: &str) -> Result<(usize, usize, &str, &str)> {
+    let invalid = || OfflineError::InvalidFormat(format!("Not an offline QR chunk: {:.32}", chunk));
+    let rest = chunk.strip_prefix(QR_CHUNK_PREFIX).ok_or_else(invalid)?;
+    let (header, payload) = rest.split_once(':').ok_or_else(invalid)?;
+    let mut fields = header.splitn(3, '/');
+    let index: usize = fields.next().and_then(|f| f.parse().ok()).ok_or_else(invalid)?;
+    let total: usize = fields.next().and_then(|f| f.parse().ok()).ok_or_else(invalid)?;
+    let tag = fields.next().ok_or_else(invalid)?;
+    if index == 0 || index > total {
+        return Err(invalid());
+    }
+    Ok((index, total, tag, payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_three_chunk_round_trip() {
+        let payload = br#"{"type":"commitments","session_id":"offline_1","data":{}}"#.to_vec();
+        let chunks = chunk_for_qr(&payload, payload.len().div_ceil(3));
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[0].starts_with("MPCQR:1/3/"));
+        assert_eq!(reassemble_from_qr(chunks).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_missing_or_reordered_chunk_fails() {
+        let payload = vec![42u8; 90];
+        let chunks = chunk_for_qr(&payload, 30);
+
+        let mut missing = chunks.clone();
+        missing.remove(2);
+        assert!(matches!(reassemble_from_qr(missing), Err(OfflineError::InvalidFormat(_))));
+
+        let mut reordered = chunks.clone();
+        reordered.swap(0, 1);
+        assert!(matches!(reassemble_from_qr(reordered), Err(OfflineError::InvalidFormat(_))));
+    }
+}
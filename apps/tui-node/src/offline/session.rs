@@ -1,11 +1,21 @@
 //! Offline signing session management
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
+use frost_core::keys::{KeyPackage, PublicKeyPackage};
+use frost_core::round1::{NonceCommitment, SigningCommitments, SigningNonces};
+use frost_core::round2::SignatureShare;
+use frost_core::{Ciphersuite, Identifier, Signature};
 use serde::{Serialize, Deserialize};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use mpc_wallet_blockchain::{BlockchainRegistry, ParsedTransaction};
+use crate::keystore::{AuditAction, AuditEntry, Keystore};
 use super::{
     types::*,
-    OfflineError, Result,
+    export, import,
+    create_filename,
+    OfflineConfig, OfflineError, Result,
 };
 
 /// State of an offline signing session
@@ -266,4 +276,467 @@ pub struct SessionProgress {
     pub shares_received: usize,
     pub shares_needed: usize,
     pub expires_in: chrono::Duration,
-}
\ No newline at end of file
+}
+
+/// File round trip: each signer exports commitments and later a signature
+/// share to the SD card, the coordinator imports them and aggregates. Every
+/// step refuses to run once the session has expired.
+impl OfflineSession {
+    /// Fail with `SessionExpired` once past `expires_at`
+    fn ensure_not_expired(&self) -> Result<()> {
+        if self.is_expired() {
+            return Err(OfflineError::SessionExpired(self.expires_at));
+        }
+        Ok(())
+    }
+
+    /// Exported files expire with the session
+    fn remaining_minutes(&self) -> u64 {
+        self.expires_at.signed_duration_since(Utc::now()).num_minutes().max(1) as u64
+    }
+
+    fn check_session_id(&self, session_id: &str) -> Result<()> {
+        if session_id != self.session_id {
+            return Err(OfflineError::InvalidFormat(format!(
+                "File belongs to session {}, not {}",
+                session_id, self.session_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Generate fresh nonces for `device_id` and write the public commitments
+    /// to `output_dir`. The returned nonces must be kept for `export_share`.
+    pub fn export_commitments<C: Ciphersuite>(
+        &self,
+        device_id: &str,
+        key_package: &KeyPackage<C>,
+        output_dir: &Path,
+    ) -> Result<(PathBuf, SigningNonces<C>)> {
+        self.ensure_not_expired()?;
+        let mut rng = frost_ed25519::rand_core::OsRng;
+        let (nonces, commitments) = frost_core::round1::commit(key_package.signing_share(), &mut rng);
+        let data = CommitmentsData {
+            session_id: self.session_id.clone(),
+            device_id: device_id.to_string(),
+            identifier: hex::encode(key_package.identifier().serialize()),
+            hiding_nonce_commitment: hex::encode(crypto(commitments.hiding().serialize())?),
+            binding_nonce_commitment: hex::encode(crypto(commitments.binding().serialize())?),
+        };
+        let path = output_dir.join(create_filename("commitments", &self.session_id, Some(device_id)));
+        export::export_commitments(&data, &path, self.remaining_minutes())?;
+        Ok((path, nonces))
+    }
+
+    /// Read a signer's commitments file into the session
+    pub fn import_commitments(&mut self, path: &Path, config: &OfflineConfig) -> Result<()> {
+        self.ensure_not_expired()?;
+        let commitments = import::import_commitments(path, config)?;
+        self.check_session_id(&commitments.session_id)?;
+        self.add_commitments(commitments)
+    }
+
+    /// Write the signing package built by `create_signing_package` for signers
+    pub fn export_signing_package(&self, output_dir: &Path) -> Result<PathBuf> {
+        self.ensure_not_expired()?;
+        let package = self.signing_package.as_ref().ok_or_else(|| {
+            OfflineError::InvalidState("No signing package has been created".to_string())
+        })?;
+        let path = output_dir.join(create_filename("package", &self.session_id, None));
+        export::export_signing_package(package, &path, self.remaining_minutes())?;
+        Ok(path)
+    }
+
+    /// Signer side: load the coordinator's signing package
+    pub fn import_signing_package(&mut self, path: &Path, config: &OfflineConfig) -> Result<()> {
+        self.ensure_not_expired()?;
+        let package = import::import_signing_package(path, config)?;
+        self.check_session_id(&package.session_id)?;
+        self.signing_package = Some(package);
+        self.state = SessionState::AwaitingShares;
+        Ok(())
+    }
+
+    /// The signing request's transaction, parsed by its chain's handler
+    fn parsed_transaction(&self) -> Result<ParsedTransaction> {
+        let request = self.signing_request.as_ref()
+            .ok_or_else(|| OfflineError::InvalidState("No signing request".to_string()))?;
+        let payload = BASE64.decode(&request.transaction.payload)
+            .map_err(|e| OfflineError::InvalidFormat(format!("Transaction payload is not valid base64: {}", e)))?;
+        let registry = BlockchainRegistry::new();
+        let handler = registry.get(&request.transaction.chain_type).ok_or_else(|| {
+            OfflineError::InvalidFormat(format!("Unsupported chain: {}", request.transaction.chain_type))
+        })?;
+        handler.parse_transaction(&hex::encode(payload))
+            .map_err(|e| OfflineError::InvalidFormat(e.to_string()))
+    }
+
+    /// Refuse the session's transaction if it breaks the wallet's signing
+    /// policy in `keystore`. The transaction is only parsed when there is a
+    /// policy to check it against.
+    pub fn check_policy(&self, keystore: &Keystore) -> Result<()> {
+        let Some(wallet) = keystore.get_wallet(&self.wallet_id) else {
+            return Ok(());
+        };
+        let Some(policy) = &wallet.signing_policy else {
+            return Ok(());
+        };
+        policy.check(&self.parsed_transaction()?, &wallet.group_public_key)?;
+        Ok(())
+    }
+
+    /// Record a completed signing step in the wallet's audit log
+    fn record_audit(&self, keystore: &Keystore, action: AuditAction, participants: Vec<String>) -> Result<()> {
+        let package = self.signing_package.as_ref().ok_or_else(|| {
+            OfflineError::InvalidState("No signing package has been created".to_string())
+        })?;
+        // A payload the chain handler can't parse is still signed when no
+        // policy applies; the request's own description stands in for it
+        let summary = match self.parsed_transaction() {
+            Ok(parsed) => parsed.summary,
+            Err(_) => self.signing_request.as_ref()
+                .map(|request| request.message.clone())
+                .unwrap_or_default(),
+        };
+        let chain = self.signing_request.as_ref()
+            .map(|request| request.transaction.chain_type.clone())
+            .unwrap_or_default();
+        keystore.append_audit_entry(&AuditEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            wallet_id: self.wallet_id.clone(),
+            action,
+            message_hash: package.message.clone(),
+            participants,
+            chain,
+            summary,
+        })?;
+        Ok(())
+    }
+
+    /// Sign the session's signing package with `nonces` from
+    /// `export_commitments` and write the share to `output_dir`, once the
+    /// transaction has passed the wallet's signing policy. The share is
+    /// recorded in the wallet's audit log.
+    pub fn export_share<C: Ciphersuite>(
+        &self,
+        device_id: &str,
+        nonces: &SigningNonces<C>,
+        key_package: &KeyPackage<C>,
+        keystore: &Keystore,
+        output_dir: &Path,
+    ) -> Result<PathBuf> {
+        self.ensure_not_expired()?;
+        self.check_policy(keystore)?;
+        let signing_package = self.frost_signing_package::<C>()?;
+        let share = crypto(frost_core::round2::sign(&signing_package, nonces, key_package))?;
+        let data = SignatureShareData {
+            session_id: self.session_id.clone(),
+            device_id: device_id.to_string(),
+            identifier: hex::encode(key_package.identifier().serialize()),
+            signature_share: hex::encode(share.serialize()),
+        };
+        let path = output_dir.join(create_filename("share", &self.session_id, Some(device_id)));
+        export::export_signature_share(&data, &path, self.remaining_minutes())?;
+
+        let mut participants: Vec<String> = self.signing_package.iter()
+            .flat_map(|package| package.commitments.keys().cloned())
+            .collect();
+        participants.sort();
+        self.record_audit(keystore, AuditAction::SignatureShare, participants)?;
+        Ok(path)
+    }
+
+    /// Read a signer's share file into the session
+    pub fn import_share(&mut self, path: &Path, config: &OfflineConfig) -> Result<()> {
+        self.ensure_not_expired()?;
+        let share = import::import_signature_share(path, config)?;
+        self.check_session_id(&share.session_id)?;
+        self.add_signature_share(share)
+    }
+
+    /// Aggregate the collected shares, verify the result against the group
+    /// key, complete the session and record it in the wallet's audit log
+    pub fn aggregate<C: Ciphersuite>(
+        &mut self,
+        public_key_package: &PublicKeyPackage<C>,
+        keystore: &Keystore,
+    ) -> Result<Signature<C>> {
+        self.validate_state(&SessionState::SharesReady)?;
+        let transaction = self.signing_request.as_ref()
+            .map(|request| request.transaction.clone())
+            .ok_or_else(|| OfflineError::InvalidState("No signing request".to_string()))?;
+        let signing_package = self.frost_signing_package::<C>()?;
+
+        let mut shares = BTreeMap::new();
+        for share in self.signature_shares.values() {
+            let identifier = parse_identifier::<C>(&share.identifier)?;
+            let bytes = decode_hex(&share.signature_share)?;
+            shares.insert(identifier, crypto(SignatureShare::<C>::deserialize(&bytes))?);
+        }
+        crypto(mpc_wallet_frost_core::validate_contributions(&signing_package, &shares, public_key_package))?;
+        let signature = crypto(frost_core::aggregate(&signing_package, &shares, public_key_package))?;
+
+        let bytes = crypto(signature.serialize())?;
+        let (algorithm, value) = if C::ID.contains("ED25519") {
+            ("eddsa", SignatureValue::Eddsa { signature: hex::encode(&bytes) })
+        } else {
+            let (r, z) = bytes.split_at(bytes.len() - 32);
+            ("schnorr", SignatureValue::Schnorr { r: hex::encode(r), z: hex::encode(z) })
+        };
+        let mut signers: Vec<String> = self.signature_shares.keys().cloned().collect();
+        signers.sort();
+        self.record_audit(keystore, AuditAction::AggregatedSignature, signers.clone())?;
+        self.complete_with_signature(AggregatedSignature {
+            session_id: self.session_id.clone(),
+            signature: SignatureData { algorithm: algorithm.to_string(), value },
+            signers,
+            transaction,
+        })?;
+        Ok(signature)
+    }
+
+    /// Rebuild the FROST signing package from the hex commitments
+    fn frost_signing_package<C: Ciphersuite>(&self) -> Result<frost_core::SigningPackage<C>> {
+        let package = self.signing_package.as_ref().ok_or_else(|| {
+            OfflineError::InvalidState("No signing package has been created".to_string())
+        })?;
+        let mut commitments = BTreeMap::new();
+        for participant in package.commitments.values() {
+            let hiding = crypto(NonceCommitment::<C>::deserialize(&decode_hex(&participant.hiding)?))?;
+            let binding = crypto(NonceCommitment::<C>::deserialize(&decode_hex(&participant.binding)?))?;
+            commitments.insert(
+                parse_identifier::<C>(&participant.identifier)?,
+                SigningCommitments::new(hiding, binding),
+            );
+        }
+        Ok(frost_core::SigningPackage::new(commitments, &decode_hex(&package.message)?))
+    }
+}
+
+fn crypto<T, E: std::fmt::Display>(result: std::result::Result<T, E>) -> Result<T> {
+    result.map_err(|e| OfflineError::CryptoError(e.to_string()))
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>> {
+    hex::decode(value).map_err(|e| OfflineError::InvalidFormat(format!("Invalid hex: {}", e)))
+}
+
+fn parse_identifier<C: Ciphersuite>(value: &str) -> Result<Identifier<C>> {
+    crypto(Identifier::<C>::deserialize(&decode_hex(value)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1::Secp256K1Sha256;
+
+    fn signing_request(message: &[u8]) -> SigningRequest {
+        SigningRequest {
+            wallet_id: "cold_wallet".to_string(),
+            transaction: TransactionData {
+                chain_type: "ethereum".to_string(),
+                payload: String::new(),
+                hash: hex::encode(message),
+                chain_data: None,
+            },
+            message: "Offline round trip".to_string(),
+            required_signers: vec!["alice".to_string(), "bob".to_string()],
+            threshold: 2,
+            metadata: None,
+        }
+    }
+
+    fn two_of_two_keys() -> (Vec<KeyPackage<Secp256K1Sha256>>, PublicKeyPackage<Secp256K1Sha256>) {
+        let mut rng = frost_ed25519::rand_core::OsRng;
+        let (shares, public_key_package) = frost_core::keys::generate_with_dealer::<Secp256K1Sha256, _>(
+            2, 2, frost_core::keys::IdentifierList::Default, &mut rng,
+        ).unwrap();
+        let mut key_packages: Vec<KeyPackage<Secp256K1Sha256>> = shares.into_values()
+            .map(|share| KeyPackage::try_from(share).unwrap())
+            .collect();
+        key_packages.sort_by_key(|kp| *kp.identifier());
+        (key_packages, public_key_package)
+    }
+
+    fn keystore_with_cold_wallet(dir: &Path, device: &str) -> Keystore {
+        let mut keystore = Keystore::new(dir, device).unwrap();
+        keystore.create_wallet_multi_chain(
+            "cold_wallet", "secp256k1", Vec::new(), 2, 2, "02abcd", b"share", "pass", Vec::new(), None, 1,
+        ).unwrap();
+        keystore
+    }
+
+    /// Runs a whole 2-of-2 file round trip for `message` and returns the
+    /// coordinator's session and signature
+    fn sign_through_files(
+        session_id: &str,
+        message: [u8; 32],
+        (key_packages, public_key_package): &(Vec<KeyPackage<Secp256K1Sha256>>, PublicKeyPackage<Secp256K1Sha256>),
+        signer_keystore: &Keystore,
+        coordinator_keystore: &Keystore,
+    ) -> (OfflineSession, Signature<Secp256K1Sha256>) {
+        let sdcard = tempfile::tempdir().unwrap();
+        let config = OfflineConfig::default();
+        let signers = ["alice", "bob"].map(String::from);
+
+        let mut coordinator = OfflineSession::new(
+            session_id.to_string(), "cold_wallet".to_string(), signers.to_vec(), 2, 60,
+        );
+        coordinator.add_signing_request(signing_request(&message)).unwrap();
+
+        // Signers commit from their own copies of the session
+        let mut signer_sessions = vec![coordinator.clone(), coordinator.clone()];
+        let mut nonces = Vec::new();
+        for ((device, session), key_package) in signers.iter().zip(&signer_sessions).zip(key_packages) {
+            let (path, signer_nonces) = session.export_commitments(device, key_package, sdcard.path()).unwrap();
+            coordinator.import_commitments(&path, &config).unwrap();
+            nonces.push(signer_nonces);
+        }
+        assert_eq!(coordinator.state, SessionState::CommitmentsReady);
+        coordinator.create_signing_package(hex::encode(message)).unwrap();
+        let package_path = coordinator.export_signing_package(sdcard.path()).unwrap();
+
+        for (i, device) in signers.iter().enumerate() {
+            let session = &mut signer_sessions[i];
+            session.import_signing_package(&package_path, &config).unwrap();
+            let path = session.export_share(device, &nonces[i], &key_packages[i], signer_keystore, sdcard.path()).unwrap();
+            coordinator.import_share(&path, &config).unwrap();
+        }
+        assert_eq!(coordinator.state, SessionState::SharesReady);
+
+        let signature = coordinator.aggregate(public_key_package, coordinator_keystore).unwrap();
+        (coordinator, signature)
+    }
+
+    #[test]
+    fn test_two_of_two_signing_through_files() {
+        let keys = two_of_two_keys();
+        let message = [7u8; 32];
+        let keystore_dir = tempfile::tempdir().unwrap();
+        let keystore = keystore_with_cold_wallet(keystore_dir.path(), "alice");
+
+        let (coordinator, signature) = sign_through_files("offline_1", message, &keys, &keystore, &keystore);
+        assert!(keys.1.verifying_key().verify(&message, &signature).is_ok());
+        assert_eq!(coordinator.state, SessionState::Complete);
+        let aggregated = coordinator.aggregated_signature.as_ref().unwrap();
+        assert_eq!(aggregated.signers, vec!["alice".to_string(), "bob".to_string()]);
+
+        assert_eq!(aggregated.signature.algorithm, "schnorr");
+        let bytes = signature.serialize().unwrap();
+        let json = serde_json::to_string(&aggregated.signature).unwrap();
+        let parsed: SignatureData = serde_json::from_str(&json).unwrap();
+        match parsed.value {
+            SignatureValue::Schnorr { r, z } => assert_eq!(r + &z, hex::encode(bytes)),
+            other => panic!("expected a Schnorr signature, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_each_signing_appends_an_audit_entry() {
+        let keys = two_of_two_keys();
+        let signer_dir = tempfile::tempdir().unwrap();
+        let signer_keystore = keystore_with_cold_wallet(signer_dir.path(), "alice");
+        let coordinator_dir = tempfile::tempdir().unwrap();
+        let coordinator_keystore = keystore_with_cold_wallet(coordinator_dir.path(), "coordinator");
+
+        sign_through_files("offline_1", [1u8; 32], &keys, &signer_keystore, &coordinator_keystore);
+        sign_through_files("offline_2", [2u8; 32], &keys, &signer_keystore, &coordinator_keystore);
+
+        let log = coordinator_keystore.get_audit_log("cold_wallet").unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].message_hash, hex::encode([1u8; 32]));
+        assert_eq!(log[1].message_hash, hex::encode([2u8; 32]));
+        assert!(log[0].timestamp <= log[1].timestamp);
+        for entry in &log {
+            assert_eq!(entry.action, AuditAction::AggregatedSignature);
+            assert_eq!(entry.wallet_id, "cold_wallet");
+            assert_eq!(entry.participants, vec!["alice".to_string(), "bob".to_string()]);
+            assert_eq!(entry.chain, "ethereum");
+            assert!(!entry.summary.is_empty());
+        }
+
+        let shares = signer_keystore.get_audit_log("cold_wallet").unwrap();
+        assert_eq!(shares.len(), 4);
+        assert!(shares.iter().all(|entry| entry.action == AuditAction::SignatureShare));
+    }
+
+    /// Unsigned EIP-1559 transaction sending `value` wei to `to` with `data`
+    fn ethereum_request(to: &str, value: u64, data: &[u8]) -> SigningRequest {
+        use ethers_core::types::transaction::eip2718::TypedTransaction;
+        use ethers_core::types::{Address, Eip1559TransactionRequest};
+
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .to(to.parse::<Address>().unwrap())
+            .value(value)
+            .data(data.to_vec())
+            .chain_id(1)
+            .into();
+        let mut request = signing_request(&[0u8; 32]);
+        request.transaction.payload = BASE64.encode(tx.rlp());
+        request
+    }
+
+    #[test]
+    fn test_policy_checks_ethereum_destination_and_value() {
+        use crate::keystore::{PolicyViolation, SigningPolicy};
+
+        const TREASURY: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        const EXCHANGE: &str = "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359";
+        let keystore_dir = tempfile::tempdir().unwrap();
+        let mut keystore = keystore_with_cold_wallet(keystore_dir.path(), "alice");
+        let check = |keystore: &Keystore, request: SigningRequest| {
+            let mut session = OfflineSession::new(
+                "offline_3".to_string(), "cold_wallet".to_string(), vec!["alice".to_string()], 1, 60,
+            );
+            session.add_signing_request(request).unwrap();
+            session.check_policy(keystore)
+        };
+
+        // Without a policy the payload isn't parsed at all
+        assert!(check(&keystore, signing_request(&[0u8; 32])).is_ok());
+
+        keystore.set_signing_policy("cold_wallet", Some(SigningPolicy {
+            max_value: Some(1_000_000_000_000_000_000),
+            allowed_destinations: vec![TREASURY.to_lowercase()],
+        })).unwrap();
+        assert!(check(&keystore, ethereum_request(TREASURY, 1_000_000_000_000_000_000, &[])).is_ok());
+        assert!(matches!(
+            check(&keystore, ethereum_request(TREASURY, 1_000_000_000_000_000_001, &[])),
+            Err(OfflineError::PolicyViolation(PolicyViolation::ValueExceedsLimit { .. }))
+        ));
+        assert!(matches!(
+            check(&keystore, ethereum_request(EXCHANGE, 1, &[])),
+            Err(OfflineError::PolicyViolation(PolicyViolation::DestinationNotAllowed(to))) if to == EXCHANGE
+        ));
+        // A zero-value call could still move tokens
+        assert!(matches!(
+            check(&keystore, ethereum_request(TREASURY, 0, &[0xa9, 0x05, 0x9c, 0xbb])),
+            Err(OfflineError::PolicyViolation(PolicyViolation::UnknownTransfers(_)))
+        ));
+    }
+
+    #[test]
+    fn test_expired_session_rejects_file_steps() {
+        let mut rng = frost_ed25519::rand_core::OsRng;
+        let (shares, _) = frost_core::keys::generate_with_dealer::<Secp256K1Sha256, _>(
+            2, 2, frost_core::keys::IdentifierList::Default, &mut rng,
+        ).unwrap();
+        let key_package = KeyPackage::try_from(shares.into_values().next().unwrap()).unwrap();
+        let sdcard = tempfile::tempdir().unwrap();
+
+        let mut session = OfflineSession::new(
+            "offline_2".to_string(), "cold_wallet".to_string(), vec!["alice".to_string()], 1, 60,
+        );
+        let (path, _) = session.export_commitments("alice", &key_package, sdcard.path()).unwrap();
+        session.expires_at = Utc::now() - chrono::Duration::minutes(1);
+
+        assert!(matches!(
+            session.export_commitments("alice", &key_package, sdcard.path()),
+            Err(OfflineError::SessionExpired(_))
+        ));
+        assert!(matches!(
+            session.import_commitments(&path, &OfflineConfig::default()),
+            Err(OfflineError::SessionExpired(_))
+        ));
+    }
+}
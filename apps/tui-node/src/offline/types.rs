@@ -28,6 +28,9 @@ pub struct OfflineData {
     
     /// The actual data payload
     pub data: serde_json::Value,
+
+    /// SHA-256 (hex) over every other field, catches truncated or corrupted files
+    pub checksum: String,
 }
 
 /// Types of offline data that can be transferred
@@ -170,7 +173,7 @@ pub struct AggregatedSignature {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignatureData {
     /// Signature algorithm
-    pub algorithm: String, // "ecdsa" or "eddsa"
+    pub algorithm: String, // "ecdsa", "eddsa" or "schnorr"
     
     /// The signature value
     pub value: SignatureValue,
@@ -185,6 +188,9 @@ pub enum SignatureValue {
     
     /// EdDSA signature
     Eddsa { signature: String },
+
+    /// Schnorr signature (R, z)
+    Schnorr { r: String, z: String },
 }
 
 impl OfflineData {
@@ -201,14 +207,33 @@ impl OfflineData {
         let data_value = serde_json::to_value(data)
             .map_err(|e| super::OfflineError::SerializationError(e.to_string()))?;
         
-        Ok(Self {
+        let mut offline_data = Self {
             version: OFFLINE_DATA_VERSION.to_string(),
             data_type,
             session_id,
             created_at: now,
             expires_at,
             data: data_value,
-        })
+            checksum: String::new(),
+        };
+        offline_data.checksum = offline_data.compute_checksum()?;
+        Ok(offline_data)
+    }
+    
+    /// SHA-256 over the envelope fields and payload, hex encoded
+    pub fn compute_checksum(&self) -> Result<String, super::OfflineError> {
+        use sha2::{Digest, Sha256};
+        let covered = (
+            &self.version,
+            &self.data_type,
+            &self.session_id,
+            &self.created_at,
+            &self.expires_at,
+            &self.data,
+        );
+        let bytes = serde_json::to_vec(&covered)
+            .map_err(|e| super::OfflineError::SerializationError(e.to_string()))?;
+        Ok(hex::encode(Sha256::digest(&bytes)))
     }
     
     /// Check if this data has expired
@@ -226,6 +251,13 @@ impl OfflineData {
             )));
         }
         
+        // Check content integrity
+        if self.compute_checksum()? != self.checksum {
+            return Err(super::OfflineError::InvalidFormat(
+                "Checksum mismatch: file is corrupted or truncated".to_string()
+            ));
+        }
+        
         // Check expiration
         if self.is_expired() {
             return Err(super::OfflineError::SessionExpired(self.expires_at));
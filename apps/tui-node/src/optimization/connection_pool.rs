@@ -0,0 +1,358 @@
+//! Pool of per-device WebRTC connections with keep-alive and idle eviction
+//!
+//! The pool does not own the underlying `RTCPeerConnection`s; it tracks which
+//! devices have a live connection, which logical sessions (DKG, signing) are
+//! using each one and when traffic was last seen. The caller sends the
+//! keep-alive pings returned by [`ConnectionPool::keep_alive_due`] and closes
+//! the connections returned by [`ConnectionPool::evict_idle`]. Health checks
+//! work the same way: [`ConnectionPool::health_check`] names the devices to
+//! ping and evicts those whose last ping went unanswered, so a half-open
+//! connection is not handed out again.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Pool configuration
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    /// Maximum number of pooled connections
+    pub max_connections: usize,
+    /// Close a connection after this long without traffic
+    pub idle_timeout: Duration,
+    /// Connection establishment retries
+    pub retry_limit: u32,
+    /// Concurrent connection establishment attempts
+    pub parallel_attempts: usize,
+    /// Ping connections that are in use after this long without traffic
+    pub keep_alive_interval: Duration,
+    /// A connection whose health-check ping goes unanswered this long is dead
+    pub health_timeout: Duration,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 32,
+            idle_timeout: Duration::from_secs(60),
+            retry_limit: 3,
+            parallel_attempts: 3,
+            keep_alive_interval: Duration::from_secs(15),
+            health_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum PoolError {
+    #[error("connection pool exhausted ({0} connections)")]
+    Exhausted(usize),
+}
+
+/// Bookkeeping for one pooled connection
+#[derive(Debug, Clone)]
+pub struct PooledConnection {
+    pub device_id: String,
+    pub created_at: Instant,
+    pub last_activity: Instant,
+    /// Logical sessions currently using this connection
+    pub sessions: HashSet<String>,
+    /// When the outstanding health-check ping was sent
+    pub ping_sent_at: Option<Instant>,
+    /// Set by [`ConnectionPool::mark_dead`], e.g. when the data channel closed
+    pub dead: bool,
+}
+
+impl PooledConnection {
+    fn new(device_id: &str) -> Self {
+        let now = Instant::now();
+        Self {
+            device_id: device_id.to_string(),
+            created_at: now,
+            last_activity: now,
+            sessions: HashSet::new(),
+            ping_sent_at: None,
+            dead: false,
+        }
+    }
+
+    /// Marked dead, or its health-check ping is older than `health_timeout`
+    fn is_dead(&self, health_timeout: Duration) -> bool {
+        self.dead || self.ping_sent_at.is_some_and(|sent| sent.elapsed() >= health_timeout)
+    }
+
+    /// True if any logical session still references this connection
+    pub fn in_use(&self) -> bool {
+        !self.sessions.is_empty()
+    }
+}
+
+/// Outcome of one [`ConnectionPool::health_check`] pass
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HealthCheck {
+    /// Devices to send a health-check ping to
+    pub ping: Vec<String>,
+    /// Devices whose connections were dead and have been removed
+    pub evicted: Vec<String>,
+}
+
+/// Shared, cloneable connection pool
+#[derive(Clone)]
+pub struct ConnectionPool {
+    config: PoolConfig,
+    connections: Arc<Mutex<HashMap<String, PooledConnection>>>,
+}
+
+impl ConnectionPool {
+    pub fn new(config: PoolConfig) -> Self {
+        Self {
+            config,
+            connections: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn config(&self) -> &PoolConfig {
+        &self.config
+    }
+
+    /// Get the pooled connection for a device, registering it if needed. A
+    /// pooled connection that is dead is replaced rather than handed out.
+    pub async fn get_or_create(&self, device_id: &str) -> Result<PooledConnection, PoolError> {
+        let mut connections = self.connections.lock().unwrap();
+        match connections.get(device_id) {
+            Some(conn) if conn.is_dead(self.config.health_timeout) => {
+                connections.remove(device_id);
+            }
+            Some(conn) => return Ok(conn.clone()),
+            None => {}
+        }
+
+        if connections.len() >= self.config.max_connections {
+            let idle_timeout = self.config.idle_timeout;
+            connections.retain(|_, c| c.last_activity.elapsed() < idle_timeout);
+            if connections.len() >= self.config.max_connections {
+                return Err(PoolError::Exhausted(self.config.max_connections));
+            }
+        }
+
+        let conn = PooledConnection::new(device_id);
+        connections.insert(device_id.to_string(), conn.clone());
+        Ok(conn)
+    }
+
+    /// Mark a connection as used by a logical session
+    pub fn acquire(&self, device_id: &str, session_id: &str) {
+        if let Some(conn) = self.connections.lock().unwrap().get_mut(device_id) {
+            conn.sessions.insert(session_id.to_string());
+            conn.last_activity = Instant::now();
+        }
+    }
+
+    /// Drop a logical session's claim on a connection
+    pub fn release(&self, device_id: &str, session_id: &str) {
+        if let Some(conn) = self.connections.lock().unwrap().get_mut(device_id) {
+            conn.sessions.remove(session_id);
+        }
+    }
+
+    /// Record traffic (including a keep-alive pong), resetting the idle timer
+    /// and answering any outstanding health-check ping
+    pub fn record_activity(&self, device_id: &str) {
+        if let Some(conn) = self.connections.lock().unwrap().get_mut(device_id) {
+            conn.last_activity = Instant::now();
+            conn.ping_sent_at = None;
+        }
+    }
+
+    /// Flag a connection as dead so the next health check or checkout drops it
+    pub fn mark_dead(&self, device_id: &str) {
+        if let Some(conn) = self.connections.lock().unwrap().get_mut(device_id) {
+            conn.dead = true;
+        }
+    }
+
+    /// Evict dead connections and list the rest that need a health-check
+    /// ping, recording the ping as sent. A connection that still owes a
+    /// reply is not pinged again.
+    pub fn health_check(&self) -> HealthCheck {
+        let health_timeout = self.config.health_timeout;
+        let mut connections = self.connections.lock().unwrap();
+        let evicted: Vec<String> = connections
+            .values()
+            .filter(|c| c.is_dead(health_timeout))
+            .map(|c| c.device_id.clone())
+            .collect();
+        for device_id in &evicted {
+            connections.remove(device_id);
+        }
+
+        let now = Instant::now();
+        let ping = connections
+            .values_mut()
+            .filter(|c| c.ping_sent_at.is_none())
+            .map(|c| {
+                c.ping_sent_at = Some(now);
+                c.device_id.clone()
+            })
+            .collect();
+        HealthCheck { ping, evicted }
+    }
+
+    /// Run `health_check` every `interval`, passing each result to `on_check`
+    /// to send the pings and close evicted connections
+    pub fn spawn_health_checks<F>(&self, interval: Duration, mut on_check: F) -> tokio::task::JoinHandle<()>
+    where
+        F: FnMut(HealthCheck) + Send + 'static,
+    {
+        let pool = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                on_check(pool.health_check());
+            }
+        })
+    }
+
+    /// Devices that are in use but quiet for `keep_alive_interval` and should be pinged
+    ///
+    /// Unreferenced connections are never pinged so they can age out.
+    pub fn keep_alive_due(&self) -> Vec<String> {
+        let interval = self.config.keep_alive_interval;
+        self.connections
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|c| c.in_use() && c.last_activity.elapsed() >= interval)
+            .map(|c| c.device_id.clone())
+            .collect()
+    }
+
+    /// Remove connections with no traffic for `idle_timeout` and return their device ids
+    ///
+    /// An in-use connection whose keep-alives go unanswered is evicted too.
+    pub fn evict_idle(&self) -> Vec<String> {
+        let idle_timeout = self.config.idle_timeout;
+        let mut connections = self.connections.lock().unwrap();
+        let expired: Vec<String> = connections
+            .values()
+            .filter(|c| c.last_activity.elapsed() >= idle_timeout)
+            .map(|c| c.device_id.clone())
+            .collect();
+        for device_id in &expired {
+            connections.remove(device_id);
+        }
+        expired
+    }
+
+    /// Remove a connection explicitly (e.g. after the peer disconnected)
+    pub fn remove(&self, device_id: &str) -> Option<PooledConnection> {
+        self.connections.lock().unwrap().remove(device_id)
+    }
+
+    pub fn contains(&self, device_id: &str) -> bool {
+        self.connections.lock().unwrap().contains_key(device_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.connections.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_keep_alive_outlives_idle_timeout() {
+        let pool = ConnectionPool::new(PoolConfig {
+            idle_timeout: Duration::from_millis(60),
+            keep_alive_interval: Duration::from_millis(10),
+            ..PoolConfig::default()
+        });
+        pool.get_or_create("signer").await.unwrap();
+        pool.get_or_create("stranger").await.unwrap();
+        pool.acquire("signer", "signing-1");
+
+        // Run well past idle_timeout, answering every keep-alive ping
+        for _ in 0..15 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            for device_id in pool.keep_alive_due() {
+                assert_eq!(device_id, "signer");
+                pool.record_activity(&device_id);
+            }
+        }
+
+        assert_eq!(pool.evict_idle(), vec!["stranger".to_string()]);
+        assert!(pool.contains("signer"));
+
+        // Once the session ends the connection ages out as well
+        pool.release("signer", "signing-1");
+        assert!(pool.keep_alive_due().is_empty());
+        tokio::time::sleep(Duration::from_millis(70)).await;
+        assert_eq!(pool.evict_idle(), vec!["signer".to_string()]);
+        assert!(pool.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dead_connection_is_evicted_and_recreated() {
+        let pool = ConnectionPool::new(PoolConfig {
+            health_timeout: Duration::from_millis(20),
+            ..PoolConfig::default()
+        });
+        let first = pool.get_or_create("peer").await.unwrap();
+        pool.acquire("peer", "signing-1");
+        pool.mark_dead("peer");
+
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        let second = pool.get_or_create("peer").await.unwrap();
+        assert!(second.created_at > first.created_at);
+        assert!(!second.dead && !second.in_use());
+
+        // An unanswered health-check ping gets the connection evicted
+        pool.get_or_create("quiet").await.unwrap();
+        let mut check = pool.health_check();
+        check.ping.sort();
+        assert_eq!(check, HealthCheck { ping: vec!["peer".to_string(), "quiet".to_string()], evicted: vec![] });
+        pool.record_activity("peer");
+        tokio::time::sleep(Duration::from_millis(25)).await;
+        let check = pool.health_check();
+        assert_eq!(check.evicted, vec!["quiet".to_string()]);
+        assert_eq!(check.ping, vec!["peer".to_string()]);
+        assert!(!pool.contains("quiet"));
+    }
+
+    #[tokio::test]
+    async fn test_spawned_health_checks_evict_unanswered_connections() {
+        let pool = ConnectionPool::new(PoolConfig {
+            health_timeout: Duration::from_millis(15),
+            ..PoolConfig::default()
+        });
+        pool.get_or_create("peer").await.unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let handle = pool.spawn_health_checks(Duration::from_millis(20), move |check| {
+            let _ = tx.send(check);
+        });
+
+        assert_eq!(rx.recv().await.unwrap().ping, vec!["peer".to_string()]);
+        assert_eq!(rx.recv().await.unwrap().evicted, vec!["peer".to_string()]);
+        assert!(pool.is_empty());
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_pool_exhaustion() {
+        let pool = ConnectionPool::new(PoolConfig {
+            max_connections: 1,
+            ..PoolConfig::default()
+        });
+        pool.get_or_create("a").await.unwrap();
+        assert!(pool.get_or_create("a").await.is_ok());
+        assert_eq!(pool.get_or_create("b").await.unwrap_err(), PoolError::Exhausted(1));
+    }
+}
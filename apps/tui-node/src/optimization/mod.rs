@@ -0,0 +1,5 @@
+//! Performance-oriented building blocks for the networking layer
+
+pub mod connection_pool;
+
+pub use connection_pool::{ConnectionPool, HealthCheck, PoolConfig, PoolError, PooledConnection};
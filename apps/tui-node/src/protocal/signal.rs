@@ -53,6 +53,10 @@ pub struct SessionInfo {
     /// Coordination type from the proposer
     #[serde(default = "default_coordination_type")]
     pub coordination_type: String,
+    /// Participant index each device was bound to when the roster first
+    /// filled up; a device that drops and rejoins gets the same one back
+    #[serde(default)]
+    pub participant_indices: std::collections::BTreeMap<String, u16>,
 }
 
 // --- WebRTC Signaling Data (sent via Relay) ---
@@ -93,6 +97,8 @@ pub enum WebSocketMessage {
     /// Session accepted (compatibility with message validator)
     SessionAccepted { device_id: String, session_id: String },
     WebRTCSignal(WebRTCSignal),
+    /// DKG was cancelled; every participant tears the session down
+    DKGAbort { session_id: String, reason: String },
 }
 
 /// Session proposal information
@@ -185,6 +191,45 @@ impl SessionInfo {
             self.proposer_id.clone()
         }
     }
+
+    /// `device_id`'s participant index: the one it is bound to, or else its
+    /// position among the sorted `participants`
+    pub fn participant_index(&self, device_id: &str) -> Option<u16> {
+        match self.participant_indices.get(device_id) {
+            Some(index) => Some(*index),
+            None => mpc_wallet_frost_core::participant_index(&self.participants, device_id).ok(),
+        }
+    }
+
+    /// Binds every device in `participants` to its index among them. Fails,
+    /// leaving the bindings as they were, if that would move a bound device
+    /// to another index or give its index to a different device.
+    pub fn bind_participant_indices(&mut self) -> Result<(), String> {
+        let indices = mpc_wallet_frost_core::participant_index_map(&self.participants)
+            .map_err(|e| e.to_string())?;
+        for (device, index) in &indices {
+            if let Some(bound) = self.participant_indices.get(device)
+                && bound != index
+            {
+                return Err(format!(
+                    "{} is bound to participant {} and cannot move to {}",
+                    device, bound, index
+                ));
+            }
+            if let Some((holder, _)) = self
+                .participant_indices
+                .iter()
+                .find(|(holder, bound)| *bound == index && *holder != device)
+            {
+                return Err(format!(
+                    "{} cannot take participant index {}, which belongs to {}",
+                    device, index, holder
+                ));
+            }
+        }
+        self.participant_indices.extend(indices);
+        Ok(())
+    }
 }
 
 // --- Application-Level Messages (sent over established WebRTC Data Channel) ---
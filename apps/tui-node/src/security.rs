@@ -89,9 +89,7 @@ impl KdfConfig {
             .unwrap_or(DEFAULT_PBKDF2_ITERATIONS);
         
         // Clamp to valid range
-        let iterations = iterations
-            .max(MIN_PBKDF2_ITERATIONS)
-            .min(MAX_PBKDF2_ITERATIONS);
+        let iterations = iterations.clamp(MIN_PBKDF2_ITERATIONS, MAX_PBKDF2_ITERATIONS);
         
         Self {
             iterations,
@@ -165,6 +163,100 @@ pub fn security_info() -> String {
     )
 }
 
+/// Advisory warning about a threshold/total combination
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThresholdWarning {
+    /// Every participant must sign (n-of-n); losing one share locks the wallet
+    NoFaultTolerance,
+    /// A single compromised share is enough to sign (1-of-n)
+    SinglePointOfCompromise,
+    /// Threshold is not a strict majority, so a minority coalition can sign
+    BelowMajority { recommended_min: u16 },
+    /// Threshold exceeds total or total is zero; the configuration cannot run
+    Invalid(String),
+}
+
+impl ThresholdWarning {
+    /// Human-readable message for UI display
+    pub fn message(&self) -> String {
+        match self {
+            ThresholdWarning::NoFaultTolerance => {
+                "No fault tolerance: losing any single share makes the wallet unusable".to_string()
+            }
+            ThresholdWarning::SinglePointOfCompromise => {
+                "Single point of compromise: any one participant can sign alone".to_string()
+            }
+            ThresholdWarning::BelowMajority { recommended_min } => format!(
+                "Threshold below majority: recommended at least {} signers",
+                recommended_min
+            ),
+            ThresholdWarning::Invalid(reason) => format!("Invalid configuration: {}", reason),
+        }
+    }
+}
+
+/// Result of assessing a threshold configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecurityAssessment {
+    pub threshold: u16,
+    pub total: u16,
+    pub warnings: Vec<ThresholdWarning>,
+    /// Recommended threshold range `(min, max)` for this total
+    pub recommended_range: (u16, u16),
+}
+
+impl SecurityAssessment {
+    /// Whether the configuration has no warnings
+    pub fn is_sound(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+/// Assess a threshold/total combination and report weaknesses
+///
+/// This is advisory: callers decide whether to block or just warn. The
+/// recommended range keeps a strict majority while leaving at least one
+/// share of redundancy.
+pub fn assess_threshold_security(threshold: u16, total: u16) -> SecurityAssessment {
+    let majority = total / 2 + 1;
+    let recommended_range = if total >= 3 {
+        (majority, total - 1)
+    } else {
+        (total, total)
+    };
+
+    let mut warnings = Vec::new();
+    if total == 0 || threshold == 0 {
+        warnings.push(ThresholdWarning::Invalid(
+            "threshold and total must be at least 1".to_string(),
+        ));
+    } else if threshold > total {
+        warnings.push(ThresholdWarning::Invalid(format!(
+            "threshold {} exceeds total {}",
+            threshold, total
+        )));
+    } else {
+        if threshold == 1 {
+            warnings.push(ThresholdWarning::SinglePointOfCompromise);
+        }
+        if threshold == total {
+            warnings.push(ThresholdWarning::NoFaultTolerance);
+        }
+        if threshold > 1 && threshold < majority {
+            warnings.push(ThresholdWarning::BelowMajority {
+                recommended_min: majority,
+            });
+        }
+    }
+
+    SecurityAssessment {
+        threshold,
+        total,
+        warnings,
+        recommended_range,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -199,4 +291,31 @@ mod tests {
         let invalid_key = vec![0u8; 16];
         assert!(validate_key(&invalid_key).is_err());
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_threshold_assessment_one_of_three() {
+        let assessment = assess_threshold_security(1, 3);
+        assert!(!assessment.is_sound());
+        assert!(assessment.warnings.contains(&ThresholdWarning::SinglePointOfCompromise));
+        assert!(!assessment.warnings.contains(&ThresholdWarning::NoFaultTolerance));
+    }
+    
+    #[test]
+    fn test_threshold_assessment_three_of_three() {
+        let assessment = assess_threshold_security(3, 3);
+        assert_eq!(assessment.warnings, vec![ThresholdWarning::NoFaultTolerance]);
+    }
+    
+    #[test]
+    fn test_threshold_assessment_two_of_three() {
+        let assessment = assess_threshold_security(2, 3);
+        assert!(assessment.is_sound());
+        assert_eq!(assessment.recommended_range, (2, 2));
+    }
+    
+    #[test]
+    fn test_threshold_assessment_invalid() {
+        let assessment = assess_threshold_security(4, 3);
+        assert!(matches!(assessment.warnings[0], ThresholdWarning::Invalid(_)));
+    }
+}
@@ -27,11 +27,21 @@ pub struct AppState<C: Ciphersuite> {
     pub current_wallet_id: Option<String>,
     pub device_connections: Arc<tokio::sync::Mutex<std::collections::HashMap<String, Arc<webrtc::peer_connection::RTCPeerConnection>>>>,
     pub data_channels: std::collections::HashMap<String, Arc<webrtc::data_channel::RTCDataChannel>>,
+    // Peers whose direct WebRTC negotiation timed out; their peer
+    // connections only gather TURN relay candidates
+    pub turn_relay_peers: std::collections::HashSet<String>,
+    // Partial data-channel frames per peer
+    pub dc_frame_buffers: crate::network::framing::PeerFrameBuffers,
+    // Open data channels, health-checked with pings so a half-open one is
+    // closed instead of reused
+    pub connection_pool: crate::optimization::ConnectionPool,
     pub device_statuses: std::collections::HashMap<String, webrtc::peer_connection::peer_connection_state::RTCPeerConnectionState>,
     pub pending_ice_candidates: std::collections::HashMap<String, Vec<webrtc::ice_transport::ice_candidate::RTCIceCandidateInit>>,
     pub making_offer: std::collections::HashMap<String, bool>,
     pub mesh_status: MeshStatus,
     pub dkg_state: DkgState,
+    // When the current DKG round started, for the round deadline
+    pub dkg_round_started_at: Option<std::time::Instant>,
     pub received_dkg_packages: std::collections::HashMap<String, Vec<u8>>,
     pub received_dkg_round2_packages: std::collections::HashMap<String, Vec<u8>>,
     pub webrtc_initiation_in_progress: bool,
@@ -45,6 +55,10 @@ pub struct AppState<C: Ciphersuite> {
     pub dkg_part2_secret_package: Option<Vec<u8>>,
     pub dkg_round1_packages: std::collections::BTreeMap<frost_core::Identifier<C>, frost_core::keys::dkg::round1::Package<C>>,
     pub dkg_round2_packages: std::collections::BTreeMap<frost_core::Identifier<C>, frost_core::keys::dkg::round2::Package<C>>,
+    // Our Round 2 packages by recipient, kept for replay until acked
+    pub dkg_round2_outbox: std::collections::HashMap<String, Vec<u8>>,
+    // (round, device_id) pairs whose recipient acked our DKG package
+    pub dkg_acks: std::collections::HashSet<(u8, String)>,
     pub key_package: Option<frost_core::keys::KeyPackage<C>>,
     pub group_public_key: Option<frost_core::VerifyingKey<C>>,
     pub public_key_package: Option<frost_core::keys::PublicKeyPackage<C>>,
@@ -77,6 +91,16 @@ pub struct AppState<C: Ciphersuite> {
     // relay frames, …) enqueues a serialized JSON string here. A single sender
     // task drains it into the socket. There is only one of these per process.
     pub websocket_msg_tx: Option<tokio::sync::mpsc::UnboundedSender<String>>,
+    // Payloads the sender task couldn't write before its socket died; the
+    // next reconnect replays them after re-registering
+    pub websocket_replay_queue: crate::elm::ws_runtime::ReplayQueue,
+    // DKG lifecycle milestones, drained into a `DkgManager` by the command
+    // that started the DKG; `None` outside a DKG
+    pub dkg_events: Option<tokio::sync::mpsc::UnboundedSender<crate::core::dkg_manager::DkgEvent>>,
+    // Liveness of the running session's participants, fed by every inbound
+    // peer message; `liveness_monitor` polls it for dropouts
+    pub session_manager: Option<Arc<crate::core::session_manager::SessionManager>>,
+    pub liveness_monitor: Option<tokio::task::JoinHandle<()>>,
     // Primary inbound fan-out — the single WebSocket reader parses each server
     // frame once and broadcasts an `Arc<ServerMsg>` on this channel. Any task
     // that needs to react (Elm-side bridge, DKG WebRTC signaling handler,
@@ -85,6 +109,12 @@ pub struct AppState<C: Ciphersuite> {
     // knowing about each other.
     pub server_msg_broadcast_tx:
         Option<tokio::sync::broadcast::Sender<Arc<webrtc_signal_server::ServerMsg>>>,
+    // STUN/TURN servers for every peer connection we create
+    pub stun_turn_config: crate::network::ice_config::StunTurnConfig,
+    // Address formats the next wallet's addresses are derived in
+    pub address_settings: crate::blockchain_config::AddressSettings,
+    // Partially received chunked relays from the signal server
+    pub relay_reassembler: webrtc_signal_server::chunking::RelayReassembler,
     // ICE candidate queue for handling race conditions
     pub ice_candidate_queue: Arc<tokio::sync::Mutex<std::collections::HashMap<String, Vec<webrtc::ice_transport::ice_candidate::RTCIceCandidateInit>>>>,
 }
@@ -112,11 +142,15 @@ where
             current_wallet_id: None,
             device_connections: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
             data_channels: std::collections::HashMap::new(),
+            turn_relay_peers: std::collections::HashSet::new(),
+            dc_frame_buffers: Default::default(),
+            connection_pool: crate::optimization::ConnectionPool::new(Default::default()),
             device_statuses: std::collections::HashMap::new(),
             pending_ice_candidates: std::collections::HashMap::new(),
             making_offer: std::collections::HashMap::new(),
             mesh_status: MeshStatus::Incomplete,
             dkg_state: DkgState::Idle,
+            dkg_round_started_at: None,
             received_dkg_packages: std::collections::HashMap::new(),
             received_dkg_round2_packages: std::collections::HashMap::new(),
             webrtc_initiation_in_progress: false,
@@ -129,6 +163,8 @@ where
             dkg_part2_secret_package: None,
             dkg_round1_packages: std::collections::BTreeMap::new(),
             dkg_round2_packages: std::collections::BTreeMap::new(),
+            dkg_round2_outbox: std::collections::HashMap::new(),
+            dkg_acks: std::collections::HashSet::new(),
             key_package: None,
             group_public_key: None,
             public_key_package: None,
@@ -155,7 +191,14 @@ where
             websocket_error: None,
             websocket_internal_cmd_tx: None,
             websocket_msg_tx: None,
+            websocket_replay_queue: Default::default(),
+            dkg_events: None,
+            session_manager: None,
+            liveness_monitor: None,
             server_msg_broadcast_tx: None,
+            stun_turn_config: crate::network::ice_config::StunTurnConfig::default(),
+            address_settings: crate::blockchain_config::AddressSettings::default(),
+            relay_reassembler: webrtc_signal_server::chunking::RelayReassembler::default(),
             ice_candidate_queue: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         }
     }
@@ -182,11 +225,15 @@ where
             current_wallet_id: None,
             device_connections: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
             data_channels: std::collections::HashMap::new(),
+            turn_relay_peers: std::collections::HashSet::new(),
+            dc_frame_buffers: Default::default(),
+            connection_pool: crate::optimization::ConnectionPool::new(Default::default()),
             device_statuses: std::collections::HashMap::new(),
             pending_ice_candidates: std::collections::HashMap::new(),
             making_offer: std::collections::HashMap::new(),
             mesh_status: MeshStatus::Incomplete,
             dkg_state: DkgState::Idle,
+            dkg_round_started_at: None,
             received_dkg_packages: std::collections::HashMap::new(),
             received_dkg_round2_packages: std::collections::HashMap::new(),
             webrtc_initiation_in_progress: false,
@@ -199,6 +246,8 @@ where
             dkg_part2_secret_package: None,
             dkg_round1_packages: std::collections::BTreeMap::new(),
             dkg_round2_packages: std::collections::BTreeMap::new(),
+            dkg_round2_outbox: std::collections::HashMap::new(),
+            dkg_acks: std::collections::HashSet::new(),
             key_package: None,
             group_public_key: None,
             public_key_package: None,
@@ -225,7 +274,14 @@ where
             websocket_error: None,
             websocket_internal_cmd_tx: None,
             websocket_msg_tx: None,
+            websocket_replay_queue: Default::default(),
+            dkg_events: None,
+            session_manager: None,
+            liveness_monitor: None,
             server_msg_broadcast_tx: None,
+            stun_turn_config: crate::network::ice_config::StunTurnConfig::default(),
+            address_settings: crate::blockchain_config::AddressSettings::default(),
+            relay_reassembler: webrtc_signal_server::chunking::RelayReassembler::default(),
             ice_candidate_queue: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
         }
     }
@@ -247,6 +303,33 @@ where
         // Simple check based on mesh status
         matches!(self.mesh_status, MeshStatus::Ready)
     }
+
+    /// Session participants (other than us) without an open data channel
+    pub fn peers_missing_open_channel(&self) -> Vec<String> {
+        let Some(session) = &self.session else {
+            return Vec::new();
+        };
+        session
+            .participants
+            .iter()
+            .filter(|p| **p != self.device_id)
+            .filter(|p| !self.has_open_data_channel(p))
+            .cloned()
+            .collect()
+    }
+
+    /// True if the data channel to `device_id` is open
+    pub fn has_open_data_channel(&self, device_id: &str) -> bool {
+        self.data_channels.get(device_id).is_some_and(|dc| {
+            dc.ready_state() == webrtc::data_channel::data_channel_state::RTCDataChannelState::Open
+        })
+    }
+
+    /// True once every other session participant has an open data channel,
+    /// mirroring `WebRTCMeshManager::is_full_mesh_ready`
+    pub fn is_full_mesh_ready(&self) -> bool {
+        self.session.is_some() && self.peers_missing_open_channel().is_empty()
+    }
 }
 
 /// Create a Mutex-wrapped AppState for compatibility
@@ -45,7 +45,7 @@ pub async fn send_webrtc_message<C>(
             let msg_json = serde_json::to_string(&message)
                 .map_err(|e| format!("Failed to serialize envelope: {}", e))?;
 
-            if let Err(_e) = dc.send_text(msg_json).await {
+            if let Err(_e) = crate::network::framing::send_payload(&dc, &msg_json).await {
                 return Err(format!("Failed to send message: {}", _e));
             }
 
@@ -256,6 +256,7 @@ pub async fn create_and_setup_device_connection<C>(
                                 guard.dkg_part1_public_package = None;
                                 guard.dkg_part1_secret_package = None;
                                 guard.received_dkg_packages.clear();
+                                guard.dkg_round2_outbox.clear();
                             }
                             
                             // Always attempt immediate reconnection on Disconnected state
@@ -276,6 +277,7 @@ pub async fn create_and_setup_device_connection<C>(
                                 guard.dkg_part1_public_package = None;
                                 guard.dkg_part1_secret_package = None;
                                 guard.received_dkg_packages.clear();
+                                guard.dkg_round2_outbox.clear();
                             }
                             
                             // Check if we should attempt reconnection (simple time-based check)
@@ -7,6 +7,7 @@ pub mod state;
 pub mod appstate_compat;
 pub mod performance;
 pub mod erc20_encoder;
+pub mod eth_tx_builder;
 pub mod solana_encoder;
 // Maps a FROST `Ciphersuite` generic to the "secp256k1" / "ed25519"
 // string names the blockchain helpers expect. Previously orphaned in
@@ -8,6 +8,7 @@ use solana_sdk::{
     message::Message,
 };
 use solana_system_program;
+use mpc_wallet_blockchain::solana::SolanaHandler;
 use serde::{Serialize, Deserialize};
 
 /// SPL Token program ID
@@ -36,6 +37,13 @@ impl TokenMints {
     pub const RAY: &'static str = "4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R";
 }
 
+/// Public key of a user-supplied base58 address; `role` names it in the error
+fn address(addr: &str, role: &str) -> Result<Pubkey, String> {
+    SolanaHandler::validate_address(addr)
+        .map(Pubkey::new_from_array)
+        .map_err(|e| format!("Invalid {}: {}", role, e))
+}
+
 /// Solana transaction builder
 #[derive(Debug, Clone)]
 pub struct SolanaTransactionBuilder {
@@ -56,8 +64,7 @@ impl SolanaTransactionBuilder {
     
     /// Sets the fee payer
     pub fn fee_payer(mut self, payer: &str) -> Result<Self, String> {
-        let pubkey = payer.parse::<Pubkey>()
-            .map_err(|e| format!("Invalid fee payer: {}", e))?;
+        let pubkey = address(payer, "fee payer")?;
         self.fee_payer = Some(pubkey);
         Ok(self)
     }
@@ -77,10 +84,8 @@ impl SolanaTransactionBuilder {
         to: &str,
         _lamports: u64,
     ) -> Result<Self, String> {
-        let from_pubkey = from.parse::<Pubkey>()
-            .map_err(|e| format!("Invalid from address: {}", e))?;
-        let to_pubkey = to.parse::<Pubkey>()
-            .map_err(|e| format!("Invalid to address: {}", e))?;
+        let from_pubkey = address(from, "from address")?;
+        let to_pubkey = address(to, "to address")?;
         
         // Create a system transfer instruction manually
         let instruction = Instruction {
@@ -107,12 +112,9 @@ impl SolanaTransactionBuilder {
     ) -> Result<Self, String> {
         let token_program_id = token_program.parse::<Pubkey>()
             .unwrap_or_else(|_| TOKEN_PROGRAM_ID.parse().unwrap());
-        let source_pubkey = source.parse::<Pubkey>()
-            .map_err(|e| format!("Invalid source: {}", e))?;
-        let dest_pubkey = destination.parse::<Pubkey>()
-            .map_err(|e| format!("Invalid destination: {}", e))?;
-        let authority_pubkey = authority.parse::<Pubkey>()
-            .map_err(|e| format!("Invalid authority: {}", e))?;
+        let source_pubkey = address(source, "source")?;
+        let dest_pubkey = address(destination, "destination")?;
+        let authority_pubkey = address(authority, "authority")?;
         
         // SPL Token Transfer instruction
         // Add discriminator for Transfer (3 for SPL Token)
@@ -140,12 +142,9 @@ impl SolanaTransactionBuilder {
         wallet: &str,
         mint: &str,
     ) -> Result<Self, String> {
-        let payer_pubkey = payer.parse::<Pubkey>()
-            .map_err(|e| format!("Invalid payer: {}", e))?;
-        let wallet_pubkey = wallet.parse::<Pubkey>()
-            .map_err(|e| format!("Invalid wallet: {}", e))?;
-        let mint_pubkey = mint.parse::<Pubkey>()
-            .map_err(|e| format!("Invalid mint: {}", e))?;
+        let payer_pubkey = address(payer, "payer")?;
+        let wallet_pubkey = address(wallet, "wallet")?;
+        let mint_pubkey = address(mint, "mint")?;
         
         // Derive the associated token account address
         let ata = Self::derive_ata(&wallet_pubkey, &mint_pubkey);
@@ -352,6 +351,16 @@ mod tests {
         assert!(message.is_ok());
     }
     
+    #[test]
+    fn test_builder_rejects_malformed_recipient() {
+        let from = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+        let blockhash = "11111111111111111111111111111111";
+
+        let err = SolanaHelper::sol_transfer(from, "2fG3hR8SxZDkMEmL3Khc", 1.0, blockhash).unwrap_err();
+        assert!(err.starts_with("Invalid to address"), "{}", err);
+        assert!(err.contains("expected 32"), "{}", err);
+    }
+    
     #[test]
     fn test_format_amount() {
         assert_eq!(SolanaHelper::format_amount(1.0, 9), 1_000_000_000); // 1 SOL
@@ -284,6 +284,13 @@ pub enum DkgState {
     Finalizing,
     Complete,
     Failed(String),
+    /// Round 1 deadline passed without packages from `missing`. Unlike
+    /// `Failed`, the session and received packages are kept, so a late or
+    /// replayed package still completes the round.
+    Round1TimedOut { missing: Vec<String> },
+    /// Round 2 deadline passed without packages from `missing`; retriable
+    /// like `Round1TimedOut`
+    Round2TimedOut { missing: Vec<String> },
 }
 
 /// Mesh status tracking enum
@@ -437,6 +444,12 @@ impl DkgStateDisplay for DkgState {
             DkgState::Finalizing => "Finalizing".to_string(),
             DkgState::Complete => "DKG Complete".to_string(),
             DkgState::Failed(reason) => format!("Failed: {}", reason),
+            DkgState::Round1TimedOut { missing } => {
+                format!("Round 1 Timed Out (waiting for {})", missing.join(", "))
+            }
+            DkgState::Round2TimedOut { missing } => {
+                format!("Round 2 Timed Out (waiting for {})", missing.join(", "))
+            }
         }
     }
 
@@ -64,6 +64,10 @@ pub fn validate_state_combination(state: &CompositeState) -> StateValidity {
         (true, Ready, Finalizing) => StateValidity::Valid,
         (true, Ready, Complete) => StateValidity::Valid,
         
+        // Timed-out rounds keep the session so a late package can resume them
+        (true, _, Round1TimedOut { .. }) => StateValidity::Valid,
+        (true, _, Round2TimedOut { .. }) => StateValidity::Valid,
+        
         // Failed states
         (true, Ready, Failed(_)) => StateValidity::Valid,
         (true, PartiallyReady { .. }, Failed(_)) => StateValidity::Valid,
@@ -144,6 +148,12 @@ pub fn validate_transition(
         (Ready, Finalizing, Ready, Complete) => Ok(()),
         (Ready, Finalizing, Ready, Failed(_)) => Ok(()),
         
+        // Round deadlines, and late packages completing a timed-out round
+        (_, Round1InProgress, _, Round1TimedOut { .. }) => Ok(()),
+        (_, Round2InProgress, _, Round2TimedOut { .. }) => Ok(()),
+        (_, Round1TimedOut { .. }, _, Round1Complete) => Ok(()),
+        (_, Round2TimedOut { .. }, _, Complete) => Ok(()),
+        
         // Mesh state changes
         (Incomplete, dkg, PartiallyReady { .. }, new_dkg) if dkg == new_dkg => Ok(()),
         (PartiallyReady { .. }, dkg, Ready, new_dkg) if dkg == new_dkg => Ok(()),
@@ -1,6 +1,7 @@
 //! Connection monitoring with heartbeat and quality metrics
 
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use serde::{Serialize, Deserialize};
@@ -68,6 +69,75 @@ pub struct Heartbeat {
     pub timestamp: u64,
 }
 
+/// Exponential backoff for re-negotiating a dropped peer connection
+#[derive(Debug, Clone)]
+pub struct ReconnectPolicy {
+    /// Delay before the first attempt
+    pub initial_delay: Duration,
+    /// Upper bound for the doubled delay
+    pub max_delay: Duration,
+    /// Attempts before giving up
+    pub max_attempts: u32,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(8),
+            max_attempts: 5,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Delay before `attempt` (1-based): 1s, 2s, 4s, ... capped at `max_delay`
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let factor = 1u32 << attempt.saturating_sub(1).min(16);
+        self.initial_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+/// Reconnection progress for a single peer
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReconnectStatus {
+    /// Waiting `delay` before attempt number `attempt`
+    Attempting { attempt: u32, delay: Duration },
+    /// Connection restored after `attempts` tries
+    Reconnected { attempts: u32 },
+    /// Gave up after `attempts` tries
+    Failed { attempts: u32 },
+}
+
+/// Retries `renegotiate` with the policy's backoff until it reports the link
+/// is back or the attempts run out. Every status change goes to `on_status`;
+/// the final one is also returned.
+pub async fn reconnect_with_backoff<F, Fut>(
+    policy: &ReconnectPolicy,
+    mut renegotiate: F,
+    mut on_status: impl FnMut(&ReconnectStatus),
+) -> ReconnectStatus
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    for attempt in 1..=policy.max_attempts {
+        let delay = policy.delay_for(attempt);
+        on_status(&ReconnectStatus::Attempting { attempt, delay });
+        tokio::time::sleep(delay).await;
+
+        if renegotiate(attempt).await {
+            let status = ReconnectStatus::Reconnected { attempts: attempt };
+            on_status(&status);
+            return status;
+        }
+    }
+
+    let status = ReconnectStatus::Failed { attempts: policy.max_attempts };
+    on_status(&status);
+    status
+}
+
 /// Connection monitor for tracking peer health
 pub struct ConnectionMonitor {
     /// Heartbeat interval
@@ -80,6 +150,8 @@ pub struct ConnectionMonitor {
     pub heartbeat_sequences: Arc<Mutex<HashMap<PeerId, u64>>>,
     /// Pending heartbeats (for RTT calculation)
     pub pending_heartbeats: Arc<Mutex<HashMap<(PeerId, u64), Instant>>>,
+    /// Backoff used by `reconnect_peer`
+    pub reconnect_policy: ReconnectPolicy,
 }
 
 impl ConnectionMonitor {
@@ -91,9 +163,31 @@ impl ConnectionMonitor {
             quality_metrics: Arc::new(Mutex::new(HashMap::new())),
             heartbeat_sequences: Arc::new(Mutex::new(HashMap::new())),
             pending_heartbeats: Arc::new(Mutex::new(HashMap::new())),
+            reconnect_policy: ReconnectPolicy::default(),
         }
     }
 
+    /// Re-negotiates a dropped peer with backoff, restoring its quality
+    /// metrics once `renegotiate` succeeds
+    pub async fn reconnect_peer<F, Fut>(
+        &self,
+        peer: PeerId,
+        renegotiate: F,
+        on_status: impl FnMut(&ReconnectStatus),
+    ) -> ReconnectStatus
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = bool>,
+    {
+        println!("  🔁 Reconnecting to peer {}", peer);
+        let status = reconnect_with_backoff(&self.reconnect_policy, renegotiate, on_status).await;
+        match status {
+            ReconnectStatus::Reconnected { .. } => self.restore_connection(peer),
+            _ => println!("  ❌ Gave up reconnecting to peer {}", peer),
+        }
+        status
+    }
+
     /// Starts monitoring a peer
     pub fn start_monitoring(&self, peer: PeerId) {
         let mut metrics = self.quality_metrics.lock().unwrap();
@@ -274,4 +368,11 @@ mod tests {
         assert!(quality.is_healthy());
     }
 
+    #[test]
+    fn test_reconnect_backoff_is_capped() {
+        let policy = ReconnectPolicy::default();
+        let delays: Vec<u64> = (1..=6).map(|a| policy.delay_for(a).as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 8, 8]);
+    }
+
 }
\ No newline at end of file
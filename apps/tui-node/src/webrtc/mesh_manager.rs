@@ -4,6 +4,7 @@ use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use serde::{Serialize, Deserialize};
+use tokio::sync::broadcast;
 
 /// Peer identifier
 pub type PeerId = u16;
@@ -23,6 +24,15 @@ pub enum ConnectionState {
     Reconnecting,
 }
 
+/// How messages to a peer travel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PeerTransport {
+    /// Direct WebRTC data channel
+    WebRtc,
+    /// Through a TURN relay after direct WebRTC negotiation timed out
+    Relay,
+}
+
 /// WebRTC data channel
 #[derive(Debug, Clone)]
 pub struct RTCDataChannel {
@@ -132,6 +142,15 @@ impl MeshTopology {
     }
 }
 
+/// Mesh readiness transition, published by `WebRTCMeshManager`
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeshTopologyEvent {
+    /// Every expected peer has an open data channel
+    FullyConnected,
+    /// A previously full mesh lost an open data channel
+    Degraded,
+}
+
 /// WebRTC mesh network manager
 pub struct WebRTCMeshManager {
     /// Local peer ID
@@ -146,6 +165,14 @@ pub struct WebRTCMeshManager {
     pub mesh_topology: Arc<Mutex<MeshTopology>>,
     /// Message buffer for offline peers
     pub message_buffer: Arc<Mutex<HashMap<PeerId, Vec<Vec<u8>>>>>,
+    /// Peers the mesh must reach before it counts as ready
+    pub expected_peers: Arc<Mutex<HashSet<PeerId>>>,
+    /// Peers reached through a TURN relay instead of a direct path
+    pub relayed_peers: Arc<Mutex<HashSet<PeerId>>>,
+    /// Last readiness published on `mesh_events`
+    mesh_ready: Arc<Mutex<bool>>,
+    /// Readiness transitions
+    mesh_events: broadcast::Sender<MeshTopologyEvent>,
 }
 
 impl WebRTCMeshManager {
@@ -158,6 +185,104 @@ impl WebRTCMeshManager {
             connection_states: Arc::new(Mutex::new(HashMap::new())),
             mesh_topology: Arc::new(Mutex::new(MeshTopology::new(total_peers, threshold))),
             message_buffer: Arc::new(Mutex::new(HashMap::new())),
+            expected_peers: Arc::new(Mutex::new(HashSet::new())),
+            relayed_peers: Arc::new(Mutex::new(HashSet::new())),
+            mesh_ready: Arc::new(Mutex::new(false)),
+            mesh_events: broadcast::channel(16).0,
+        }
+    }
+
+    /// Subscribes to mesh readiness transitions
+    pub fn subscribe_mesh_events(&self) -> broadcast::Receiver<MeshTopologyEvent> {
+        self.mesh_events.subscribe()
+    }
+
+    /// True once every expected peer has an open data channel or is relayed
+    pub fn is_full_mesh_ready(&self) -> bool {
+        let expected = self.expected_peers.lock().unwrap();
+        let channels = self.data_channels.lock().unwrap();
+        let relayed = self.relayed_peers.lock().unwrap();
+        !expected.is_empty()
+            && expected.iter().all(|peer| {
+                relayed.contains(peer)
+                    || channels
+                        .get(peer)
+                        .is_some_and(|channel| channel.state == ConnectionState::Connected)
+            })
+    }
+
+    /// True if a direct data channel to `peer` is open
+    pub fn has_open_data_channel(&self, peer: PeerId) -> bool {
+        self.data_channels
+            .lock()
+            .unwrap()
+            .get(&peer)
+            .is_some_and(|channel| channel.state == ConnectionState::Connected)
+    }
+
+    /// Transport currently used for messages to `peer`
+    pub fn transport_for(&self, peer: PeerId) -> PeerTransport {
+        if self.relayed_peers.lock().unwrap().contains(&peer) {
+            PeerTransport::Relay
+        } else {
+            PeerTransport::WebRtc
+        }
+    }
+
+    /// Marks the data channel to `peer` as open. A direct channel always
+    /// wins over the relay, so a late-opening channel ends the fallback.
+    pub fn on_data_channel_open(&mut self, peer: PeerId) {
+        self.relayed_peers.lock().unwrap().remove(&peer);
+        self.set_data_channel_state(peer, ConnectionState::Connected);
+    }
+
+    /// Called when WebRTC negotiation gives up: every expected peer still
+    /// without an open data channel is switched to a TURN relay.
+    /// Returns the peers that were switched.
+    pub fn on_negotiation_timeout(&mut self) -> Vec<PeerId> {
+        let stalled: Vec<PeerId> = {
+            let expected = self.expected_peers.lock().unwrap();
+            let channels = self.data_channels.lock().unwrap();
+            let mut stalled: Vec<PeerId> = expected
+                .iter()
+                .filter(|peer| {
+                    !channels
+                        .get(peer)
+                        .is_some_and(|channel| channel.state == ConnectionState::Connected)
+                })
+                .copied()
+                .collect();
+            stalled.sort();
+            stalled
+        };
+        for peer in &stalled {
+            println!("  🛰️ No data channel {} → {}; relaying via TURN", self.local_peer, peer);
+        }
+        self.relayed_peers.lock().unwrap().extend(stalled.iter().copied());
+        self.publish_readiness();
+        stalled
+    }
+
+    fn set_data_channel_state(&self, peer: PeerId, state: ConnectionState) {
+        if let Some(channel) = self.data_channels.lock().unwrap().get_mut(&peer) {
+            channel.state = state;
+        }
+        self.publish_readiness();
+    }
+
+    /// Publishes an event if readiness changed since the last call
+    fn publish_readiness(&self) {
+        let ready = self.is_full_mesh_ready();
+        let mut last = self.mesh_ready.lock().unwrap();
+        if *last != ready {
+            *last = ready;
+            let event = if ready {
+                MeshTopologyEvent::FullyConnected
+            } else {
+                MeshTopologyEvent::Degraded
+            };
+            // No subscribers is fine
+            let _ = self.mesh_events.send(event);
         }
     }
 
@@ -165,6 +290,9 @@ impl WebRTCMeshManager {
     pub async fn establish_mesh(&mut self, peers: Vec<PeerId>) -> Result<(), String> {
         println!("🌐 Establishing WebRTC mesh for peer {}", self.local_peer);
         
+        self.expected_peers.lock().unwrap().extend(
+            peers.iter().copied().filter(|peer| *peer != self.local_peer),
+        );
         for peer in peers {
             if peer != self.local_peer {
                 self.connect_to_peer(peer).await?;
@@ -207,7 +335,7 @@ impl WebRTCMeshManager {
         connection.state = ConnectionState::Connected;
         
         // Create data channels
-        let _reliable = connection.create_data_channel("reliable", true, true);
+        let reliable = connection.create_data_channel("reliable", true, true);
         let _unreliable = connection.create_data_channel("unreliable", false, false);
         
         // Update connection state
@@ -216,6 +344,9 @@ impl WebRTCMeshManager {
         // Store connection
         self.connections.lock().unwrap().insert(peer, connection.clone());
         self.connection_states.lock().unwrap().insert(peer, ConnectionState::Connected);
+        // The channel stays `Connecting` until `on_data_channel_open`
+        self.data_channels.lock().unwrap().insert(peer, reliable);
+        self.publish_readiness();
         
         // Update topology
         self.mesh_topology.lock().unwrap().add_connection(self.local_peer, peer);
@@ -230,6 +361,7 @@ impl WebRTCMeshManager {
         
         // Update connection state
         self.connection_states.lock().unwrap().insert(peer, ConnectionState::Disconnected);
+        self.set_data_channel_state(peer, ConnectionState::Disconnected);
         
         // Remove from topology
         self.mesh_topology.lock().unwrap().remove_connection(self.local_peer, peer);
@@ -258,6 +390,20 @@ impl WebRTCMeshManager {
         Ok(())
     }
 
+    /// Marks a peer whose connection recovered in place (e.g. after an ICE
+    /// restart) as connected again, reopening its data channel
+    pub fn restore_peer(&mut self, peer: PeerId) {
+        println!("  ♻️ Connection {} ↔ {} restored", self.local_peer, peer);
+
+        self.connection_states.lock().unwrap().insert(peer, ConnectionState::Connected);
+        self.mesh_topology.lock().unwrap().add_connection(self.local_peer, peer);
+        self.set_data_channel_state(peer, ConnectionState::Connected);
+
+        if let Some(messages) = self.message_buffer.lock().unwrap().remove(&peer) {
+            println!("  📤 Sending {} buffered messages to {}", messages.len(), peer);
+        }
+    }
+
     /// Gets list of connected peers
     pub fn get_connected_peers(&self) -> Vec<PeerId> {
         self.connection_states.lock().unwrap()
@@ -272,8 +418,13 @@ impl WebRTCMeshManager {
         self.mesh_topology.lock().unwrap().meets_threshold()
     }
 
-    /// Sends a message to a peer
+    /// Sends a message to a peer over its current transport
     pub fn send_message(&self, to: PeerId, message: Vec<u8>) -> Result<(), String> {
+        if self.transport_for(to) == PeerTransport::Relay {
+            println!("  🛰️ Relaying message from {} to {} via TURN", self.local_peer, to);
+            return Ok(());
+        }
+
         let states = self.connection_states.lock().unwrap();
         
         match states.get(&to) {
@@ -311,6 +462,7 @@ impl WebRTCMeshManager {
         
         for peer in peers {
             self.connection_states.lock().unwrap().insert(peer, ConnectionState::Failed("Network failure".to_string()));
+            self.set_data_channel_state(peer, ConnectionState::Failed("Network failure".to_string()));
             self.mesh_topology.lock().unwrap().remove_connection(self.local_peer, peer);
         }
     }
@@ -5,9 +5,10 @@ use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use serde::{Serialize, Deserialize};
 
-use super::mesh_manager::{WebRTCMeshManager, PeerId};
-use super::connection_monitor::ConnectionMonitor;
+use super::mesh_manager::{WebRTCMeshManager, PeerId, PeerTransport};
+use super::connection_monitor::{ConnectionMonitor, ReconnectStatus};
 use super::rejoin_coordinator::RejoinCoordinator;
+use super::preflight::PreflightReport;
 
 /// Network condition for simulation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +21,9 @@ pub enum NetworkCondition {
     Failed,
     /// Intermittent connectivity
     Intermittent { up_time: Duration, down_time: Duration },
+    /// No ICE candidate pair ever connects (symmetric NAT, UDP blocked),
+    /// but a TURN server is still reachable
+    DirectBlocked,
 }
 
 /// Simulation event
@@ -41,6 +45,10 @@ pub enum SimulationEvent {
     MessageSent { from: PeerId, to: PeerId, size: usize },
     /// Rejoin attempt
     RejoinAttempt(PeerId),
+    /// Data channel from the first peer to the second opens
+    DataChannelOpen(PeerId, PeerId),
+    /// The peer's WebRTC negotiation timer fires
+    NegotiationTimeout(PeerId),
 }
 
 /// Simulation scenario
@@ -238,6 +246,19 @@ impl MeshSimulator {
                 self.log_event(format!("🔄 Peer {} attempting to rejoin", peer));
                 self.handle_rejoin_attempt(peer).await;
             }
+            SimulationEvent::DataChannelOpen(local, remote) => {
+                self.log_event(format!("📂 Data channel open: {} → {}", local, remote));
+                if let Some(manager) = self.managers.get(&local) {
+                    manager.lock().unwrap().on_data_channel_open(remote);
+                }
+            }
+            SimulationEvent::NegotiationTimeout(peer) => {
+                let relayed = match self.managers.get(&peer) {
+                    Some(manager) => manager.lock().unwrap().on_negotiation_timeout(),
+                    None => Vec::new(),
+                };
+                self.log_event(format!("⏱️ Negotiation timed out for peer {}; relaying to {:?}", peer, relayed));
+            }
         }
     }
 
@@ -320,11 +341,79 @@ impl MeshSimulator {
             NetworkCondition::Intermittent { .. } => {
                 // Could implement periodic up/down simulation
             }
+            NetworkCondition::DirectBlocked => {
+                // Data channels to this peer simply never open
+            }
+        }
+    }
+
+    /// Re-negotiates `local`'s connection to `remote` with the monitor's
+    /// backoff. An attempt succeeds once neither side's network is `Failed`.
+    pub async fn reconnect_peer(&self, local: PeerId, remote: PeerId) -> ReconnectStatus {
+        let Some(monitor) = self.monitors.get(&local) else {
+            return ReconnectStatus::Failed { attempts: 0 };
+        };
+
+        let conditions = self.network_conditions.clone();
+        let status = monitor.reconnect_peer(
+            remote,
+            |_attempt| {
+                let conditions = conditions.lock().unwrap();
+                let reachable = [local, remote]
+                    .iter()
+                    .all(|peer| !matches!(conditions.get(peer), Some(NetworkCondition::Failed)));
+                std::future::ready(reachable)
+            },
+            |status| self.log_event(format!("🔁 Reconnect {} → {}: {:?}", local, remote, status)),
+        ).await;
+
+        if let ReconnectStatus::Reconnected { .. } = status
+            && let Some(manager) = self.managers.get(&local)
+        {
+            manager.lock().unwrap().restore_peer(remote);
+        }
+        status
+    }
+
+    /// Negotiates every pair under perfect negotiation: only the lower id
+    /// offers, and the channel opens on both ends unless either side has
+    /// `Failed` or blocks direct connections.
+    pub async fn negotiate_mesh(&mut self) {
+        let mut peers: Vec<PeerId> = self.managers.keys().copied().collect();
+        peers.sort();
+        let conditions = self.network_conditions.lock().unwrap().clone();
+        let direct = |peer: &PeerId| {
+            !matches!(conditions.get(peer), Some(NetworkCondition::Failed | NetworkCondition::DirectBlocked))
+        };
+        for (i, offerer) in peers.iter().enumerate() {
+            for answerer in peers[i + 1..].iter() {
+                if direct(offerer) && direct(answerer) {
+                    self.handle_event(SimulationEvent::DataChannelOpen(*offerer, *answerer)).await;
+                    self.handle_event(SimulationEvent::DataChannelOpen(*answerer, *offerer)).await;
+                }
+            }
         }
     }
 
-    /// Handles DKG start
-    async fn handle_dkg_start(&mut self) {
+    /// Runs a DKG preflight from `local`: peers whose network has `Failed`
+    /// never registered, and the rest need an open data channel, whichever
+    /// side offered it. The preflight itself opens nothing.
+    pub async fn preflight(&mut self, local: PeerId) -> PreflightReport<PeerId> {
+        let mut peers: Vec<PeerId> = self.managers.keys().copied().collect();
+        peers.sort();
+        let conditions = self.network_conditions.lock().unwrap().clone();
+        let online = |peer: &PeerId| !matches!(conditions.get(peer), Some(NetworkCondition::Failed));
+
+        let manager = self.managers[&local].clone();
+        let manager = manager.lock().unwrap();
+        let report = PreflightReport::new(&peers, &local, online, |peer| manager.has_open_data_channel(*peer));
+        self.log_event(format!("🩺 Preflight from peer {}: {}", local, report));
+        report
+    }
+
+    /// Handles DKG start. Returns true if every peer received every other
+    /// peer's package.
+    async fn handle_dkg_start(&mut self) -> bool {
         {
             let coordinator = self.rejoin_coordinator.lock().unwrap();
             coordinator.advance_round();
@@ -332,13 +421,22 @@ impl MeshSimulator {
 
         // Simulate DKG message exchange
         let peers: Vec<PeerId> = self.managers.keys().copied().collect();
+        let expected = peers.len() * peers.len().saturating_sub(1);
+        let mut delivered = 0;
         for from in &peers {
             for to in &peers {
-                if from != to {
-                    self.handle_message_sent(*from, *to, 256).await;
+                if from != to && self.handle_message_sent(*from, *to, 256).await {
+                    delivered += 1;
                 }
             }
         }
+
+        if delivered == expected {
+            self.log_event("✅ DKG packages delivered to every peer".to_string());
+        } else {
+            self.log_event(format!("❌ DKG incomplete: {}/{} packages delivered", delivered, expected));
+        }
+        delivered == expected
     }
 
     /// Handles signing start
@@ -356,8 +454,11 @@ impl MeshSimulator {
         }
     }
 
-    /// Handles message sent
-    async fn handle_message_sent(&mut self, from: PeerId, to: PeerId, size: usize) {
+    /// Handles message sent. Returns whether the message was delivered.
+    async fn handle_message_sent(&mut self, from: PeerId, to: PeerId, size: usize) -> bool {
+        let relayed = self.managers.get(&from)
+            .is_some_and(|manager| manager.lock().unwrap().transport_for(to) == PeerTransport::Relay);
+
         // Check network conditions
         let conditions = self.network_conditions.lock().unwrap();
         
@@ -367,6 +468,9 @@ impl MeshSimulator {
 
         let delivered = match (&from_condition, &to_condition) {
             (NetworkCondition::Failed, _) | (_, NetworkCondition::Failed) => false,
+            // Both ends still reach the TURN server
+            _ if relayed => true,
+            (NetworkCondition::DirectBlocked, _) | (_, NetworkCondition::DirectBlocked) => false,
             (NetworkCondition::Degraded { packet_loss, .. }, _) => {
                 // Simulation-only random (not security-sensitive). rand 0.10's
                 // convenience API: `random_range` on the thread-local CSPRNG.
@@ -380,6 +484,7 @@ impl MeshSimulator {
             let coordinator = self.rejoin_coordinator.lock().unwrap();
             coordinator.record_message(from, 1, "SIMULATION", vec![0; size]);
         }
+        delivered
     }
 
     /// Handles rejoin attempt
@@ -478,6 +583,8 @@ impl MeshSimulator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::webrtc::connection_monitor::ReconnectPolicy;
+    use crate::webrtc::mesh_manager::MeshTopologyEvent;
 
     #[tokio::test]
     async fn test_basic_mesh_simulation() {
@@ -486,4 +593,136 @@ mod tests {
         simulator.run_scenario(scenario).await;
     }
 
-}
\ No newline at end of file
+    #[tokio::test]
+    async fn test_full_mesh_ready_after_last_channel_opens() {
+        let mut simulator = MeshSimulator::new(vec![1, 2, 3], 2);
+        for peer in [1, 2, 3] {
+            simulator.handle_event(SimulationEvent::PeerJoin(peer)).await;
+        }
+
+        let manager = simulator.managers[&1].clone();
+        let mut events = manager.lock().unwrap().subscribe_mesh_events();
+        assert!(!manager.lock().unwrap().is_full_mesh_ready());
+
+        simulator.handle_event(SimulationEvent::DataChannelOpen(1, 2)).await;
+        assert!(!manager.lock().unwrap().is_full_mesh_ready());
+        assert!(events.try_recv().is_err());
+
+        simulator.handle_event(SimulationEvent::DataChannelOpen(1, 3)).await;
+        assert!(manager.lock().unwrap().is_full_mesh_ready());
+        assert_eq!(events.try_recv().unwrap(), MeshTopologyEvent::FullyConnected);
+
+        simulator.handle_event(SimulationEvent::PeerCrash(3)).await;
+        assert!(!manager.lock().unwrap().is_full_mesh_ready());
+        assert_eq!(events.try_recv().unwrap(), MeshTopologyEvent::Degraded);
+    }
+
+    fn fast_reconnect_monitor() -> Arc<ConnectionMonitor> {
+        Arc::new(ConnectionMonitor {
+            reconnect_policy: ReconnectPolicy {
+                initial_delay: Duration::from_millis(10),
+                max_delay: Duration::from_millis(40),
+                max_attempts: 5,
+            },
+            ..ConnectionMonitor::new()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_after_network_restored() {
+        let mut simulator = MeshSimulator::new(vec![1, 2], 2);
+        simulator.monitors.insert(1, fast_reconnect_monitor());
+        simulator.handle_event(SimulationEvent::PeerJoin(1)).await;
+        simulator.handle_event(SimulationEvent::DataChannelOpen(1, 2)).await;
+
+        simulator.handle_event(SimulationEvent::NetworkChange(1, NetworkCondition::Failed)).await;
+        assert!(!simulator.managers[&1].lock().unwrap().is_full_mesh_ready());
+
+        // Network comes back between the first (10ms) and second (30ms) attempt
+        let conditions = simulator.network_conditions.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            conditions.lock().unwrap().insert(1, NetworkCondition::Perfect);
+        });
+
+        let status = simulator.reconnect_peer(1, 2).await;
+        assert_eq!(status, ReconnectStatus::Reconnected { attempts: 2 });
+        assert!(simulator.managers[&1].lock().unwrap().is_full_mesh_ready());
+    }
+
+    #[tokio::test]
+    async fn test_dkg_completes_via_relay_when_peer_unreachable_over_webrtc() {
+        let mut simulator = MeshSimulator::new(vec![1, 2, 3], 2);
+        simulator.handle_event(SimulationEvent::NetworkChange(3, NetworkCondition::DirectBlocked)).await;
+        for peer in [1, 2, 3] {
+            simulator.handle_event(SimulationEvent::PeerJoin(peer)).await;
+        }
+        // Only the 1 ↔ 2 channel ever opens
+        simulator.handle_event(SimulationEvent::DataChannelOpen(1, 2)).await;
+        simulator.handle_event(SimulationEvent::DataChannelOpen(2, 1)).await;
+        assert!(!simulator.handle_dkg_start().await);
+
+        for peer in [1, 2, 3] {
+            simulator.handle_event(SimulationEvent::NegotiationTimeout(peer)).await;
+        }
+        for peer in [1, 2, 3] {
+            assert!(simulator.managers[&peer].lock().unwrap().is_full_mesh_ready());
+        }
+        let manager = simulator.managers[&1].clone();
+        assert_eq!(manager.lock().unwrap().transport_for(2), PeerTransport::WebRtc);
+        assert_eq!(manager.lock().unwrap().transport_for(3), PeerTransport::Relay);
+        assert_eq!(simulator.managers[&3].lock().unwrap().transport_for(1), PeerTransport::Relay);
+
+        assert!(simulator.handle_dkg_start().await);
+    }
+
+    #[tokio::test]
+    async fn test_preflight_reports_peer_without_data_channel() {
+        use crate::webrtc::preflight::PreflightStatus;
+
+        let mut simulator = MeshSimulator::new(vec![1, 2, 3], 2);
+        for peer in [1, 2, 3] {
+            simulator.handle_event(SimulationEvent::PeerJoin(peer)).await;
+        }
+        simulator.handle_event(SimulationEvent::NetworkChange(3, NetworkCondition::DirectBlocked)).await;
+
+        // The preflight probes channels but never opens one
+        let report = simulator.preflight(2).await;
+        assert_eq!(
+            report.results,
+            vec![(1, PreflightStatus::NoDataChannel), (3, PreflightStatus::NoDataChannel)]
+        );
+
+        simulator.negotiate_mesh().await;
+        let report = simulator.preflight(1).await;
+        assert_eq!(
+            report.results,
+            vec![(2, PreflightStatus::Reachable), (3, PreflightStatus::NoDataChannel)]
+        );
+        assert!(!report.is_ready());
+        assert_eq!(report.unreachable(), vec![&3]);
+        assert_eq!(report.to_string(), "2 ✅ reachable, 3 ❌ no data channel");
+
+        // Peer 2 never offers to peer 1, yet reaches it over 1's channel
+        let report = simulator.preflight(2).await;
+        assert_eq!(
+            report.results,
+            vec![(1, PreflightStatus::Reachable), (3, PreflightStatus::NoDataChannel)]
+        );
+
+        // No DKG round ran
+        let coordinator = simulator.rejoin_coordinator.lock().unwrap();
+        assert_eq!(coordinator.session_state.lock().unwrap().current_round, 0);
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_gives_up_after_max_attempts() {
+        let mut simulator = MeshSimulator::new(vec![1, 2], 2);
+        simulator.monitors.insert(1, fast_reconnect_monitor());
+        simulator.handle_event(SimulationEvent::PeerJoin(1)).await;
+        simulator.handle_event(SimulationEvent::NetworkChange(2, NetworkCondition::Failed)).await;
+
+        let status = simulator.reconnect_peer(1, 2).await;
+        assert_eq!(status, ReconnectStatus::Failed { attempts: 5 });
+    }
+}
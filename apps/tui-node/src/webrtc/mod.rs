@@ -4,8 +4,10 @@ pub mod mesh_manager;
 pub mod connection_monitor;
 pub mod rejoin_coordinator;
 pub mod mesh_simulator;
+pub mod preflight;
 
-pub use mesh_manager::{WebRTCMeshManager, MeshTopology, ConnectionState};
-pub use connection_monitor::{ConnectionMonitor, ConnectionQuality};
+pub use mesh_manager::{WebRTCMeshManager, MeshTopology, MeshTopologyEvent, ConnectionState, PeerTransport};
+pub use connection_monitor::{ConnectionMonitor, ConnectionQuality, ReconnectPolicy, ReconnectStatus, reconnect_with_backoff};
 pub use rejoin_coordinator::{RejoinCoordinator, RejoinRequest, SessionState};
-pub use mesh_simulator::{MeshSimulator, NetworkCondition, SimulationEvent, SimulationScenario};
\ No newline at end of file
+pub use mesh_simulator::{MeshSimulator, NetworkCondition, SimulationEvent, SimulationScenario};
+pub use preflight::{PreflightReport, PreflightStatus};
\ No newline at end of file
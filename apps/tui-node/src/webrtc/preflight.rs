@@ -0,0 +1,79 @@
+//! DKG preflight: confirm every participant is online and reachable over a
+//! data channel before any key material is generated
+
+use std::fmt;
+
+/// Preflight outcome for one participant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreflightStatus {
+    /// A data channel to the participant is open
+    Reachable,
+    /// Missing from the signal server's device list
+    Offline,
+    /// Registered, but no data channel in either direction opened before the
+    /// preflight gave up
+    NoDataChannel,
+}
+
+/// Per-participant preflight results, in the order the participants were given
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreflightReport<P> {
+    pub results: Vec<(P, PreflightStatus)>,
+}
+
+impl<P: Clone + PartialEq> PreflightReport<P> {
+    /// Classifies every participant except `local`
+    pub fn new(
+        participants: &[P],
+        local: &P,
+        is_online: impl Fn(&P) -> bool,
+        has_data_channel: impl Fn(&P) -> bool,
+    ) -> Self {
+        let results = participants
+            .iter()
+            .filter(|p| *p != local)
+            .map(|p| {
+                let status = if !is_online(p) {
+                    PreflightStatus::Offline
+                } else if !has_data_channel(p) {
+                    PreflightStatus::NoDataChannel
+                } else {
+                    PreflightStatus::Reachable
+                };
+                (p.clone(), status)
+            })
+            .collect();
+        Self { results }
+    }
+
+    /// True when the DKG can start without waiting on anyone
+    pub fn is_ready(&self) -> bool {
+        self.results.iter().all(|(_, status)| *status == PreflightStatus::Reachable)
+    }
+
+    /// Participants that failed the preflight
+    pub fn unreachable(&self) -> Vec<&P> {
+        self.results
+            .iter()
+            .filter(|(_, status)| *status != PreflightStatus::Reachable)
+            .map(|(p, _)| p)
+            .collect()
+    }
+}
+
+impl<P: fmt::Display> fmt::Display for PreflightReport<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (participant, status)) in self.results.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            let status = match status {
+                PreflightStatus::Reachable => "✅ reachable",
+                PreflightStatus::Offline => "❌ offline",
+                PreflightStatus::NoDataChannel => "❌ no data channel",
+            };
+            write!(f, "{} {}", participant, status)?;
+        }
+        Ok(())
+    }
+}
@@ -257,7 +257,7 @@ impl RejoinCoordinator {
         let mut buffers = self.message_buffers.lock().unwrap();
         
         // Add to all peer buffers except sender
-        let session = self.session_state.lock().unwrap();
+        let mut session = self.session_state.lock().unwrap();
         for peer in &session.participants {
             if *peer != from {
                 buffers.entry(*peer)
@@ -267,7 +267,6 @@ impl RejoinCoordinator {
         }
 
         // Increment message count
-        let mut session = self.session_state.lock().unwrap();
         session.message_count += 1;
     }
 
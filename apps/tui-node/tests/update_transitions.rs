@@ -255,3 +255,46 @@ fn submit_password_stashes_value_and_advances_to_dkg_progress() {
         model.current_screen
     );
 }
+
+// -----------------------------------------------------------------
+// WebSocket drop mid-DKG — reconnect must resume, not just redial
+// -----------------------------------------------------------------
+#[test]
+fn websocket_drop_mid_dkg_reconnects_with_resume_dkg() {
+    let mut model = fresh_model();
+    model.wallet_state.dkg_in_progress = true;
+    model.wallet_state.dkg_round = DKGRound::Round2;
+    model.active_session = Some(tui_node::protocal::signal::SessionInfo {
+        session_id: "dkg_resume".to_string(),
+        proposer_id: "test-device".to_string(),
+        total: 3,
+        threshold: 2,
+        participants: vec!["test-device".to_string(), "peer-alice".to_string(), "peer-bob".to_string()],
+        session_type: tui_node::protocal::signal::SessionType::DKG,
+        curve_type: "unified".to_string(),
+        coordination_type: "Network".to_string(),
+        participant_indices: Default::default(),
+    });
+
+    // The drop schedules a reconnect...
+    let cmd = update(&mut model, Message::WebSocketDisconnected);
+    let scheduled = match cmd {
+        Some(Command::ScheduleMessage { message, .. }) => *message,
+        other => panic!("expected a scheduled reconnect, got {:?}", other),
+    };
+    assert!(matches!(scheduled, Message::TriggerReconnect));
+
+    // ...which resumes the same session instead of a bare redial
+    match update(&mut model, scheduled) {
+        Some(Command::ResumeDKG { session_id }) => assert_eq!(session_id, "dkg_resume"),
+        other => panic!("expected ResumeDKG, got {:?}", other),
+    }
+
+    // Once connected and outside a DKG, reconnects are plain again
+    let _ = update(&mut model, Message::WebSocketConnected);
+    model.wallet_state.dkg_in_progress = false;
+    assert!(matches!(
+        update(&mut model, Message::TriggerReconnect),
+        Some(Command::ReconnectWebSocket)
+    ));
+}
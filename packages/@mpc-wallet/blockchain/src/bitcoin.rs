@@ -1,29 +1,230 @@
 //! Bitcoin blockchain handler implementation
 
-use super::{BlockchainHandler, ParsedTransaction, SignatureData, Result, BlockchainError};
+use super::{
+    BlockchainHandler, ParsedTransaction, SignatureData, SignatureFormat, Result, BlockchainError,
+    normalize_ecdsa_signature,
+};
+use ::bitcoin::{Address, Amount, CompressedPublicKey, Denomination, Network, ScriptBuf, Transaction, TxOut};
+use ::bitcoin::key::{Secp256k1, XOnlyPublicKey};
+use ::bitcoin::psbt::Psbt;
 
 pub struct BitcoinHandler {
     network: BitcoinNetwork,
+    /// Fee rates above this (sat/vB) set `fee_warning` in the metadata
+    max_fee_rate: f64,
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Far above any fee market so far; hitting it almost always means a
+/// missing or mistyped change output
+pub const DEFAULT_MAX_FEE_RATE: f64 = 1000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BitcoinNetwork {
     Mainnet,
     Testnet,
 }
 
+/// Script type a wallet receives to, i.e. the address format it shows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BitcoinAddressType {
+    /// Native SegWit v0, bech32 (`bc1q...`)
+    #[default]
+    P2wpkh,
+    /// Taproot key path with no script tree (BIP-86), bech32m (`bc1p...`)
+    P2tr,
+    /// SegWit v0 wrapped in P2SH for older senders (`3...`)
+    P2shP2wpkh,
+}
+
+impl BitcoinAddressType {
+    /// The name stored as a wallet's address format
+    pub fn name(&self) -> &'static str {
+        match self {
+            BitcoinAddressType::P2wpkh => "P2WPKH",
+            BitcoinAddressType::P2tr => "P2TR",
+            BitcoinAddressType::P2shP2wpkh => "P2SH-P2WPKH",
+        }
+    }
+}
+
+impl std::str::FromStr for BitcoinAddressType {
+    type Err = BlockchainError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_uppercase().replace('_', "-").as_str() {
+            "P2WPKH" => Ok(BitcoinAddressType::P2wpkh),
+            "P2TR" => Ok(BitcoinAddressType::P2tr),
+            "P2SH-P2WPKH" => Ok(BitcoinAddressType::P2shP2wpkh),
+            _ => Err(BlockchainError::ParseError(format!("Unknown Bitcoin address type '{}'", s))),
+        }
+    }
+}
+
 impl BitcoinHandler {
     pub fn new() -> Self {
         Self {
             network: BitcoinNetwork::Mainnet,
+            max_fee_rate: DEFAULT_MAX_FEE_RATE,
         }
     }
     
     pub fn new_testnet() -> Self {
         Self {
             network: BitcoinNetwork::Testnet,
+            max_fee_rate: DEFAULT_MAX_FEE_RATE,
         }
     }
+
+    /// Warn above `sat_per_vbyte` instead of [`DEFAULT_MAX_FEE_RATE`]
+    pub fn with_max_fee_rate(mut self, sat_per_vbyte: f64) -> Self {
+        self.max_fee_rate = sat_per_vbyte;
+        self
+    }
+
+    /// Address of `address_type` on this handler's network for a 33-byte
+    /// compressed public key. P2TR commits to the BIP-86 tweaked key, so
+    /// only a signer that applies the same tweak can spend from it.
+    pub fn derive_address(&self, pubkey_bytes: &[u8], address_type: BitcoinAddressType) -> Result<String> {
+        let pubkey = CompressedPublicKey::from_slice(pubkey_bytes)
+            .map_err(|e| BlockchainError::ParseError(format!("Invalid compressed public key: {}", e)))?;
+        let network = self.address_network();
+        let address = match address_type {
+            BitcoinAddressType::P2wpkh => Address::p2wpkh(&pubkey, network),
+            BitcoinAddressType::P2shP2wpkh => Address::p2shwpkh(&pubkey, network),
+            BitcoinAddressType::P2tr => {
+                Address::p2tr(&Secp256k1::verification_only(), XOnlyPublicKey::from(pubkey.0), None, network)
+            }
+        };
+        Ok(address.to_string())
+    }
+
+    fn address_network(&self) -> Network {
+        match self.network {
+            BitcoinNetwork::Mainnet => Network::Bitcoin,
+            BitcoinNetwork::Testnet => Network::Testnet,
+        }
+    }
+
+    /// Decodes a hex PSBT (`70736274ff...`) or raw transaction into its
+    /// unsigned transaction plus whatever the PSBT knows about inputs/outputs
+    fn decode(raw_bytes: &[u8]) -> Result<DecodedTx> {
+        if raw_bytes.starts_with(PSBT_MAGIC) {
+            let psbt = Psbt::deserialize(raw_bytes)
+                .map_err(|e| BlockchainError::ParseError(format!("Invalid PSBT: {}", e)))?;
+            let spent: Vec<Option<&TxOut>> = psbt.inputs.iter().zip(&psbt.unsigned_tx.input)
+                .map(|(input, txin)| match (&input.witness_utxo, &input.non_witness_utxo) {
+                    (Some(utxo), _) => Some(utxo),
+                    (None, Some(prev)) => prev.output.get(txin.previous_output.vout as usize),
+                    (None, None) => None,
+                })
+                .collect();
+            let input_values = spent.iter().map(|utxo| utxo.map(|o| o.value)).collect();
+            let input_scripts = spent.iter().map(|utxo| utxo.map(|o| o.script_pubkey.clone())).collect();
+            // Outputs the PSBT gives a key origin for claim to go back to the
+            // wallet; only the signer can check the claim against its own key
+            let key_origins: Vec<Vec<KeyOrigin>> = psbt.outputs.iter()
+                .map(|output| {
+                    let bip32 = output.bip32_derivation.iter()
+                        .map(|(key, (_, path))| KeyOrigin { pubkey: key.serialize().to_vec(), path: path.to_string() });
+                    let taproot = output.tap_key_origins.iter()
+                        .map(|(key, (_, (_, path)))| KeyOrigin { pubkey: key.serialize().to_vec(), path: path.to_string() });
+                    bip32.chain(taproot).collect()
+                })
+                .collect();
+            let change = key_origins.iter().map(|origins| !origins.is_empty()).collect();
+            return Ok(DecodedTx { format: "psbt", tx: psbt.unsigned_tx, input_values, input_scripts, change, key_origins });
+        }
+
+        let tx: Transaction = ::bitcoin::consensus::deserialize(raw_bytes)
+            .map_err(|e| BlockchainError::ParseError(format!("Invalid transaction: {}", e)))?;
+        Ok(DecodedTx {
+            format: "raw",
+            // A raw transaction does not carry the values it spends
+            input_values: vec![None; tx.input.len()],
+            input_scripts: vec![None; tx.input.len()],
+            change: vec![false; tx.output.len()],
+            key_origins: vec![Vec::new(); tx.output.len()],
+            tx,
+        })
+    }
+
+    fn output_address(&self, output: &TxOut) -> Option<String> {
+        Address::from_script(&output.script_pubkey, self.address_network())
+            .ok()
+            .map(|address| address.to_string())
+    }
+}
+
+const PSBT_MAGIC: &[u8] = b"psbt\xff";
+
+struct DecodedTx {
+    format: &'static str,
+    tx: Transaction,
+    /// Value of each input's previous output, where known
+    input_values: Vec<Option<Amount>>,
+    /// Script of each input's previous output, where known
+    input_scripts: Vec<Option<ScriptBuf>>,
+    /// Whether each output claims to be change
+    change: Vec<bool>,
+    /// Keys each output claims to pay, with their derivation paths
+    key_origins: Vec<Vec<KeyOrigin>>,
+}
+
+/// A key and derivation path from a PSBT output's `bip32_derivation` or
+/// `tap_key_origins`: 33-byte compressed or 32-byte x-only
+#[derive(Debug, Clone)]
+struct KeyOrigin {
+    pubkey: Vec<u8>,
+    path: String,
+}
+
+impl DecodedTx {
+    /// Inputs minus outputs, if every input value is known
+    fn fee(&self) -> Option<Amount> {
+        let inputs = self.input_values.iter()
+            .try_fold(Amount::ZERO, |total, value| total.checked_add((*value)?))?;
+        let outputs = self.tx.output.iter()
+            .try_fold(Amount::ZERO, |total, output| total.checked_add(output.value))?;
+        inputs.checked_sub(outputs)
+    }
+
+    /// Virtual size once signed. Unsigned inputs get the signature data their
+    /// script type will need, so a PSBT isn't rated on its bare skeleton;
+    /// witness bytes count a quarter, as the segwit discount has it.
+    fn estimated_vsize(&self) -> u64 {
+        let mut weight = self.tx.weight().to_wu();
+        let mut adds_witness = false;
+        for (txin, script) in self.tx.input.iter().zip(&self.input_scripts) {
+            let Some(script) = script else { continue };
+            if !txin.script_sig.is_empty() || !txin.witness.is_empty() {
+                continue;
+            }
+            if script.is_p2wpkh() {
+                // 72-byte DER signature and 33-byte key, each length-prefixed
+                weight += P2WPKH_WITNESS_WEIGHT;
+                adds_witness = true;
+            } else if script.is_p2tr() {
+                // 64-byte Schnorr signature, length-prefixed
+                weight += P2TR_KEY_SPEND_WITNESS_WEIGHT;
+                adds_witness = true;
+            } else if script.is_p2pkh() {
+                weight += P2PKH_SCRIPT_SIG_LEN * 4;
+            }
+        }
+        if adds_witness && self.tx.input.iter().all(|txin| txin.witness.is_empty()) {
+            // Segwit marker and flag, plus a witness item count per input
+            weight += 2 + self.tx.input.len() as u64;
+        }
+        weight.div_ceil(4)
+    }
+}
+
+const P2WPKH_WITNESS_WEIGHT: u64 = 1 + 72 + 1 + 33;
+const P2TR_KEY_SPEND_WITNESS_WEIGHT: u64 = 1 + 64;
+const P2PKH_SCRIPT_SIG_LEN: u64 = 1 + 72 + 1 + 33;
+
+fn format_btc(amount: Amount) -> String {
+    amount.display_in(Denomination::Bitcoin).to_string()
 }
 
 impl BlockchainHandler for BitcoinHandler {
@@ -48,12 +249,32 @@ impl BlockchainHandler for BitcoinHandler {
                 format!("Invalid hex transaction: {}", e)
             ))?;
         
-        // Calculate transaction ID (double SHA256, reversed)
-        use sha2::{Digest, Sha256};
-        let first_hash = Sha256::digest(&raw_bytes);
-        let second_hash = Sha256::digest(first_hash);
-        let mut tx_id = second_hash.to_vec();
-        tx_id.reverse(); // Bitcoin displays tx IDs in reverse byte order
+        let decoded = Self::decode(&raw_bytes)?;
+        let fee = decoded.fee();
+        let vsize = decoded.estimated_vsize();
+        let fee_rate = fee.map(|fee| fee.to_sat() as f64 / vsize as f64);
+        let fee_warning = fee_rate.is_some_and(|rate| rate > self.max_fee_rate);
+        
+        let inputs: Vec<_> = decoded.tx.input.iter().zip(&decoded.input_values)
+            .map(|(input, value)| serde_json::json!({
+                "txid": input.previous_output.txid.to_string(),
+                "vout": input.previous_output.vout,
+                "value_sat": value.map(Amount::to_sat),
+            }))
+            .collect();
+        // `change` is only the PSBT's claim; a signing policy re-derives
+        // `key_origins` from the wallet key before trusting it
+        let outputs: Vec<_> = decoded.tx.output.iter().zip(&decoded.change).zip(&decoded.key_origins)
+            .map(|((output, change), origins)| serde_json::json!({
+                "address": self.output_address(output),
+                "script_pubkey": hex::encode(output.script_pubkey.as_bytes()),
+                "value_sat": output.value.to_sat(),
+                "change": change,
+                "key_origins": origins.iter()
+                    .map(|origin| serde_json::json!({ "pubkey": hex::encode(&origin.pubkey), "path": origin.path }))
+                    .collect::<Vec<_>>(),
+            }))
+            .collect();
         
         let metadata = serde_json::json!({
             "network": match self.network {
@@ -61,20 +282,42 @@ impl BlockchainHandler for BitcoinHandler {
                 BitcoinNetwork::Testnet => "testnet",
             },
             "size": raw_bytes.len(),
+            "format": decoded.format,
+            "inputs": inputs,
+            "outputs": outputs,
+            "fee_sat": fee.map(Amount::to_sat),
+            "vsize": vsize,
+            "fee_rate_sat_vb": fee_rate.map(|rate| (rate * 100.0).round() / 100.0),
+            "fee_warning": fee_warning,
         });
         
-        let summary = format!(
-            "Bitcoin {} transaction (size: {} bytes)",
-            match self.network {
-                BitcoinNetwork::Mainnet => "mainnet",
-                BitcoinNetwork::Testnet => "testnet",
-            },
-            raw_bytes.len()
-        );
+        // Name the non-change outputs; a pure self-transfer lists everything
+        let mut recipients: Vec<_> = decoded.tx.output.iter().zip(&decoded.change)
+            .filter(|(_, change)| !**change)
+            .map(|(output, _)| output)
+            .collect();
+        if recipients.is_empty() {
+            recipients = decoded.tx.output.iter().collect();
+        }
+        let payments = recipients.iter()
+            .map(|output| format!(
+                "{} BTC to {}",
+                format_btc(output.value),
+                self.output_address(output).unwrap_or_else(|| "non-standard script".to_string())
+            ))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut summary = match fee {
+            Some(fee) => format!("Send {} (fee {} sat)", payments, fee.to_sat()),
+            None => format!("Send {} (fee unknown)", payments),
+        };
+        if let (true, Some(rate)) = (fee_warning, fee_rate) {
+            summary.push_str(&format!(" - WARNING: fee rate {:.0} sat/vB is unusually high", rate));
+        }
         
         Ok(ParsedTransaction {
             raw_bytes,
-            hash: hex::encode(tx_id),
+            hash: decoded.tx.compute_txid().to_string(),
             summary,
             chain_id: None,
             metadata,
@@ -93,33 +336,18 @@ impl BlockchainHandler for BitcoinHandler {
         Ok(second_hash.to_vec())
     }
     
-    fn serialize_signature(&self, signature_bytes: &[u8]) -> Result<SignatureData> {
-        // Bitcoin uses DER encoding for signatures
-        if signature_bytes.len() < 64 {
+    fn serialize_signature(&self, signature_bytes: &[u8], format: SignatureFormat) -> Result<SignatureData> {
+        // Legacy and SegWit v0 scripts verify DER-encoded ECDSA with low s
+        if format != SignatureFormat::Ecdsa {
             return Err(BlockchainError::SignatureError(
-                format!("Invalid signature length: expected at least 64 bytes, got {}", signature_bytes.len())
+                "Bitcoin scripts need an ECDSA signature".to_string()
             ));
         }
-        
-        // Extract r and s components (assuming 64 bytes total)
-        let r = &signature_bytes[..32];
-        let s = &signature_bytes[32..64];
-        
-        // Create DER encoding (simplified - use bitcoin crate in production)
-        let mut der = Vec::new();
-        der.push(0x30); // SEQUENCE
-        der.push(0x44); // Total length (68 bytes typical)
-        
-        // r component
-        der.push(0x02); // INTEGER
-        der.push(0x20); // Length (32 bytes)
-        der.extend_from_slice(r);
-        
-        // s component
-        der.push(0x02); // INTEGER
-        der.push(0x20); // Length (32 bytes)
-        der.extend_from_slice(s);
-        
+        let normalized = normalize_ecdsa_signature(signature_bytes)?;
+        let signature = k256::ecdsa::Signature::from_scalars(normalized.r, normalized.s)
+            .map_err(|e| BlockchainError::SignatureError(format!("Invalid ECDSA signature: {}", e)))?;
+
+        let mut der = signature.to_der().as_bytes().to_vec();
         // Add SIGHASH_ALL
         der.push(0x01);
         
@@ -136,4 +364,166 @@ impl BlockchainHandler for BitcoinHandler {
     fn get_tx_hash(&self, tx: &ParsedTransaction) -> String {
         tx.hash.clone()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::bitcoin::absolute::LockTime;
+    use ::bitcoin::bip32::{DerivationPath, Fingerprint};
+    use ::bitcoin::hashes::Hash;
+    use ::bitcoin::transaction::Version;
+    use ::bitcoin::{OutPoint, ScriptBuf, Sequence, TxIn, Txid, Witness};
+    use std::str::FromStr;
+
+    const RECIPIENT: &str = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+    const RECIPIENT_TESTNET: &str = "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx";
+    const CHANGE: &str = "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2";
+
+    fn script(address: &str) -> ScriptBuf {
+        Address::from_str(address).unwrap().assume_checked().script_pubkey()
+    }
+
+    /// Pays 0.01 BTC to `recipient` and 118,800 sat change; the PSBT inputs
+    /// below spend 1,120,000 sat, leaving a 1200 sat fee
+    fn two_in_two_out(recipient: &str) -> Transaction {
+        let input = |byte: u8, vout| TxIn {
+            previous_output: OutPoint::new(Txid::from_byte_array([byte; 32]), vout),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        };
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![input(0xaa, 0), input(0xbb, 3)],
+            output: vec![
+                TxOut { value: Amount::from_sat(1_000_000), script_pubkey: script(recipient) },
+                TxOut { value: Amount::from_sat(118_800), script_pubkey: script(CHANGE) },
+            ],
+        }
+    }
+
+    fn psbt_hex(recipient: &str) -> String {
+        psbt_spending(recipient, CHANGE, [600_000, 520_000])
+    }
+
+    fn psbt_spending(recipient: &str, spent_address: &str, values: [u64; 2]) -> String {
+        let mut psbt = Psbt::from_unsigned_tx(two_in_two_out(recipient)).unwrap();
+        for (input, value) in psbt.inputs.iter_mut().zip(values) {
+            input.witness_utxo = Some(TxOut { value: Amount::from_sat(value), script_pubkey: script(spent_address) });
+        }
+        // Any key path on the output marks it as ours
+        let key = ::bitcoin::secp256k1::PublicKey::from_str(
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+        ).unwrap();
+        psbt.outputs[1].bip32_derivation.insert(key, (Fingerprint::default(), DerivationPath::master()));
+        hex::encode(psbt.serialize())
+    }
+
+    #[test]
+    fn test_psbt_summary_and_fee() {
+        let tx = BitcoinHandler::new().parse_transaction(&psbt_hex(RECIPIENT)).unwrap();
+        assert_eq!(tx.summary, format!("Send 0.01 BTC to {} (fee 1200 sat)", RECIPIENT));
+        assert_eq!(tx.hash, two_in_two_out(RECIPIENT).compute_txid().to_string());
+
+        let metadata = &tx.metadata;
+        assert_eq!(metadata["format"], "psbt");
+        assert_eq!(metadata["fee_sat"], 1200);
+        assert_eq!(metadata["inputs"][1]["txid"], Txid::from_byte_array([0xbb; 32]).to_string());
+        assert_eq!(metadata["inputs"][1]["vout"], 3);
+        assert_eq!(metadata["inputs"][1]["value_sat"], 520_000);
+        assert_eq!(metadata["outputs"][0]["address"], RECIPIENT);
+        assert_eq!(metadata["outputs"][0]["value_sat"], 1_000_000);
+        assert_eq!(metadata["outputs"][1]["address"], CHANGE);
+        assert_eq!(metadata["outputs"][1]["change"], true);
+        assert_eq!(
+            metadata["outputs"][1]["key_origins"],
+            serde_json::json!([{
+                "pubkey": "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+                "path": DerivationPath::master().to_string(),
+            }])
+        );
+        assert_eq!(metadata["outputs"][0]["key_origins"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_raw_transaction_has_unknown_fee() {
+        let raw = hex::encode(::bitcoin::consensus::serialize(&two_in_two_out(RECIPIENT)));
+        let tx = BitcoinHandler::new().parse_transaction(&raw).unwrap();
+        assert_eq!(tx.metadata["format"], "raw");
+        assert!(tx.metadata["fee_sat"].is_null());
+        assert!(tx.metadata["inputs"][0]["value_sat"].is_null());
+        // Without PSBT derivation info every output is a potential recipient
+        assert_eq!(
+            tx.summary,
+            format!("Send 0.01 BTC to {}, 0.001188 BTC to {} (fee unknown)", RECIPIENT, CHANGE)
+        );
+    }
+
+    #[test]
+    fn test_testnet_addresses() {
+        let tx = BitcoinHandler::new_testnet().parse_transaction(&psbt_hex(RECIPIENT_TESTNET)).unwrap();
+        assert_eq!(tx.metadata["outputs"][0]["address"], RECIPIENT_TESTNET);
+        assert!(tx.summary.starts_with(&format!("Send 0.01 BTC to {}", RECIPIENT_TESTNET)));
+    }
+
+    #[test]
+    fn test_derive_address_vectors() {
+        let generator = hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798").unwrap();
+        let mainnet = BitcoinHandler::new();
+        let testnet = BitcoinHandler::new_testnet();
+        assert_eq!(mainnet.derive_address(&generator, BitcoinAddressType::P2wpkh).unwrap(), RECIPIENT);
+        assert_eq!(testnet.derive_address(&generator, BitcoinAddressType::P2wpkh).unwrap(), RECIPIENT_TESTNET);
+
+        // BIP-49 first receiving address
+        let bip49 = hex::decode("03a1af804ac108a8a51782198c2d034b28bf90c8803f5a53f76276fa69a4eae77f").unwrap();
+        assert_eq!(
+            testnet.derive_address(&bip49, BitcoinAddressType::P2shP2wpkh).unwrap(),
+            "2Mww8dCYPUpKHofjgcXcBCEGmniw9CoaiD2"
+        );
+
+        // BIP-86 first receiving address; only the x coordinate matters
+        let bip86 = hex::decode("03cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115").unwrap();
+        assert_eq!(
+            mainnet.derive_address(&bip86, BitcoinAddressType::P2tr).unwrap(),
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+        );
+
+        assert!(mainnet.derive_address(&generator[1..], BitcoinAddressType::P2wpkh).is_err());
+        assert_eq!("p2sh_p2wpkh".parse::<BitcoinAddressType>().unwrap(), BitcoinAddressType::P2shP2wpkh);
+        assert_eq!(BitcoinAddressType::P2tr.name().parse::<BitcoinAddressType>().unwrap(), BitcoinAddressType::P2tr);
+        assert!("p2pkh".parse::<BitcoinAddressType>().is_err());
+    }
+
+    #[test]
+    fn test_rejects_garbage() {
+        assert!(BitcoinHandler::new().parse_transaction("deadbeef").is_err());
+    }
+
+    #[test]
+    fn test_normal_fee_rate_counts_unsigned_p2pkh_inputs() {
+        let tx = BitcoinHandler::new().parse_transaction(&psbt_hex(RECIPIENT)).unwrap();
+        // 157-byte skeleton plus a 107-byte scriptSig per input
+        assert_eq!(tx.metadata["vsize"], 371);
+        assert_eq!(tx.metadata["fee_rate_sat_vb"], 3.23);
+        assert_eq!(tx.metadata["fee_warning"], false);
+        assert!(!tx.summary.contains("WARNING"));
+    }
+
+    #[test]
+    fn test_absurd_fee_rate_is_flagged() {
+        // A second input of 0.0552 BTC with only 0.001188 BTC of change
+        let hex = psbt_spending(RECIPIENT, RECIPIENT, [600_000, 5_520_000]);
+        let tx = BitcoinHandler::new().parse_transaction(&hex).unwrap();
+        assert_eq!(tx.metadata["fee_sat"], 5_001_200);
+        // P2WPKH witnesses are discounted: (628 + 4 + 2 * 107) / 4 rounded up
+        assert_eq!(tx.metadata["vsize"], 212);
+        assert_eq!(tx.metadata["fee_warning"], true);
+        assert!(tx.summary.ends_with("WARNING: fee rate 23591 sat/vB is unusually high"));
+
+        // The threshold is configurable; the transaction still parses either way
+        let tx = BitcoinHandler::new().with_max_fee_rate(50_000.0).parse_transaction(&hex).unwrap();
+        assert_eq!(tx.metadata["fee_warning"], false);
+    }
+}
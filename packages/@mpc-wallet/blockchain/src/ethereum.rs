@@ -1,15 +1,53 @@
 //! Ethereum blockchain handler implementation
 
-use super::{BlockchainHandler, ParsedTransaction, SignatureData, Result, BlockchainError};
+use super::{
+    BlockchainHandler, ParsedTransaction, SignatureData, SignatureFormat, Result, BlockchainError,
+    normalize_ecdsa_signature,
+};
 
 pub struct EthereumHandler {
     // Can add configuration here if needed
 }
 
+/// Prefix `personal_sign` (EIP-191 version `0x45`) puts before the message length
+pub const PERSONAL_SIGN_PREFIX: &str = "\x19Ethereum Signed Message:\n";
+
+/// A `personal_sign` request ready for FROST signing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersonalSignMessage {
+    /// keccak256 of the prefixed message
+    pub digest: Vec<u8>,
+    /// The original message: as text when it is UTF-8, otherwise 0x-prefixed hex
+    pub display: String,
+}
+
 impl EthereumHandler {
     pub fn new() -> Self {
         Self {}
     }
+
+    /// EIP-191 digest of `message`: keccak256 of the prefix, the decimal
+    /// message length and the message itself
+    pub fn format_personal_sign(&self, message: &[u8]) -> Vec<u8> {
+        use sha3::{Digest, Keccak256};
+        let mut hasher = Keccak256::new();
+        hasher.update(PERSONAL_SIGN_PREFIX.as_bytes());
+        hasher.update(message.len().to_string().as_bytes());
+        hasher.update(message);
+        hasher.finalize().to_vec()
+    }
+
+    /// The digest to sign for `message` along with the message as shown to the user
+    pub fn prepare_personal_sign(&self, message: &[u8]) -> PersonalSignMessage {
+        let display = match std::str::from_utf8(message) {
+            Ok(text) => text.to_string(),
+            Err(_) => format!("0x{}", hex::encode(message)),
+        };
+        PersonalSignMessage {
+            digest: self.format_personal_sign(message),
+            display,
+        }
+    }
     
     /// Parse Ethereum transaction and extract key fields
     fn parse_eth_transaction(tx_bytes: &[u8]) -> Result<(String, u64, serde_json::Value)> {
@@ -27,14 +65,30 @@ impl EthereumHandler {
         use sha3::{Digest, Keccak256};
         let tx_hash = hex::encode(Keccak256::digest(tx_bytes));
         
-        // Extract chain ID (simplified - in production use proper RLP parsing)
-        // For EIP-155 transactions, chain_id is encoded in the transaction
-        let chain_id = 1u64; // Default to mainnet, should parse from tx
-        
-        // Create metadata
+        // Unsigned transactions as they are built for signing. Anything else
+        // stays opaque, without fields a caller could mistake for decoded ones.
+        use ethers_core::types::transaction::eip2718::TypedTransaction;
+        let Ok(tx) = ethers_core::utils::rlp::decode::<TypedTransaction>(tx_bytes) else {
+            let metadata = serde_json::json!({
+                "type": "unknown",
+                "size": tx_bytes.len(),
+            });
+            return Ok((tx_hash, 1, metadata));
+        };
+        let tx_type = match &tx {
+            TypedTransaction::Legacy(_) => "legacy",
+            TypedTransaction::Eip2930(_) => "eip2930",
+            TypedTransaction::Eip1559(_) => "eip1559",
+        };
+        let chain_id = tx.chain_id().map_or(1, |id| id.as_u64());
         let metadata = serde_json::json!({
-            "type": "legacy", // or "eip1559", "eip2930"
+            "type": tx_type,
             "size": tx_bytes.len(),
+            "nonce": tx.nonce().map(|nonce| nonce.to_string()),
+            // `null` for a contract creation
+            "to": tx.to_addr().map(|to| ethers_core::utils::to_checksum(to, None)),
+            "value": tx.value().copied().unwrap_or_default().to_string(),
+            "data": format!("0x{}", hex::encode(tx.data().map(|data| data.as_ref()).unwrap_or_default())),
         });
         
         Ok((tx_hash, chain_id, metadata))
@@ -86,32 +140,27 @@ impl BlockchainHandler for EthereumHandler {
         Ok(hash.to_vec())
     }
     
-    fn serialize_signature(&self, signature_bytes: &[u8]) -> Result<SignatureData> {
-        // FROST signatures are typically 64 bytes (r,s)
-        if signature_bytes.len() < 64 {
+    fn serialize_signature(&self, signature_bytes: &[u8], format: SignatureFormat) -> Result<SignatureData> {
+        // Transactions and ecrecover verify ECDSA; a Schnorr (R, z) squeezed
+        // into r, s, v would never recover the sender
+        if format != SignatureFormat::Ecdsa {
             return Err(BlockchainError::SignatureError(
-                format!("Invalid signature length: expected at least 64 bytes, got {}", signature_bytes.len())
+                "Ethereum transactions need an ECDSA signature".to_string()
             ));
         }
-        
-        // For Ethereum, we need to format as r,s,v
-        // FROST gives us the signature, but we need to calculate v (recovery id)
-        // This is complex and requires the public key and message
-        
-        // For now, return the raw signature
-        // In production, calculate proper recovery ID
-        let r = &signature_bytes[..32];
-        let s = &signature_bytes[32..64];
-        
+        let signature = normalize_ecdsa_signature(signature_bytes)?;
+        let recovery_id = signature.recovery_id;
+
         // Format as 0x-prefixed hex
-        let signature_hex = format!("0x{}{}", hex::encode(r), hex::encode(s));
-        
+        let signature_hex = format!("0x{}{}", hex::encode(signature.r), hex::encode(signature.s));
+
         Ok(SignatureData {
             signature: signature_hex,
-            recovery_id: Some(27), // Placeholder - need proper calculation
+            recovery_id: Some(recovery_id),
             metadata: serde_json::json!({
                 "format": "ethereum",
-                "note": "Recovery ID needs proper calculation"
+                "scheme": "ecdsa",
+                "v": 27 + recovery_id,
             }),
         })
     }
@@ -121,3 +170,85 @@ impl BlockchainHandler for EthereumHandler {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// secp256k1 group order n
+    const ORDER: &str = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+
+    #[test]
+    fn test_high_s_signature_is_normalized() {
+        let r = [0x11u8; 32];
+        let low_s = {
+            let mut s = [0u8; 32];
+            s[31] = 5;
+            s
+        };
+        // n - 5 is in the upper half of the order
+        let mut high_s = hex::decode(ORDER).unwrap();
+        high_s[31] -= 5;
+
+        let handler = EthereumHandler::new();
+        let high = handler
+            .serialize_signature(&[&r[..], &high_s, &[28]].concat(), SignatureFormat::Ecdsa)
+            .unwrap();
+        assert_eq!(high.signature, format!("0x{}{}", hex::encode(r), hex::encode(low_s)));
+        assert_eq!(high.recovery_id, Some(0));
+        assert_eq!(high.metadata["v"], 27);
+
+        // Already canonical signatures pass through untouched
+        let low = handler
+            .serialize_signature(&[&r[..], &low_s, &[1]].concat(), SignatureFormat::Ecdsa)
+            .unwrap();
+        assert_eq!(low.signature, high.signature);
+        assert_eq!(low.recovery_id, Some(1));
+
+        // Ethereum can't verify FROST Schnorr output, however it is laid out
+        for frost in [[&[0x03][..], &r, &high_s].concat(), vec![0u8; 64]] {
+            let error = handler.serialize_signature(&frost, SignatureFormat::FrostSchnorr).unwrap_err();
+            assert!(error.to_string().contains("need an ECDSA signature"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_parse_unsigned_eip1559_transfer() {
+        use ethers_core::types::{Address, Eip1559TransactionRequest};
+        use ethers_core::types::transaction::eip2718::TypedTransaction;
+
+        let to: Address = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse().unwrap();
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .to(to)
+            .value(1_500_000_000_000_000_000u64)
+            .nonce(3)
+            .chain_id(5)
+            .into();
+
+        let parsed = EthereumHandler::new().parse_transaction(&hex::encode(tx.rlp())).unwrap();
+        assert_eq!(parsed.chain_id, Some(5));
+        assert_eq!(parsed.metadata["type"], "eip1559");
+        assert_eq!(parsed.metadata["to"], "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+        assert_eq!(parsed.metadata["value"], "1500000000000000000");
+        assert_eq!(parsed.metadata["data"], "0x");
+
+        // Bytes that aren't a transaction carry no decoded fields
+        let opaque = EthereumHandler::new().parse_transaction(&hex::encode([7u8; 32])).unwrap();
+        assert_eq!(opaque.metadata["type"], "unknown");
+        assert!(opaque.metadata.get("to").is_none());
+    }
+
+    #[test]
+    fn test_personal_sign_hello_world_vector() {
+        let handler = EthereumHandler::new();
+        let prepared = handler.prepare_personal_sign(b"hello world");
+        assert_eq!(
+            hex::encode(&prepared.digest),
+            "d9eba16ed0ecae432b71fe008c98cc872bb4cc214d3220a36f365326cf807d68"
+        );
+        assert_eq!(prepared.digest, ethers_core::utils::hash_message("hello world").as_bytes());
+        assert_eq!(prepared.display, "hello world");
+
+        assert_eq!(handler.prepare_personal_sign(&[0xff, 0x00]).display, "0xff00");
+    }
+}
@@ -39,8 +39,8 @@ pub trait BlockchainHandler: Send + Sync {
     fn format_for_signing(&self, tx: &ParsedTransaction) -> Result<Vec<u8>>;
     
     /// Serialize a signature to blockchain-specific format
-    /// The signature is provided as raw bytes
-    fn serialize_signature(&self, signature_bytes: &[u8]) -> Result<SignatureData>;
+    /// The signature is provided as raw bytes in the given `format`
+    fn serialize_signature(&self, signature_bytes: &[u8], format: SignatureFormat) -> Result<SignatureData>;
     
     /// Get transaction hash for display/logging
     fn get_tx_hash(&self, tx: &ParsedTransaction) -> String;
@@ -61,6 +61,57 @@ pub struct ParsedTransaction {
     pub metadata: serde_json::Value,
 }
 
+/// Shape of the raw signature bytes handed to `serialize_signature`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureFormat {
+    /// FROST output as-is: the serialized nonce commitment followed by the
+    /// response scalar. Schnorr signatures are not malleable, so no
+    /// normalization is applied.
+    FrostSchnorr,
+    /// secp256k1 ECDSA `r || s`, optionally followed by a recovery id (0/1)
+    /// or an Ethereum `v` (27/28). `s` is normalized to the lower half of
+    /// the curve order.
+    Ecdsa,
+}
+
+/// A secp256k1 ECDSA signature in canonical low-`s` form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EcdsaSignature {
+    pub r: [u8; 32],
+    pub s: [u8; 32],
+    /// Parity of the nonce point's y coordinate (0 or 1)
+    pub recovery_id: u8,
+}
+
+/// Parse an ECDSA signature and enforce low-`s` (BIP-62 / EIP-2). Replacing
+/// a high `s` with `n - s` negates the nonce point, so the recovery id flips.
+/// Without a trailing recovery id, 0 is assumed.
+pub fn normalize_ecdsa_signature(signature_bytes: &[u8]) -> Result<EcdsaSignature> {
+    let recovery_id = match (signature_bytes.len(), signature_bytes.last()) {
+        (64, _) => 0,
+        (65, Some(v @ (0 | 1))) => *v,
+        (65, Some(v @ (27 | 28))) => v - 27,
+        _ => {
+            return Err(BlockchainError::SignatureError(format!(
+                "Invalid ECDSA signature: expected r || s with an optional recovery id, got {} bytes",
+                signature_bytes.len()
+            )));
+        }
+    };
+    let signature = k256::ecdsa::Signature::from_slice(&signature_bytes[..64])
+        .map_err(|e| BlockchainError::SignatureError(format!("Invalid ECDSA signature: {}", e)))?;
+    let (signature, recovery_id) = match signature.normalize_s() {
+        Some(low_s) => (low_s, recovery_id ^ 1),
+        None => (signature, recovery_id),
+    };
+    let (r, s) = signature.split_bytes();
+    Ok(EcdsaSignature {
+        r: r.into(),
+        s: s.into(),
+        recovery_id,
+    })
+}
+
 /// Serialized signature data
 #[derive(Debug, Clone)]
 pub struct SignatureData {
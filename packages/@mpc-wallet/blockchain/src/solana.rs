@@ -1,6 +1,6 @@
 //! Solana blockchain handler implementation
 
-use super::{BlockchainHandler, ParsedTransaction, SignatureData, Result, BlockchainError};
+use super::{BlockchainHandler, ParsedTransaction, SignatureData, SignatureFormat, Result, BlockchainError};
 use solana_sdk::bs58;
 
 pub struct SolanaHandler {
@@ -11,6 +11,19 @@ impl SolanaHandler {
     pub fn new() -> Self {
         Self {}
     }
+
+    /// Decodes a base58 Solana address, e.g. a pasted recipient, into its
+    /// 32-byte public key, failing on invalid characters or a wrong length
+    pub fn validate_address(addr: &str) -> Result<[u8; 32]> {
+        let bytes = bs58::decode(addr.trim())
+            .into_vec()
+            .map_err(|e| BlockchainError::ParseError(format!("Invalid Solana address '{}': {}", addr, e)))?;
+        let len = bytes.len();
+        bytes.try_into().map_err(|_| BlockchainError::ParseError(format!(
+            "Invalid Solana address '{}': decodes to {} bytes, expected 32",
+            addr, len
+        )))
+    }
     
     /// Parse Solana transaction
     fn parse_solana_transaction(tx_bytes: &[u8]) -> Result<(String, serde_json::Value)> {
@@ -21,24 +34,318 @@ impl SolanaHandler {
             ));
         }
         
-        // For Solana, we typically sign the message directly
-        // Transaction format is more complex (includes recent blockhash, instructions, etc.)
+        let (signatures, message) = SolanaMessage::locate(tx_bytes)?;
         
-        // Calculate transaction ID (first signature will be the ID)
-        // For unsigned tx, we'll use a hash
+        // Signatures cover the message only, so hash that rather than the wire bytes
         use sha2::{Digest, Sha256};
-        let tx_hash = hex::encode(Sha256::digest(tx_bytes));
+        let tx_hash = hex::encode(Sha256::digest(&tx_bytes[message.range.clone()]));
         
         let metadata = serde_json::json!({
-            "type": "transaction",
+            "type": if signatures > 0 { "transaction" } else { "message" },
             "size": tx_bytes.len(),
-            "version": "legacy", // or "v0"
+            "version": message.version_label(),
+            "signatures": signatures,
+            "required_signatures": message.num_required_signatures,
+            "account_keys": message.account_keys.len(),
+            "instructions": message.instructions.len(),
+            "address_table_lookups": message.num_address_table_lookups,
+            "token_transfers": message.instructions.iter()
+                .filter_map(|ix| decode_token_transfer(&message, ix))
+                .collect::<Vec<_>>(),
+            "durable_nonce": decode_durable_nonce(&message),
         });
         
         Ok((tx_hash, metadata))
     }
 }
 
+/// Layout facts about a serialized Solana message (legacy or v0)
+#[derive(Debug, Clone, PartialEq)]
+struct SolanaMessage {
+    /// `None` for legacy messages, `Some(0)` for v0
+    version: Option<u8>,
+    num_required_signatures: u8,
+    /// Static account keys; v0 lookup-table accounts are indexed after these
+    account_keys: Vec<[u8; 32]>,
+    /// The stored nonce rather than a recent blockhash in durable-nonce transactions
+    recent_blockhash: [u8; 32],
+    instructions: Vec<CompiledInstruction>,
+    num_address_table_lookups: usize,
+    /// Byte range of the message inside the input
+    range: std::ops::Range<usize>,
+}
+
+impl SolanaMessage {
+    /// Set on the first message byte for versioned messages; legacy messages
+    /// start with `num_required_signatures`, which never has this bit set
+    const VERSION_PREFIX: u8 = 0x80;
+
+    /// Finds the message in either a full wire transaction (compact array of
+    /// signatures followed by the message) or a bare message
+    fn locate(bytes: &[u8]) -> Result<(usize, SolanaMessage)> {
+        let mut reader = Reader::new(bytes);
+        if let Ok(count) = reader.compact_u16()
+            && reader.take(count * 64).is_ok()
+            && let Ok(message) = Self::parse(bytes, reader.pos)
+            && count == message.num_required_signatures as usize
+        {
+            return Ok((count, message));
+        }
+        Ok((0, Self::parse(bytes, 0)?))
+    }
+
+    /// Parses a message starting at `start` that must run to the end of `bytes`
+    fn parse(bytes: &[u8], start: usize) -> Result<SolanaMessage> {
+        let mut reader = Reader { bytes, pos: start };
+
+        let version = match reader.peek()? {
+            prefix if prefix & Self::VERSION_PREFIX != 0 => {
+                reader.u8()?;
+                match prefix & !Self::VERSION_PREFIX {
+                    0 => Some(0),
+                    other => return Err(BlockchainError::ParseError(
+                        format!("Unsupported Solana message version {}", other)
+                    )),
+                }
+            }
+            _ => None,
+        };
+
+        let header = reader.take(3)?;
+        let num_required_signatures = header[0];
+        let num_account_keys = reader.compact_u16()?;
+        let account_keys = reader.take(num_account_keys * 32)?
+            .chunks_exact(32)
+            .map(|key| key.try_into().expect("32-byte chunk"))
+            .collect();
+        let recent_blockhash = reader.take(32)?.try_into().expect("32 bytes");
+
+        let num_instructions = reader.compact_u16()?;
+        let mut instructions = Vec::with_capacity(num_instructions);
+        for _ in 0..num_instructions {
+            let program_id_index = reader.u8()?;
+            let accounts = reader.compact_u16()?;
+            let accounts = reader.take(accounts)?.to_vec();
+            let data = reader.compact_u16()?;
+            let data = reader.take(data)?.to_vec();
+            instructions.push(CompiledInstruction { program_id_index, accounts, data });
+        }
+
+        let mut num_address_table_lookups = 0;
+        if version.is_some() {
+            num_address_table_lookups = reader.compact_u16()?;
+            for _ in 0..num_address_table_lookups {
+                // Table address, writable indexes, readonly indexes
+                reader.take(32)?;
+                let writable = reader.compact_u16()?;
+                reader.take(writable)?;
+                let readonly = reader.compact_u16()?;
+                reader.take(readonly)?;
+            }
+        }
+
+        if reader.pos != bytes.len() {
+            return Err(BlockchainError::ParseError(format!(
+                "{} trailing bytes after Solana message",
+                bytes.len() - reader.pos
+            )));
+        }
+
+        Ok(SolanaMessage {
+            version,
+            num_required_signatures,
+            account_keys,
+            recent_blockhash,
+            instructions,
+            num_address_table_lookups,
+            range: start..bytes.len(),
+        })
+    }
+
+    fn version_label(&self) -> &'static str {
+        match self.version {
+            None => "legacy",
+            Some(_) => "v0",
+        }
+    }
+
+    /// Base58 address of account `index`, if it is a static key. Accounts
+    /// loaded from address lookup tables cannot be resolved offline.
+    fn account_address(&self, index: u8) -> String {
+        match self.account_keys.get(index as usize) {
+            Some(key) => bs58::encode(key).into_string(),
+            None => format!("<lookup table account #{}>", index),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CompiledInstruction {
+    program_id_index: u8,
+    accounts: Vec<u8>,
+    data: Vec<u8>,
+}
+
+/// System program id (`11111111111111111111111111111111`)
+const SYSTEM_PROGRAM: [u8; 32] = [0; 32];
+
+/// `SystemInstruction::AdvanceNonceAccount`, a little-endian u32 tag
+const SYSTEM_IX_ADVANCE_NONCE_ACCOUNT: [u8; 4] = [4, 0, 0, 0];
+
+/// A durable-nonce transaction must advance its nonce in the first
+/// instruction; the runtime then accepts the stored nonce in place of a
+/// recent blockhash, so the signature doesn't expire. Returns the nonce
+/// account, its authority and the nonce value, or `None` for an ordinary
+/// transaction.
+fn decode_durable_nonce(message: &SolanaMessage) -> Option<serde_json::Value> {
+    let ix = message.instructions.first()?;
+    if message.account_keys.get(ix.program_id_index as usize)? != &SYSTEM_PROGRAM
+        || ix.data != SYSTEM_IX_ADVANCE_NONCE_ACCOUNT
+    {
+        return None;
+    }
+    // Accounts: nonce account, RecentBlockhashes sysvar, nonce authority
+    let nonce_account = message.account_address(*ix.accounts.first()?);
+    let authority = message.account_address(*ix.accounts.get(2)?);
+    Some(serde_json::json!({
+        "nonce_account": nonce_account,
+        "authority": authority,
+        "nonce": bs58::encode(message.recent_blockhash).into_string(),
+    }))
+}
+
+/// SPL Token and Token-2022 share the same transfer instruction layout
+const TOKEN_PROGRAMS: &[(&str, &str)] = &[
+    ("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA", "spl-token"),
+    ("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb", "spl-token-2022"),
+];
+
+/// Mints shown by symbol instead of address
+const KNOWN_MINTS: &[(&str, &str)] = &[
+    ("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "USDC"),
+];
+
+const TOKEN_IX_TRANSFER: u8 = 3;
+const TOKEN_IX_TRANSFER_CHECKED: u8 = 12;
+
+/// Decodes an SPL Token `Transfer` or `TransferChecked` instruction into
+/// structured metadata with a `display` line. Returns `None` for any other
+/// program or instruction.
+fn decode_token_transfer(message: &SolanaMessage, ix: &CompiledInstruction) -> Option<serde_json::Value> {
+    let program_key = message.account_keys.get(ix.program_id_index as usize)?;
+    let program_id = bs58::encode(program_key).into_string();
+    let (_, program) = TOKEN_PROGRAMS.iter().find(|(id, _)| *id == program_id)?;
+
+    let (&tag, rest) = ix.data.split_first()?;
+    let amount = u64::from_le_bytes(rest.get(..8)?.try_into().ok()?);
+    let account = |i: usize| ix.accounts.get(i).map(|index| message.account_address(*index));
+
+    match tag {
+        TOKEN_IX_TRANSFER if rest.len() == 8 => {
+            // Accounts: source, destination, authority
+            let destination = account(1)?;
+            Some(serde_json::json!({
+                "program": program,
+                "instruction": "transfer",
+                "source": account(0)?,
+                "destination": destination,
+                "authority": account(2)?,
+                "amount": amount.to_string(),
+                // Plain Transfer carries neither mint nor decimals
+                "display": format!("Transfer {} token base units to {}", amount, destination),
+            }))
+        }
+        TOKEN_IX_TRANSFER_CHECKED if rest.len() == 9 => {
+            // Accounts: source, mint, destination, authority
+            let decimals = rest[8];
+            let mint = account(1)?;
+            let destination = account(2)?;
+            let ui_amount = format_token_amount(amount, decimals);
+            let token = KNOWN_MINTS.iter()
+                .find(|(address, _)| *address == mint)
+                .map(|(_, symbol)| symbol.to_string())
+                .unwrap_or_else(|| format!("tokens of mint {}", mint));
+            Some(serde_json::json!({
+                "program": program,
+                "instruction": "transferChecked",
+                "source": account(0)?,
+                "mint": mint,
+                "destination": destination,
+                "authority": account(3)?,
+                "amount": amount.to_string(),
+                "decimals": decimals,
+                "ui_amount": ui_amount,
+                "display": format!("Transfer {} {} to {}", ui_amount, token, destination),
+            }))
+        }
+        _ => None,
+    }
+}
+
+/// `1500000` with 6 decimals -> `"1.5"`
+fn format_token_amount(amount: u64, decimals: u8) -> String {
+    let Some(scale) = 10u128.checked_pow(decimals as u32) else {
+        return amount.to_string();
+    };
+    let (whole, fraction) = (amount as u128 / scale, amount as u128 % scale);
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let fraction = format!("{:0width$}", fraction, width = decimals as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+/// Cursor over wire bytes with Solana's compact-u16 ("shortvec") lengths
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn peek(&self) -> Result<u8> {
+        self.bytes.get(self.pos).copied().ok_or_else(Self::truncated)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.bytes.len())
+            .ok_or_else(Self::truncated)?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    /// Little-endian base-128, at most 3 bytes
+    fn compact_u16(&mut self) -> Result<usize> {
+        let mut value = 0usize;
+        for i in 0..3 {
+            let byte = self.u8()?;
+            value |= ((byte & 0x7f) as usize) << (7 * i);
+            if byte & 0x80 == 0 {
+                return if value > u16::MAX as usize {
+                    Err(BlockchainError::ParseError("compact-u16 overflow".to_string()))
+                } else {
+                    Ok(value)
+                };
+            }
+        }
+        Err(BlockchainError::ParseError("compact-u16 longer than 3 bytes".to_string()))
+    }
+
+    fn truncated() -> BlockchainError {
+        BlockchainError::ParseError("Truncated Solana message".to_string())
+    }
+}
+
 impl BlockchainHandler for SolanaHandler {
     fn blockchain_id(&self) -> &str {
         "solana"
@@ -67,11 +374,24 @@ impl BlockchainHandler for SolanaHandler {
         // Parse transaction
         let (hash, metadata) = Self::parse_solana_transaction(&raw_bytes)?;
         
-        // Create summary
-        let summary = format!(
-            "Solana transaction (size: {} bytes)",
-            raw_bytes.len()
-        );
+        // Create summary: decoded token transfers if any, generic otherwise
+        let transfers: Vec<&str> = metadata["token_transfers"].as_array()
+            .map(|transfers| transfers.iter().filter_map(|t| t["display"].as_str()).collect())
+            .unwrap_or_default();
+        let mut summary = if transfers.is_empty() {
+            format!(
+                "Solana {} transaction: {} instruction(s), {} address lookup table(s) (size: {} bytes)",
+                metadata["version"].as_str().unwrap_or("legacy"),
+                metadata["instructions"],
+                metadata["address_table_lookups"],
+                raw_bytes.len()
+            )
+        } else {
+            transfers.join("; ")
+        };
+        if let Some(nonce_account) = metadata["durable_nonce"]["nonce_account"].as_str() {
+            summary.push_str(&format!(" (durable nonce account {})", nonce_account));
+        }
         
         Ok(ParsedTransaction {
             raw_bytes,
@@ -83,12 +403,19 @@ impl BlockchainHandler for SolanaHandler {
     }
     
     fn format_for_signing(&self, tx: &ParsedTransaction) -> Result<Vec<u8>> {
-        // For Solana, we sign the serialized transaction bytes directly
-        // No additional hashing needed (Solana does this internally)
-        Ok(tx.raw_bytes.clone())
+        // Ed25519 signs the serialized message (including the v0 prefix byte)
+        // as-is; a wire transaction's signature array is not part of it
+        let (_, message) = SolanaMessage::locate(&tx.raw_bytes)?;
+        Ok(tx.raw_bytes[message.range].to_vec())
     }
     
-    fn serialize_signature(&self, signature_bytes: &[u8]) -> Result<SignatureData> {
+    fn serialize_signature(&self, signature_bytes: &[u8], format: SignatureFormat) -> Result<SignatureData> {
+        if format != SignatureFormat::FrostSchnorr {
+            return Err(BlockchainError::SignatureError(
+                "Solana only accepts Ed25519 signatures".to_string()
+            ));
+        }
+
         // Solana expects 64-byte signatures
         if signature_bytes.len() != 64 {
             return Err(BlockchainError::SignatureError(
@@ -112,4 +439,200 @@ impl BlockchainHandler for SolanaHandler {
     fn get_tx_hash(&self, tx: &ParsedTransaction) -> String {
         tx.hash.clone()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// System program transfer from `payer` (key 0) to key 1, program key 2
+    const LEGACY_MESSAGE: &str = concat!(
+        "010001", "03",
+        "1111111111111111111111111111111111111111111111111111111111111111",
+        "2222222222222222222222222222222222222222222222222222222222222222",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "3333333333333333333333333333333333333333333333333333333333333333",
+        "01", "02", "020001", "0c", "0200000040420f0000000000",
+    );
+
+    /// Same transfer as a v0 message whose recipient comes from one lookup table
+    const V0_MESSAGE: &str = concat!(
+        "80", "010001", "02",
+        "1111111111111111111111111111111111111111111111111111111111111111",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "3333333333333333333333333333333333333333333333333333333333333333",
+        "01", "01", "020002", "0c", "0200000040420f0000000000",
+        "01", "4444444444444444444444444444444444444444444444444444444444444444", "0105", "00",
+    );
+
+    fn with_signature(message: &str) -> String {
+        format!("01{}{}", "ab".repeat(64), message)
+    }
+
+    #[test]
+    fn test_parse_legacy_message() {
+        let handler = SolanaHandler::new();
+        let tx = handler.parse_transaction(LEGACY_MESSAGE).unwrap();
+        assert_eq!(tx.metadata["version"], "legacy");
+        assert_eq!(tx.metadata["instructions"], 1);
+        assert_eq!(tx.metadata["address_table_lookups"], 0);
+        assert!(tx.summary.contains("1 instruction(s), 0 address lookup table(s)"));
+        assert_eq!(handler.format_for_signing(&tx).unwrap(), hex::decode(LEGACY_MESSAGE).unwrap());
+    }
+
+    #[test]
+    fn test_parse_v0_transaction() {
+        let handler = SolanaHandler::new();
+        let tx = handler.parse_transaction(&with_signature(V0_MESSAGE)).unwrap();
+        assert_eq!(tx.metadata["version"], "v0");
+        assert_eq!(tx.metadata["signatures"], 1);
+        assert_eq!(tx.metadata["instructions"], 1);
+        assert_eq!(tx.metadata["address_table_lookups"], 1);
+        assert!(tx.summary.starts_with("Solana v0 transaction: 1 instruction(s), 1 address lookup table(s)"));
+
+        // Only the message is signed, with its version prefix
+        let message = handler.format_for_signing(&tx).unwrap();
+        assert_eq!(message, hex::decode(V0_MESSAGE).unwrap());
+        assert_eq!(message[0], 0x80);
+
+        // Same hash whether or not the signature array is present
+        assert_eq!(tx.hash, handler.parse_transaction(V0_MESSAGE).unwrap().hash);
+    }
+
+    /// Legacy message with a single instruction calling `keys[program]`
+    fn single_instruction_message(keys: &[[u8; 32]], program: u8, accounts: &[u8], data: &[u8]) -> String {
+        let mut message = vec![1, 0, 1, keys.len() as u8];
+        keys.iter().for_each(|key| message.extend_from_slice(key));
+        message.extend_from_slice(&[0x77; 32]);
+        message.extend_from_slice(&[1, program, accounts.len() as u8]);
+        message.extend_from_slice(accounts);
+        message.push(data.len() as u8);
+        message.extend_from_slice(data);
+        hex::encode(message)
+    }
+
+    fn pubkey(address: &str) -> [u8; 32] {
+        bs58::decode(address).into_vec().unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_decodes_usdc_transfer_checked() {
+        let owner = pubkey("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM");
+        let source = pubkey("3emsAVdmGKERbHjmGfQ6oZ1e35dkf5iYcS6U4CPKFVaa");
+        let destination = pubkey("Hd2cPyqk4BXp3G3GmDzkRsVc2ZAJwwxrQjGM3sVYpJyo");
+        let keys = [
+            owner,
+            source,
+            destination,
+            pubkey("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+            pubkey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+        ];
+        // TransferChecked { amount: 1_500_000, decimals: 6 }
+        let mut data = vec![12];
+        data.extend_from_slice(&1_500_000u64.to_le_bytes());
+        data.push(6);
+
+        let handler = SolanaHandler::new();
+        let tx = handler.parse_transaction(&single_instruction_message(&keys, 4, &[1, 3, 2, 0], &data)).unwrap();
+        assert_eq!(tx.summary, "Transfer 1.5 USDC to Hd2cPyqk4BXp3G3GmDzkRsVc2ZAJwwxrQjGM3sVYpJyo");
+
+        let transfer = &tx.metadata["token_transfers"][0];
+        assert_eq!(transfer["program"], "spl-token");
+        assert_eq!(transfer["instruction"], "transferChecked");
+        assert_eq!(transfer["source"], "3emsAVdmGKERbHjmGfQ6oZ1e35dkf5iYcS6U4CPKFVaa");
+        assert_eq!(transfer["authority"], "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM");
+        assert_eq!(transfer["amount"], "1500000");
+        assert_eq!(transfer["ui_amount"], "1.5");
+    }
+
+    #[test]
+    fn test_decodes_plain_token_transfer() {
+        let keys = [[1; 32], [2; 32], [3; 32], pubkey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA")];
+        let mut data = vec![3];
+        data.extend_from_slice(&42u64.to_le_bytes());
+
+        let tx = SolanaHandler::new()
+            .parse_transaction(&single_instruction_message(&keys, 3, &[1, 2, 0], &data))
+            .unwrap();
+        let destination = bs58::encode([3; 32]).into_string();
+        assert_eq!(tx.summary, format!("Transfer 42 token base units to {}", destination));
+        assert_eq!(tx.metadata["token_transfers"][0]["instruction"], "transfer");
+    }
+
+    #[test]
+    fn test_unknown_program_falls_back_to_generic_summary() {
+        let keys = [[1; 32], [2; 32], [9; 32]];
+        // Same bytes as a TransferChecked, but not sent to a token program
+        let mut data = vec![12];
+        data.extend_from_slice(&1_500_000u64.to_le_bytes());
+        data.push(6);
+
+        let tx = SolanaHandler::new()
+            .parse_transaction(&single_instruction_message(&keys, 2, &[1, 0], &data))
+            .unwrap();
+        assert!(tx.summary.starts_with("Solana legacy transaction: 1 instruction(s)"));
+        assert_eq!(tx.metadata["token_transfers"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_validate_address() {
+        let usdc_mint = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        assert_eq!(SolanaHandler::validate_address(usdc_mint).unwrap(), pubkey(usdc_mint));
+
+        let short = bs58::encode([7u8; 31]).into_string();
+        let err = SolanaHandler::validate_address(&short).unwrap_err().to_string();
+        assert!(err.contains("decodes to 31 bytes, expected 32"), "{}", err);
+
+        // '0', 'O', 'I' and 'l' are not in the base58 alphabet
+        let err = SolanaHandler::validate_address("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt10").unwrap_err();
+        assert!(err.to_string().contains("invalid character"), "{}", err);
+    }
+
+    #[test]
+    fn test_format_token_amount() {
+        assert_eq!(format_token_amount(1_500_000, 6), "1.5");
+        assert_eq!(format_token_amount(2_000_000, 6), "2");
+        assert_eq!(format_token_amount(1, 9), "0.000000001");
+        assert_eq!(format_token_amount(7, 0), "7");
+    }
+
+    #[test]
+    fn test_rejects_truncated_and_unknown_versions() {
+        let handler = SolanaHandler::new();
+        assert!(handler.parse_transaction(&V0_MESSAGE[..V0_MESSAGE.len() - 2]).is_err());
+        let v1 = format!("81{}", &V0_MESSAGE[2..]);
+        assert!(handler.parse_transaction(&v1).is_err());
+    }
+
+    #[test]
+    fn test_durable_nonce_transfer() {
+        let authority = pubkey("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM");
+        let nonce_account = pubkey("3emsAVdmGKERbHjmGfQ6oZ1e35dkf5iYcS6U4CPKFVaa");
+        let recipient = pubkey("Hd2cPyqk4BXp3G3GmDzkRsVc2ZAJwwxrQjGM3sVYpJyo");
+        let recent_blockhashes = pubkey("SysvarRecentB1ockHashes11111111111111111111");
+        let nonce = [0x5a; 32];
+
+        // AdvanceNonceAccount, then a 1 SOL system transfer
+        let mut message = vec![1, 0, 2, 5];
+        for key in [authority, nonce_account, recipient, recent_blockhashes, SYSTEM_PROGRAM] {
+            message.extend_from_slice(&key);
+        }
+        message.extend_from_slice(&nonce);
+        message.extend_from_slice(&[2, 4, 3, 1, 3, 0, 4, 4, 0, 0, 0]);
+        message.extend_from_slice(&[4, 2, 0, 2, 12, 2, 0, 0, 0]);
+        message.extend_from_slice(&1_000_000_000u64.to_le_bytes());
+
+        let handler = SolanaHandler::new();
+        let tx = handler.parse_transaction(&with_signature(&hex::encode(&message))).unwrap();
+        let durable_nonce = &tx.metadata["durable_nonce"];
+        assert_eq!(durable_nonce["nonce_account"], "3emsAVdmGKERbHjmGfQ6oZ1e35dkf5iYcS6U4CPKFVaa");
+        assert_eq!(durable_nonce["authority"], "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM");
+        assert_eq!(durable_nonce["nonce"], bs58::encode(nonce).into_string());
+        assert!(tx.summary.ends_with("(durable nonce account 3emsAVdmGKERbHjmGfQ6oZ1e35dkf5iYcS6U4CPKFVaa)"));
+
+        // The nonce sits where the blockhash would, so the signed bytes are the message unchanged
+        assert_eq!(handler.format_for_signing(&tx).unwrap(), message);
+
+        assert!(handler.parse_transaction(LEGACY_MESSAGE).unwrap().metadata["durable_nonce"].is_null());
+    }
+}
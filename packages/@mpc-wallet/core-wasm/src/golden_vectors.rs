@@ -0,0 +1,231 @@
+//! Golden vectors shared by the CLI and the WASM wrappers.
+//!
+//! Every participant draws its randomness from a ChaCha20 stream derived
+//! from a fixed seed, so a DKG and signing run is fully reproducible. The
+//! same run goes once through `mpc_wallet_frost_core` directly, the path the
+//! CLI takes, and once through the `FrostDkg*` wrappers, and both must match
+//! `tests/vectors/frost_golden_v1.json` byte for byte. Packages are recorded
+//! as the hex of their JSON, the encoding the wrappers put on the wire, so a
+//! change in how either side encodes them fails here before it fails a
+//! cross-implementation session.
+//!
+//! Runs natively with `cargo test` and on wasm32 with `wasm-pack test --node`.
+//! After an intentional format change, regenerate the fixture natively with
+//! `UPDATE_GOLDEN_VECTORS=1` and bump `GOLDEN_VECTORS_VERSION`.
+
+use super::*;
+use serde::{Deserialize, Serialize};
+
+const GOLDEN_VECTORS_VERSION: u32 = 1;
+const GOLDEN_VECTORS: &str = include_str!("../tests/vectors/frost_golden_v1.json");
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct GoldenVectors {
+    version: u32,
+    /// Hex root secret each participant's RNG is derived from
+    seed: String,
+    threshold: u16,
+    total: u16,
+    /// 1-based indices of the participants who sign
+    signers: Vec<u16>,
+    /// Hex message signed
+    message: String,
+    ed25519: CurveVectors,
+    secp256k1: CurveVectors,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct CurveVectors {
+    ciphersuite_context: String,
+    participants: Vec<ParticipantVectors>,
+    group_public_key: String,
+    signature: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ParticipantVectors {
+    index: u16,
+    round1_package: String,
+    /// Keyed by recipient index
+    round2_packages: BTreeMap<u16, String>,
+    /// Only signers commit and sign
+    commitment: Option<String>,
+    signature_share: Option<String>,
+}
+
+fn wire_hex<T: Serialize>(value: &T) -> String {
+    hex::encode(serde_json::to_string(value).unwrap())
+}
+
+/// Randomness for participant `index` on `curve`
+fn participant_rng(seed: &str, curve: &str, index: u16) -> Box<dyn CryptoRngCore> {
+    let seed: [u8; 32] = hex::decode(seed).unwrap().try_into().unwrap();
+    let rng = RootSecret::from_bytes(seed)
+        .derive_rng(&format!("golden-vectors/{}/participant-{}", curve, index))
+        .unwrap();
+    Box::new(rng)
+}
+
+/// The run through frost-core's `FrostCurve` API, as the CLI drives it
+fn frost_core_vectors<C: FrostCurve>(vectors: &GoldenVectors, curve: &str) -> CurveVectors {
+    let indices: Vec<u16> = (1..=vectors.total).collect();
+    let identifiers: Vec<C::Identifier> = indices.iter().map(|&i| C::identifier_from_u16(i).unwrap()).collect();
+    let mut rngs: Vec<_> = indices.iter().map(|&i| participant_rng(&vectors.seed, curve, i)).collect();
+
+    let round1: Vec<_> = identifiers
+        .iter()
+        .zip(rngs.iter_mut())
+        .map(|(id, rng)| C::dkg_part1(*id, vectors.total, vectors.threshold, rng.as_mut()).unwrap())
+        .collect();
+    let round1_received = |own: usize| -> BTreeMap<C::Identifier, C::Round1Package> {
+        (0..indices.len()).filter(|&j| j != own).map(|j| (identifiers[j], round1[j].1.clone())).collect()
+    };
+    let round2: Vec<_> = (0..indices.len())
+        .map(|i| C::dkg_part2(round1[i].0.clone(), &round1_received(i)).unwrap())
+        .collect();
+    let keys: Vec<_> = (0..indices.len())
+        .map(|i| {
+            let received = (0..indices.len())
+                .filter(|&j| j != i)
+                .map(|j| (identifiers[j], round2[j].1[&identifiers[i]].clone()))
+                .collect();
+            C::dkg_part3(&round2[i].0, &round1_received(i), &received).unwrap()
+        })
+        .collect();
+
+    let signer = |i: usize| vectors.signers.contains(&indices[i]);
+    let message = hex::decode(&vectors.message).unwrap();
+    let mut nonces = BTreeMap::new();
+    let mut commitments = BTreeMap::new();
+    for i in (0..indices.len()).filter(|&i| signer(i)) {
+        let (nonce, commitment) = C::generate_signing_commitment_with_rng(&keys[i].0, rngs[i].as_mut()).unwrap();
+        nonces.insert(i, nonce);
+        commitments.insert(identifiers[i], commitment);
+    }
+    let signing_package = C::create_signing_package(&commitments, &message).unwrap();
+    let shares: BTreeMap<_, _> = nonces
+        .iter()
+        .map(|(&i, nonce)| (i, C::generate_signature_share(&signing_package, nonce, &keys[i].0).unwrap()))
+        .collect();
+    let signature = C::aggregate_signature(
+        &signing_package,
+        &shares.iter().map(|(&i, share)| (identifiers[i], share.clone())).collect(),
+        &keys[0].1,
+    )
+    .unwrap();
+
+    CurveVectors {
+        ciphersuite_context: C::ciphersuite_context(),
+        participants: (0..indices.len())
+            .map(|i| ParticipantVectors {
+                index: indices[i],
+                round1_package: wire_hex(&round1[i].1),
+                round2_packages: (0..indices.len())
+                    .filter(|&j| j != i)
+                    .map(|j| (indices[j], wire_hex(&round2[i].1[&identifiers[j]])))
+                    .collect(),
+                commitment: signer(i).then(|| wire_hex(&commitments[&identifiers[i]])),
+                signature_share: shares.get(&i).map(wire_hex),
+            })
+            .collect(),
+        group_public_key: hex::encode(C::serialize_verifying_key(&C::verifying_key(&keys[0].1)).unwrap()),
+        signature: hex::encode(C::serialize_signature(&signature).unwrap()),
+    }
+}
+
+/// The same run through a `FrostDkg*` wrapper's public methods
+macro_rules! wasm_vectors {
+    ($wrapper:ty, $curve:expr, $vectors:expr) => {{
+        let vectors: &GoldenVectors = $vectors;
+        let (total, threshold) = (vectors.total, vectors.threshold);
+        let mut parties: Vec<$wrapper> = (1..=total)
+            .map(|i| {
+                let mut party = <$wrapper>::new();
+                party.rng = participant_rng(&vectors.seed, $curve, i);
+                party.init_dkg(i, total, threshold).unwrap();
+                party
+            })
+            .collect();
+
+        let round1: Vec<String> = parties.iter_mut().map(|party| party.generate_round1().unwrap()).collect();
+        for (i, party) in parties.iter_mut().enumerate() {
+            for (j, package) in round1.iter().enumerate().filter(|(j, _)| *j != i) {
+                party.add_round1_package(j as u16 + 1, package).unwrap();
+            }
+        }
+        let round2: Vec<BTreeMap<u16, String>> = parties
+            .iter_mut()
+            .map(|party| serde_json::from_str(&party.generate_round2().unwrap()).unwrap())
+            .collect();
+        for (i, party) in parties.iter_mut().enumerate() {
+            for (j, packages) in round2.iter().enumerate().filter(|(j, _)| *j != i) {
+                party.add_round2_package(j as u16 + 1, &packages[&(i as u16 + 1)]).unwrap();
+            }
+            party.finalize_dkg().unwrap();
+        }
+
+        let commitments: BTreeMap<u16, String> = vectors
+            .signers
+            .iter()
+            .map(|&i| (i, parties[i as usize - 1].signing_commit().unwrap()))
+            .collect();
+        for &i in &vectors.signers {
+            for (&j, commitment) in &commitments {
+                parties[i as usize - 1].add_signing_commitment(j, commitment).unwrap();
+            }
+        }
+        let shares: BTreeMap<u16, String> = vectors
+            .signers
+            .iter()
+            .map(|&i| (i, parties[i as usize - 1].sign(&vectors.message).unwrap()))
+            .collect();
+        let aggregator = &mut parties[vectors.signers[0] as usize - 1];
+        for (&j, share) in &shares {
+            aggregator.add_signature_share(j, share).unwrap();
+        }
+
+        CurveVectors {
+            ciphersuite_context: parties[0].ciphersuite_context(),
+            participants: round1
+                .into_iter()
+                .zip(round2)
+                .enumerate()
+                .map(|(i, (round1_package, round2_packages))| {
+                    let index = i as u16 + 1;
+                    ParticipantVectors {
+                        index,
+                        round1_package,
+                        round2_packages,
+                        commitment: commitments.get(&index).cloned(),
+                        signature_share: shares.get(&index).cloned(),
+                    }
+                })
+                .collect(),
+            group_public_key: parties[0].get_group_public_key().unwrap(),
+            signature: parties[vectors.signers[0] as usize - 1].aggregate_signature(&vectors.message).unwrap(),
+        }
+    }};
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+fn test_cli_and_wasm_match_golden_vectors() {
+    let expected: GoldenVectors = serde_json::from_str(GOLDEN_VECTORS).unwrap();
+    assert_eq!(expected.version, GOLDEN_VECTORS_VERSION);
+
+    let actual = GoldenVectors {
+        ed25519: frost_core_vectors::<Ed25519Curve>(&expected, "ed25519"),
+        secp256k1: frost_core_vectors::<Secp256k1Curve>(&expected, "secp256k1"),
+        ..serde_json::from_str(GOLDEN_VECTORS).unwrap()
+    };
+    assert_eq!(wasm_vectors!(FrostDkgEd25519, "ed25519", &expected), actual.ed25519, "ed25519 WASM output diverges from frost-core");
+    assert_eq!(wasm_vectors!(FrostDkgSecp256k1, "secp256k1", &expected), actual.secp256k1, "secp256k1 WASM output diverges from frost-core");
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if std::env::var_os("UPDATE_GOLDEN_VECTORS").is_some() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/vectors/frost_golden_v1.json");
+        std::fs::write(path, serde_json::to_string_pretty(&actual).unwrap() + "\n").unwrap();
+        return;
+    }
+    assert_eq!(actual, expected, "frost-core output no longer matches the golden vectors");
+}
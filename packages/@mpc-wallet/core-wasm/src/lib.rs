@@ -1,27 +1,45 @@
 use wasm_bindgen::prelude::*;
 use mpc_wallet_frost_core::{
-    FrostCurve, FrostError,
+    BundleContents, FrostCurve, FrostError, SigningBundle, check_ciphersuite_context,
     ed25519::Ed25519Curve,
     secp256k1::Secp256k1Curve,
+    hd_derivation::{ChainCode, DerivedKeys, HARDENED_BIT, derive_child_key},
     keystore::{Keystore, KeystoreData},
     root_secret::RootSecret,
+    reshare::{reshare_step1, reshare_step2, reshare_step3},
     unified_dkg::{UnifiedDkg, UnifiedRound1Package},
 };
-use rand::rngs::OsRng;
-use std::collections::BTreeMap;
+use aes_gcm::Aes256Gcm;
+use aes_gcm::aead::{Aead, KeyInit, Nonce, Payload};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use flate2::Compression;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use rand_core::{CryptoRngCore, OsRng, RngCore};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+use zeroize::{Zeroize, Zeroizing};
 
 // Re-export specific FROST types needed by WASM
+use frost_core::{
+    Ciphersuite, Identifier, SigningPackage,
+    round1::{SigningCommitments, SigningNonces},
+    round2::SignatureShare,
+};
+
 use frost_ed25519::{
     Identifier as Ed25519Identifier,
     keys::{KeyPackage as Ed25519KeyPackage, PublicKeyPackage as Ed25519PublicKeyPackage},
-    round1::{SigningCommitments as Ed25519SigningCommitments, SigningNonces as Ed25519SigningNonces},
+    round1::SigningCommitments as Ed25519SigningCommitments,
     round2::SignatureShare as Ed25519SignatureShare,
 };
 
 use frost_secp256k1::{
     Identifier as Secp256k1Identifier,
     keys::{KeyPackage as Secp256k1KeyPackage, PublicKeyPackage as Secp256k1PublicKeyPackage},
-    round1::{SigningCommitments as Secp256k1SigningCommitments, SigningNonces as Secp256k1SigningNonces},
+    round1::SigningCommitments as Secp256k1SigningCommitments,
     round2::SignatureShare as Secp256k1SignatureShare,
 };
 
@@ -29,10 +47,62 @@ use frost_secp256k1::{
 extern "C" {
     #[wasm_bindgen(js_namespace = console)]
     fn log(s: &str);
+
+    #[wasm_bindgen(js_namespace = console, js_name = warn)]
+    fn log_warn(s: &str);
+
+    #[wasm_bindgen(js_namespace = console, js_name = error)]
+    fn log_error(s: &str);
+}
+
+// Console verbosity, most to least severe
+pub const LOG_ERROR: u8 = 1;
+pub const LOG_WARN: u8 = 2;
+pub const LOG_INFO: u8 = 3;
+pub const LOG_DEBUG: u8 = 4;
+
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(LOG_INFO);
+
+/// Set how much the library writes to the browser console: 0 silences it,
+/// 1 keeps errors only, 2 adds warnings, 3 (the default) adds lifecycle
+/// messages and 4 adds debug output.
+///
+/// ```js
+/// setLogLevel(1); // errors only
+/// ```
+#[wasm_bindgen(js_name = setLogLevel)]
+pub fn set_log_level(level: u8) {
+    LOG_LEVEL.store(level.min(LOG_DEBUG), Ordering::Relaxed);
+}
+
+fn log_enabled(level: u8) -> bool {
+    level <= LOG_LEVEL.load(Ordering::Relaxed)
 }
 
-macro_rules! console_log {
-    ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
+#[cfg(target_arch = "wasm32")]
+fn write_log(level: u8, message: &str) {
+    match level {
+        LOG_ERROR => log_error(message),
+        LOG_WARN => log_warn(message),
+        _ => log(message),
+    }
+}
+
+// The console binding only exists inside a JS host
+#[cfg(not(target_arch = "wasm32"))]
+fn write_log(_level: u8, _message: &str) {}
+
+// Messages above the current level are dropped before they are formatted
+macro_rules! log_at {
+    ($level:expr, $($t:tt)*) => {
+        if log_enabled($level) {
+            write_log($level, &format!($($t)*));
+        }
+    };
+}
+
+macro_rules! console_info {
+    ($($t:tt)*) => (log_at!(LOG_INFO, $($t)*))
 }
 
 // Error type for WASM
@@ -65,22 +135,876 @@ impl From<FrostError> for WasmError {
     }
 }
 
+// Reject parameters FROST would only fail on deep inside dkg_part1. Returns
+// the participant index normalized to 1-based.
+fn validate_dkg_params(participant_index: u16, index_base: u16, total: u16, threshold: u16) -> Result<u16, WasmError> {
+    validate_threshold(total, threshold)?;
+    normalize_index(participant_index, index_base, total)
+}
+
+fn validate_threshold(total: u16, threshold: u16) -> Result<(), WasmError> {
+    if threshold == 0 {
+        return Err(WasmError::new("Threshold must be at least 1"));
+    }
+    if threshold > total {
+        return Err(WasmError::new("Threshold cannot be greater than total participants"));
+    }
+    Ok(())
+}
+
+// FROST identifiers are built from 1-based indices; callers that number
+// participants from `index_base` (e.g. 0) are shifted onto that range
+fn normalize_index(index: u16, index_base: u16, total: u16) -> Result<u16, WasmError> {
+    index
+        .checked_sub(index_base)
+        .and_then(|offset| offset.checked_add(1))
+        .filter(|normalized| (1..=total).contains(normalized))
+        .ok_or_else(|| {
+            let mut message = "Participant index must be between 1 and total participants".to_string();
+            if index_base != 1 {
+                message.push_str(&format!(" (got {} with index base {})", index, index_base));
+            }
+            WasmError::new(&message)
+        })
+}
+
+// Inverse of `normalize_index`: the caller's number for 1-based `index`
+fn external_index(index: u16, index_base: u16) -> Result<u16, WasmError> {
+    index
+        .checked_sub(1)
+        .and_then(|offset| offset.checked_add(index_base))
+        .ok_or_else(|| {
+            WasmError::new(&format!(
+                "Participant {} has no index when counting from {}",
+                index, index_base
+            ))
+        })
+}
+
+/// Leads a deflate-compressed round 2 package. 0xff never starts UTF-8 text,
+/// so a plain JSON package can't be mistaken for a compressed one.
+pub const COMPRESSED_PACKAGE_MAGIC: [u8; 2] = [0xff, 0x44];
+
+// Hex-encode a package's JSON, deflated behind the magic header when `compress` is set
+fn encode_package(package_json: &str, compress: bool) -> Result<String, WasmError> {
+    if !compress {
+        return Ok(hex::encode(package_json));
+    }
+    let mut encoder = DeflateEncoder::new(COMPRESSED_PACKAGE_MAGIC.to_vec(), Compression::best());
+    encoder.write_all(package_json.as_bytes())
+        .map_err(|e| WasmError::new(&e.to_string()))?;
+    let bytes = encoder.finish()
+        .map_err(|e| WasmError::new(&e.to_string()))?;
+    Ok(hex::encode(bytes))
+}
+
+// Hex-decode a package, inflating it if it carries the magic header
+fn decode_package(package_hex: &str) -> Result<Vec<u8>, WasmError> {
+    let bytes = hex::decode(package_hex)
+        .map_err(|e| WasmError::new(&e.to_string()))?;
+    let Some(compressed) = bytes.strip_prefix(&COMPRESSED_PACKAGE_MAGIC) else {
+        return Ok(bytes);
+    };
+    let mut package_json = Vec::new();
+    DeflateDecoder::new(compressed).read_to_end(&mut package_json)
+        .map_err(|e| WasmError::new(&format!("Failed to decompress round2 package: {}", e)))?;
+    Ok(package_json)
+}
+
+fn parse_round1_package<T: serde::de::DeserializeOwned>(package_hex: &str) -> Result<T, WasmError> {
+    let package_json = hex::decode(package_hex)
+        .map_err(|e| WasmError::new(&e.to_string()))?;
+    serde_json::from_slice(&package_json)
+        .map_err(|e| WasmError::new(&e.to_string()))
+}
+
+// Round 2 packages from the older extension path arrive JSON-encoded twice;
+// try the package directly, then as a JSON string holding it
+fn parse_round2_package<T: serde::de::DeserializeOwned>(package_hex: &str) -> Result<T, WasmError> {
+    let package_json = decode_package(package_hex)?;
+    serde_json::from_slice(&package_json)
+        .or_else(|_| {
+            let inner: String = serde_json::from_slice(&package_json)?;
+            serde_json::from_str(&inner)
+        })
+        .map_err(|e| WasmError::new(&format!("Failed to deserialize round2 package: {}", e)))
+}
+
+// The JSON `dkg_status` methods report; `expected` counts the other
+// participants, whose packages are collected in each round
+fn dkg_status(round: u8, received: usize, expected: usize, complete: bool) -> String {
+    serde_json::json!({
+        "round": round,
+        "packages_received": received,
+        "packages_expected": expected,
+        "complete": complete,
+    })
+    .to_string()
+}
+
+// Parse a JSON object of participant index -> package hex for the
+// `add_round*_packages` batch methods. Fails on the first bad entry, so the
+// caller stores nothing unless every entry is valid.
+fn parse_package_map<I, T>(
+    map_json: &str,
+    parse: impl Fn(&str) -> Result<T, WasmError>,
+    identifier: impl Fn(u16) -> Result<I, WasmError>,
+) -> Result<Vec<(I, T)>, WasmError> {
+    let entries: BTreeMap<u16, String> = serde_json::from_str(map_json)
+        .map_err(|e| WasmError::new(&format!("Failed to parse package map: {}", e)))?;
+    entries
+        .into_iter()
+        .map(|(index, package_hex)| {
+            let package = parse(&package_hex)
+                .map_err(|e| WasmError::new(&format!("Package from participant {}: {}", index, e.message)))?;
+            Ok((identifier(index)?, package))
+        })
+        .collect()
+}
+
+// Decode the `{dealer index: share hex}` map a new participant collected
+// from `reshare_generate`, keyed by 1-based dealer index
+fn parse_reshare_shares<T: serde::de::DeserializeOwned>(
+    shares_json: &str,
+    index_base: u16,
+    old_total: u16,
+) -> Result<BTreeMap<u16, T>, WasmError> {
+    let shares: BTreeMap<u16, String> = serde_json::from_str(shares_json)
+        .map_err(|e| WasmError::new(&e.to_string()))?;
+    shares
+        .into_iter()
+        .map(|(dealer, share_hex)| {
+            let share = serde_json::from_slice(&decode_package(&share_hex)?)
+                .map_err(|e| WasmError::new(&format!("Failed to deserialize re-shared share: {}", e)))?;
+            Ok((normalize_index(dealer, index_base, old_total)?, share))
+        })
+        .collect()
+}
+
+// Decode the `{participant index: digest hex}` map of the digests the other
+// new participants broadcast from `reshare_receive`
+fn parse_reshare_digests(digests_json: &str, index_base: u16, new_total: u16) -> Result<BTreeMap<u16, [u8; 32]>, WasmError> {
+    let digests: BTreeMap<u16, String> = serde_json::from_str(digests_json)
+        .map_err(|e| WasmError::new(&e.to_string()))?;
+    digests
+        .into_iter()
+        .map(|(participant, digest_hex)| {
+            let digest = hex::decode(&digest_hex)
+                .ok()
+                .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                .ok_or_else(|| WasmError::new(&format!("Invalid re-share digest from participant {}", participant)))?;
+            Ok((normalize_index(participant, index_base, new_total)?, digest))
+        })
+        .collect()
+}
+
+// Key material from `reshare_receive`, held back until `reshare_approve` has
+// matched every other new participant's digest
+struct PendingReshare<K, P> {
+    key_package: K,
+    public_key_package: P,
+    participant_index: u16,
+    threshold: u16,
+    total: u16,
+}
+
+// Parse a keystore, rejecting one made for the other curve before its key
+// packages hit a confusing deserialization error
+fn parse_keystore<C: FrostCurve>(keystore_json: &str, expected_curve: &str) -> Result<KeystoreData, WasmError> {
+    if serde_json::from_str::<serde_json::Value>(keystore_json).is_ok_and(|keystore| keystore.get("encryption").is_some()) {
+        return Err(WasmError::new("Keystore is encrypted; use import_keystore_encrypted"));
+    }
+    let check_curve = |curve: &str| {
+        if curve != expected_curve {
+            return Err(WasmError::new(&format!(
+                "keystore is {} but this instance is {}",
+                curve, expected_curve
+            )));
+        }
+        Ok(())
+    };
+    match serde_json::from_str::<KeystoreData>(keystore_json) {
+        Ok(keystore_data) => {
+            check_curve(&keystore_data.curve)?;
+            Ok(keystore_data)
+        }
+        Err(error) => {
+            let cli_keystore: CliKeystore = serde_json::from_str(keystore_json)
+                .map_err(|_| WasmError::new(&error.to_string()))?;
+            check_curve(&cli_keystore.curve)?;
+            cli_keystore.into_keystore_data::<C>()
+        }
+    }
+}
+
+/// `encryption` marker of keystores from `export_keystore_encrypted`
+pub const KEYSTORE_ENCRYPTION: &str = "argon2id-aesgcm";
+
+// Keystore fields `export_keystore_encrypted` moves into the ciphertext
+const KEYSTORE_SECRET_FIELDS: [&str; 2] = ["key_package", "public_key_package"];
+
+// Argon2 costs `import_keystore_encrypted` accepts at most, so a crafted
+// keystore can't make the key derivation allocate gigabytes or run for minutes
+const MAX_KDF_M_COST_KIB: u32 = 256 * 1024;
+const MAX_KDF_T_COST: u32 = 16;
+const MAX_KDF_P_COST: u32 = 4;
+
+// Encrypt the key packages of a plaintext keystore under `password`. The
+// other fields stay readable but are authenticated with the ciphertext, so
+// editing e.g. the curve or threshold makes decryption fail.
+fn encrypt_keystore(keystore_json: &str, password: &str) -> Result<String, WasmError> {
+    if password.is_empty() {
+        return Err(WasmError::new("Keystore password must not be empty"));
+    }
+    let mut keystore: serde_json::Map<String, serde_json::Value> = serde_json::from_str(keystore_json)
+        .map_err(|e| WasmError::new(&format!("Failed to parse keystore: {}", e)))?;
+    let mut secret = serde_json::Map::new();
+    for field in KEYSTORE_SECRET_FIELDS {
+        if let Some(value) = keystore.remove(field) {
+            secret.insert(field.to_string(), value);
+        }
+    }
+    let plaintext = Zeroizing::new(serde_json::to_vec(&secret).map_err(|e| WasmError::new(&e.to_string()))?);
+
+    let mut salt = [0u8; 16];
+    let mut nonce = Nonce::<Aes256Gcm>::default();
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+    keystore.insert("encryption".to_string(), KEYSTORE_ENCRYPTION.into());
+    keystore.insert(
+        "kdf_params".to_string(),
+        serde_json::json!({
+            "m_cost": Params::DEFAULT_M_COST,
+            "t_cost": Params::DEFAULT_T_COST,
+            "p_cost": Params::DEFAULT_P_COST,
+        }),
+    );
+    keystore.insert("salt".to_string(), BASE64.encode(salt).into());
+    keystore.insert("nonce".to_string(), BASE64.encode(nonce).into());
+
+    let payload = Payload { msg: &plaintext, aad: &keystore_header(&keystore) };
+    let ciphertext = keystore_cipher(&keystore, password)?
+        .encrypt(&nonce, payload)
+        .map_err(|_| WasmError::new("Failed to encrypt keystore"))?;
+    keystore.insert("ciphertext".to_string(), BASE64.encode(ciphertext).into());
+    Ok(serde_json::Value::Object(keystore).to_string())
+}
+
+// Inverse of `encrypt_keystore`: the plaintext keystore `import_keystore` takes
+fn decrypt_keystore(keystore_json: &str, password: &str) -> Result<Zeroizing<String>, WasmError> {
+    let mut keystore: serde_json::Map<String, serde_json::Value> = serde_json::from_str(keystore_json)
+        .map_err(|e| WasmError::new(&format!("Failed to parse keystore: {}", e)))?;
+    match keystore.get("encryption").and_then(|value| value.as_str()) {
+        Some(KEYSTORE_ENCRYPTION) => {}
+        Some(other) => return Err(WasmError::new(&format!("Unsupported keystore encryption {}", other))),
+        None => return Err(WasmError::new("Keystore is not encrypted; use import_keystore")),
+    }
+    let ciphertext = keystore_bytes(&keystore, "ciphertext")?;
+    keystore.remove("ciphertext");
+    let nonce = keystore_bytes(&keystore, "nonce")?;
+    if nonce.len() != Nonce::<Aes256Gcm>::default().len() {
+        return Err(WasmError::new("Encrypted keystore nonce must be 12 bytes"));
+    }
+
+    let payload = Payload { msg: &ciphertext, aad: &keystore_header(&keystore) };
+    let plaintext = Zeroizing::new(
+        keystore_cipher(&keystore, password)?
+            .decrypt(Nonce::<Aes256Gcm>::from_slice(&nonce), payload)
+            .map_err(|_| WasmError::new("Failed to decrypt keystore: wrong password or modified keystore"))?,
+    );
+    let secret: serde_json::Map<String, serde_json::Value> = serde_json::from_slice(&plaintext)
+        .map_err(|e| WasmError::new(&format!("Failed to parse decrypted key packages: {}", e)))?;
+    for field in ["encryption", "kdf_params", "salt", "nonce"] {
+        keystore.remove(field);
+    }
+    keystore.extend(secret);
+    Ok(Zeroizing::new(serde_json::Value::Object(keystore).to_string()))
+}
+
+// Base64 field of an encrypted keystore
+fn keystore_bytes(keystore: &serde_json::Map<String, serde_json::Value>, field: &str) -> Result<Vec<u8>, WasmError> {
+    let value = keystore.get(field).and_then(|value| value.as_str())
+        .ok_or_else(|| WasmError::new(&format!("Encrypted keystore is missing {}", field)))?;
+    BASE64.decode(value).map_err(|e| WasmError::new(&format!("Failed to decode {}: {}", field, e)))
+}
+
+// The readable fields of an encrypted keystore in key order, authenticated as
+// AES-GCM associated data
+fn keystore_header(keystore: &serde_json::Map<String, serde_json::Value>) -> Vec<u8> {
+    let header: BTreeMap<&String, &serde_json::Value> = keystore.iter().collect();
+    serde_json::to_vec(&header).unwrap()
+}
+
+// AES-256-GCM keyed by Argon2id over `password`, with the salt and costs the
+// keystore records
+fn keystore_cipher(keystore: &serde_json::Map<String, serde_json::Value>, password: &str) -> Result<Aes256Gcm, WasmError> {
+    let salt = keystore_bytes(keystore, "salt")?;
+    let cost = |name: &str, max: u32| -> Result<u32, WasmError> {
+        let value = keystore.get("kdf_params").and_then(|params| params.get(name)).and_then(|value| value.as_u64())
+            .ok_or_else(|| WasmError::new(&format!("Encrypted keystore is missing kdf_params.{}", name)))?;
+        if value > u64::from(max) {
+            return Err(WasmError::new(&format!(
+                "Encrypted keystore kdf_params.{} is {}, above the limit of {}",
+                name, value, max
+            )));
+        }
+        Ok(value as u32)
+    };
+    let params = Params::new(
+        cost("m_cost", MAX_KDF_M_COST_KIB)?,
+        cost("t_cost", MAX_KDF_T_COST)?,
+        cost("p_cost", MAX_KDF_P_COST)?,
+        Some(32),
+    )
+    .map_err(|e| WasmError::new(&format!("Invalid Argon2 parameters: {}", e)))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), &salt, key.as_mut())
+        .map_err(|e| WasmError::new(&format!("Key derivation failed: {}", e)))?;
+    Ok(Aes256Gcm::new((&*key).into()))
+}
+
+/// A keystore as a CLI node writes it to a `.dat` file: the packages as raw
+/// JSON strings and none of `KeystoreData`'s index and threshold fields
+#[derive(serde::Deserialize)]
+struct CliKeystore {
+    key_package: String,
+    #[serde(alias = "public_key_package")]
+    group_public_key: String,
+    curve: String,
+}
+
+impl CliKeystore {
+    // The participant index, threshold and participant count are read from
+    // the packages themselves
+    fn into_keystore_data<C: FrostCurve>(self) -> Result<KeystoreData, WasmError> {
+        let key_package: serde_json::Value = serde_json::from_str(&self.key_package)
+            .map_err(|e| WasmError::new(&format!("Failed to parse key_package: {}", e)))?;
+        let public_key_package: serde_json::Value = serde_json::from_str(&self.group_public_key)
+            .map_err(|e| WasmError::new(&format!("Failed to parse group_public_key: {}", e)))?;
+        let total = public_key_package["verifying_shares"]
+            .as_object()
+            .and_then(|shares| u16::try_from(shares.len()).ok())
+            .ok_or_else(|| WasmError::new("group_public_key has no verifying_shares"))?;
+        let threshold = key_package["min_signers"]
+            .as_u64()
+            .and_then(|threshold| u16::try_from(threshold).ok())
+            .ok_or_else(|| WasmError::new("key_package has no min_signers"))?;
+        validate_threshold(total, threshold)?;
+        let participant_index = (1..=total)
+            .find(|&index| {
+                C::identifier_from_u16(index)
+                    .ok()
+                    .and_then(|identifier| serde_json::to_value(identifier).ok())
+                    .is_some_and(|identifier| identifier == key_package["identifier"])
+            })
+            .ok_or_else(|| WasmError::new("key_package identifier is not one of the wallet's participants"))?;
+
+        Ok(KeystoreData {
+            key_package: BASE64.encode(&self.key_package),
+            public_key_package: BASE64.encode(&self.group_public_key),
+            min_signers: threshold,
+            max_signers: total,
+            participant_index,
+            participant_indices: (1..=total).collect(),
+            curve: self.curve,
+            ciphersuite_context: None,
+            wallet_id: None,
+            device_id: None,
+            device_name: None,
+            session_id: None,
+            timestamp: None,
+        })
+    }
+}
+
+// `export_tagged_signing_package` output: the package bytes plus the
+// ciphersuite context they were built under
+fn tagged_signing_package(ciphersuite_context: String, package_bytes: &[u8]) -> String {
+    serde_json::json!({
+        "ciphersuite_context": ciphersuite_context,
+        "signing_package": hex::encode(package_bytes),
+    })
+    .to_string()
+}
+
+// Parse a signing bundle, rejecting a sender index outside the wallet's
+// participants before it reaches FROST as an unknown identifier
+fn parse_signing_bundle(bundle_json: &str, total: u16) -> Result<SigningBundle, WasmError> {
+    let bundle: SigningBundle = serde_json::from_str(bundle_json)
+        .map_err(|e| WasmError::new(&format!("Malformed signing bundle: {}", e)))?;
+    if !(1..=total).contains(&bundle.participant_index) {
+        return Err(WasmError::new(&format!(
+            "Signing bundle is from participant {}, outside this wallet's participants 1 to {}",
+            bundle.participant_index, total
+        )));
+    }
+    Ok(bundle)
+}
+
+// Catch a coordinator aggregating a different message than the one the
+// commitments were used to sign, which FROST reports as a bad share
+fn check_signing_message(signed: &Option<Vec<u8>>, message: &[u8]) -> Result<(), WasmError> {
+    match signed {
+        Some(signed) if signed.as_slice() != message => Err(WasmError::new(&format!(
+            "Message to aggregate ({}) does not match the message signed in this session ({})",
+            hex::encode(message),
+            hex::encode(signed)
+        ))),
+        _ => Ok(()),
+    }
+}
+
+// One set of nonces signs one share. A second share under the same nonces,
+// above all for another message, lets the other signers solve for our key
+// share, so a session that already signed needs a fresh `signing_commit`.
+fn check_not_signed(signed: &Option<Vec<u8>>, message: &[u8]) -> Result<(), WasmError> {
+    match signed {
+        Some(signed) if signed.as_slice() != message => Err(WasmError::new(&format!(
+            "Already signed {} in this session; call signing_commit before signing {}",
+            hex::encode(signed),
+            hex::encode(message)
+        ))),
+        Some(_) => Err(WasmError::new(
+            "Already signed this message in this session; call signing_commit to sign it again",
+        )),
+        None => Ok(()),
+    }
+}
+
+fn check_prehashed_digest(digest_hex: &str) -> Result<(), WasmError> {
+    let digest = hex::decode(digest_hex)
+        .map_err(|e| WasmError::new(&e.to_string()))?;
+    if digest.len() != 32 {
+        return Err(WasmError::new(&format!(
+            "Prehashed digest must be exactly 32 bytes, got {}",
+            digest.len()
+        )));
+    }
+    Ok(())
+}
+
+// After `clear_signing_state` the nonces and commitments are gone, which
+// `sign` would only report as missing; name the reset instead
+fn check_signing_epoch(committed: Option<u64>, current: u64) -> Result<(), WasmError> {
+    match committed {
+        Some(committed) if committed != current => Err(WasmError::new(&format!(
+            "Signing state was reset after commitment (committed at epoch {}, now epoch {}); call signing_commit again",
+            committed, current
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// `aggregate_signature` waits this long for shares, by default
+pub const DEFAULT_SIGNING_TIMEOUT_SECS: u32 = 60;
+
+// Past the deadline, a signer set with shares still missing fails naming
+// who sent one and who didn't, so the user knows whom to nudge or exclude
+fn check_signing_deadline<I: Ord>(
+    elapsed_ms: u64,
+    timeout_secs: u32,
+    signers: &[(u16, I)],
+    shares: &BTreeMap<I, impl Sized>,
+) -> Result<(), WasmError> {
+    if elapsed_ms < u64::from(timeout_secs) * 1000 {
+        return Ok(());
+    }
+    let (provided, missing): (Vec<_>, Vec<_>) = signers.iter().partition(|(_, id)| shares.contains_key(id));
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let indices = |signers: Vec<&(u16, I)>| signers.iter().map(|(index, _)| *index).collect::<Vec<_>>();
+    Err(WasmError::new(&format!(
+        "Signing timed out after {}s: shares received from {:?}, missing from {:?}",
+        elapsed_ms / 1000,
+        indices(provided),
+        indices(missing)
+    )))
+}
+
+/// `sign` refuses nonces committed longer ago than this, by default
+pub const DEFAULT_NONCE_TTL_SECS: u32 = 600;
+
+// Milliseconds since the Unix epoch
+fn now_ms() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+// Nonces from a session that stalled could otherwise be picked up again by a
+// retry and sign a second message, leaking the key share
+fn check_nonce_age(committed_at_ms: u64, now_ms: u64, ttl_secs: u32) -> Result<(), WasmError> {
+    let age_ms = now_ms.saturating_sub(committed_at_ms);
+    if age_ms > u64::from(ttl_secs) * 1000 {
+        return Err(WasmError::new(&format!(
+            "Signing nonces expired after {}s (TTL {}s); call signing_commit again",
+            age_ms / 1000,
+            ttl_secs
+        )));
+    }
+    Ok(())
+}
+
+// FROST rejects a signing package short of the threshold only once shares
+// are checked, so fail early with the counts instead
+fn check_signing_commitments<I: Ord, T>(
+    commitments: &BTreeMap<I, T>,
+    own_identifier: &I,
+    threshold: u16,
+) -> Result<(), WasmError> {
+    if commitments.len() < threshold as usize {
+        return Err(WasmError::new(&format!(
+            "Need at least {} signing commitments, have {}",
+            threshold,
+            commitments.len()
+        )));
+    }
+    if !commitments.contains_key(own_identifier) {
+        return Err(WasmError::new("Our own signing commitment has not been added"));
+    }
+    Ok(())
+}
+
+// The signer set a coordinator fixes: with a signing quorum, its own
+// commitment plus the first others to arrive at it; 0 takes every
+// commitment received. Excluded signers never take part.
+fn quorum_commitments<I: Ord + Copy, T: Clone>(
+    commitments: &BTreeMap<I, T>,
+    arrival_order: &[I],
+    quorum: u16,
+    own_identifier: &I,
+    excluded: &BTreeSet<I>,
+) -> BTreeMap<I, T> {
+    let quorum = if quorum == 0 { usize::MAX } else { quorum as usize };
+    std::iter::once(own_identifier)
+        .chain(arrival_order.iter().filter(|id| *id != own_identifier))
+        .filter(|id| !excluded.contains(id))
+        .filter_map(|id| commitments.get(id).map(|commitment| (*id, commitment.clone())))
+        .take(quorum)
+        .collect()
+}
+
+// `export_tagged_signing_package` output as `import_signing_package` reads it
+#[derive(serde::Deserialize)]
+struct TaggedSigningPackage {
+    ciphersuite_context: String,
+    signing_package: String,
+}
+
+// The 1-based index behind a failed aggregation whose culprit sent a share;
+// a signer that only failed to send one is still waited for
+fn bad_share_sender<I: Ord>(error: &FrostError, shares: &BTreeMap<I, impl Sized>, identifier: impl Fn(u16) -> Option<I>) -> Option<u16> {
+    let FrostError::InvalidContribution { participant, .. } = error else {
+        return None;
+    };
+    let index: u16 = participant.parse().ok()?;
+    shares.contains_key(&identifier(index)?).then_some(index)
+}
+
+// SHA-256 of a serialized group verifying key or public key package, short
+// enough to broadcast and compare after DKG
+fn fingerprint(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(bytes))
+}
+
+// Diverging fingerprints mean the participants hold shares of different
+// group keys, which would otherwise only show up as a failed first signing
+fn check_fingerprint(own: &str, participant_index: u16, theirs: &str) -> Result<(), WasmError> {
+    if !own.eq_ignore_ascii_case(theirs) {
+        return Err(WasmError::new(&format!(
+            "DKG failed: participant {} finalized to group key fingerprint {}, ours is {}",
+            participant_index, theirs, own
+        )));
+    }
+    Ok(())
+}
+
+/// An optional secret that is zeroized when it is replaced, cleared or
+/// dropped, instead of leaving its scalars behind in freed memory. Copies
+/// handed to FROST (e.g. the round 1 package `dkg_part2` consumes) are
+/// FROST's to wipe.
+struct Secret<T: Zeroize>(Option<T>);
+
+impl<T: Zeroize> Secret<T> {
+    fn set(&mut self, value: T) {
+        self.clear();
+        self.0 = Some(value);
+    }
+
+    fn clear(&mut self) {
+        if let Some(value) = self.0.as_mut() {
+            value.zeroize();
+        }
+        self.0 = None;
+    }
+
+    fn as_ref(&self) -> Option<&T> {
+        self.0.as_ref()
+    }
+
+    fn is_some(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+impl<T: Zeroize> Default for Secret<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// At most this many named signing sessions are kept at once; see
+/// `session_signing_commit`
+pub const MAX_SIGNING_SESSIONS: usize = 16;
+
+/// One signing session: our nonces and what the other signers sent for it
+struct SigningContext<C: Ciphersuite> {
+    nonces: Secret<SigningNonces<C>>,
+    /// When `nonces` were generated, per `clock`
+    nonces_committed_at_ms: u64,
+    /// Bumped by every `signing_commit` that draws nonces and by every
+    /// `clear_signing_state`
+    epoch: u64,
+    /// `epoch` when our nonces were drawn; kept across `clear_signing_state`
+    /// so `sign` can tell a reset from a missing commitment
+    committed_epoch: Option<u64>,
+    commitments: BTreeMap<Identifier<C>, SigningCommitments<C>>,
+    /// Senders of `commitments`, first arrival first
+    commitment_order: Vec<Identifier<C>>,
+    signature_shares: BTreeMap<Identifier<C>, SignatureShare<C>>,
+    /// The signer set the coordinator fixed for this session, see
+    /// `fix_signing_package` and `import_signing_package`
+    fixed_package: Option<SigningPackage<C>>,
+    /// Signers whose share failed to verify in this session
+    excluded_signers: BTreeSet<Identifier<C>>,
+    /// Message this participant signed in this session
+    signed_message: Option<Vec<u8>>,
+    /// When this session started, per `clock`: our commitment or the first
+    /// one received
+    started_at_ms: Option<u64>,
+}
+
+impl<C: Ciphersuite> Default for SigningContext<C> {
+    fn default() -> Self {
+        Self {
+            nonces: Secret::default(),
+            nonces_committed_at_ms: 0,
+            epoch: 0,
+            committed_epoch: None,
+            commitments: BTreeMap::new(),
+            commitment_order: Vec::new(),
+            signature_shares: BTreeMap::new(),
+            fixed_package: None,
+            excluded_signers: BTreeSet::new(),
+            signed_message: None,
+            started_at_ms: None,
+        }
+    }
+}
+
+/// The last DKG step that failed, for `dkg_diagnostics`
+struct DkgFailure {
+    step: &'static str,
+    error: String,
+    at_ms: u64,
+}
+
+/// Timeline of the current DKG for `dkg_diagnostics`, reset by `init_dkg`
+struct DkgLog<I> {
+    started_at_ms: Option<u64>,
+    /// Our round 1 package, so the transcript hash covers every participant
+    own_round1_package: Option<serde_json::Value>,
+    /// When each sender's package arrived, per `clock`
+    round1_received_at_ms: BTreeMap<I, u64>,
+    round2_received_at_ms: BTreeMap<I, u64>,
+    failure: Option<DkgFailure>,
+}
+
+impl<I> Default for DkgLog<I> {
+    fn default() -> Self {
+        Self {
+            started_at_ms: None,
+            own_round1_package: None,
+            round1_received_at_ms: BTreeMap::new(),
+            round2_received_at_ms: BTreeMap::new(),
+            failure: None,
+        }
+    }
+}
+
+/// The ceremony a `DkgLog` belongs to
+struct DkgSnapshot {
+    curve: &'static str,
+    phase: &'static str,
+    participant_index: u16,
+    index_base: u16,
+    threshold: u16,
+    total: u16,
+    now_ms: u64,
+}
+
+impl<I: Ord> DkgLog<I> {
+    /// The `dkg_diagnostics` report; `identifiers` pairs each 1-based index
+    /// with its FROST identifier
+    fn report<P: serde::Serialize>(
+        &self,
+        dkg: &DkgSnapshot,
+        identifiers: &[(u16, I)],
+        round1_packages: &BTreeMap<I, P>,
+    ) -> String {
+        let external = |index: u16| external_index(index, dkg.index_base).unwrap_or(index);
+        let collecting_round1 = matches!(dkg.phase, "initialized" | "round1");
+        let collecting_round2 = dkg.phase == "round2";
+        let others = || identifiers.iter().filter(|(index, _)| *index != dkg.participant_index);
+        let missing_round1: Vec<u16> = others()
+            .filter(|(_, id)| collecting_round1 && !self.round1_received_at_ms.contains_key(id))
+            .map(|(index, _)| external(*index))
+            .collect();
+        let missing_round2: Vec<u16> = others()
+            .filter(|(_, id)| collecting_round2 && !self.round2_received_at_ms.contains_key(id))
+            .map(|(index, _)| external(*index))
+            .collect();
+
+        let participants: Vec<serde_json::Value> = identifiers
+            .iter()
+            .map(|(index, id)| {
+                let is_self = *index == dkg.participant_index;
+                let round1_at = self.round1_received_at_ms.get(id);
+                let round2_at = self.round2_received_at_ms.get(id);
+                let status = if is_self {
+                    "self"
+                } else if collecting_round1 && round1_at.is_none() {
+                    "waiting for round 1 package"
+                } else if collecting_round2 && round2_at.is_none() {
+                    "waiting for round 2 package"
+                } else {
+                    "ok"
+                };
+                serde_json::json!({
+                    "index": external(*index),
+                    "is_self": is_self,
+                    "round1_received_at_ms": round1_at,
+                    "round2_received_at_ms": round2_at,
+                    "status": status,
+                })
+            })
+            .collect();
+
+        // Hash of every round 1 package in participant order; it only
+        // matches across participants once they all hold the same packages
+        let transcript: Vec<Option<serde_json::Value>> = identifiers
+            .iter()
+            .map(|(index, id)| {
+                if *index == dkg.participant_index {
+                    self.own_round1_package.clone()
+                } else {
+                    round1_packages.get(id).and_then(|package| serde_json::to_value(package).ok())
+                }
+            })
+            .collect();
+        let transcript_hash = fingerprint(&serde_json::to_vec(&transcript).unwrap_or_default());
+
+        let mut next_steps = Vec::new();
+        match dkg.phase {
+            "uninitialized" => next_steps.push("Call init_dkg to start a ceremony".to_string()),
+            "initialized" => next_steps.push("Call generate_round1 and broadcast the package".to_string()),
+            _ => {}
+        }
+        if !missing_round1.is_empty() {
+            next_steps.push(format!(
+                "Waiting for round 1 packages from participants {:?}; check they are connected or ask them to resend",
+                missing_round1
+            ));
+        } else if dkg.phase == "round1" {
+            next_steps.push("Call generate_round2 and send each participant its package".to_string());
+        }
+        if !missing_round2.is_empty() {
+            next_steps.push(format!(
+                "Waiting for round 2 packages from participants {:?}; check they are connected or ask them to resend",
+                missing_round2
+            ));
+        } else if collecting_round2 {
+            next_steps.push("Call finalize_dkg".to_string());
+        }
+        if let Some(failure) = &self.failure {
+            next_steps.push(format!(
+                "{} failed; if a package was rejected, compare transcript_hash with the other participants and restart with init_dkg",
+                failure.step
+            ));
+        }
+
+        serde_json::json!({
+            "curve": dkg.curve,
+            "phase": dkg.phase,
+            "participant_index": external(dkg.participant_index),
+            "threshold": dkg.threshold,
+            "total_participants": dkg.total,
+            "started_at_ms": self.started_at_ms,
+            "generated_at_ms": dkg.now_ms,
+            "participants": participants,
+            "missing_round1": missing_round1,
+            "missing_round2": missing_round2,
+            "failure": self.failure.as_ref().map(|failure| serde_json::json!({
+                "step": failure.step,
+                "error": failure.error,
+                "at_ms": failure.at_ms,
+            })),
+            "transcript_hash": transcript_hash,
+            "next_steps": next_steps,
+        })
+        .to_string()
+    }
+}
+
 // Ed25519 WASM wrapper
 #[wasm_bindgen]
 pub struct FrostDkgEd25519 {
-    round1_secret: Option<frost_ed25519::keys::dkg::round1::SecretPackage>,
-    round2_secret: Option<frost_ed25519::keys::dkg::round2::SecretPackage>,
+    // The DKG secret packages and signing nonces are zeroized on drop and
+    // whenever they are replaced or cleared; see `Secret`
+    round1_secret: Secret<frost_ed25519::keys::dkg::round1::SecretPackage>,
+    round2_secret: Secret<frost_ed25519::keys::dkg::round2::SecretPackage>,
     key_package: Option<Ed25519KeyPackage>,
     public_key_package: Option<Ed25519PublicKeyPackage>,
     round1_packages: BTreeMap<Ed25519Identifier, frost_ed25519::keys::dkg::round1::Package>,
     round2_packages: BTreeMap<Ed25519Identifier, frost_ed25519::keys::dkg::round2::Package>,
-    signing_nonces: Option<Ed25519SigningNonces>,
-    signing_commitments: BTreeMap<Ed25519Identifier, Ed25519SigningCommitments>,
-    signature_shares: BTreeMap<Ed25519Identifier, Ed25519SignatureShare>,
+    /// The signing session the un-prefixed signing methods act on
+    signing: SigningContext<frost_ed25519::Ed25519Sha512>,
+    /// Named sessions for the `session_*` methods, at most `MAX_SIGNING_SESSIONS`
+    signing_sessions: BTreeMap<String, SigningContext<frost_ed25519::Ed25519Sha512>>,
+    /// Sign with the first this many commitments; 0 waits for all of them
+    signing_quorum: u16,
     participant_indices: Vec<u16>,
     threshold: u16,
     total: u16,
     participant_index: u16,
+    /// Deflate round 2 packages from `generate_round2`
+    compress_packages: bool,
+    /// What callers number the first participant; indices are stored 1-based
+    index_base: u16,
+    nonce_ttl_secs: u32,
+    signing_timeout_secs: u32,
+    /// Milliseconds since the Unix epoch; replaced in tests
+    clock: fn() -> u64,
+    /// Source of DKG coefficients and signing nonces; seeded in tests
+    rng: Box<dyn CryptoRngCore>,
+    /// Participants whose group key fingerprint matched ours
+    confirmed_fingerprints: BTreeSet<u16>,
+    /// Set by `finalize_dkg` until every participant has confirmed the group
+    /// key; the keystore is withheld meanwhile
+    awaiting_group_key_confirmation: bool,
+    /// Re-shared key material awaiting `reshare_approve`
+    pending_reshare: Option<PendingReshare<Ed25519KeyPackage, Ed25519PublicKeyPackage>>,
+    /// Packages, timestamps and failures of the current DKG, see `dkg_diagnostics`
+    dkg_log: DkgLog<Ed25519Identifier>,
 }
 
 #[wasm_bindgen]
@@ -88,42 +1012,95 @@ impl FrostDkgEd25519 {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
         Self {
-            round1_secret: None,
-            round2_secret: None,
+            round1_secret: Secret::default(),
+            round2_secret: Secret::default(),
             key_package: None,
             public_key_package: None,
             round1_packages: BTreeMap::new(),
             round2_packages: BTreeMap::new(),
-            signing_nonces: None,
-            signing_commitments: BTreeMap::new(),
-            signature_shares: BTreeMap::new(),
+            signing: SigningContext::default(),
+            signing_sessions: BTreeMap::new(),
+            signing_quorum: 0,
             participant_indices: Vec::new(),
             threshold: 0,
             total: 0,
             participant_index: 0,
+            compress_packages: false,
+            index_base: 1,
+            nonce_ttl_secs: DEFAULT_NONCE_TTL_SECS,
+            signing_timeout_secs: DEFAULT_SIGNING_TIMEOUT_SECS,
+            clock: now_ms,
+            rng: Box::new(OsRng),
+            confirmed_fingerprints: BTreeSet::new(),
+            awaiting_group_key_confirmation: false,
+            pending_reshare: None,
+            dkg_log: DkgLog::default(),
         }
     }
 
+    /// Compress the packages `generate_round2` produces. Off by default, since
+    /// only peers running this version can read compressed packages;
+    /// `add_round2_package` accepts either form regardless.
+    pub fn set_package_compression(&mut self, enabled: bool) {
+        self.compress_packages = enabled;
+    }
+
+    /// Number participants from `base` instead of 1, e.g. 0 for coordinators
+    /// with 0-based indices. Every index passed in is shifted to the 1-based
+    /// index FROST uses, and the keys of `generate_round2`'s map are shifted
+    /// back; the getters keep reporting 1-based indices.
+    pub fn set_index_base(&mut self, base: u16) {
+        self.index_base = base;
+    }
+
+    /// How long nonces from `signing_commit` stay usable by `sign`
+    pub fn set_nonce_ttl_secs(&mut self, ttl_secs: u32) {
+        self.nonce_ttl_secs = ttl_secs;
+    }
+
+    /// How long `aggregate_signature` waits for the signers' shares before
+    /// failing with `check_signing_timeout`'s report
+    pub fn set_signing_timeout_secs(&mut self, timeout_secs: u32) {
+        self.signing_timeout_secs = timeout_secs;
+    }
+
+    /// Sign with `quorum` signers instead of everyone who commits, e.g. 2
+    /// for a 2-of-5 wallet with more peers online. Commitments arrive in a
+    /// different order at every node, so only the coordinator picks the
+    /// signers (`fix_signing_package`) and the others sign the package it
+    /// distributes (`import_signing_package`). 0 turns the quorum off. Set
+    /// it after DKG or `import_keystore`.
+    pub fn set_signing_quorum(&mut self, quorum: u16) -> Result<(), WasmError> {
+        if quorum != 0 && !(self.threshold..=self.total).contains(&quorum) {
+            return Err(WasmError::new(&format!(
+                "Signing quorum must be between the threshold ({}) and total participants ({})",
+                self.threshold, self.total
+            )));
+        }
+        self.signing_quorum = quorum;
+        Ok(())
+    }
+
     pub fn init_dkg(&mut self, participant_index: u16, total: u16, threshold: u16) -> Result<(), WasmError> {
-        self.participant_index = participant_index;
+        self.participant_index = validate_dkg_params(participant_index, self.index_base, total, threshold)?;
         self.total = total;
         self.threshold = threshold;
         self.participant_indices = (1..=total).collect();
+        self.dkg_log = DkgLog { started_at_ms: Some((self.clock)()), ..DkgLog::default() };
         Ok(())
     }
 
     pub fn generate_round1(&mut self) -> Result<String, WasmError> {
         let identifier = Ed25519Curve::identifier_from_u16(self.participant_index)?;
-        let mut rng = OsRng;
-        
         let (round1_secret, round1_package) = Ed25519Curve::dkg_part1(
             identifier,
             self.total,
             self.threshold,
-            &mut rng,
+            self.rng.as_mut(),
         )?;
         
-        self.round1_secret = Some(round1_secret);
+        self.round1_secret.set(round1_secret);
+        self.dkg_log.own_round1_package = serde_json::to_value(&round1_package).ok();
         let package_json = serde_json::to_string(&round1_package)
             .map_err(|e| WasmError::new(&e.to_string()))?;
         
@@ -131,14 +1108,30 @@ impl FrostDkgEd25519 {
     }
 
     pub fn add_round1_package(&mut self, participant_index: u16, package_hex: &str) -> Result<(), WasmError> {
-        let package_json = hex::decode(package_hex)
-            .map_err(|e| WasmError::new(&e.to_string()))?;
-        let package: frost_ed25519::keys::dkg::round1::Package = serde_json::from_slice(&package_json)
-            .map_err(|e| WasmError::new(&e.to_string()))?;
+        self.tracked("add_round1_package", |dkg| {
+            let package: frost_ed25519::keys::dkg::round1::Package = parse_round1_package(package_hex)?;
         
-        let identifier = Ed25519Curve::identifier_from_u16(participant_index)?;
-        self.round1_packages.insert(identifier, package);
-        Ok(())
+            let identifier = Ed25519Curve::identifier_from_u16(dkg.normalize_index(participant_index)?)?;
+            dkg.round1_packages.insert(identifier, package);
+            dkg.dkg_log.round1_received_at_ms.insert(identifier, (dkg.clock)());
+            Ok(())
+        })
+    }
+
+    /// Add round 1 packages from a JSON object of participant index ->
+    /// package hex. Nothing is stored unless every entry is valid.
+    pub fn add_round1_packages(&mut self, map_json: &str) -> Result<(), WasmError> {
+        self.tracked("add_round1_packages", |dkg| {
+            let packages = parse_package_map(
+                map_json,
+                parse_round1_package::<frost_ed25519::keys::dkg::round1::Package>,
+                |index| Ok(Ed25519Curve::identifier_from_u16(dkg.normalize_index(index)?)?),
+            )?;
+            let now = (dkg.clock)();
+            dkg.dkg_log.round1_received_at_ms.extend(packages.iter().map(|(identifier, _)| (*identifier, now)));
+            dkg.round1_packages.extend(packages);
+            Ok(())
+        })
     }
 
     pub fn can_start_round2(&self) -> bool {
@@ -146,93 +1139,480 @@ impl FrostDkgEd25519 {
     }
 
     pub fn generate_round2(&mut self) -> Result<String, WasmError> {
-        let round1_secret = self.round1_secret.clone()
-            .ok_or_else(|| WasmError::new("Round 1 secret not available"))?;
+        self.tracked("generate_round2", |dkg| {
+            let round1_secret = dkg.round1_secret.as_ref().cloned()
+                .ok_or_else(|| WasmError::new("Round 1 secret not available"))?;
         
-        let (round2_secret, round2_packages) = Ed25519Curve::dkg_part2(
-            round1_secret,
-            &self.round1_packages,
-        )?;
+            let (round2_secret, round2_packages) = Ed25519Curve::dkg_part2(
+                round1_secret,
+                &dkg.round1_packages,
+            )?;
         
-        self.round2_secret = Some(round2_secret);
+            dkg.round2_secret.set(round2_secret);
         
-        let mut packages_map = BTreeMap::new();
-        for (id, package) in round2_packages {
-            let id_value = id.serialize()[31] as u16 | ((id.serialize()[30] as u16) << 8);
-            packages_map.insert(id_value, hex::encode(serde_json::to_string(&package).unwrap()));
-        }
+            let mut packages_map = BTreeMap::new();
+            for (id, package) in round2_packages {
+                let id_value = id.serialize()[31] as u16 | ((id.serialize()[30] as u16) << 8);
+                packages_map.insert(external_index(id_value, dkg.index_base)?, encode_package(&serde_json::to_string(&package).unwrap(), dkg.compress_packages)?);
+            }
         
-        Ok(serde_json::to_string(&packages_map).unwrap())
+            Ok(serde_json::to_string(&packages_map).unwrap())
+        })
     }
 
     pub fn add_round2_package(&mut self, sender_index: u16, package_hex: &str) -> Result<(), WasmError> {
-        let package_json = hex::decode(package_hex)
-            .map_err(|e| WasmError::new(&e.to_string()))?;
-        let package: frost_ed25519::keys::dkg::round2::Package = serde_json::from_slice(&package_json)
-            .map_err(|e| WasmError::new(&e.to_string()))?;
+        self.tracked("add_round2_package", |dkg| {
+            let package: frost_ed25519::keys::dkg::round2::Package = parse_round2_package(package_hex)?;
         
-        let identifier = Ed25519Curve::identifier_from_u16(sender_index)?;
-        self.round2_packages.insert(identifier, package);
-        Ok(())
+            let identifier = Ed25519Curve::identifier_from_u16(dkg.normalize_index(sender_index)?)?;
+            dkg.round2_packages.insert(identifier, package);
+            dkg.dkg_log.round2_received_at_ms.insert(identifier, (dkg.clock)());
+            Ok(())
+        })
+    }
+
+    /// Add round 2 packages from a JSON object of sender index -> package
+    /// hex, as `generate_round2` returns them. Nothing is stored unless every
+    /// entry is valid.
+    pub fn add_round2_packages(&mut self, map_json: &str) -> Result<(), WasmError> {
+        self.tracked("add_round2_packages", |dkg| {
+            let packages = parse_package_map(
+                map_json,
+                parse_round2_package::<frost_ed25519::keys::dkg::round2::Package>,
+                |index| Ok(Ed25519Curve::identifier_from_u16(dkg.normalize_index(index)?)?),
+            )?;
+            let now = (dkg.clock)();
+            dkg.dkg_log.round2_received_at_ms.extend(packages.iter().map(|(identifier, _)| (*identifier, now)));
+            dkg.round2_packages.extend(packages);
+            Ok(())
+        })
     }
 
     pub fn can_finalize(&self) -> bool {
-        self.round2_packages.len() >= (self.threshold - 1) as usize && self.round2_secret.is_some()
+        // dkg_part3 needs a round 2 package from every other participant, not just threshold - 1
+        self.round2_packages.len() == (self.total as usize).saturating_sub(1) && self.round2_secret.is_some()
+    }
+
+    /// DKG progress for UI polling as JSON `{round, packages_received,
+    /// packages_expected, complete}`: round 0 before `init_dkg`, then the
+    /// round whose packages are being collected
+    pub fn dkg_status(&self) -> String {
+        let expected = (self.total as usize).saturating_sub(1);
+        let (round, received) = if self.is_dkg_complete() {
+            (2, expected)
+        } else if self.participant_index == 0 {
+            (0, 0)
+        } else if self.round2_secret.is_some() {
+            (2, self.round2_packages.len())
+        } else {
+            (1, self.round1_packages.len())
+        };
+        dkg_status(round, received, expected, self.is_dkg_complete())
+    }
+
+    /// Report for debugging a stalled or failed DKG, as JSON: phase, who
+    /// sent which packages and when, missing packages, the last failed step
+    /// with its error, a transcript hash to compare across participants, and
+    /// suggested next steps
+    pub fn dkg_diagnostics(&self) -> String {
+        let identifiers: Vec<_> = (1..=self.total)
+            .filter_map(|index| Some((index, Ed25519Curve::identifier_from_u16(index).ok()?)))
+            .collect();
+        let snapshot = DkgSnapshot {
+            curve: "ed25519",
+            phase: self.dkg_phase(),
+            participant_index: self.participant_index,
+            index_base: self.index_base,
+            threshold: self.threshold,
+            total: self.total,
+            now_ms: (self.clock)(),
+        };
+        self.dkg_log.report(&snapshot, &identifiers, &self.round1_packages)
     }
 
+    /// Finalize DKG and return the group key fingerprint to broadcast. The
+    /// keystore can only be exported once `add_peer_fingerprint` has matched
+    /// every other participant's fingerprint.
     pub fn finalize_dkg(&mut self) -> Result<String, WasmError> {
-        let round2_secret = self.round2_secret.as_ref()
-            .ok_or_else(|| WasmError::new("Round 2 secret not available"))?;
+        self.tracked("finalize_dkg", |dkg| {
+            let round2_secret = dkg.round2_secret.as_ref()
+                .ok_or_else(|| WasmError::new("Round 2 secret not available"))?;
         
-        let (key_package, public_key_package) = Ed25519Curve::dkg_part3(
-            round2_secret,
-            &self.round1_packages,
-            &self.round2_packages,
-        )?;
+            let (key_package, public_key_package) = Ed25519Curve::dkg_part3(
+                round2_secret,
+                &dkg.round1_packages,
+                &dkg.round2_packages,
+            )?;
         
-        self.key_package = Some(key_package.clone());
-        self.public_key_package = Some(public_key_package.clone());
+            dkg.key_package = Some(key_package);
+            dkg.public_key_package = Some(public_key_package);
+            dkg.confirmed_fingerprints.clear();
+            dkg.awaiting_group_key_confirmation = true;
         
-        let keystore_data = Keystore::export_keystore::<Ed25519Curve>(
-            &key_package,
-            &public_key_package,
-            self.threshold,
-            self.total,
-            self.participant_index,
-            self.participant_indices.clone(),
-            "ed25519",
-        )?;
+            dkg.group_key_fingerprint()
+        })
+    }
+
+    pub fn get_group_public_key(&self) -> Result<String, WasmError> {
+        let public_key_package = self.public_key_package.as_ref()
+            .ok_or_else(|| WasmError::new("DKG not complete"))?;
         
-        Ok(serde_json::to_string(&keystore_data).unwrap())
+        let verifying_key = Ed25519Curve::verifying_key(public_key_package);
+        let key_bytes = Ed25519Curve::serialize_verifying_key(&verifying_key)?;
+        Ok(hex::encode(key_bytes))
+    }
+
+    /// Fingerprint of the group key to broadcast once DKG finalizes
+    pub fn group_key_fingerprint(&self) -> Result<String, WasmError> {
+        let public_key_package = self.public_key_package.as_ref()
+            .ok_or_else(|| WasmError::new("DKG not complete"))?;
+
+        let verifying_key = Ed25519Curve::verifying_key(public_key_package);
+        Ok(fingerprint(&Ed25519Curve::serialize_verifying_key(&verifying_key)?))
+    }
+
+    /// Canonical serialization (hex) of the public key package: the group
+    /// key plus every participant's verifying share
+    pub fn export_public_key_package(&self) -> Result<String, WasmError> {
+        Ok(hex::encode(self.public_key_package_bytes()?))
+    }
+
+    /// Fingerprint of `export_public_key_package`, for a coordinator to diff
+    /// across nodes when aggregation fails. Every participant of one DKG must
+    /// report the same value; a mismatch means DKG packages were misrouted,
+    /// even if `group_key_fingerprint` agrees.
+    pub fn public_key_package_fingerprint(&self) -> Result<String, WasmError> {
+        Ok(fingerprint(&self.public_key_package_bytes()?))
+    }
+
+    fn public_key_package_bytes(&self) -> Result<Vec<u8>, WasmError> {
+        self.public_key_package.as_ref()
+            .ok_or_else(|| WasmError::new("DKG not complete"))?
+            .serialize()
+            .map_err(|e| WasmError::new(&e.to_string()))
+    }
+
+    /// Compare a peer's broadcast fingerprint with ours. On a mismatch the
+    /// key material is discarded so nothing can be signed with it.
+    pub fn add_peer_fingerprint(&mut self, participant_index: u16, fingerprint: &str) -> Result<(), WasmError> {
+        self.tracked("add_peer_fingerprint", |dkg| {
+            let own = dkg.group_key_fingerprint()?;
+            let index = dkg.normalize_index(participant_index)?;
+            if index == dkg.participant_index {
+                return Err(WasmError::new("A fingerprint from our own participant index confirms nothing"));
+            }
+            if let Err(error) = check_fingerprint(&own, participant_index, fingerprint) {
+                dkg.key_package = None;
+                dkg.public_key_package = None;
+                dkg.clear_signing_state();
+                return Err(error);
+            }
+            dkg.confirmed_fingerprints.insert(index);
+            if dkg.is_group_key_confirmed() {
+                dkg.awaiting_group_key_confirmation = false;
+            }
+            Ok(())
+        })
+    }
+
+    /// Every other participant has reported the same group key as ours
+    pub fn is_group_key_confirmed(&self) -> bool {
+        self.is_dkg_complete() && self.confirmed_fingerprints.len() == (self.total as usize).saturating_sub(1)
+    }
+
+    pub fn get_address(&self) -> Result<String, WasmError> {
+        let public_key_package = self.public_key_package.as_ref()
+            .ok_or_else(|| WasmError::new("DKG not complete"))?;
+        
+        let verifying_key = Ed25519Curve::verifying_key(public_key_package);
+        Ok(Ed25519Curve::get_address(&verifying_key))
+    }
+
+    pub fn is_dkg_complete(&self) -> bool {
+        self.key_package.is_some() && self.public_key_package.is_some()
+    }
+
+    /// This instance's own 1-based participant index; 0 before `init_dkg`
+    pub fn participant_index(&self) -> u16 {
+        self.participant_index
+    }
+
+    pub fn threshold(&self) -> u16 {
+        self.threshold
+    }
+
+    pub fn total_participants(&self) -> u16 {
+        self.total
+    }
+
+    /// Indices of every participant in the wallet, including this one
+    pub fn participant_indices(&self) -> Vec<u16> {
+        self.participant_indices.clone()
+    }
+
+    /// Deal this participant's part of the group key to a new group of
+    /// `new_total` participants, any `new_threshold` of which can sign for
+    /// the same address. Each of `dealers` (at least the current threshold,
+    /// this participant among them) calls this with the same list. Returns
+    /// JSON mapping each new participant's index to the share to send it.
+    pub fn reshare_generate(&self, dealers: Vec<u16>, new_total: u16, new_threshold: u16) -> Result<String, WasmError> {
+        let key_package = self.key_package.as_ref()
+            .ok_or_else(|| WasmError::new("DKG not complete"))?;
+        validate_threshold(new_total, new_threshold)?;
+        let dealers = dealers
+            .into_iter()
+            .map(|index| Ok(Ed25519Curve::identifier_from_u16(self.normalize_index(index)?)?))
+            .collect::<Result<Vec<_>, WasmError>>()?;
+        let new_participants = (1..=new_total)
+            .map(Ed25519Curve::identifier_from_u16)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let shares = reshare_step1(key_package, &dealers, &new_participants, new_threshold, &mut OsRng)?;
+        let mut shares_map = BTreeMap::new();
+        for (index, identifier) in (1..=new_total).zip(&new_participants) {
+            shares_map.insert(external_index(index, self.index_base)?, encode_package(&serde_json::to_string(&shares[identifier]).unwrap(), false)?);
+        }
+        Ok(serde_json::to_string(&shares_map).unwrap())
+    }
+
+    /// Take up the key as participant `participant_index` of the group set up
+    /// by `reshare_generate`. `public_key_package_hex` is the old group's
+    /// `export_public_key_package` and `shares_json` maps each dealer's index
+    /// to the share it sent here. Returns the digest of the re-shared public
+    /// key package to broadcast to the other new participants; the key
+    /// material this instance held stays in use until `reshare_approve`.
+    pub fn reshare_receive(
+        &mut self,
+        participant_index: u16,
+        new_total: u16,
+        new_threshold: u16,
+        public_key_package_hex: &str,
+        shares_json: &str,
+    ) -> Result<String, WasmError> {
+        let index = validate_dkg_params(participant_index, self.index_base, new_total, new_threshold)?;
+        let public_key_package_bytes = hex::decode(public_key_package_hex)
+            .map_err(|e| WasmError::new(&e.to_string()))?;
+        let old_public_key_package = Ed25519PublicKeyPackage::deserialize(&public_key_package_bytes)
+            .map_err(|e| WasmError::new(&e.to_string()))?;
+        let old_total = u16::try_from(old_public_key_package.verifying_shares().len()).unwrap_or(u16::MAX);
+        let shares = parse_reshare_shares::<frost_ed25519::keys::SecretShare>(shares_json, self.index_base, old_total)?
+            .into_iter()
+            .map(|(dealer, share)| Ok((Ed25519Curve::identifier_from_u16(dealer)?, share)))
+            .collect::<Result<BTreeMap<_, _>, WasmError>>()?;
+        let new_participants = (1..=new_total)
+            .map(Ed25519Curve::identifier_from_u16)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (key_package, public_key_package) = reshare_step2(
+            Ed25519Curve::identifier_from_u16(index)?,
+            &old_public_key_package,
+            &new_participants,
+            new_threshold,
+            &shares,
+        )?;
+        let digest = fingerprint(
+            &public_key_package.serialize().map_err(|e| WasmError::new(&e.to_string()))?,
+        );
+        self.pending_reshare = Some(PendingReshare {
+            key_package,
+            public_key_package,
+            participant_index: index,
+            threshold: new_threshold,
+            total: new_total,
+        });
+        Ok(digest)
+    }
+
+    /// Switch to the key material from `reshare_receive` once `digests_json`,
+    /// mapping every other new participant's index to the digest it
+    /// broadcast, matches ours. On a mismatch the re-shared key is discarded
+    /// and the old one stays in use. Returns the new keystore like
+    /// `finalize_dkg`.
+    pub fn reshare_approve(&mut self, digests_json: &str) -> Result<String, WasmError> {
+        let pending = self.pending_reshare.take()
+            .ok_or_else(|| WasmError::new("No re-shared key awaiting approval"))?;
+        let digests = parse_reshare_digests(digests_json, self.index_base, pending.total)?
+            .into_iter()
+            .map(|(index, digest)| Ok((Ed25519Curve::identifier_from_u16(index)?, digest)))
+            .collect::<Result<BTreeMap<_, _>, WasmError>>()?;
+        let new_participants = (1..=pending.total)
+            .map(Ed25519Curve::identifier_from_u16)
+            .collect::<Result<Vec<_>, _>>()?;
+        reshare_step3(
+            Ed25519Curve::identifier_from_u16(pending.participant_index)?,
+            &pending.public_key_package,
+            &new_participants,
+            &digests,
+        )?;
+
+        self.clear_signing_state();
+        self.key_package = Some(pending.key_package);
+        self.public_key_package = Some(pending.public_key_package);
+        self.confirmed_fingerprints.clear();
+        self.awaiting_group_key_confirmation = false;
+        self.threshold = pending.threshold;
+        self.total = pending.total;
+        self.participant_index = pending.participant_index;
+        self.participant_indices = (1..=pending.total).collect();
+        self.export_keystore()
+    }
+
+    /// Drop nonces past their TTL so the next `sign` needs a fresh commitment
+    fn discard_stale_nonces(&mut self) -> Result<(), WasmError> {
+        if self.signing.nonces.is_some()
+            && let Err(error) = check_nonce_age(self.signing.nonces_committed_at_ms, (self.clock)(), self.nonce_ttl_secs)
+        {
+            self.signing.nonces.clear();
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    fn normalize_index(&self, index: u16) -> Result<u16, WasmError> {
+        normalize_index(index, self.index_base, self.total)
+    }
+
+    /// Run a DKG step, recording its error for `dkg_diagnostics`
+    fn tracked<T>(
+        &mut self,
+        step: &'static str,
+        f: impl FnOnce(&mut Self) -> Result<T, WasmError>,
+    ) -> Result<T, WasmError> {
+        let result = f(self);
+        if let Err(error) = &result {
+            self.dkg_log.failure = Some(DkgFailure { step, error: error.message.clone(), at_ms: (self.clock)() });
+        }
+        result
+    }
+
+    fn dkg_phase(&self) -> &'static str {
+        if self.participant_index == 0 {
+            "uninitialized"
+        } else if self.is_dkg_complete() {
+            "complete"
+        } else if self.round2_secret.is_some() {
+            "round2"
+        } else if self.round1_secret.is_some() {
+            "round1"
+        } else {
+            "initialized"
+        }
+    }
+
+    /// The fixed signing package if there is one; without a quorum, the
+    /// package over every commitment received, which is the same at every node
+    fn signing_package(&self, message: &[u8]) -> Result<frost_ed25519::SigningPackage, WasmError> {
+        if let Some(package) = &self.signing.fixed_package {
+            if package.message() != message {
+                return Err(WasmError::new("Message does not match the fixed signing package"));
+            }
+            return Ok(package.clone());
+        }
+        if self.signing_quorum != 0 {
+            return Err(WasmError::new(
+                "With a signing quorum, sign the package the coordinator fixed; see import_signing_package",
+            ));
+        }
+        self.select_signing_package(message)
+    }
+
+    fn select_signing_package(&self, message: &[u8]) -> Result<frost_ed25519::SigningPackage, WasmError> {
+        let own_identifier = Ed25519Curve::identifier_from_u16(self.participant_index)?;
+        let commitments = quorum_commitments(
+            &self.signing.commitments,
+            &self.signing.commitment_order,
+            self.signing_quorum,
+            &own_identifier,
+            &self.signing.excluded_signers,
+        );
+        check_signing_commitments(&commitments, &own_identifier, self.threshold)?;
+        Ok(Ed25519Curve::create_signing_package(&commitments, message)?)
     }
 
-    pub fn get_group_public_key(&self) -> Result<String, WasmError> {
-        let public_key_package = self.public_key_package.as_ref()
-            .ok_or_else(|| WasmError::new("DKG not complete"))?;
-        
-        let verifying_key = Ed25519Curve::verifying_key(public_key_package);
-        let key_bytes = Ed25519Curve::serialize_verifying_key(&verifying_key)?;
-        Ok(hex::encode(key_bytes))
+    /// Shares from the signers in `signing_package`; shares from anyone else
+    /// are dropped rather than failing aggregation
+    fn quorum_shares(&self, signing_package: &frost_ed25519::SigningPackage) -> BTreeMap<Ed25519Identifier, Ed25519SignatureShare> {
+        self.signing.signature_shares
+            .iter()
+            .filter(|(id, _)| signing_package.signing_commitments().contains_key(id))
+            .map(|(id, share)| (*id, *share))
+            .collect()
     }
 
-    pub fn get_address(&self) -> Result<String, WasmError> {
-        let public_key_package = self.public_key_package.as_ref()
-            .ok_or_else(|| WasmError::new("DKG not complete"))?;
-        
-        let verifying_key = Ed25519Curve::verifying_key(public_key_package);
-        Ok(Ed25519Curve::get_address(&verifying_key))
+    /// As coordinator, fix the signers for `message_hex`: our own commitment
+    /// plus, with a quorum, the first others to arrive here. Returns the
+    /// package as `export_tagged_signing_package` does, for every signer to
+    /// pass to `import_signing_package`.
+    pub fn fix_signing_package(&mut self, message_hex: &str) -> Result<String, WasmError> {
+        let message = hex::decode(message_hex)
+            .map_err(|e| WasmError::new(&e.to_string()))?;
+        self.signing.fixed_package = Some(self.select_signing_package(&message)?);
+        self.export_tagged_signing_package(message_hex)
     }
 
-    pub fn is_dkg_complete(&self) -> bool {
-        self.key_package.is_some() && self.public_key_package.is_some()
+    /// Sign the package the coordinator fixed with `fix_signing_package`
+    /// instead of one built from the commitments that reached us. Fails if
+    /// we are not among its signers or it carries another commitment for us.
+    pub fn import_signing_package(&mut self, package_json: &str) -> Result<(), WasmError> {
+        let tagged: TaggedSigningPackage = serde_json::from_str(package_json)
+            .map_err(|e| WasmError::new(&format!("Malformed signing package: {}", e)))?;
+        check_ciphersuite_context::<Ed25519Curve>(&tagged.ciphersuite_context)?;
+        let bytes = hex::decode(&tagged.signing_package)
+            .map_err(|e| WasmError::new(&e.to_string()))?;
+        let package = frost_ed25519::SigningPackage::deserialize(&bytes)
+            .map_err(|e| WasmError::new(&e.to_string()))?;
+
+        let own_identifier = Ed25519Curve::identifier_from_u16(self.participant_index)?;
+        let nonces = self.signing.nonces.as_ref()
+            .ok_or_else(|| WasmError::new("Signing nonces not available"))?;
+        match package.signing_commitments().get(&own_identifier) {
+            None => return Err(WasmError::new("The coordinator did not select us to sign")),
+            Some(commitment) if commitment != nonces.commitments() => {
+                return Err(WasmError::new("The signing package carries a different commitment for us"));
+            }
+            Some(_) => {}
+        }
+        self.signing.fixed_package = Some(package);
+        Ok(())
+    }
+
+    /// After `sender`'s share failed to verify, leave it out and start over
+    /// with the next consistent signer set: the other signers of the failed
+    /// package spent their nonces and must commit again, while commitments
+    /// from signers left out of it are still unused
+    fn exclude_signer(&mut self, sender: Ed25519Identifier, failed: &frost_ed25519::SigningPackage) {
+        self.signing.excluded_signers.insert(sender);
+        for signer in failed.signing_commitments().keys() {
+            self.signing.commitments.remove(signer);
+            self.signing.signature_shares.remove(signer);
+        }
+        self.signing.commitment_order.retain(|id| !failed.signing_commitments().contains_key(id));
+        self.signing.fixed_package = None;
+        self.signing.nonces.clear();
+        self.signing.signed_message = None;
     }
 
     pub fn signing_commit(&mut self) -> Result<String, WasmError> {
+        // A repeated call hands back the commitment already sent instead of
+        // replacing the nonces behind it, as long as they haven't signed yet
+        if self.signing.signed_message.is_none()
+            && self.discard_stale_nonces().is_ok()
+            && let Some(nonces) = self.signing.nonces.as_ref()
+        {
+            return Ok(hex::encode(serde_json::to_string(nonces.commitments()).unwrap()));
+        }
+
         let key_package = self.key_package.as_ref()
             .ok_or_else(|| WasmError::new("Key package not available"))?;
         
-        let (nonces, commitments) = Ed25519Curve::generate_signing_commitment(key_package)?;
-        self.signing_nonces = Some(nonces);
+        let (nonces, commitments) = Ed25519Curve::generate_signing_commitment_with_rng(key_package, self.rng.as_mut())?;
+        self.signing.nonces.set(nonces);
+        self.signing.nonces_committed_at_ms = (self.clock)();
+        self.signing.started_at_ms = Some(self.signing.nonces_committed_at_ms);
+        self.signing.epoch += 1;
+        self.signing.committed_epoch = Some(self.signing.epoch);
+        self.signing.signed_message = None;
+        self.signing.fixed_package = None;
         
         let commitment_hex = hex::encode(serde_json::to_string(&commitments).unwrap());
         Ok(commitment_hex)
@@ -244,25 +1624,158 @@ impl FrostDkgEd25519 {
         let commitment: Ed25519SigningCommitments = serde_json::from_slice(&commitment_json)
             .map_err(|e| WasmError::new(&e.to_string()))?;
         
-        let identifier = Ed25519Curve::identifier_from_u16(participant_index)?;
-        self.signing_commitments.insert(identifier, commitment);
+        let identifier = Ed25519Curve::identifier_from_u16(self.normalize_index(participant_index)?)?;
+        self.insert_signing_commitment(identifier, commitment);
         Ok(())
     }
 
+    fn insert_signing_commitment(&mut self, identifier: Ed25519Identifier, commitment: Ed25519SigningCommitments) {
+        self.signing.started_at_ms.get_or_insert_with(self.clock);
+        if self.signing.commitments.insert(identifier, commitment).is_none() {
+            self.signing.commitment_order.push(identifier);
+        }
+    }
+
+    /// Fails once the signing timeout has passed with shares still missing
+    /// from this session's signers: those of the fixed signing package, or
+    /// else everyone who committed. The error lists who sent a share and
+    /// who didn't.
+    pub fn check_signing_timeout(&self) -> Result<(), WasmError> {
+        let Some(started_at_ms) = self.signing.started_at_ms else {
+            return Ok(());
+        };
+        let own_identifier = Ed25519Curve::identifier_from_u16(self.participant_index)?;
+        let signers: BTreeSet<Ed25519Identifier> = match &self.signing.fixed_package {
+            Some(package) => package.signing_commitments().keys().copied().collect(),
+            None => quorum_commitments(
+                &self.signing.commitments,
+                &self.signing.commitment_order,
+                self.signing_quorum,
+                &own_identifier,
+                &self.signing.excluded_signers,
+            )
+            .into_keys()
+            .collect(),
+        };
+        let signers: Vec<(u16, Ed25519Identifier)> = (1..=self.total)
+            .filter_map(|index| {
+                let identifier = Ed25519Curve::identifier_from_u16(index).ok()?;
+                if !signers.contains(&identifier) {
+                    return None;
+                }
+                Some((external_index(index, self.index_base).ok()?, identifier))
+            })
+            .collect();
+        check_signing_deadline(
+            (self.clock)().saturating_sub(started_at_ms),
+            self.signing_timeout_secs,
+            &signers,
+            &self.signing.signature_shares,
+        )
+    }
+
+    /// The canonical serialization (hex) of the signing package `sign` would
+    /// build for `message_hex` from the commitments received so far, so two
+    /// implementations' packages can be compared byte for byte
+    pub fn export_signing_package(&self, message_hex: &str) -> Result<String, WasmError> {
+        Ok(hex::encode(self.signing_package_bytes(message_hex)?))
+    }
+
+    /// `export_signing_package` as JSON together with the `ciphersuite_context`
+    /// it was built under: `{"ciphersuite_context", "signing_package"}`
+    pub fn export_tagged_signing_package(&self, message_hex: &str) -> Result<String, WasmError> {
+        Ok(tagged_signing_package(Ed25519Curve::ciphersuite_context(), &self.signing_package_bytes(message_hex)?))
+    }
+
+    fn signing_package_bytes(&self, message_hex: &str) -> Result<Vec<u8>, WasmError> {
+        let message = hex::decode(message_hex)
+            .map_err(|e| WasmError::new(&e.to_string()))?;
+        self.signing_package(&message)?
+            .serialize()
+            .map_err(|e| WasmError::new(&e.to_string()))
+    }
+
+    /// The FROST ciphersuite and library version this build signs with
+    pub fn ciphersuite_context(&self) -> String {
+        Ed25519Curve::ciphersuite_context()
+    }
+
+    /// Fails if a peer's `ciphersuite_context` differs from ours, in which
+    /// case signing together would only produce shares that don't aggregate
+    pub fn check_ciphersuite_context(&self, peer_context: &str) -> Result<(), WasmError> {
+        Ok(check_ciphersuite_context::<Ed25519Curve>(peer_context)?)
+    }
+
     pub fn sign(&mut self, message_hex: &str) -> Result<String, WasmError> {
+        let signature_share = self.signature_share(message_hex)?;
+        Ok(hex::encode(serde_json::to_string(&signature_share).unwrap()))
+    }
+
+    /// `sign` for a caller-computed 32-byte digest, used as the FROST
+    /// message as is, without any chain handler's formatting or hashing.
+    /// Aggregate with the same digest hex.
+    pub fn sign_prehashed(&mut self, digest_hex: &str) -> Result<String, WasmError> {
+        check_prehashed_digest(digest_hex)?;
+        self.sign(digest_hex)
+    }
+
+    fn signature_share(&mut self, message_hex: &str) -> Result<Ed25519SignatureShare, WasmError> {
         let message = hex::decode(message_hex)
             .map_err(|e| WasmError::new(&e.to_string()))?;
+        check_signing_epoch(self.signing.committed_epoch, self.signing.epoch)?;
+        check_not_signed(&self.signing.signed_message, &message)?;
         
-        let signing_package = Ed25519Curve::create_signing_package(&self.signing_commitments, &message)?;
+        let signing_package = self.signing_package(&message)?;
         
-        let nonces = self.signing_nonces.as_ref()
+        self.discard_stale_nonces()?;
+        let nonces = self.signing.nonces.as_ref()
             .ok_or_else(|| WasmError::new("Signing nonces not available"))?;
         let key_package = self.key_package.as_ref()
             .ok_or_else(|| WasmError::new("Key package not available"))?;
         
         let signature_share = Ed25519Curve::generate_signature_share(&signing_package, nonces, key_package)?;
-        
-        Ok(hex::encode(serde_json::to_string(&signature_share).unwrap()))
+        self.signing.nonces.clear();
+        self.signing.signed_message = Some(message);
+        Ok(signature_share)
+    }
+
+    /// Our signing commitment, or with `message_hex` our signature share for
+    /// it, as `SigningBundle` JSON signed with our key share so the other
+    /// signers of `session_id` can check who sent it, however it was relayed
+    pub fn export_signing_bundle(&mut self, session_id: &str, message_hex: Option<String>) -> Result<String, WasmError> {
+        let share = message_hex.map(|message_hex| self.signature_share(&message_hex)).transpose()?;
+        if share.is_none() {
+            self.signing_commit()?;
+        }
+        let key_package = self.key_package.as_ref()
+            .ok_or_else(|| WasmError::new("Key package not available"))?;
+        let context = Ed25519Curve::ciphersuite_context();
+        let bundle = match share {
+            Some(share) => SigningBundle::signature_share(key_package, self.participant_index, &context, session_id, &share)?,
+            None => {
+                let nonces = self.signing.nonces.as_ref()
+                    .ok_or_else(|| WasmError::new("Signing nonces not available"))?;
+                SigningBundle::commitment(key_package, self.participant_index, &context, session_id, nonces.commitments())?
+            }
+        };
+        Ok(serde_json::to_string(&bundle).unwrap())
+    }
+
+    /// Verify a bundle from `export_signing_bundle` against its sender's
+    /// public share, then add the commitment or share it carries. Returns
+    /// the sender's 1-based participant index.
+    pub fn import_signing_bundle(&mut self, session_id: &str, bundle_json: &str) -> Result<u16, WasmError> {
+        let bundle = parse_signing_bundle(bundle_json, self.total)?;
+        let public_key_package = self.public_key_package.as_ref()
+            .ok_or_else(|| WasmError::new("Public key package not available"))?;
+        let sender = bundle.verify(&Ed25519Curve::ciphersuite_context(), session_id, public_key_package)?;
+        match bundle.contents {
+            BundleContents::Commitment => self.insert_signing_commitment(sender, bundle.to_commitment()?),
+            BundleContents::SignatureShare => {
+                self.signing.signature_shares.insert(sender, bundle.to_signature_share()?);
+            }
+        }
+        Ok(bundle.participant_index)
     }
 
     pub fn add_signature_share(&mut self, participant_index: u16, share_hex: &str) -> Result<(), WasmError> {
@@ -271,43 +1784,129 @@ impl FrostDkgEd25519 {
         let share: Ed25519SignatureShare = serde_json::from_slice(&share_json)
             .map_err(|e| WasmError::new(&e.to_string()))?;
         
-        let identifier = Ed25519Curve::identifier_from_u16(participant_index)?;
-        self.signature_shares.insert(identifier, share);
+        let identifier = Ed25519Curve::identifier_from_u16(self.normalize_index(participant_index)?)?;
+        self.signing.signature_shares.insert(identifier, share);
         Ok(())
     }
 
-    pub fn aggregate_signature(&self, message_hex: &str) -> Result<String, WasmError> {
+    /// Aggregate the shares of the signing package's signers. A signer whose
+    /// share does not verify is excluded from the rest of the session; fix a
+    /// new package once the remaining signers have committed again.
+    pub fn aggregate_signature(&mut self, message_hex: &str) -> Result<String, WasmError> {
         let message = hex::decode(message_hex)
             .map_err(|e| WasmError::new(&e.to_string()))?;
+        check_signing_message(&self.signing.signed_message, &message)?;
+        self.check_signing_timeout()?;
         
-        let signing_package = Ed25519Curve::create_signing_package(&self.signing_commitments, &message)?;
+        let signing_package = self.signing_package(&message)?;
         let public_key_package = self.public_key_package.as_ref()
             .ok_or_else(|| WasmError::new("Public key package not available"))?;
         
-        let signature = Ed25519Curve::aggregate_signature(&signing_package, &self.signature_shares, public_key_package)?;
+        let shares = self.quorum_shares(&signing_package);
+        let signature = match Ed25519Curve::aggregate_signature(&signing_package, &shares, public_key_package) {
+            Ok(signature) => signature,
+            Err(error) => {
+                let sender = bad_share_sender(&error, &shares, |index| Ed25519Curve::identifier_from_u16(index).ok());
+                if let Some(sender) = sender {
+                    self.exclude_signer(Ed25519Curve::identifier_from_u16(sender)?, &signing_package);
+                    return Err(WasmError::new(&format!(
+                        "{}; excluded participant {} from this session, collect fresh commitments from the other signers",
+                        error, sender
+                    )));
+                }
+                return Err(error.into());
+            }
+        };
         let sig_bytes = Ed25519Curve::serialize_signature(&signature)?;
         
         Ok(hex::encode(sig_bytes))
     }
 
+    /// Drop this signing session: nonces (zeroized), commitments, shares and
+    /// the signed message
     pub fn clear_signing_state(&mut self) {
-        self.signing_nonces = None;
-        self.signing_commitments.clear();
-        self.signature_shares.clear();
+        self.signing = SigningContext {
+            epoch: self.signing.epoch + 1,
+            committed_epoch: self.signing.committed_epoch,
+            ..SigningContext::default()
+        };
     }
 
     pub fn has_signing_nonces(&self) -> bool {
-        self.signing_nonces.is_some()
+        self.signing.nonces.is_some()
+    }
+
+    /// `signing_commit` for the named signing session `session_id`. Each
+    /// session keeps its own nonces, commitments and shares, so one wallet
+    /// can take part in several signings at once; the un-prefixed methods
+    /// act on a session of their own. Fails once `MAX_SIGNING_SESSIONS` are
+    /// open.
+    pub fn session_signing_commit(&mut self, session_id: &str) -> Result<String, WasmError> {
+        self.in_signing_session(session_id, true, |dkg| dkg.signing_commit())
+    }
+
+    /// `add_signing_commitment` for signing session `session_id`
+    pub fn session_add_signing_commitment(&mut self, session_id: &str, participant_index: u16, commitment_hex: &str) -> Result<(), WasmError> {
+        self.in_signing_session(session_id, true, |dkg| dkg.add_signing_commitment(participant_index, commitment_hex))
+    }
+
+    /// `sign` for signing session `session_id`
+    pub fn session_sign(&mut self, session_id: &str, message_hex: &str) -> Result<String, WasmError> {
+        self.in_signing_session(session_id, false, |dkg| dkg.sign(message_hex))
+    }
+
+    /// `add_signature_share` for signing session `session_id`
+    pub fn session_add_signature_share(&mut self, session_id: &str, participant_index: u16, share_hex: &str) -> Result<(), WasmError> {
+        self.in_signing_session(session_id, false, |dkg| dkg.add_signature_share(participant_index, share_hex))
+    }
+
+    /// `aggregate_signature` for signing session `session_id`
+    pub fn session_aggregate_signature(&mut self, session_id: &str, message_hex: &str) -> Result<String, WasmError> {
+        self.in_signing_session(session_id, false, |dkg| dkg.aggregate_signature(message_hex))
+    }
+
+    /// Drop signing session `session_id`, zeroizing its nonces
+    pub fn end_signing_session(&mut self, session_id: &str) {
+        self.signing_sessions.remove(session_id);
+    }
+
+    /// Run `f` with signing session `session_id` in place of our own one;
+    /// only commitments open a new session
+    fn in_signing_session<T>(
+        &mut self,
+        session_id: &str,
+        open: bool,
+        f: impl FnOnce(&mut Self) -> Result<T, WasmError>,
+    ) -> Result<T, WasmError> {
+        let mut context = match self.signing_sessions.remove(session_id) {
+            Some(context) => context,
+            None if !open => {
+                return Err(WasmError::new(&format!("Unknown signing session {}", session_id)));
+            }
+            None if self.signing_sessions.len() >= MAX_SIGNING_SESSIONS => {
+                return Err(WasmError::new(&format!(
+                    "{} signing sessions are already open; end one with end_signing_session first",
+                    MAX_SIGNING_SESSIONS
+                )));
+            }
+            None => SigningContext::default(),
+        };
+        std::mem::swap(&mut self.signing, &mut context);
+        let result = f(self);
+        std::mem::swap(&mut self.signing, &mut context);
+        self.signing_sessions.insert(session_id.to_string(), context);
+        result
     }
 
     pub fn import_keystore(&mut self, keystore_json: &str) -> Result<(), WasmError> {
-        let keystore_data: KeystoreData = serde_json::from_str(keystore_json)
-            .map_err(|e| WasmError::new(&e.to_string()))?;
+        let keystore_data = parse_keystore::<Ed25519Curve>(keystore_json, "ed25519")?;
         
         let (key_package, public_key_package) = Keystore::import_keystore::<Ed25519Curve>(&keystore_data)?;
         
         self.key_package = Some(key_package);
         self.public_key_package = Some(public_key_package);
+        self.confirmed_fingerprints.clear();
+        self.awaiting_group_key_confirmation = false;
         self.threshold = keystore_data.min_signers;
         self.total = keystore_data.max_signers;
         self.participant_index = keystore_data.participant_index;
@@ -317,6 +1916,9 @@ impl FrostDkgEd25519 {
     }
 
     pub fn export_keystore(&self) -> Result<String, WasmError> {
+        if self.awaiting_group_key_confirmation {
+            return Err(WasmError::new("The group key has not been confirmed by every participant yet"));
+        }
         let key_package = self.key_package.as_ref()
             .ok_or_else(|| WasmError::new("Key package not available"))?;
         let public_key_package = self.public_key_package.as_ref()
@@ -334,24 +1936,59 @@ impl FrostDkgEd25519 {
         
         Ok(serde_json::to_string(&keystore_data).unwrap())
     }
+
+    /// `export_keystore` with the key packages encrypted under `password`
+    /// (Argon2id, AES-256-GCM) and marked `"encryption": "argon2id-aesgcm"`.
+    /// The plaintext `export_keystore` stays for the CLI.
+    pub fn export_keystore_encrypted(&self, password: &str) -> Result<String, WasmError> {
+        encrypt_keystore(&Zeroizing::new(self.export_keystore()?), password)
+    }
+
+    pub fn import_keystore_encrypted(&mut self, keystore_json: &str, password: &str) -> Result<(), WasmError> {
+        self.import_keystore(&decrypt_keystore(keystore_json, password)?)
+    }
 }
 
 // Secp256k1 WASM wrapper
 #[wasm_bindgen]
 pub struct FrostDkgSecp256k1 {
-    round1_secret: Option<frost_secp256k1::keys::dkg::round1::SecretPackage>,
-    round2_secret: Option<frost_secp256k1::keys::dkg::round2::SecretPackage>,
+    // The DKG secret packages and signing nonces are zeroized on drop and
+    // whenever they are replaced or cleared; see `Secret`
+    round1_secret: Secret<frost_secp256k1::keys::dkg::round1::SecretPackage>,
+    round2_secret: Secret<frost_secp256k1::keys::dkg::round2::SecretPackage>,
     key_package: Option<Secp256k1KeyPackage>,
     public_key_package: Option<Secp256k1PublicKeyPackage>,
     round1_packages: BTreeMap<Secp256k1Identifier, frost_secp256k1::keys::dkg::round1::Package>,
     round2_packages: BTreeMap<Secp256k1Identifier, frost_secp256k1::keys::dkg::round2::Package>,
-    signing_nonces: Option<Secp256k1SigningNonces>,
-    signing_commitments: BTreeMap<Secp256k1Identifier, Secp256k1SigningCommitments>,
-    signature_shares: BTreeMap<Secp256k1Identifier, Secp256k1SignatureShare>,
+    /// The signing session the un-prefixed signing methods act on
+    signing: SigningContext<frost_secp256k1::Secp256K1Sha256>,
+    /// Named sessions for the `session_*` methods, at most `MAX_SIGNING_SESSIONS`
+    signing_sessions: BTreeMap<String, SigningContext<frost_secp256k1::Secp256K1Sha256>>,
+    /// Sign with the first this many commitments; 0 waits for all of them
+    signing_quorum: u16,
     participant_indices: Vec<u16>,
     threshold: u16,
     total: u16,
     participant_index: u16,
+    /// Deflate round 2 packages from `generate_round2`
+    compress_packages: bool,
+    /// What callers number the first participant; indices are stored 1-based
+    index_base: u16,
+    nonce_ttl_secs: u32,
+    signing_timeout_secs: u32,
+    /// Milliseconds since the Unix epoch; replaced in tests
+    clock: fn() -> u64,
+    /// Source of DKG coefficients and signing nonces; seeded in tests
+    rng: Box<dyn CryptoRngCore>,
+    /// Participants whose group key fingerprint matched ours
+    confirmed_fingerprints: BTreeSet<u16>,
+    /// Set by `finalize_dkg` until every participant has confirmed the group
+    /// key; the keystore is withheld meanwhile
+    awaiting_group_key_confirmation: bool,
+    /// Re-shared key material awaiting `reshare_approve`
+    pending_reshare: Option<PendingReshare<Secp256k1KeyPackage, Secp256k1PublicKeyPackage>>,
+    /// Packages, timestamps and failures of the current DKG, see `dkg_diagnostics`
+    dkg_log: DkgLog<Secp256k1Identifier>,
 }
 
 #[wasm_bindgen]
@@ -359,42 +1996,95 @@ impl FrostDkgSecp256k1 {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
         Self {
-            round1_secret: None,
-            round2_secret: None,
+            round1_secret: Secret::default(),
+            round2_secret: Secret::default(),
             key_package: None,
             public_key_package: None,
             round1_packages: BTreeMap::new(),
             round2_packages: BTreeMap::new(),
-            signing_nonces: None,
-            signing_commitments: BTreeMap::new(),
-            signature_shares: BTreeMap::new(),
+            signing: SigningContext::default(),
+            signing_sessions: BTreeMap::new(),
+            signing_quorum: 0,
             participant_indices: Vec::new(),
             threshold: 0,
             total: 0,
             participant_index: 0,
+            compress_packages: false,
+            index_base: 1,
+            nonce_ttl_secs: DEFAULT_NONCE_TTL_SECS,
+            signing_timeout_secs: DEFAULT_SIGNING_TIMEOUT_SECS,
+            clock: now_ms,
+            rng: Box::new(OsRng),
+            confirmed_fingerprints: BTreeSet::new(),
+            awaiting_group_key_confirmation: false,
+            pending_reshare: None,
+            dkg_log: DkgLog::default(),
+        }
+    }
+
+    /// Compress the packages `generate_round2` produces. Off by default, since
+    /// only peers running this version can read compressed packages;
+    /// `add_round2_package` accepts either form regardless.
+    pub fn set_package_compression(&mut self, enabled: bool) {
+        self.compress_packages = enabled;
+    }
+
+    /// Number participants from `base` instead of 1, e.g. 0 for coordinators
+    /// with 0-based indices. Every index passed in is shifted to the 1-based
+    /// index FROST uses, and the keys of `generate_round2`'s map are shifted
+    /// back; the getters keep reporting 1-based indices.
+    pub fn set_index_base(&mut self, base: u16) {
+        self.index_base = base;
+    }
+
+    /// How long nonces from `signing_commit` stay usable by `sign`
+    pub fn set_nonce_ttl_secs(&mut self, ttl_secs: u32) {
+        self.nonce_ttl_secs = ttl_secs;
+    }
+
+    /// How long `aggregate_signature` waits for the signers' shares before
+    /// failing with `check_signing_timeout`'s report
+    pub fn set_signing_timeout_secs(&mut self, timeout_secs: u32) {
+        self.signing_timeout_secs = timeout_secs;
+    }
+
+    /// Sign with `quorum` signers instead of everyone who commits, e.g. 2
+    /// for a 2-of-5 wallet with more peers online. Commitments arrive in a
+    /// different order at every node, so only the coordinator picks the
+    /// signers (`fix_signing_package`) and the others sign the package it
+    /// distributes (`import_signing_package`). 0 turns the quorum off. Set
+    /// it after DKG or `import_keystore`.
+    pub fn set_signing_quorum(&mut self, quorum: u16) -> Result<(), WasmError> {
+        if quorum != 0 && !(self.threshold..=self.total).contains(&quorum) {
+            return Err(WasmError::new(&format!(
+                "Signing quorum must be between the threshold ({}) and total participants ({})",
+                self.threshold, self.total
+            )));
         }
+        self.signing_quorum = quorum;
+        Ok(())
     }
 
     pub fn init_dkg(&mut self, participant_index: u16, total: u16, threshold: u16) -> Result<(), WasmError> {
-        self.participant_index = participant_index;
+        self.participant_index = validate_dkg_params(participant_index, self.index_base, total, threshold)?;
         self.total = total;
         self.threshold = threshold;
         self.participant_indices = (1..=total).collect();
+        self.dkg_log = DkgLog { started_at_ms: Some((self.clock)()), ..DkgLog::default() };
         Ok(())
     }
 
     pub fn generate_round1(&mut self) -> Result<String, WasmError> {
         let identifier = Secp256k1Curve::identifier_from_u16(self.participant_index)?;
-        let mut rng = OsRng;
-        
         let (round1_secret, round1_package) = Secp256k1Curve::dkg_part1(
             identifier,
             self.total,
             self.threshold,
-            &mut rng,
+            self.rng.as_mut(),
         )?;
         
-        self.round1_secret = Some(round1_secret);
+        self.round1_secret.set(round1_secret);
+        self.dkg_log.own_round1_package = serde_json::to_value(&round1_package).ok();
         let package_json = serde_json::to_string(&round1_package)
             .map_err(|e| WasmError::new(&e.to_string()))?;
         
@@ -402,14 +2092,30 @@ impl FrostDkgSecp256k1 {
     }
 
     pub fn add_round1_package(&mut self, participant_index: u16, package_hex: &str) -> Result<(), WasmError> {
-        let package_json = hex::decode(package_hex)
-            .map_err(|e| WasmError::new(&e.to_string()))?;
-        let package: frost_secp256k1::keys::dkg::round1::Package = serde_json::from_slice(&package_json)
-            .map_err(|e| WasmError::new(&e.to_string()))?;
+        self.tracked("add_round1_package", |dkg| {
+            let package: frost_secp256k1::keys::dkg::round1::Package = parse_round1_package(package_hex)?;
         
-        let identifier = Secp256k1Curve::identifier_from_u16(participant_index)?;
-        self.round1_packages.insert(identifier, package);
-        Ok(())
+            let identifier = Secp256k1Curve::identifier_from_u16(dkg.normalize_index(participant_index)?)?;
+            dkg.round1_packages.insert(identifier, package);
+            dkg.dkg_log.round1_received_at_ms.insert(identifier, (dkg.clock)());
+            Ok(())
+        })
+    }
+
+    /// Add round 1 packages from a JSON object of participant index ->
+    /// package hex. Nothing is stored unless every entry is valid.
+    pub fn add_round1_packages(&mut self, map_json: &str) -> Result<(), WasmError> {
+        self.tracked("add_round1_packages", |dkg| {
+            let packages = parse_package_map(
+                map_json,
+                parse_round1_package::<frost_secp256k1::keys::dkg::round1::Package>,
+                |index| Ok(Secp256k1Curve::identifier_from_u16(dkg.normalize_index(index)?)?),
+            )?;
+            let now = (dkg.clock)();
+            dkg.dkg_log.round1_received_at_ms.extend(packages.iter().map(|(identifier, _)| (*identifier, now)));
+            dkg.round1_packages.extend(packages);
+            Ok(())
+        })
     }
 
     pub fn can_start_round2(&self) -> bool {
@@ -417,64 +2123,118 @@ impl FrostDkgSecp256k1 {
     }
 
     pub fn generate_round2(&mut self) -> Result<String, WasmError> {
-        let round1_secret = self.round1_secret.clone()
-            .ok_or_else(|| WasmError::new("Round 1 secret not available"))?;
+        self.tracked("generate_round2", |dkg| {
+            let round1_secret = dkg.round1_secret.as_ref().cloned()
+                .ok_or_else(|| WasmError::new("Round 1 secret not available"))?;
         
-        let (round2_secret, round2_packages) = Secp256k1Curve::dkg_part2(
-            round1_secret,
-            &self.round1_packages,
-        )?;
+            let (round2_secret, round2_packages) = Secp256k1Curve::dkg_part2(
+                round1_secret,
+                &dkg.round1_packages,
+            )?;
         
-        self.round2_secret = Some(round2_secret);
+            dkg.round2_secret.set(round2_secret);
         
-        let mut packages_map = BTreeMap::new();
-        for (id, package) in round2_packages {
-            let id_value = id.serialize()[31] as u16 | ((id.serialize()[30] as u16) << 8);
-            packages_map.insert(id_value, hex::encode(serde_json::to_string(&package).unwrap()));
-        }
+            let mut packages_map = BTreeMap::new();
+            for (id, package) in round2_packages {
+                let id_value = id.serialize()[31] as u16 | ((id.serialize()[30] as u16) << 8);
+                packages_map.insert(external_index(id_value, dkg.index_base)?, encode_package(&serde_json::to_string(&package).unwrap(), dkg.compress_packages)?);
+            }
         
-        Ok(serde_json::to_string(&packages_map).unwrap())
+            Ok(serde_json::to_string(&packages_map).unwrap())
+        })
     }
 
     pub fn add_round2_package(&mut self, sender_index: u16, package_hex: &str) -> Result<(), WasmError> {
-        let package_json = hex::decode(package_hex)
-            .map_err(|e| WasmError::new(&e.to_string()))?;
-        let package: frost_secp256k1::keys::dkg::round2::Package = serde_json::from_slice(&package_json)
-            .map_err(|e| WasmError::new(&e.to_string()))?;
+        self.tracked("add_round2_package", |dkg| {
+            let package: frost_secp256k1::keys::dkg::round2::Package = parse_round2_package(package_hex)?;
         
-        let identifier = Secp256k1Curve::identifier_from_u16(sender_index)?;
-        self.round2_packages.insert(identifier, package);
-        Ok(())
+            let identifier = Secp256k1Curve::identifier_from_u16(dkg.normalize_index(sender_index)?)?;
+            dkg.round2_packages.insert(identifier, package);
+            dkg.dkg_log.round2_received_at_ms.insert(identifier, (dkg.clock)());
+            Ok(())
+        })
+    }
+
+    /// Add round 2 packages from a JSON object of sender index -> package
+    /// hex, as `generate_round2` returns them. Nothing is stored unless every
+    /// entry is valid.
+    pub fn add_round2_packages(&mut self, map_json: &str) -> Result<(), WasmError> {
+        self.tracked("add_round2_packages", |dkg| {
+            let packages = parse_package_map(
+                map_json,
+                parse_round2_package::<frost_secp256k1::keys::dkg::round2::Package>,
+                |index| Ok(Secp256k1Curve::identifier_from_u16(dkg.normalize_index(index)?)?),
+            )?;
+            let now = (dkg.clock)();
+            dkg.dkg_log.round2_received_at_ms.extend(packages.iter().map(|(identifier, _)| (*identifier, now)));
+            dkg.round2_packages.extend(packages);
+            Ok(())
+        })
     }
 
     pub fn can_finalize(&self) -> bool {
-        self.round2_packages.len() >= (self.threshold - 1) as usize && self.round2_secret.is_some()
+        // dkg_part3 needs a round 2 package from every other participant, not just threshold - 1
+        self.round2_packages.len() == (self.total as usize).saturating_sub(1) && self.round2_secret.is_some()
+    }
+
+    /// DKG progress for UI polling as JSON `{round, packages_received,
+    /// packages_expected, complete}`: round 0 before `init_dkg`, then the
+    /// round whose packages are being collected
+    pub fn dkg_status(&self) -> String {
+        let expected = (self.total as usize).saturating_sub(1);
+        let (round, received) = if self.is_dkg_complete() {
+            (2, expected)
+        } else if self.participant_index == 0 {
+            (0, 0)
+        } else if self.round2_secret.is_some() {
+            (2, self.round2_packages.len())
+        } else {
+            (1, self.round1_packages.len())
+        };
+        dkg_status(round, received, expected, self.is_dkg_complete())
     }
 
+    /// Report for debugging a stalled or failed DKG, as JSON: phase, who
+    /// sent which packages and when, missing packages, the last failed step
+    /// with its error, a transcript hash to compare across participants, and
+    /// suggested next steps
+    pub fn dkg_diagnostics(&self) -> String {
+        let identifiers: Vec<_> = (1..=self.total)
+            .filter_map(|index| Some((index, Secp256k1Curve::identifier_from_u16(index).ok()?)))
+            .collect();
+        let snapshot = DkgSnapshot {
+            curve: "secp256k1",
+            phase: self.dkg_phase(),
+            participant_index: self.participant_index,
+            index_base: self.index_base,
+            threshold: self.threshold,
+            total: self.total,
+            now_ms: (self.clock)(),
+        };
+        self.dkg_log.report(&snapshot, &identifiers, &self.round1_packages)
+    }
+
+    /// Finalize DKG and return the group key fingerprint to broadcast. The
+    /// keystore can only be exported once `add_peer_fingerprint` has matched
+    /// every other participant's fingerprint.
     pub fn finalize_dkg(&mut self) -> Result<String, WasmError> {
-        let round2_secret = self.round2_secret.as_ref()
-            .ok_or_else(|| WasmError::new("Round 2 secret not available"))?;
-        
-        let (key_package, public_key_package) = Secp256k1Curve::dkg_part3(
-            round2_secret,
-            &self.round1_packages,
-            &self.round2_packages,
-        )?;
+        self.tracked("finalize_dkg", |dkg| {
+            let round2_secret = dkg.round2_secret.as_ref()
+                .ok_or_else(|| WasmError::new("Round 2 secret not available"))?;
         
-        self.key_package = Some(key_package.clone());
-        self.public_key_package = Some(public_key_package.clone());
+            let (key_package, public_key_package) = Secp256k1Curve::dkg_part3(
+                round2_secret,
+                &dkg.round1_packages,
+                &dkg.round2_packages,
+            )?;
         
-        let keystore_data = Keystore::export_keystore::<Secp256k1Curve>(
-            &key_package,
-            &public_key_package,
-            self.threshold,
-            self.total,
-            self.participant_index,
-            self.participant_indices.clone(),
-            "secp256k1",
-        )?;
+            dkg.key_package = Some(key_package);
+            dkg.public_key_package = Some(public_key_package);
+            dkg.confirmed_fingerprints.clear();
+            dkg.awaiting_group_key_confirmation = true;
         
-        Ok(serde_json::to_string(&keystore_data).unwrap())
+            dkg.group_key_fingerprint()
+        })
     }
 
     pub fn get_group_public_key(&self) -> Result<String, WasmError> {
@@ -486,6 +2246,64 @@ impl FrostDkgSecp256k1 {
         Ok(hex::encode(key_bytes))
     }
 
+    /// Fingerprint of the group key to broadcast once DKG finalizes
+    pub fn group_key_fingerprint(&self) -> Result<String, WasmError> {
+        let public_key_package = self.public_key_package.as_ref()
+            .ok_or_else(|| WasmError::new("DKG not complete"))?;
+
+        let verifying_key = Secp256k1Curve::verifying_key(public_key_package);
+        Ok(fingerprint(&Secp256k1Curve::serialize_verifying_key(&verifying_key)?))
+    }
+
+    /// Canonical serialization (hex) of the public key package: the group
+    /// key plus every participant's verifying share
+    pub fn export_public_key_package(&self) -> Result<String, WasmError> {
+        Ok(hex::encode(self.public_key_package_bytes()?))
+    }
+
+    /// Fingerprint of `export_public_key_package`, for a coordinator to diff
+    /// across nodes when aggregation fails. Every participant of one DKG must
+    /// report the same value; a mismatch means DKG packages were misrouted,
+    /// even if `group_key_fingerprint` agrees.
+    pub fn public_key_package_fingerprint(&self) -> Result<String, WasmError> {
+        Ok(fingerprint(&self.public_key_package_bytes()?))
+    }
+
+    fn public_key_package_bytes(&self) -> Result<Vec<u8>, WasmError> {
+        self.public_key_package.as_ref()
+            .ok_or_else(|| WasmError::new("DKG not complete"))?
+            .serialize()
+            .map_err(|e| WasmError::new(&e.to_string()))
+    }
+
+    /// Compare a peer's broadcast fingerprint with ours. On a mismatch the
+    /// key material is discarded so nothing can be signed with it.
+    pub fn add_peer_fingerprint(&mut self, participant_index: u16, fingerprint: &str) -> Result<(), WasmError> {
+        self.tracked("add_peer_fingerprint", |dkg| {
+            let own = dkg.group_key_fingerprint()?;
+            let index = dkg.normalize_index(participant_index)?;
+            if index == dkg.participant_index {
+                return Err(WasmError::new("A fingerprint from our own participant index confirms nothing"));
+            }
+            if let Err(error) = check_fingerprint(&own, participant_index, fingerprint) {
+                dkg.key_package = None;
+                dkg.public_key_package = None;
+                dkg.clear_signing_state();
+                return Err(error);
+            }
+            dkg.confirmed_fingerprints.insert(index);
+            if dkg.is_group_key_confirmed() {
+                dkg.awaiting_group_key_confirmation = false;
+            }
+            Ok(())
+        })
+    }
+
+    /// Every other participant has reported the same group key as ours
+    pub fn is_group_key_confirmed(&self) -> bool {
+        self.is_dkg_complete() && self.confirmed_fingerprints.len() == (self.total as usize).saturating_sub(1)
+    }
+
     pub fn get_address(&self) -> Result<String, WasmError> {
         let public_key_package = self.public_key_package.as_ref()
             .ok_or_else(|| WasmError::new("DKG not complete"))?;
@@ -506,12 +2324,360 @@ impl FrostDkgSecp256k1 {
         self.key_package.is_some() && self.public_key_package.is_some()
     }
 
+    /// This instance's own 1-based participant index; 0 before `init_dkg`
+    pub fn participant_index(&self) -> u16 {
+        self.participant_index
+    }
+
+    pub fn threshold(&self) -> u16 {
+        self.threshold
+    }
+
+    pub fn total_participants(&self) -> u16 {
+        self.total
+    }
+
+    /// Indices of every participant in the wallet, including this one
+    pub fn participant_indices(&self) -> Vec<u16> {
+        self.participant_indices.clone()
+    }
+
+    /// Deal this participant's part of the group key to a new group of
+    /// `new_total` participants, any `new_threshold` of which can sign for
+    /// the same address. Each of `dealers` (at least the current threshold,
+    /// this participant among them) calls this with the same list. Returns
+    /// JSON mapping each new participant's index to the share to send it.
+    pub fn reshare_generate(&self, dealers: Vec<u16>, new_total: u16, new_threshold: u16) -> Result<String, WasmError> {
+        let key_package = self.key_package.as_ref()
+            .ok_or_else(|| WasmError::new("DKG not complete"))?;
+        validate_threshold(new_total, new_threshold)?;
+        let dealers = dealers
+            .into_iter()
+            .map(|index| Ok(Secp256k1Curve::identifier_from_u16(self.normalize_index(index)?)?))
+            .collect::<Result<Vec<_>, WasmError>>()?;
+        let new_participants = (1..=new_total)
+            .map(Secp256k1Curve::identifier_from_u16)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let shares = reshare_step1(key_package, &dealers, &new_participants, new_threshold, &mut OsRng)?;
+        let mut shares_map = BTreeMap::new();
+        for (index, identifier) in (1..=new_total).zip(&new_participants) {
+            shares_map.insert(external_index(index, self.index_base)?, encode_package(&serde_json::to_string(&shares[identifier]).unwrap(), false)?);
+        }
+        Ok(serde_json::to_string(&shares_map).unwrap())
+    }
+
+    /// Take up the key as participant `participant_index` of the group set up
+    /// by `reshare_generate`. `public_key_package_hex` is the old group's
+    /// `export_public_key_package` and `shares_json` maps each dealer's index
+    /// to the share it sent here. Returns the digest of the re-shared public
+    /// key package to broadcast to the other new participants; the key
+    /// material this instance held stays in use until `reshare_approve`.
+    pub fn reshare_receive(
+        &mut self,
+        participant_index: u16,
+        new_total: u16,
+        new_threshold: u16,
+        public_key_package_hex: &str,
+        shares_json: &str,
+    ) -> Result<String, WasmError> {
+        let index = validate_dkg_params(participant_index, self.index_base, new_total, new_threshold)?;
+        let public_key_package_bytes = hex::decode(public_key_package_hex)
+            .map_err(|e| WasmError::new(&e.to_string()))?;
+        let old_public_key_package = Secp256k1PublicKeyPackage::deserialize(&public_key_package_bytes)
+            .map_err(|e| WasmError::new(&e.to_string()))?;
+        let old_total = u16::try_from(old_public_key_package.verifying_shares().len()).unwrap_or(u16::MAX);
+        let shares = parse_reshare_shares::<frost_secp256k1::keys::SecretShare>(shares_json, self.index_base, old_total)?
+            .into_iter()
+            .map(|(dealer, share)| Ok((Secp256k1Curve::identifier_from_u16(dealer)?, share)))
+            .collect::<Result<BTreeMap<_, _>, WasmError>>()?;
+        let new_participants = (1..=new_total)
+            .map(Secp256k1Curve::identifier_from_u16)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (key_package, public_key_package) = reshare_step2(
+            Secp256k1Curve::identifier_from_u16(index)?,
+            &old_public_key_package,
+            &new_participants,
+            new_threshold,
+            &shares,
+        )?;
+        let digest = fingerprint(
+            &public_key_package.serialize().map_err(|e| WasmError::new(&e.to_string()))?,
+        );
+        self.pending_reshare = Some(PendingReshare {
+            key_package,
+            public_key_package,
+            participant_index: index,
+            threshold: new_threshold,
+            total: new_total,
+        });
+        Ok(digest)
+    }
+
+    /// Switch to the key material from `reshare_receive` once `digests_json`,
+    /// mapping every other new participant's index to the digest it
+    /// broadcast, matches ours. On a mismatch the re-shared key is discarded
+    /// and the old one stays in use. Returns the new keystore like
+    /// `finalize_dkg`.
+    pub fn reshare_approve(&mut self, digests_json: &str) -> Result<String, WasmError> {
+        let pending = self.pending_reshare.take()
+            .ok_or_else(|| WasmError::new("No re-shared key awaiting approval"))?;
+        let digests = parse_reshare_digests(digests_json, self.index_base, pending.total)?
+            .into_iter()
+            .map(|(index, digest)| Ok((Secp256k1Curve::identifier_from_u16(index)?, digest)))
+            .collect::<Result<BTreeMap<_, _>, WasmError>>()?;
+        let new_participants = (1..=pending.total)
+            .map(Secp256k1Curve::identifier_from_u16)
+            .collect::<Result<Vec<_>, _>>()?;
+        reshare_step3(
+            Secp256k1Curve::identifier_from_u16(pending.participant_index)?,
+            &pending.public_key_package,
+            &new_participants,
+            &digests,
+        )?;
+
+        self.clear_signing_state();
+        self.key_package = Some(pending.key_package);
+        self.public_key_package = Some(pending.public_key_package);
+        self.confirmed_fingerprints.clear();
+        self.awaiting_group_key_confirmation = false;
+        self.threshold = pending.threshold;
+        self.total = pending.total;
+        self.participant_index = pending.participant_index;
+        self.participant_indices = (1..=pending.total).collect();
+        self.export_keystore()
+    }
+
+    /// Public key (hex, compressed) of child key `index` (hardened, `m/index'`).
+    ///
+    /// No address is derived from it: the child key only signs FROST Schnorr
+    /// signatures, which no ECDSA chain such as Ethereum accepts, so an
+    /// address encoding is left to a chain that verifies Schnorr.
+    ///
+    /// Child keys are the group key shifted by a public offset:
+    /// `offset = HMAC-SHA512(chain_code, group_key || index)`, where the chain
+    /// code is itself derived from the group key in the keystore. Each signer
+    /// shifts its share by the same offset at signing time
+    /// (`sign_with_tweak`), so no new DKG is needed per address.
+    ///
+    /// Threat model: the offset depends only on public data, so anyone who
+    /// knows the group public key can compute every child key and link the
+    /// child addresses to the wallet. "Hardened" here only separates the
+    /// index range from non-hardened BIP-32 indices; unlike real BIP-32 it
+    /// gives no isolation between children. Spending from any child still
+    /// needs a threshold of the parent shares.
+    pub fn derive_child_public_key(&self, index: u32) -> Result<String, WasmError> {
+        let derived = self.derive_child(index)?;
+        let verifying_key = Secp256k1Curve::verifying_key(&derived.public_key_package);
+        Ok(hex::encode(Secp256k1Curve::serialize_verifying_key(&verifying_key)?))
+    }
+
+    /// Like `sign`, but with this participant's share shifted to child key `index`.
+    /// Every signer in the session must use the same index.
+    pub fn sign_with_tweak(&mut self, message_hex: &str, index: u32) -> Result<String, WasmError> {
+        let derived = self.derive_child(index)?;
+        let message = hex::decode(message_hex)
+            .map_err(|e| WasmError::new(&e.to_string()))?;
+        check_signing_epoch(self.signing.committed_epoch, self.signing.epoch)?;
+        check_not_signed(&self.signing.signed_message, &message)?;
+
+        let signing_package = self.signing_package(&message)?;
+        self.discard_stale_nonces()?;
+        let nonces = self.signing.nonces.as_ref()
+            .ok_or_else(|| WasmError::new("Signing nonces not available"))?;
+
+        let signature_share = Secp256k1Curve::generate_signature_share(&signing_package, nonces, &derived.key_package)?;
+        self.signing.nonces.clear();
+        self.signing.signed_message = Some(message);
+
+        Ok(hex::encode(serde_json::to_string(&signature_share).unwrap()))
+    }
+
+    /// Aggregate shares produced by `sign_with_tweak`; the result verifies under child key `index`.
+    pub fn aggregate_signature_with_tweak(&self, message_hex: &str, index: u32) -> Result<String, WasmError> {
+        let derived = self.derive_child(index)?;
+        let message = hex::decode(message_hex)
+            .map_err(|e| WasmError::new(&e.to_string()))?;
+        check_signing_message(&self.signing.signed_message, &message)?;
+
+        let signing_package = self.signing_package(&message)?;
+        let signature = Secp256k1Curve::aggregate_signature(&signing_package, &self.quorum_shares(&signing_package), &derived.public_key_package)?;
+        let sig_bytes = Secp256k1Curve::serialize_signature(&signature)?;
+
+        Ok(hex::encode(sig_bytes))
+    }
+
+    fn derive_child(&self, index: u32) -> Result<DerivedKeys<frost_secp256k1::Secp256K1Sha256>, WasmError> {
+        if index >= HARDENED_BIT {
+            return Err(WasmError::new("Child index must be below 2^31"));
+        }
+        let key_package = self.key_package.as_ref()
+            .ok_or_else(|| WasmError::new("Key package not available"))?;
+        let public_key_package = self.public_key_package.as_ref()
+            .ok_or_else(|| WasmError::new("Public key package not available"))?;
+
+        let verifying_key = Secp256k1Curve::verifying_key(public_key_package);
+        let chain_code = ChainCode::from_group_key(&Secp256k1Curve::serialize_verifying_key(&verifying_key)?);
+        Ok(derive_child_key(key_package, public_key_package, &chain_code, index | HARDENED_BIT)?)
+    }
+
+    /// Drop nonces past their TTL so the next `sign` needs a fresh commitment
+    fn discard_stale_nonces(&mut self) -> Result<(), WasmError> {
+        if self.signing.nonces.is_some()
+            && let Err(error) = check_nonce_age(self.signing.nonces_committed_at_ms, (self.clock)(), self.nonce_ttl_secs)
+        {
+            self.signing.nonces.clear();
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    fn normalize_index(&self, index: u16) -> Result<u16, WasmError> {
+        normalize_index(index, self.index_base, self.total)
+    }
+
+    /// Run a DKG step, recording its error for `dkg_diagnostics`
+    fn tracked<T>(
+        &mut self,
+        step: &'static str,
+        f: impl FnOnce(&mut Self) -> Result<T, WasmError>,
+    ) -> Result<T, WasmError> {
+        let result = f(self);
+        if let Err(error) = &result {
+            self.dkg_log.failure = Some(DkgFailure { step, error: error.message.clone(), at_ms: (self.clock)() });
+        }
+        result
+    }
+
+    fn dkg_phase(&self) -> &'static str {
+        if self.participant_index == 0 {
+            "uninitialized"
+        } else if self.is_dkg_complete() {
+            "complete"
+        } else if self.round2_secret.is_some() {
+            "round2"
+        } else if self.round1_secret.is_some() {
+            "round1"
+        } else {
+            "initialized"
+        }
+    }
+
+    /// The fixed signing package if there is one; without a quorum, the
+    /// package over every commitment received, which is the same at every node
+    fn signing_package(&self, message: &[u8]) -> Result<frost_secp256k1::SigningPackage, WasmError> {
+        if let Some(package) = &self.signing.fixed_package {
+            if package.message() != message {
+                return Err(WasmError::new("Message does not match the fixed signing package"));
+            }
+            return Ok(package.clone());
+        }
+        if self.signing_quorum != 0 {
+            return Err(WasmError::new(
+                "With a signing quorum, sign the package the coordinator fixed; see import_signing_package",
+            ));
+        }
+        self.select_signing_package(message)
+    }
+
+    fn select_signing_package(&self, message: &[u8]) -> Result<frost_secp256k1::SigningPackage, WasmError> {
+        let own_identifier = Secp256k1Curve::identifier_from_u16(self.participant_index)?;
+        let commitments = quorum_commitments(
+            &self.signing.commitments,
+            &self.signing.commitment_order,
+            self.signing_quorum,
+            &own_identifier,
+            &self.signing.excluded_signers,
+        );
+        check_signing_commitments(&commitments, &own_identifier, self.threshold)?;
+        Ok(Secp256k1Curve::create_signing_package(&commitments, message)?)
+    }
+
+    /// Shares from the signers in `signing_package`; shares from anyone else
+    /// are dropped rather than failing aggregation
+    fn quorum_shares(&self, signing_package: &frost_secp256k1::SigningPackage) -> BTreeMap<Secp256k1Identifier, Secp256k1SignatureShare> {
+        self.signing.signature_shares
+            .iter()
+            .filter(|(id, _)| signing_package.signing_commitments().contains_key(id))
+            .map(|(id, share)| (*id, *share))
+            .collect()
+    }
+
+    /// As coordinator, fix the signers for `message_hex`: our own commitment
+    /// plus, with a quorum, the first others to arrive here. Returns the
+    /// package as `export_tagged_signing_package` does, for every signer to
+    /// pass to `import_signing_package`.
+    pub fn fix_signing_package(&mut self, message_hex: &str) -> Result<String, WasmError> {
+        let message = hex::decode(message_hex)
+            .map_err(|e| WasmError::new(&e.to_string()))?;
+        self.signing.fixed_package = Some(self.select_signing_package(&message)?);
+        self.export_tagged_signing_package(message_hex)
+    }
+
+    /// Sign the package the coordinator fixed with `fix_signing_package`
+    /// instead of one built from the commitments that reached us. Fails if
+    /// we are not among its signers or it carries another commitment for us.
+    pub fn import_signing_package(&mut self, package_json: &str) -> Result<(), WasmError> {
+        let tagged: TaggedSigningPackage = serde_json::from_str(package_json)
+            .map_err(|e| WasmError::new(&format!("Malformed signing package: {}", e)))?;
+        check_ciphersuite_context::<Secp256k1Curve>(&tagged.ciphersuite_context)?;
+        let bytes = hex::decode(&tagged.signing_package)
+            .map_err(|e| WasmError::new(&e.to_string()))?;
+        let package = frost_secp256k1::SigningPackage::deserialize(&bytes)
+            .map_err(|e| WasmError::new(&e.to_string()))?;
+
+        let own_identifier = Secp256k1Curve::identifier_from_u16(self.participant_index)?;
+        let nonces = self.signing.nonces.as_ref()
+            .ok_or_else(|| WasmError::new("Signing nonces not available"))?;
+        match package.signing_commitments().get(&own_identifier) {
+            None => return Err(WasmError::new("The coordinator did not select us to sign")),
+            Some(commitment) if commitment != nonces.commitments() => {
+                return Err(WasmError::new("The signing package carries a different commitment for us"));
+            }
+            Some(_) => {}
+        }
+        self.signing.fixed_package = Some(package);
+        Ok(())
+    }
+
+    /// After `sender`'s share failed to verify, leave it out and start over
+    /// with the next consistent signer set: the other signers of the failed
+    /// package spent their nonces and must commit again, while commitments
+    /// from signers left out of it are still unused
+    fn exclude_signer(&mut self, sender: Secp256k1Identifier, failed: &frost_secp256k1::SigningPackage) {
+        self.signing.excluded_signers.insert(sender);
+        for signer in failed.signing_commitments().keys() {
+            self.signing.commitments.remove(signer);
+            self.signing.signature_shares.remove(signer);
+        }
+        self.signing.commitment_order.retain(|id| !failed.signing_commitments().contains_key(id));
+        self.signing.fixed_package = None;
+        self.signing.nonces.clear();
+        self.signing.signed_message = None;
+    }
+
     pub fn signing_commit(&mut self) -> Result<String, WasmError> {
+        // A repeated call hands back the commitment already sent instead of
+        // replacing the nonces behind it, as long as they haven't signed yet
+        if self.signing.signed_message.is_none()
+            && self.discard_stale_nonces().is_ok()
+            && let Some(nonces) = self.signing.nonces.as_ref()
+        {
+            return Ok(hex::encode(serde_json::to_string(nonces.commitments()).unwrap()));
+        }
+
         let key_package = self.key_package.as_ref()
             .ok_or_else(|| WasmError::new("Key package not available"))?;
-        
-        let (nonces, commitments) = Secp256k1Curve::generate_signing_commitment(key_package)?;
-        self.signing_nonces = Some(nonces);
+
+        let (nonces, commitments) = Secp256k1Curve::generate_signing_commitment_with_rng(key_package, self.rng.as_mut())?;
+        self.signing.nonces.set(nonces);
+        self.signing.nonces_committed_at_ms = (self.clock)();
+        self.signing.started_at_ms = Some(self.signing.nonces_committed_at_ms);
+        self.signing.epoch += 1;
+        self.signing.committed_epoch = Some(self.signing.epoch);
+        self.signing.signed_message = None;
+        self.signing.fixed_package = None;
         
         let commitment_hex = hex::encode(serde_json::to_string(&commitments).unwrap());
         Ok(commitment_hex)
@@ -523,25 +2689,158 @@ impl FrostDkgSecp256k1 {
         let commitment: Secp256k1SigningCommitments = serde_json::from_slice(&commitment_json)
             .map_err(|e| WasmError::new(&e.to_string()))?;
         
-        let identifier = Secp256k1Curve::identifier_from_u16(participant_index)?;
-        self.signing_commitments.insert(identifier, commitment);
+        let identifier = Secp256k1Curve::identifier_from_u16(self.normalize_index(participant_index)?)?;
+        self.insert_signing_commitment(identifier, commitment);
         Ok(())
     }
 
+    fn insert_signing_commitment(&mut self, identifier: Secp256k1Identifier, commitment: Secp256k1SigningCommitments) {
+        self.signing.started_at_ms.get_or_insert_with(self.clock);
+        if self.signing.commitments.insert(identifier, commitment).is_none() {
+            self.signing.commitment_order.push(identifier);
+        }
+    }
+
+    /// Fails once the signing timeout has passed with shares still missing
+    /// from this session's signers: those of the fixed signing package, or
+    /// else everyone who committed. The error lists who sent a share and
+    /// who didn't.
+    pub fn check_signing_timeout(&self) -> Result<(), WasmError> {
+        let Some(started_at_ms) = self.signing.started_at_ms else {
+            return Ok(());
+        };
+        let own_identifier = Secp256k1Curve::identifier_from_u16(self.participant_index)?;
+        let signers: BTreeSet<Secp256k1Identifier> = match &self.signing.fixed_package {
+            Some(package) => package.signing_commitments().keys().copied().collect(),
+            None => quorum_commitments(
+                &self.signing.commitments,
+                &self.signing.commitment_order,
+                self.signing_quorum,
+                &own_identifier,
+                &self.signing.excluded_signers,
+            )
+            .into_keys()
+            .collect(),
+        };
+        let signers: Vec<(u16, Secp256k1Identifier)> = (1..=self.total)
+            .filter_map(|index| {
+                let identifier = Secp256k1Curve::identifier_from_u16(index).ok()?;
+                if !signers.contains(&identifier) {
+                    return None;
+                }
+                Some((external_index(index, self.index_base).ok()?, identifier))
+            })
+            .collect();
+        check_signing_deadline(
+            (self.clock)().saturating_sub(started_at_ms),
+            self.signing_timeout_secs,
+            &signers,
+            &self.signing.signature_shares,
+        )
+    }
+
+    /// The canonical serialization (hex) of the signing package `sign` would
+    /// build for `message_hex` from the commitments received so far, so two
+    /// implementations' packages can be compared byte for byte
+    pub fn export_signing_package(&self, message_hex: &str) -> Result<String, WasmError> {
+        Ok(hex::encode(self.signing_package_bytes(message_hex)?))
+    }
+
+    /// `export_signing_package` as JSON together with the `ciphersuite_context`
+    /// it was built under: `{"ciphersuite_context", "signing_package"}`
+    pub fn export_tagged_signing_package(&self, message_hex: &str) -> Result<String, WasmError> {
+        Ok(tagged_signing_package(Secp256k1Curve::ciphersuite_context(), &self.signing_package_bytes(message_hex)?))
+    }
+
+    fn signing_package_bytes(&self, message_hex: &str) -> Result<Vec<u8>, WasmError> {
+        let message = hex::decode(message_hex)
+            .map_err(|e| WasmError::new(&e.to_string()))?;
+        self.signing_package(&message)?
+            .serialize()
+            .map_err(|e| WasmError::new(&e.to_string()))
+    }
+
+    /// The FROST ciphersuite and library version this build signs with
+    pub fn ciphersuite_context(&self) -> String {
+        Secp256k1Curve::ciphersuite_context()
+    }
+
+    /// Fails if a peer's `ciphersuite_context` differs from ours, in which
+    /// case signing together would only produce shares that don't aggregate
+    pub fn check_ciphersuite_context(&self, peer_context: &str) -> Result<(), WasmError> {
+        Ok(check_ciphersuite_context::<Secp256k1Curve>(peer_context)?)
+    }
+
     pub fn sign(&mut self, message_hex: &str) -> Result<String, WasmError> {
+        let signature_share = self.signature_share(message_hex)?;
+        Ok(hex::encode(serde_json::to_string(&signature_share).unwrap()))
+    }
+
+    /// `sign` for a caller-computed 32-byte digest, used as the FROST
+    /// message as is, without any chain handler's formatting or hashing.
+    /// Aggregate with the same digest hex.
+    pub fn sign_prehashed(&mut self, digest_hex: &str) -> Result<String, WasmError> {
+        check_prehashed_digest(digest_hex)?;
+        self.sign(digest_hex)
+    }
+
+    fn signature_share(&mut self, message_hex: &str) -> Result<Secp256k1SignatureShare, WasmError> {
         let message = hex::decode(message_hex)
             .map_err(|e| WasmError::new(&e.to_string()))?;
+        check_signing_epoch(self.signing.committed_epoch, self.signing.epoch)?;
+        check_not_signed(&self.signing.signed_message, &message)?;
         
-        let signing_package = Secp256k1Curve::create_signing_package(&self.signing_commitments, &message)?;
+        let signing_package = self.signing_package(&message)?;
         
-        let nonces = self.signing_nonces.as_ref()
+        self.discard_stale_nonces()?;
+        let nonces = self.signing.nonces.as_ref()
             .ok_or_else(|| WasmError::new("Signing nonces not available"))?;
         let key_package = self.key_package.as_ref()
             .ok_or_else(|| WasmError::new("Key package not available"))?;
         
         let signature_share = Secp256k1Curve::generate_signature_share(&signing_package, nonces, key_package)?;
-        
-        Ok(hex::encode(serde_json::to_string(&signature_share).unwrap()))
+        self.signing.nonces.clear();
+        self.signing.signed_message = Some(message);
+        Ok(signature_share)
+    }
+
+    /// Our signing commitment, or with `message_hex` our signature share for
+    /// it, as `SigningBundle` JSON signed with our key share so the other
+    /// signers of `session_id` can check who sent it, however it was relayed
+    pub fn export_signing_bundle(&mut self, session_id: &str, message_hex: Option<String>) -> Result<String, WasmError> {
+        let share = message_hex.map(|message_hex| self.signature_share(&message_hex)).transpose()?;
+        if share.is_none() {
+            self.signing_commit()?;
+        }
+        let key_package = self.key_package.as_ref()
+            .ok_or_else(|| WasmError::new("Key package not available"))?;
+        let context = Secp256k1Curve::ciphersuite_context();
+        let bundle = match share {
+            Some(share) => SigningBundle::signature_share(key_package, self.participant_index, &context, session_id, &share)?,
+            None => {
+                let nonces = self.signing.nonces.as_ref()
+                    .ok_or_else(|| WasmError::new("Signing nonces not available"))?;
+                SigningBundle::commitment(key_package, self.participant_index, &context, session_id, nonces.commitments())?
+            }
+        };
+        Ok(serde_json::to_string(&bundle).unwrap())
+    }
+
+    /// Verify a bundle from `export_signing_bundle` against its sender's
+    /// public share, then add the commitment or share it carries. Returns
+    /// the sender's 1-based participant index.
+    pub fn import_signing_bundle(&mut self, session_id: &str, bundle_json: &str) -> Result<u16, WasmError> {
+        let bundle = parse_signing_bundle(bundle_json, self.total)?;
+        let public_key_package = self.public_key_package.as_ref()
+            .ok_or_else(|| WasmError::new("Public key package not available"))?;
+        let sender = bundle.verify(&Secp256k1Curve::ciphersuite_context(), session_id, public_key_package)?;
+        match bundle.contents {
+            BundleContents::Commitment => self.insert_signing_commitment(sender, bundle.to_commitment()?),
+            BundleContents::SignatureShare => {
+                self.signing.signature_shares.insert(sender, bundle.to_signature_share()?);
+            }
+        }
+        Ok(bundle.participant_index)
     }
 
     pub fn add_signature_share(&mut self, participant_index: u16, share_hex: &str) -> Result<(), WasmError> {
@@ -550,43 +2849,129 @@ impl FrostDkgSecp256k1 {
         let share: Secp256k1SignatureShare = serde_json::from_slice(&share_json)
             .map_err(|e| WasmError::new(&e.to_string()))?;
         
-        let identifier = Secp256k1Curve::identifier_from_u16(participant_index)?;
-        self.signature_shares.insert(identifier, share);
+        let identifier = Secp256k1Curve::identifier_from_u16(self.normalize_index(participant_index)?)?;
+        self.signing.signature_shares.insert(identifier, share);
         Ok(())
     }
 
-    pub fn aggregate_signature(&self, message_hex: &str) -> Result<String, WasmError> {
+    /// Aggregate the shares of the signing package's signers. A signer whose
+    /// share does not verify is excluded from the rest of the session; fix a
+    /// new package once the remaining signers have committed again.
+    pub fn aggregate_signature(&mut self, message_hex: &str) -> Result<String, WasmError> {
         let message = hex::decode(message_hex)
             .map_err(|e| WasmError::new(&e.to_string()))?;
+        check_signing_message(&self.signing.signed_message, &message)?;
+        self.check_signing_timeout()?;
         
-        let signing_package = Secp256k1Curve::create_signing_package(&self.signing_commitments, &message)?;
+        let signing_package = self.signing_package(&message)?;
         let public_key_package = self.public_key_package.as_ref()
             .ok_or_else(|| WasmError::new("Public key package not available"))?;
         
-        let signature = Secp256k1Curve::aggregate_signature(&signing_package, &self.signature_shares, public_key_package)?;
+        let shares = self.quorum_shares(&signing_package);
+        let signature = match Secp256k1Curve::aggregate_signature(&signing_package, &shares, public_key_package) {
+            Ok(signature) => signature,
+            Err(error) => {
+                let sender = bad_share_sender(&error, &shares, |index| Secp256k1Curve::identifier_from_u16(index).ok());
+                if let Some(sender) = sender {
+                    self.exclude_signer(Secp256k1Curve::identifier_from_u16(sender)?, &signing_package);
+                    return Err(WasmError::new(&format!(
+                        "{}; excluded participant {} from this session, collect fresh commitments from the other signers",
+                        error, sender
+                    )));
+                }
+                return Err(error.into());
+            }
+        };
         let sig_bytes = Secp256k1Curve::serialize_signature(&signature)?;
         
         Ok(hex::encode(sig_bytes))
     }
 
+    /// Drop this signing session: nonces (zeroized), commitments, shares and
+    /// the signed message
     pub fn clear_signing_state(&mut self) {
-        self.signing_nonces = None;
-        self.signing_commitments.clear();
-        self.signature_shares.clear();
+        self.signing = SigningContext {
+            epoch: self.signing.epoch + 1,
+            committed_epoch: self.signing.committed_epoch,
+            ..SigningContext::default()
+        };
     }
 
     pub fn has_signing_nonces(&self) -> bool {
-        self.signing_nonces.is_some()
+        self.signing.nonces.is_some()
+    }
+
+    /// `signing_commit` for the named signing session `session_id`. Each
+    /// session keeps its own nonces, commitments and shares, so one wallet
+    /// can take part in several signings at once; the un-prefixed methods
+    /// act on a session of their own. Fails once `MAX_SIGNING_SESSIONS` are
+    /// open.
+    pub fn session_signing_commit(&mut self, session_id: &str) -> Result<String, WasmError> {
+        self.in_signing_session(session_id, true, |dkg| dkg.signing_commit())
+    }
+
+    /// `add_signing_commitment` for signing session `session_id`
+    pub fn session_add_signing_commitment(&mut self, session_id: &str, participant_index: u16, commitment_hex: &str) -> Result<(), WasmError> {
+        self.in_signing_session(session_id, true, |dkg| dkg.add_signing_commitment(participant_index, commitment_hex))
+    }
+
+    /// `sign` for signing session `session_id`
+    pub fn session_sign(&mut self, session_id: &str, message_hex: &str) -> Result<String, WasmError> {
+        self.in_signing_session(session_id, false, |dkg| dkg.sign(message_hex))
+    }
+
+    /// `add_signature_share` for signing session `session_id`
+    pub fn session_add_signature_share(&mut self, session_id: &str, participant_index: u16, share_hex: &str) -> Result<(), WasmError> {
+        self.in_signing_session(session_id, false, |dkg| dkg.add_signature_share(participant_index, share_hex))
+    }
+
+    /// `aggregate_signature` for signing session `session_id`
+    pub fn session_aggregate_signature(&mut self, session_id: &str, message_hex: &str) -> Result<String, WasmError> {
+        self.in_signing_session(session_id, false, |dkg| dkg.aggregate_signature(message_hex))
+    }
+
+    /// Drop signing session `session_id`, zeroizing its nonces
+    pub fn end_signing_session(&mut self, session_id: &str) {
+        self.signing_sessions.remove(session_id);
+    }
+
+    /// Run `f` with signing session `session_id` in place of our own one;
+    /// only commitments open a new session
+    fn in_signing_session<T>(
+        &mut self,
+        session_id: &str,
+        open: bool,
+        f: impl FnOnce(&mut Self) -> Result<T, WasmError>,
+    ) -> Result<T, WasmError> {
+        let mut context = match self.signing_sessions.remove(session_id) {
+            Some(context) => context,
+            None if !open => {
+                return Err(WasmError::new(&format!("Unknown signing session {}", session_id)));
+            }
+            None if self.signing_sessions.len() >= MAX_SIGNING_SESSIONS => {
+                return Err(WasmError::new(&format!(
+                    "{} signing sessions are already open; end one with end_signing_session first",
+                    MAX_SIGNING_SESSIONS
+                )));
+            }
+            None => SigningContext::default(),
+        };
+        std::mem::swap(&mut self.signing, &mut context);
+        let result = f(self);
+        std::mem::swap(&mut self.signing, &mut context);
+        self.signing_sessions.insert(session_id.to_string(), context);
+        result
     }
 
     pub fn import_keystore(&mut self, keystore_json: &str) -> Result<(), WasmError> {
-        let keystore_data: KeystoreData = serde_json::from_str(keystore_json)
-            .map_err(|e| WasmError::new(&e.to_string()))?;
+        let keystore_data = parse_keystore::<Secp256k1Curve>(keystore_json, "secp256k1")?;
         
         let (key_package, public_key_package) = Keystore::import_keystore::<Secp256k1Curve>(&keystore_data)?;
         
         self.key_package = Some(key_package);
         self.public_key_package = Some(public_key_package);
+        self.confirmed_fingerprints.clear();
+        self.awaiting_group_key_confirmation = false;
         self.threshold = keystore_data.min_signers;
         self.total = keystore_data.max_signers;
         self.participant_index = keystore_data.participant_index;
@@ -596,6 +2981,9 @@ impl FrostDkgSecp256k1 {
     }
 
     pub fn export_keystore(&self) -> Result<String, WasmError> {
+        if self.awaiting_group_key_confirmation {
+            return Err(WasmError::new("The group key has not been confirmed by every participant yet"));
+        }
         let key_package = self.key_package.as_ref()
             .ok_or_else(|| WasmError::new("Key package not available"))?;
         let public_key_package = self.public_key_package.as_ref()
@@ -613,6 +3001,24 @@ impl FrostDkgSecp256k1 {
         
         Ok(serde_json::to_string(&keystore_data).unwrap())
     }
+
+    /// `export_keystore` with the key packages encrypted under `password`
+    /// (Argon2id, AES-256-GCM) and marked `"encryption": "argon2id-aesgcm"`.
+    /// The plaintext `export_keystore` stays for the CLI.
+    pub fn export_keystore_encrypted(&self, password: &str) -> Result<String, WasmError> {
+        encrypt_keystore(&Zeroizing::new(self.export_keystore()?), password)
+    }
+
+    pub fn import_keystore_encrypted(&mut self, keystore_json: &str, password: &str) -> Result<(), WasmError> {
+        self.import_keystore(&decrypt_keystore(keystore_json, password)?)
+    }
+}
+
+// 1-based participant index for `device_id`, from the lexicographic order of
+// `device_ids`; every node gets the same answer whatever order they joined in
+#[wasm_bindgen(js_name = participantIndex)]
+pub fn participant_index(device_ids: Vec<String>, device_id: &str) -> Result<u16, WasmError> {
+    Ok(mpc_wallet_frost_core::participant_index(&device_ids, device_id)?)
 }
 
 #[wasm_bindgen]
@@ -620,7 +3026,7 @@ pub fn main() {
     #[cfg(feature = "console_error_panic_hook")]
     console_error_panic_hook::set_once();
     
-    console_log!("MPC Wallet WASM initialized");
+    console_info!("MPC Wallet WASM initialized");
 }
 
 // Called when the WASM module is instantiated
@@ -761,4 +3167,1153 @@ impl FrostDkgUnified {
         serde_json::to_string(&keystore.secp256k1)
             .map_err(|e| WasmError::new(&e.to_string()))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod golden_vectors;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_init_dkg_rejects_invalid_params() {
+        // (participant_index, total, threshold)
+        let invalid = [(1, 3, 0), (1, 0, 0), (1, 2, 3), (0, 3, 2), (4, 3, 2)];
+        for (index, total, threshold) in invalid {
+            assert!(
+                FrostDkgEd25519::new().init_dkg(index, total, threshold).is_err(),
+                "ed25519 accepted index {} total {} threshold {}",
+                index, total, threshold
+            );
+            assert!(
+                FrostDkgSecp256k1::new().init_dkg(index, total, threshold).is_err(),
+                "secp256k1 accepted index {} total {} threshold {}",
+                index, total, threshold
+            );
+        }
+    }
+
+    #[test]
+    fn test_log_level_gates_messages() {
+        set_log_level(LOG_ERROR);
+        assert!(log_enabled(LOG_ERROR));
+        assert!(!log_enabled(LOG_WARN) && !log_enabled(LOG_INFO) && !log_enabled(LOG_DEBUG));
+        set_log_level(0);
+        assert!(!log_enabled(LOG_ERROR));
+        set_log_level(u8::MAX);
+        assert!(log_enabled(LOG_DEBUG));
+        set_log_level(LOG_INFO);
+        assert!(log_enabled(LOG_INFO) && !log_enabled(LOG_DEBUG));
+    }
+
+    #[test]
+    fn test_init_dkg_error_messages() {
+        let err = FrostDkgEd25519::new().init_dkg(1, 3, 0).unwrap_err();
+        assert_eq!(err.message(), "Threshold must be at least 1");
+        let err = FrostDkgEd25519::new().init_dkg(1, 2, 3).unwrap_err();
+        assert_eq!(err.message(), "Threshold cannot be greater than total participants");
+        let err = FrostDkgSecp256k1::new().init_dkg(4, 3, 2).unwrap_err();
+        assert_eq!(err.message(), "Participant index must be between 1 and total participants");
+
+        assert!(FrostDkgEd25519::new().init_dkg(3, 3, 2).is_ok());
+    }
+
+    fn import_signing_package(package_hex: &str) -> frost_secp256k1::SigningPackage {
+        frost_secp256k1::SigningPackage::deserialize(&hex::decode(package_hex).unwrap()).unwrap()
+    }
+
+    /// Run a full 2-of-3 secp256k1 DKG
+    fn secp256k1_parties() -> Vec<FrostDkgSecp256k1> {
+        secp256k1_group(3, 2)
+    }
+
+    /// Run a full `threshold`-of-`total` secp256k1 DKG, including the
+    /// group key fingerprint exchange
+    fn secp256k1_group(total: u16, threshold: u16) -> Vec<FrostDkgSecp256k1> {
+        let (mut parties, fingerprints) = secp256k1_dkg(total, threshold);
+        for (i, party) in parties.iter_mut().enumerate() {
+            for (j, fingerprint) in fingerprints.iter().enumerate().filter(|(j, _)| *j != i) {
+                party.add_peer_fingerprint(j as u16 + 1, fingerprint).unwrap();
+            }
+            assert!(party.is_group_key_confirmed());
+        }
+        parties
+    }
+
+    /// DKG rounds only; returns each party's fingerprint from `finalize_dkg`
+    fn secp256k1_dkg(total: u16, threshold: u16) -> (Vec<FrostDkgSecp256k1>, Vec<String>) {
+        let mut parties: Vec<FrostDkgSecp256k1> = (1..=total).map(|_| FrostDkgSecp256k1::new()).collect();
+        let mut round1 = Vec::new();
+        for (i, party) in parties.iter_mut().enumerate() {
+            party.init_dkg(i as u16 + 1, total, threshold).unwrap();
+            round1.push(party.generate_round1().unwrap());
+        }
+        for (i, party) in parties.iter_mut().enumerate() {
+            for (j, package) in round1.iter().enumerate().filter(|(j, _)| *j != i) {
+                party.add_round1_package(j as u16 + 1, package).unwrap();
+            }
+        }
+        let round2: Vec<BTreeMap<u16, String>> = parties
+            .iter_mut()
+            .map(|party| serde_json::from_str(&party.generate_round2().unwrap()).unwrap())
+            .collect();
+        for (i, party) in parties.iter_mut().enumerate() {
+            let recipient = i as u16 + 1;
+            for (j, packages) in round2.iter().enumerate().filter(|(j, _)| *j != i) {
+                party.add_round2_package(j as u16 + 1, &packages[&recipient]).unwrap();
+            }
+        }
+        let fingerprints = parties.iter_mut().map(|party| party.finalize_dkg().unwrap()).collect();
+        (parties, fingerprints)
+    }
+
+    #[test]
+    fn test_tweaked_signature_verifies_under_child_key() {
+        let mut parties = secp256k1_parties();
+        let index = 7;
+        let message = b"pay from child address";
+
+        let child_key = parties[0].derive_child_public_key(index).unwrap();
+        assert_eq!(child_key, parties[2].derive_child_public_key(index).unwrap());
+        assert_ne!(child_key, parties[0].get_group_public_key().unwrap());
+        assert_ne!(child_key, parties[0].derive_child_public_key(index + 1).unwrap());
+        assert_eq!(hex::decode(&child_key).unwrap().len(), 33);
+
+        let message_hex = hex::encode(message);
+        let commitments: Vec<String> = parties[..2].iter_mut().map(|p| p.signing_commit().unwrap()).collect();
+        for party in parties[..2].iter_mut() {
+            for (j, commitment) in commitments.iter().enumerate() {
+                party.add_signing_commitment(j as u16 + 1, commitment).unwrap();
+            }
+        }
+        let shares: Vec<String> = parties[..2]
+            .iter_mut()
+            .map(|p| p.sign_with_tweak(&message_hex, index).unwrap())
+            .collect();
+        for (j, share) in shares.iter().enumerate() {
+            parties[0].add_signature_share(j as u16 + 1, share).unwrap();
+        }
+        let signature_hex = parties[0].aggregate_signature_with_tweak(&message_hex, index).unwrap();
+
+        let signature = frost_secp256k1::Signature::deserialize(&hex::decode(signature_hex).unwrap()).unwrap();
+        let child = parties[0].derive_child(index).unwrap().public_key_package;
+        assert_eq!(hex::encode(child.verifying_key().serialize().unwrap()), child_key);
+        assert!(child.verifying_key().verify(message, &signature).is_ok());
+        let parent = parties[0].public_key_package.as_ref().unwrap();
+        assert!(parent.verifying_key().verify(message, &signature).is_err());
+    }
+
+    #[test]
+    fn test_import_keystore_rejects_other_curve() {
+        let secp256k1_keystore = secp256k1_parties()[0].export_keystore().unwrap();
+        let err = FrostDkgEd25519::new().import_keystore(&secp256k1_keystore).unwrap_err();
+        assert_eq!(err.message(), "keystore is secp256k1 but this instance is ed25519");
+        assert!(FrostDkgSecp256k1::new().import_keystore(&secp256k1_keystore).is_ok());
+
+        let mut ed25519_keystore: serde_json::Value = serde_json::from_str(&secp256k1_keystore).unwrap();
+        ed25519_keystore["curve"] = "ed25519".into();
+        let err = FrostDkgSecp256k1::new().import_keystore(&ed25519_keystore.to_string()).unwrap_err();
+        assert_eq!(err.message(), "keystore is ed25519 but this instance is secp256k1");
+    }
+
+    /// Keystore as a CLI node writes it to a `.dat` file
+    fn cli_dat(party: &FrostDkgSecp256k1, curve: &str) -> String {
+        serde_json::json!({
+            "key_package": serde_json::to_string(party.key_package.as_ref().unwrap()).unwrap(),
+            "group_public_key": serde_json::to_string(party.public_key_package.as_ref().unwrap()).unwrap(),
+            "session_id": "wallet_2of3",
+            "curve": curve,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_import_cli_dat_and_sign() {
+        let parties = secp256k1_parties();
+        let mut imported: Vec<FrostDkgSecp256k1> = parties[1..]
+            .iter()
+            .map(|party| {
+                let mut dkg = FrostDkgSecp256k1::new();
+                dkg.import_keystore(&cli_dat(party, "secp256k1")).unwrap();
+                dkg
+            })
+            .collect();
+        assert_eq!(imported[1].participant_index(), 3);
+        assert_eq!(imported[1].threshold(), 2);
+        assert_eq!(imported[1].total_participants(), 3);
+        assert_eq!(imported[0].get_group_public_key().unwrap(), parties[0].get_group_public_key().unwrap());
+
+        let commitments: Vec<String> = imported.iter_mut().map(|dkg| dkg.signing_commit().unwrap()).collect();
+        for dkg in imported.iter_mut() {
+            dkg.add_signing_commitment(2, &commitments[0]).unwrap();
+            dkg.add_signing_commitment(3, &commitments[1]).unwrap();
+        }
+        let message_hex = hex::encode(b"signed from a CLI keystore");
+        let shares: Vec<String> = imported.iter_mut().map(|dkg| dkg.sign(&message_hex).unwrap()).collect();
+        imported[0].add_signature_share(2, &shares[0]).unwrap();
+        imported[0].add_signature_share(3, &shares[1]).unwrap();
+        let signature = imported[0].aggregate_signature(&message_hex).unwrap();
+        let signature = frost_secp256k1::Signature::deserialize(&hex::decode(signature).unwrap()).unwrap();
+        let public_key_package = parties[0].public_key_package.as_ref().unwrap();
+        assert!(public_key_package.verifying_key().verify(b"signed from a CLI keystore", &signature).is_ok());
+
+        let err = FrostDkgEd25519::new().import_keystore(&cli_dat(&parties[0], "secp256k1")).unwrap_err();
+        assert_eq!(err.message(), "keystore is secp256k1 but this instance is ed25519");
+    }
+
+    #[test]
+    fn test_differing_ciphersuite_contexts_are_incompatible() {
+        let ours = FrostDkgSecp256k1::new();
+        assert_eq!(ours.ciphersuite_context(), "FROST-secp256k1-SHA256-v1/frost-core-2.2");
+        let older = "FROST-secp256k1-SHA256-v1/frost-core-1.0";
+        assert!(ours.check_ciphersuite_context(&FrostDkgSecp256k1::new().ciphersuite_context()).is_ok());
+        let err = ours.check_ciphersuite_context(older).unwrap_err();
+        assert!(err.message().contains(&format!("peer uses {}, this node uses {}", older, ours.ciphersuite_context())));
+        assert!(ours.check_ciphersuite_context(&FrostDkgEd25519::new().ciphersuite_context()).is_err());
+
+        // A keystore written under another context is refused; one from
+        // before the tag existed still loads
+        let keystore = secp256k1_parties()[0].export_keystore().unwrap();
+        let mut tagged: serde_json::Value = serde_json::from_str(&keystore).unwrap();
+        assert_eq!(tagged["ciphersuite_context"], ours.ciphersuite_context());
+        tagged["ciphersuite_context"] = older.into();
+        assert!(FrostDkgSecp256k1::new().import_keystore(&tagged.to_string()).is_err());
+        tagged.as_object_mut().unwrap().remove("ciphersuite_context");
+        assert!(FrostDkgSecp256k1::new().import_keystore(&tagged.to_string()).is_ok());
+    }
+
+    #[test]
+    fn test_getters_reflect_init_and_import() {
+        let mut ed25519 = FrostDkgEd25519::new();
+        assert_eq!(ed25519.participant_index(), 0);
+        assert!(ed25519.participant_indices().is_empty());
+        ed25519.init_dkg(2, 5, 3).unwrap();
+        assert_eq!(
+            (ed25519.participant_index(), ed25519.threshold(), ed25519.total_participants()),
+            (2, 3, 5)
+        );
+        assert_eq!(ed25519.participant_indices(), vec![1, 2, 3, 4, 5]);
+
+        let keystore = secp256k1_parties()[2].export_keystore().unwrap();
+        let mut imported = FrostDkgSecp256k1::new();
+        imported.import_keystore(&keystore).unwrap();
+        assert_eq!(
+            (imported.participant_index(), imported.threshold(), imported.total_participants()),
+            (3, 2, 3)
+        );
+        assert_eq!(imported.participant_indices(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_aggregate_rejects_different_message() {
+        let mut parties = secp256k1_parties();
+        let commitments: Vec<String> = parties[..2].iter_mut().map(|p| p.signing_commit().unwrap()).collect();
+        for party in parties[..2].iter_mut() {
+            for (j, commitment) in commitments.iter().enumerate() {
+                party.add_signing_commitment(j as u16 + 1, commitment).unwrap();
+            }
+        }
+        let shares: Vec<String> = parties[..2].iter_mut().map(|p| p.sign("aa").unwrap()).collect();
+        for (j, share) in shares.iter().enumerate() {
+            parties[0].add_signature_share(j as u16 + 1, share).unwrap();
+        }
+
+        let err = parties[0].aggregate_signature("bb").unwrap_err();
+        assert_eq!(err.message(), "Message to aggregate (bb) does not match the message signed in this session (aa)");
+        assert!(parties[0].aggregate_signature("aa").is_ok());
+    }
+
+    thread_local! {
+        static MOCK_NOW_MS: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+    }
+
+    fn mock_now() -> u64 {
+        MOCK_NOW_MS.with(|now| now.get())
+    }
+
+    /// Every party commits and receives every other party's commitment
+    fn commit_all(parties: &mut [FrostDkgSecp256k1]) {
+        let commitments: Vec<String> = parties.iter_mut().map(|p| p.signing_commit().unwrap()).collect();
+        for party in parties.iter_mut() {
+            for (j, commitment) in commitments.iter().enumerate() {
+                party.add_signing_commitment(j as u16 + 1, commitment).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_stale_nonces_require_fresh_commitment() {
+        let mut parties = secp256k1_parties();
+        for party in parties.iter_mut() {
+            party.clock = mock_now;
+            party.set_nonce_ttl_secs(60);
+        }
+        MOCK_NOW_MS.with(|now| now.set(1_000_000));
+        commit_all(&mut parties[..2]);
+
+        // Still within the TTL
+        MOCK_NOW_MS.with(|now| now.set(1_000_000 + 60_000));
+        assert!(parties[1].sign("aa").is_ok());
+
+        MOCK_NOW_MS.with(|now| now.set(1_000_000 + 60_001));
+        let err = parties[0].sign("aa").unwrap_err();
+        assert_eq!(err.message(), "Signing nonces expired after 60s (TTL 60s); call signing_commit again");
+        assert!(!parties[0].has_signing_nonces());
+        assert_eq!(parties[0].sign("aa").unwrap_err().message(), "Signing nonces not available");
+
+        commit_all(&mut parties[..2]);
+        assert!(parties[0].sign("aa").is_ok());
+    }
+
+    #[test]
+    fn test_signing_timeout_names_missing_signer() {
+        let mut parties = secp256k1_parties();
+        for party in parties.iter_mut() {
+            party.clock = mock_now;
+            party.set_signing_timeout_secs(30);
+        }
+        MOCK_NOW_MS.with(|now| now.set(1_000_000));
+        commit_all(&mut parties[..2]);
+        let share = parties[0].sign("aa").unwrap();
+        parties[0].add_signature_share(1, &share).unwrap();
+
+        // Within the deadline a missing share is just "not yet"
+        MOCK_NOW_MS.with(|now| now.set(1_000_000 + 29_999));
+        assert!(parties[0].check_signing_timeout().is_ok());
+
+        // Participant 2 never sends its share
+        MOCK_NOW_MS.with(|now| now.set(1_000_000 + 30_000));
+        assert_eq!(
+            parties[0].aggregate_signature("aa").unwrap_err().message(),
+            "Signing timed out after 30s: shares received from [1], missing from [2]"
+        );
+
+        // A late share still completes the session
+        let share = parties[1].sign("aa").unwrap();
+        parties[0].add_signature_share(2, &share).unwrap();
+        assert!(parties[0].aggregate_signature("aa").is_ok());
+    }
+
+    #[test]
+    fn test_interleaved_signing_sessions_keep_separate_nonces() {
+        let mut parties = secp256k1_parties();
+        let sessions = [("session-a", "aa"), ("session-b", "bb")];
+        // Both sessions commit before either signs
+        for (session, _) in sessions {
+            let commitments: Vec<String> = parties[..2]
+                .iter_mut()
+                .map(|party| party.session_signing_commit(session).unwrap())
+                .collect();
+            for party in parties[..2].iter_mut() {
+                for (j, commitment) in commitments.iter().enumerate() {
+                    party.session_add_signing_commitment(session, j as u16 + 1, commitment).unwrap();
+                }
+            }
+        }
+        assert!(!parties[0].has_signing_nonces());
+
+        for (session, message) in sessions.into_iter().rev() {
+            let shares: Vec<String> = parties[..2]
+                .iter_mut()
+                .map(|party| party.session_sign(session, message).unwrap())
+                .collect();
+            for (j, share) in shares.iter().enumerate() {
+                parties[0].session_add_signature_share(session, j as u16 + 1, share).unwrap();
+            }
+            let signature = parties[0].session_aggregate_signature(session, message).unwrap();
+            let signature = frost_secp256k1::Signature::deserialize(&hex::decode(signature).unwrap()).unwrap();
+            let public_key_package = parties[0].public_key_package.as_ref().unwrap();
+            assert!(public_key_package.verifying_key().verify(&hex::decode(message).unwrap(), &signature).is_ok());
+        }
+
+        parties[0].end_signing_session("session-a");
+        assert_eq!(
+            parties[0].session_sign("session-a", "aa").unwrap_err().message(),
+            "Unknown signing session session-a"
+        );
+        for i in 1..MAX_SIGNING_SESSIONS {
+            parties[0].session_signing_commit(&format!("extra-{}", i)).unwrap();
+        }
+        assert_eq!(
+            parties[0].session_signing_commit("one-too-many").unwrap_err().message(),
+            "16 signing sessions are already open; end one with end_signing_session first"
+        );
+    }
+
+    #[test]
+    fn test_sign_prehashed_requires_32_bytes() {
+        let mut parties = secp256k1_parties();
+        commit_all(&mut parties[..2]);
+        for len in [31, 33] {
+            assert_eq!(
+                parties[0].sign_prehashed(&hex::encode(vec![0x42; len])).unwrap_err().message(),
+                format!("Prehashed digest must be exactly 32 bytes, got {}", len)
+            );
+        }
+        // A rejected digest leaves the nonces for the real one
+        assert!(parties[0].has_signing_nonces());
+
+        let digest = [0x42; 32];
+        let shares: Vec<String> = parties[..2]
+            .iter_mut()
+            .map(|party| party.sign_prehashed(&hex::encode(digest)).unwrap())
+            .collect();
+        for (i, share) in shares.iter().enumerate() {
+            parties[0].add_signature_share(i as u16 + 1, share).unwrap();
+        }
+        let signature = parties[0].aggregate_signature(&hex::encode(digest)).unwrap();
+        let signature = frost_secp256k1::Signature::deserialize(&hex::decode(signature).unwrap()).unwrap();
+        let public_key_package = parties[0].public_key_package.as_ref().unwrap();
+        assert!(public_key_package.verifying_key().verify(&digest, &signature).is_ok());
+    }
+
+    #[test]
+    fn test_repeated_signing_commit_keeps_nonces() {
+        let mut parties = secp256k1_parties();
+        let first = parties[0].signing_commit().unwrap();
+        assert_eq!(parties[0].signing_commit().unwrap(), first);
+
+        // The commitment peers already hold still signs
+        let second = parties[1].signing_commit().unwrap();
+        for party in parties[..2].iter_mut() {
+            party.add_signing_commitment(1, &first).unwrap();
+            party.add_signing_commitment(2, &second).unwrap();
+        }
+        let own = parties[0].sign("aa").unwrap();
+        let share = parties[1].sign("aa").unwrap();
+        parties[0].add_signature_share(1, &own).unwrap();
+        parties[0].add_signature_share(2, &share).unwrap();
+        parties[0].aggregate_signature("aa").unwrap();
+
+        // Nonces that produced a share are never handed out again
+        assert_ne!(parties[0].signing_commit().unwrap(), first);
+        parties[1].clear_signing_state();
+        assert_ne!(parties[1].signing_commit().unwrap(), second);
+    }
+
+    #[test]
+    fn test_nonces_sign_only_one_share() {
+        let mut parties = secp256k1_parties();
+        commit_all(&mut parties[..2]);
+        parties[0].sign("aa").unwrap();
+        assert!(!parties[0].has_signing_nonces());
+
+        // A second message under the same commitment is refused outright
+        assert_eq!(
+            parties[0].sign("bb").unwrap_err().message(),
+            "Already signed aa in this session; call signing_commit before signing bb"
+        );
+        assert!(parties[0].sign_with_tweak("bb", 7).is_err());
+        assert_eq!(
+            parties[0].sign("aa").unwrap_err().message(),
+            "Already signed this message in this session; call signing_commit to sign it again"
+        );
+
+        // A fresh commitment opens a new session
+        commit_all(&mut parties[..2]);
+        assert!(parties[0].sign("bb").is_ok());
+    }
+
+    #[test]
+    fn test_clear_signing_state_wipes_nonces() {
+        let mut parties = secp256k1_parties();
+        commit_all(&mut parties[..2]);
+        assert!(parties[0].has_signing_nonces());
+
+        parties[0].clear_signing_state();
+        assert!(!parties[0].has_signing_nonces());
+        assert!(parties[0].signing.nonces.as_ref().is_none());
+
+        // Replacing a secret keeps only the new value
+        let mut secret = Secret::default();
+        secret.set(vec![7u8; 32]);
+        secret.set(vec![9u8; 32]);
+        assert_eq!(secret.as_ref(), Some(&vec![9u8; 32]));
+        secret.clear();
+        assert!(!secret.is_some());
+    }
+
+    #[test]
+    fn test_sign_after_clear_reports_epoch_mismatch() {
+        let mut parties = secp256k1_parties();
+        let commitment = parties[1].signing_commit().unwrap();
+        commit_all(&mut parties[..2]);
+        parties[0].clear_signing_state();
+        // A late commitment from the peer arrives after the reset
+        parties[0].add_signing_commitment(2, &commitment).unwrap();
+
+        assert_eq!(
+            parties[0].sign("aa").unwrap_err().message(),
+            "Signing state was reset after commitment (committed at epoch 1, now epoch 2); call signing_commit again"
+        );
+        assert!(parties[0].sign_with_tweak("aa", 7).is_err());
+
+        // Committing again starts a fresh epoch that signs normally
+        commit_all(&mut parties[..2]);
+        assert!(parties[0].sign("aa").is_ok());
+    }
+
+    #[test]
+    fn test_can_finalize_waits_for_all_round2_packages() {
+        let mut parties: Vec<FrostDkgEd25519> = (1..=3).map(|_| FrostDkgEd25519::new()).collect();
+        let mut round1 = Vec::new();
+        for (i, party) in parties.iter_mut().enumerate() {
+            party.init_dkg(i as u16 + 1, 3, 2).unwrap();
+            round1.push(party.generate_round1().unwrap());
+        }
+        for (i, party) in parties.iter_mut().enumerate() {
+            for (j, package) in round1.iter().enumerate().filter(|(j, _)| *j != i) {
+                party.add_round1_package(j as u16 + 1, package).unwrap();
+            }
+        }
+        let round2: Vec<BTreeMap<u16, String>> = parties
+            .iter_mut()
+            .map(|party| serde_json::from_str(&party.generate_round2().unwrap()).unwrap())
+            .collect();
+
+        // Participant 1 of a 2-of-3 wallet: one round 2 package is not enough
+        let first = &mut parties[0];
+        assert!(!first.can_finalize());
+        first.add_round2_package(2, &round2[1][&1]).unwrap();
+        assert!(!first.can_finalize());
+        first.add_round2_package(3, &round2[2][&1]).unwrap();
+        assert!(first.can_finalize());
+        assert!(first.finalize_dkg().is_ok());
+    }
+
+    /// Run a 2-of-3 ed25519 DKG with every party numbering participants from `base`
+    fn ed25519_parties_with_base(base: u16) -> Vec<FrostDkgEd25519> {
+        let mut parties: Vec<FrostDkgEd25519> = (0..3).map(|_| FrostDkgEd25519::new()).collect();
+        let mut round1 = Vec::new();
+        for (i, party) in parties.iter_mut().enumerate() {
+            party.set_index_base(base);
+            party.init_dkg(base + i as u16, 3, 2).unwrap();
+            round1.push(party.generate_round1().unwrap());
+        }
+        for (i, party) in parties.iter_mut().enumerate() {
+            for (j, package) in round1.iter().enumerate().filter(|(j, _)| *j != i) {
+                party.add_round1_package(base + j as u16, package).unwrap();
+            }
+        }
+        let round2: Vec<BTreeMap<u16, String>> = parties
+            .iter_mut()
+            .map(|party| serde_json::from_str(&party.generate_round2().unwrap()).unwrap())
+            .collect();
+        for (i, party) in parties.iter_mut().enumerate() {
+            let recipient = base + i as u16;
+            for (j, packages) in round2.iter().enumerate().filter(|(j, _)| *j != i) {
+                party.add_round2_package(base + j as u16, &packages[&recipient]).unwrap();
+            }
+            party.finalize_dkg().unwrap();
+        }
+        parties
+    }
+
+    #[test]
+    fn test_zero_based_indices_map_to_same_identifiers() {
+        let zero_based = ed25519_parties_with_base(0);
+        let one_based = ed25519_parties_with_base(1);
+        for (zero, one) in zero_based.iter().zip(&one_based) {
+            assert_eq!(zero.participant_index(), one.participant_index());
+            assert!(zero.round1_packages.keys().eq(one.round1_packages.keys()));
+            assert!(zero.round2_packages.keys().eq(one.round2_packages.keys()));
+            assert_eq!(
+                zero.key_package.as_ref().unwrap().identifier(),
+                one.key_package.as_ref().unwrap().identifier()
+            );
+        }
+        assert_eq!(
+            zero_based.iter().map(|p| p.participant_index()).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+
+        // Index 3 is past the end when counting from 0
+        let mut party = FrostDkgEd25519::new();
+        party.set_index_base(0);
+        let err = party.init_dkg(3, 3, 2).unwrap_err();
+        assert_eq!(
+            err.message(),
+            "Participant index must be between 1 and total participants (got 3 with index base 0)"
+        );
+        assert!(party.init_dkg(2, 3, 2).is_ok());
+
+        // Indices that would wrap around u16 are errors, not panics
+        let mut party = FrostDkgEd25519::new();
+        party.set_index_base(u16::MAX);
+        assert!(party.init_dkg(0, 3, 2).is_err());
+        assert!(party.init_dkg(u16::MAX, 3, 2).is_ok());
+        assert_eq!(external_index(1, u16::MAX).unwrap(), u16::MAX);
+        let err = external_index(2, u16::MAX).unwrap_err();
+        assert_eq!(err.message(), "Participant 2 has no index when counting from 65535");
+    }
+
+    #[test]
+    fn test_public_key_package_fingerprints_match_across_participants() {
+        let parties = secp256k1_parties();
+        let fingerprints: Vec<String> = parties.iter().map(|p| p.public_key_package_fingerprint().unwrap()).collect();
+        assert!(fingerprints.iter().all(|fp| *fp == fingerprints[0]));
+        let exported = parties[0].export_public_key_package().unwrap();
+        assert_eq!(exported, parties[2].export_public_key_package().unwrap());
+        assert_eq!(fingerprints[0], fingerprint(&hex::decode(&exported).unwrap()));
+
+        // Reloading a keystore reproduces the same package
+        let mut imported = FrostDkgSecp256k1::new();
+        imported.import_keystore(&parties[1].export_keystore().unwrap()).unwrap();
+        assert_eq!(imported.public_key_package_fingerprint().unwrap(), fingerprints[0]);
+
+        assert_ne!(secp256k1_parties()[0].public_key_package_fingerprint().unwrap(), fingerprints[0]);
+        assert_eq!(
+            FrostDkgEd25519::new().public_key_package_fingerprint().unwrap_err().message(),
+            "DKG not complete"
+        );
+    }
+
+    #[test]
+    fn test_reshare_two_of_three_to_three_of_five_keeps_address() {
+        let mut old = secp256k1_parties();
+        let address = old[0].get_eth_address().unwrap();
+        let public_key_package_hex = old[0].export_public_key_package().unwrap();
+        assert!(old[0].reshare_generate(vec![1], 5, 3).is_err());
+
+        // Participants 1 and 3 deal; 2 only receives, and 4 and 5 are new
+        let dealt: Vec<(u16, BTreeMap<u16, String>)> = [0, 2]
+            .iter()
+            .map(|&i| {
+                let shares = old[i].reshare_generate(vec![1, 3], 5, 3).unwrap();
+                (old[i].participant_index(), serde_json::from_str(&shares).unwrap())
+            })
+            .collect();
+        let mut parties: Vec<FrostDkgSecp256k1> =
+            old.drain(..).chain((4..=5).map(|_| FrostDkgSecp256k1::new())).collect();
+        let mut digests = BTreeMap::new();
+        for (i, party) in parties.iter_mut().enumerate() {
+            let index = i as u16 + 1;
+            let inbox: BTreeMap<u16, &String> = dealt.iter().map(|(dealer, shares)| (*dealer, &shares[&index])).collect();
+            let digest = party
+                .reshare_receive(index, 5, 3, &public_key_package_hex, &serde_json::to_string(&inbox).unwrap())
+                .unwrap();
+            digests.insert(index, digest);
+        }
+        // Nobody switches before the digests are compared
+        assert_eq!((parties[0].threshold(), parties[0].total_participants()), (2, 3));
+        assert!(!parties[3].is_dkg_complete());
+        for (i, party) in parties.iter_mut().enumerate() {
+            let index = i as u16 + 1;
+            let others: BTreeMap<u16, &String> = digests.iter().filter(|(other, _)| **other != index).map(|(k, v)| (*k, v)).collect();
+            party.reshare_approve(&serde_json::to_string(&others).unwrap()).unwrap();
+            assert_eq!(party.get_eth_address().unwrap(), address);
+            assert_eq!((party.threshold(), party.total_participants()), (3, 5));
+        }
+        let fingerprint = parties[0].public_key_package_fingerprint().unwrap();
+        assert!(parties.iter().all(|p| p.public_key_package_fingerprint().unwrap() == fingerprint));
+
+        // A newcomer's key survives an export round trip
+        let mut restored = FrostDkgSecp256k1::new();
+        restored.import_keystore(&parties[4].export_keystore().unwrap()).unwrap();
+        parties[4] = restored;
+
+        let message_hex = hex::encode(b"after expansion");
+        let signers = [1, 3, 4];
+        let commitments: Vec<(u16, String)> = signers
+            .iter()
+            .map(|&i| (parties[i].participant_index(), parties[i].signing_commit().unwrap()))
+            .collect();
+        for &i in &signers {
+            for (index, commitment) in &commitments {
+                parties[i].add_signing_commitment(*index, commitment).unwrap();
+            }
+        }
+        let shares: Vec<(u16, String)> = signers
+            .iter()
+            .map(|&i| (parties[i].participant_index(), parties[i].sign(&message_hex).unwrap()))
+            .collect();
+        for (index, share) in &shares {
+            parties[1].add_signature_share(*index, share).unwrap();
+        }
+        let signature_hex = parties[1].aggregate_signature(&message_hex).unwrap();
+
+        let signature = frost_secp256k1::Signature::deserialize(&hex::decode(signature_hex).unwrap()).unwrap();
+        let old_public_key_package =
+            Secp256k1PublicKeyPackage::deserialize(&hex::decode(&public_key_package_hex).unwrap()).unwrap();
+        assert!(old_public_key_package.verifying_key().verify(b"after expansion", &signature).is_ok());
+    }
+
+    #[test]
+    fn test_reshare_with_diverging_digest_keeps_old_key() {
+        let mut old = secp256k1_parties();
+        let public_key_package_hex = old[0].export_public_key_package().unwrap();
+        let dealt: Vec<(u16, BTreeMap<u16, String>)> = [0, 1]
+            .iter()
+            .map(|&i| {
+                let shares = old[i].reshare_generate(vec![1, 2], 3, 3).unwrap();
+                (old[i].participant_index(), serde_json::from_str(&shares).unwrap())
+            })
+            .collect();
+        let inbox: BTreeMap<u16, &String> = dealt.iter().map(|(dealer, shares)| (*dealer, &shares[&1])).collect();
+        let digest = old[0]
+            .reshare_receive(1, 3, 3, &public_key_package_hex, &serde_json::to_string(&inbox).unwrap())
+            .unwrap();
+        assert_eq!(
+            old[0].reshare_approve(&serde_json::to_string(&BTreeMap::from([(2, &digest)])).unwrap()).unwrap_err().message(),
+            "DKG error: participant 3 has not reported its public key package"
+        );
+        assert_eq!(old[0].reshare_approve("{}").unwrap_err().message(), "No re-shared key awaiting approval");
+
+        old[0]
+            .reshare_receive(1, 3, 3, &public_key_package_hex, &serde_json::to_string(&inbox).unwrap())
+            .unwrap();
+        let forged = "ab".repeat(32);
+        let err = old[0]
+            .reshare_approve(&serde_json::to_string(&BTreeMap::from([(2, &digest), (3, &forged)])).unwrap())
+            .unwrap_err();
+        assert_eq!(err.message(), "DKG error: participant 3 re-shared to a different public key package");
+        // Still the old 2-of-3 share, and still able to sign with it
+        assert_eq!((old[0].threshold(), old[0].total_participants()), (2, 3));
+        assert_eq!(old[0].export_public_key_package().unwrap(), public_key_package_hex);
+        assert!(old[0].export_keystore().is_ok());
+    }
+
+    #[test]
+    fn test_coordinator_fixes_signer_set_across_arrival_orders() {
+        let mut parties = secp256k1_group(4, 2);
+        assert!(parties[0].set_signing_quorum(1).is_err());
+        assert!(parties[0].set_signing_quorum(5).is_err());
+
+        // Every node hears the four commitments in a different order
+        let message_hex = hex::encode(b"coordinator decides");
+        let commitments: Vec<String> = parties.iter_mut().map(|p| p.signing_commit().unwrap()).collect();
+        for (i, party) in parties.iter_mut().enumerate() {
+            party.set_signing_quorum(2).unwrap();
+            for j in (0..4).map(|k| (i + k + 1) % 4) {
+                party.add_signing_commitment(j as u16 + 1, &commitments[j]).unwrap();
+            }
+        }
+        let err = parties[2].sign(&message_hex).unwrap_err();
+        assert!(err.message().contains("sign the package the coordinator fixed"), "{}", err.message());
+
+        // Participant 2 coordinates: itself plus the first to reach it, 3
+        let package_json = parties[1].fix_signing_package(&message_hex).unwrap();
+        let tagged: serde_json::Value = serde_json::from_str(&package_json).unwrap();
+        let package = import_signing_package(tagged["signing_package"].as_str().unwrap());
+        let signers: Vec<Secp256k1Identifier> = package.signing_commitments().keys().copied().collect();
+        assert_eq!(signers, [2, 3].map(|i| Secp256k1Identifier::try_from(i).unwrap()));
+
+        // Participant 3 heard 4 and 1 first, but signs the coordinator's set
+        parties[2].import_signing_package(&package_json).unwrap();
+        for outsider in [0, 3] {
+            let err = parties[outsider].import_signing_package(&package_json).unwrap_err();
+            assert_eq!(err.message(), "The coordinator did not select us to sign");
+        }
+        let shares: Vec<String> = [1, 2].iter().map(|&i| parties[i].sign(&message_hex).unwrap()).collect();
+        for (index, share) in [2, 3].iter().zip(&shares) {
+            parties[1].add_signature_share(*index, share).unwrap();
+        }
+        let signature_hex = parties[1].aggregate_signature(&message_hex).unwrap();
+
+        let signature = frost_secp256k1::Signature::deserialize(&hex::decode(signature_hex).unwrap()).unwrap();
+        let public_key_package = parties[0].public_key_package.as_ref().unwrap();
+        assert!(public_key_package.verifying_key().verify(b"coordinator decides", &signature).is_ok());
+    }
+
+    #[test]
+    fn test_bad_share_falls_back_to_next_signer_set() {
+        let mut parties = secp256k1_group(4, 2);
+        let message_hex = hex::encode(b"fall back");
+        let commitments: Vec<String> = parties.iter_mut().map(|p| p.signing_commit().unwrap()).collect();
+        for party in parties.iter_mut() {
+            party.set_signing_quorum(2).unwrap();
+            for (j, commitment) in commitments.iter().enumerate() {
+                party.add_signing_commitment(j as u16 + 1, commitment).unwrap();
+            }
+        }
+        let package_json = parties[0].fix_signing_package(&message_hex).unwrap();
+        parties[1].import_signing_package(&package_json).unwrap();
+        let own_share = parties[0].sign(&message_hex).unwrap();
+        parties[1].sign(&message_hex).unwrap();
+
+        // Participant 2's share doesn't verify
+        parties[0].add_signature_share(1, &own_share).unwrap();
+        parties[0].add_signature_share(2, &own_share).unwrap();
+        let err = parties[0].aggregate_signature(&message_hex).unwrap_err();
+        assert!(err.message().contains("excluded participant 2"), "{}", err.message());
+
+        // The coordinator commits afresh; 3's unused commitment fills the set
+        let recommitted = parties[0].signing_commit().unwrap();
+        assert_ne!(recommitted, commitments[0]);
+        parties[0].add_signing_commitment(1, &recommitted).unwrap();
+        parties[0].add_signing_commitment(2, &commitments[1]).unwrap();
+        let package_json = parties[0].fix_signing_package(&message_hex).unwrap();
+        let tagged: serde_json::Value = serde_json::from_str(&package_json).unwrap();
+        let package = import_signing_package(tagged["signing_package"].as_str().unwrap());
+        let signers: Vec<Secp256k1Identifier> = package.signing_commitments().keys().copied().collect();
+        assert_eq!(signers, [1, 3].map(|i| Secp256k1Identifier::try_from(i).unwrap()));
+
+        parties[2].import_signing_package(&package_json).unwrap();
+        let shares = [parties[0].sign(&message_hex).unwrap(), parties[2].sign(&message_hex).unwrap()];
+        for (index, share) in [1, 3].iter().zip(&shares) {
+            parties[0].add_signature_share(*index, share).unwrap();
+        }
+        let signature_hex = parties[0].aggregate_signature(&message_hex).unwrap();
+        let signature = frost_secp256k1::Signature::deserialize(&hex::decode(signature_hex).unwrap()).unwrap();
+        let public_key_package = parties[0].public_key_package.as_ref().unwrap();
+        assert!(public_key_package.verifying_key().verify(b"fall back", &signature).is_ok());
+    }
+
+    #[test]
+    fn test_divergent_group_key_fails_dkg_before_signing() {
+        let (mut parties, fingerprints) = secp256k1_dkg(3, 2);
+        // Party 3's fingerprint as a misrouted DKG would leave it: a different key
+        let divergent = secp256k1_dkg(3, 2).1[2].clone();
+        assert!(fingerprints.iter().all(|fp| *fp == fingerprints[0]));
+
+        // No keystore leaves a party until every other participant agrees
+        assert!(parties[1].export_keystore().is_err());
+        assert!(parties[1].add_peer_fingerprint(2, &fingerprints[1]).is_err());
+        parties[1].add_peer_fingerprint(1, &fingerprints[0]).unwrap();
+        assert!(!parties[1].is_group_key_confirmed());
+        assert!(parties[1].export_keystore().is_err());
+        parties[1].add_peer_fingerprint(3, &fingerprints[2]).unwrap();
+        assert!(parties[1].is_group_key_confirmed());
+        assert!(parties[1].export_keystore().is_ok());
+
+        let first = &mut parties[0];
+        first.add_peer_fingerprint(2, &fingerprints[1]).unwrap();
+        let err = first.add_peer_fingerprint(3, &divergent).unwrap_err();
+        assert!(err.message().contains("participant 3 finalized to group key fingerprint"), "{}", err.message());
+        assert!(!first.is_group_key_confirmed());
+        assert!(!first.is_dkg_complete());
+        assert!(first.export_keystore().is_err());
+        assert!(first.signing_commit().is_err());
+    }
+
+    #[test]
+    fn test_add_round2_package_accepts_double_encoded_json() {
+        let mut parties: Vec<FrostDkgSecp256k1> = (1..=2).map(|_| FrostDkgSecp256k1::new()).collect();
+        let round1: Vec<String> = parties
+            .iter_mut()
+            .enumerate()
+            .map(|(i, party)| {
+                party.init_dkg(i as u16 + 1, 2, 2).unwrap();
+                party.generate_round1().unwrap()
+            })
+            .collect();
+        for (i, party) in parties.iter_mut().enumerate() {
+            for (j, package) in round1.iter().enumerate().filter(|(j, _)| *j != i) {
+                party.add_round1_package(j as u16 + 1, package).unwrap();
+            }
+        }
+        let round2: BTreeMap<u16, String> = serde_json::from_str(&parties[1].generate_round2().unwrap()).unwrap();
+        parties[0].generate_round2().unwrap();
+
+        // What the older path sends: the package JSON wrapped in a JSON string
+        let package_json = String::from_utf8(hex::decode(&round2[&1]).unwrap()).unwrap();
+        let double_encoded = hex::encode(serde_json::to_string(&package_json).unwrap());
+        parties[0].add_round2_package(2, &double_encoded).unwrap();
+        assert!(parties[0].finalize_dkg().is_ok());
+
+        assert!(parties[0].add_round2_package(2, &hex::encode("\"not a package\"")).is_err());
+    }
+
+    #[test]
+    fn test_dkg_status_counts_packages() {
+        let status = |dkg: &FrostDkgSecp256k1| -> serde_json::Value {
+            serde_json::from_str(&dkg.dkg_status()).unwrap()
+        };
+        let mut parties: Vec<FrostDkgSecp256k1> = (1..=3).map(|_| FrostDkgSecp256k1::new()).collect();
+        assert_eq!(status(&parties[0])["round"], 0);
+
+        let mut round1 = Vec::new();
+        for (i, party) in parties.iter_mut().enumerate() {
+            party.init_dkg(i as u16 + 1, 3, 2).unwrap();
+            round1.push(party.generate_round1().unwrap());
+        }
+        assert_eq!(
+            status(&parties[0]),
+            serde_json::json!({ "round": 1, "packages_received": 0, "packages_expected": 2, "complete": false })
+        );
+        for (i, party) in parties.iter_mut().enumerate() {
+            for (received, (j, package)) in round1.iter().enumerate().filter(|(j, _)| *j != i).enumerate() {
+                party.add_round1_package(j as u16 + 1, package).unwrap();
+                assert_eq!(status(party)["packages_received"], received + 1);
+            }
+        }
+        assert_eq!(status(&parties[0])["packages_received"], 2);
+
+        let round2: Vec<BTreeMap<u16, String>> = parties
+            .iter_mut()
+            .map(|party| serde_json::from_str(&party.generate_round2().unwrap()).unwrap())
+            .collect();
+        assert_eq!(
+            status(&parties[0]),
+            serde_json::json!({ "round": 2, "packages_received": 0, "packages_expected": 2, "complete": false })
+        );
+        for (j, received) in [(1, 1), (2, 2)] {
+            parties[0].add_round2_package(j as u16 + 1, &round2[j][&1]).unwrap();
+            assert_eq!(status(&parties[0])["packages_received"], received);
+        }
+        assert_eq!(status(&parties[0])["complete"], false);
+        parties[0].finalize_dkg().unwrap();
+        assert_eq!(status(&parties[0])["complete"], true);
+    }
+
+    #[test]
+    fn test_encrypted_keystore_round_trip() {
+        let parties = secp256k1_parties();
+        let encrypted = parties[0].export_keystore_encrypted("correct horse").unwrap();
+        let fields: serde_json::Value = serde_json::from_str(&encrypted).unwrap();
+        assert_eq!(fields["encryption"], KEYSTORE_ENCRYPTION);
+        assert!(fields.get("key_package").is_none() && fields.get("public_key_package").is_none());
+        assert_eq!(fields["min_signers"], 2);
+
+        let mut imported = FrostDkgSecp256k1::new();
+        let err = imported.import_keystore(&encrypted).unwrap_err();
+        assert_eq!(err.message(), "Keystore is encrypted; use import_keystore_encrypted");
+        assert!(imported.import_keystore_encrypted(&encrypted, "wrong").is_err());
+
+        // The readable fields are authenticated along with the key packages
+        let mut tampered = fields.clone();
+        tampered["min_signers"] = 1.into();
+        assert!(imported.import_keystore_encrypted(&tampered.to_string(), "correct horse").is_err());
+
+        let mut expensive = fields.clone();
+        expensive["kdf_params"]["m_cost"] = (MAX_KDF_M_COST_KIB + 1).into();
+        let err = imported.import_keystore_encrypted(&expensive.to_string(), "correct horse").unwrap_err();
+        assert!(err.message().contains("above the limit"), "{}", err.message());
+
+        imported.import_keystore_encrypted(&encrypted, "correct horse").unwrap();
+        assert_eq!(imported.get_group_public_key().unwrap(), parties[0].get_group_public_key().unwrap());
+        assert_eq!(imported.export_keystore().unwrap(), parties[0].export_keystore().unwrap());
+    }
+
+    #[test]
+    fn test_dkg_diagnostics_names_missing_packages_and_failure() {
+        let report = |dkg: &FrostDkgSecp256k1| -> serde_json::Value {
+            serde_json::from_str(&dkg.dkg_diagnostics()).unwrap()
+        };
+        MOCK_NOW_MS.with(|now| now.set(1_000_000));
+        let mut parties: Vec<FrostDkgSecp256k1> = (1..=3).map(|_| FrostDkgSecp256k1::new()).collect();
+        assert_eq!(report(&parties[0])["phase"], "uninitialized");
+
+        let mut round1 = Vec::new();
+        for (i, party) in parties.iter_mut().enumerate() {
+            party.clock = mock_now;
+            party.init_dkg(i as u16 + 1, 3, 2).unwrap();
+            round1.push(party.generate_round1().unwrap());
+        }
+        MOCK_NOW_MS.with(|now| now.set(1_005_000));
+        parties[0].add_round1_package(2, &round1[1]).unwrap();
+        let waiting = report(&parties[0]);
+        assert_eq!(waiting["phase"], "round1");
+        assert_eq!(waiting["missing_round1"], serde_json::json!([3]));
+        assert_eq!(waiting["participants"][1]["round1_received_at_ms"], 1_005_000);
+        assert_eq!(waiting["started_at_ms"], 1_000_000);
+        assert_eq!(waiting["participants"][2]["status"], "waiting for round 1 package");
+
+        for (i, party) in parties.iter_mut().enumerate() {
+            for (j, package) in round1.iter().enumerate().filter(|(j, _)| *j != i) {
+                party.add_round1_package(j as u16 + 1, package).unwrap();
+            }
+        }
+        let round2: Vec<BTreeMap<u16, String>> = parties
+            .iter_mut()
+            .map(|party| serde_json::from_str(&party.generate_round2().unwrap()).unwrap())
+            .collect();
+        // Every participant holds the same round 1 packages
+        let transcript = report(&parties[0])["transcript_hash"].clone();
+        assert!(parties.iter().all(|party| report(party)["transcript_hash"] == transcript));
+
+        // Participant 3's round 2 package never arrives
+        parties[0].add_round2_package(2, &round2[1][&1]).unwrap();
+        let error = parties[0].finalize_dkg().unwrap_err();
+        let failed = report(&parties[0]);
+        assert_eq!(failed["phase"], "round2");
+        assert_eq!(failed["missing_round1"], serde_json::json!([]));
+        assert_eq!(failed["missing_round2"], serde_json::json!([3]));
+        assert_eq!(failed["participants"][2]["status"], "waiting for round 2 package");
+        assert_eq!(failed["failure"]["step"], "finalize_dkg");
+        assert_eq!(failed["failure"]["error"], error.message());
+        let next_steps = failed["next_steps"].to_string();
+        assert!(next_steps.contains("round 2 packages from participants [3]"), "{}", next_steps);
+    }
+
+    #[test]
+    fn test_batched_package_ingestion() {
+        let mut parties: Vec<FrostDkgEd25519> = (1..=3).map(|_| FrostDkgEd25519::new()).collect();
+        let mut round1 = BTreeMap::new();
+        for (i, party) in parties.iter_mut().enumerate() {
+            party.init_dkg(i as u16 + 1, 3, 2).unwrap();
+            round1.insert(i as u16 + 1, party.generate_round1().unwrap());
+        }
+        for (i, party) in parties.iter_mut().enumerate() {
+            let others: BTreeMap<_, _> = round1.iter().filter(|(j, _)| **j != i as u16 + 1).collect();
+            party.add_round1_packages(&serde_json::to_string(&others).unwrap()).unwrap();
+        }
+        let round2: Vec<BTreeMap<u16, String>> = parties
+            .iter_mut()
+            .map(|party| serde_json::from_str(&party.generate_round2().unwrap()).unwrap())
+            .collect();
+
+        let batch: BTreeMap<u16, &String> = [(2, &round2[1][&1]), (3, &round2[2][&1])].into();
+        parties[0].add_round2_packages(&serde_json::to_string(&batch).unwrap()).unwrap();
+        assert!(parties[0].can_finalize());
+        assert!(parties[0].finalize_dkg().is_ok());
+    }
+
+    #[test]
+    fn test_batched_ingestion_rejects_whole_batch() {
+        let mut alice = FrostDkgSecp256k1::new();
+        let mut bob = FrostDkgSecp256k1::new();
+        alice.init_dkg(1, 3, 2).unwrap();
+        bob.init_dkg(2, 3, 2).unwrap();
+        alice.generate_round1().unwrap();
+        let bob_round1 = bob.generate_round1().unwrap();
+
+        let batch = serde_json::json!({ "2": bob_round1, "3": hex::encode("not a package") }).to_string();
+        let error = alice.add_round1_packages(&batch).unwrap_err().message();
+        assert!(error.starts_with("Package from participant 3: "), "{}", error);
+        let batch = serde_json::json!({ "2": bob_round1, "4": bob_round1 }).to_string();
+        assert!(alice.add_round1_packages(&batch).is_err());
+
+        // Bob's valid package was not stored either time
+        assert!(alice.round1_packages.is_empty());
+    }
+
+    #[test]
+    fn test_signers_export_identical_signing_packages() {
+        let mut parties = secp256k1_parties();
+        let message_hex = hex::encode(b"compare packages");
+        let commitments: Vec<String> = parties[..2].iter_mut().map(|p| p.signing_commit().unwrap()).collect();
+        for party in parties[..2].iter_mut() {
+            // Arrival order doesn't change the package
+            for (j, commitment) in commitments.iter().enumerate().rev() {
+                party.add_signing_commitment(j as u16 + 1, commitment).unwrap();
+            }
+        }
+
+        let exported = parties[0].export_signing_package(&message_hex).unwrap();
+        assert_eq!(exported, parties[1].export_signing_package(&message_hex).unwrap());
+        assert_ne!(exported, parties[0].export_signing_package("00").unwrap());
+
+        let package = import_signing_package(&exported);
+        assert_eq!(package.message(), b"compare packages");
+        assert_eq!(package.signing_commitments(), &parties[0].signing.commitments);
+
+        // The tagged form wraps the same bytes
+        let tagged: serde_json::Value =
+            serde_json::from_str(&parties[0].export_tagged_signing_package(&message_hex).unwrap()).unwrap();
+        assert_eq!(tagged["ciphersuite_context"], Secp256k1Curve::ciphersuite_context());
+        assert_eq!(tagged["signing_package"], exported);
+    }
+
+    #[test]
+    fn test_sign_needs_threshold_commitments_including_own() {
+        let mut parties = secp256k1_parties();
+        let commitments: Vec<String> = parties.iter_mut().map(|p| p.signing_commit().unwrap()).collect();
+
+        parties[0].add_signing_commitment(1, &commitments[0]).unwrap();
+        let err = parties[0].sign("aa").unwrap_err();
+        assert_eq!(err.message(), "Need at least 2 signing commitments, have 1");
+        let err = parties[0].aggregate_signature("aa").unwrap_err();
+        assert_eq!(err.message(), "Need at least 2 signing commitments, have 1");
+
+        // Enough commitments, but none from this participant
+        for (j, commitment) in commitments.iter().enumerate().take(2) {
+            parties[2].add_signing_commitment(j as u16 + 1, commitment).unwrap();
+        }
+        let err = parties[2].sign("aa").unwrap_err();
+        assert_eq!(err.message(), "Our own signing commitment has not been added");
+    }
+
+    #[test]
+    fn test_compressed_round2_packages_shrink_and_round_trip() {
+        let mut parties: Vec<FrostDkgSecp256k1> = (1..=5).map(|_| FrostDkgSecp256k1::new()).collect();
+        let round1: Vec<String> = parties
+            .iter_mut()
+            .enumerate()
+            .map(|(i, party)| {
+                party.init_dkg(i as u16 + 1, 5, 3).unwrap();
+                party.generate_round1().unwrap()
+            })
+            .collect();
+        for (i, party) in parties.iter_mut().enumerate() {
+            for (j, package) in round1.iter().enumerate().filter(|(j, _)| *j != i) {
+                party.add_round1_package(j as u16 + 1, package).unwrap();
+            }
+        }
+        let bundle: BTreeMap<u16, String> = serde_json::from_str(&parties[0].generate_round2().unwrap()).unwrap();
+
+        let (mut plain_len, mut compressed_len) = (0, 0);
+        for (recipient, package_hex) in &bundle {
+            let package_json = String::from_utf8(hex::decode(package_hex).unwrap()).unwrap();
+            let compressed = encode_package(&package_json, true).unwrap();
+            assert_eq!(decode_package(&compressed).unwrap(), package_json.as_bytes());
+            plain_len += package_hex.len();
+            compressed_len += compressed.len();
+
+            let recipient = &mut parties[*recipient as usize - 1];
+            recipient.add_round2_package(1, &compressed).unwrap();
+        }
+        // The random signing share doesn't compress; the JSON scaffolding around it does
+        assert!(compressed_len * 100 <= plain_len * 92, "{} vs {}", compressed_len, plain_len);
+
+        parties[0].set_package_compression(true);
+        let compressed_bundle: BTreeMap<u16, String> = serde_json::from_str(&parties[0].generate_round2().unwrap()).unwrap();
+        assert!(compressed_bundle.values().all(|hex| hex.starts_with("ff44")));
+    }
+
+    #[test]
+    fn test_signing_bundles_round_trip() {
+        let mut parties = secp256k1_parties();
+        let message_hex = hex::encode(b"relayed signing");
+        let signers = [0, 2];
+
+        let commitments: Vec<String> = signers
+            .iter()
+            .map(|&i| parties[i].export_signing_bundle("session-1", None).unwrap())
+            .collect();
+        for &i in &signers {
+            for (bundle, &sender) in commitments.iter().zip(&signers) {
+                assert_eq!(parties[i].import_signing_bundle("session-1", bundle).unwrap(), sender as u16 + 1);
+            }
+        }
+        // Bundles are bound to their session and sender
+        assert!(parties[1].import_signing_bundle("session-2", &commitments[0]).is_err());
+        let mut forged: serde_json::Value = serde_json::from_str(&commitments[0]).unwrap();
+        forged["participant_index"] = 2.into();
+        let error = parties[1].import_signing_bundle("session-1", &forged.to_string()).unwrap_err();
+        assert_eq!(error.message(), "Participant 2 sent a bundle it did not sign");
+
+        let shares: Vec<String> = signers
+            .iter()
+            .map(|&i| parties[i].export_signing_bundle("session-1", Some(message_hex.clone())).unwrap())
+            .collect();
+        for bundle in &shares {
+            parties[0].import_signing_bundle("session-1", bundle).unwrap();
+        }
+        let signature = parties[0].aggregate_signature(&message_hex).unwrap();
+        let signature = frost_secp256k1::Signature::deserialize(&hex::decode(signature).unwrap()).unwrap();
+        let public_key_package = parties[0].public_key_package.as_ref().unwrap();
+        assert!(public_key_package.verifying_key().verify(b"relayed signing", &signature).is_ok());
+    }
+
+    #[test]
+    fn test_signing_bundle_index_out_of_range_is_rejected() {
+        let mut parties = secp256k1_parties();
+        // The receiver numbers participants from 0; bundles stay 1-based
+        parties[0].set_index_base(0);
+        parties[0].signing_commit().unwrap();
+        let bundle = parties[2].export_signing_bundle("session-1", None).unwrap();
+        assert_eq!(parties[0].import_signing_bundle("session-1", &bundle).unwrap(), 3);
+
+        for index in [0, 4, u16::MAX] {
+            let mut forged: serde_json::Value = serde_json::from_str(&bundle).unwrap();
+            forged["participant_index"] = index.into();
+            let error = parties[0].import_signing_bundle("session-1", &forged.to_string()).unwrap_err();
+            assert_eq!(
+                error.message(),
+                format!("Signing bundle is from participant {}, outside this wallet's participants 1 to 3", index)
+            );
+        }
+    }
+}
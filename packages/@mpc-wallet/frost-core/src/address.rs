@@ -0,0 +1,143 @@
+//! Chain address formatting, looked up by chain id.
+//!
+//! [`FrostCurve::get_address_for_chain`](crate::FrostCurve::get_address_for_chain)
+//! dispatches through a process-wide registry of [`AddressFormatter`]s, so a
+//! new chain only needs a formatter registered with
+//! [`register_address_formatter`] rather than a change to the curve impls.
+//! Ethereum and Solana are registered up front. Bitcoin is not: its
+//! SegWit v0 formats need ECDSA and P2TR needs the taproot tweak, neither of
+//! which the FROST signers produce.
+
+use crate::ed25519::Ed25519Curve;
+use crate::errors::{FrostError, Result};
+use crate::secp256k1::Secp256k1Curve;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Turns a group verifying key into an address on one chain
+pub trait AddressFormatter: Send + Sync {
+    /// Curve whose keys this chain uses, "ed25519" or "secp256k1"
+    fn curve(&self) -> &str;
+
+    /// Address for `verifying_key`, as the curve's `serialize_verifying_key`
+    /// encodes it
+    fn format(&self, verifying_key: &[u8]) -> Result<String>;
+}
+
+fn serialization_error(error: impl ToString) -> FrostError {
+    FrostError::SerializationError(error.to_string())
+}
+
+/// Keccak-256 of the uncompressed key, last 20 bytes
+pub struct EthereumFormatter;
+
+impl AddressFormatter for EthereumFormatter {
+    fn curve(&self) -> &str {
+        "secp256k1"
+    }
+
+    fn format(&self, verifying_key: &[u8]) -> Result<String> {
+        let key = frost_secp256k1::VerifyingKey::deserialize(verifying_key).map_err(serialization_error)?;
+        Secp256k1Curve::get_eth_address(&key)
+    }
+}
+
+/// Base58 of the 32-byte key
+pub struct SolanaFormatter;
+
+impl AddressFormatter for SolanaFormatter {
+    fn curve(&self) -> &str {
+        "ed25519"
+    }
+
+    fn format(&self, verifying_key: &[u8]) -> Result<String> {
+        let key = frost_ed25519::VerifyingKey::deserialize(verifying_key).map_err(serialization_error)?;
+        Ed25519Curve::get_solana_address(&key)
+    }
+}
+
+type Registry = RwLock<HashMap<String, Arc<dyn AddressFormatter>>>;
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let builtins: [(&str, Arc<dyn AddressFormatter>); 2] = [
+            ("ethereum", Arc::new(EthereumFormatter)),
+            ("solana", Arc::new(SolanaFormatter)),
+        ];
+        RwLock::new(builtins.into_iter().map(|(chain, formatter)| (chain.to_string(), formatter)).collect())
+    })
+}
+
+/// Register `formatter` for `chain` (case-insensitive), replacing any
+/// formatter already registered for it, built-ins included
+pub fn register_address_formatter(chain: &str, formatter: Arc<dyn AddressFormatter>) {
+    registry()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(chain.to_lowercase(), formatter);
+}
+
+/// The formatter registered for `chain`, if any
+pub fn address_formatter(chain: &str) -> Option<Arc<dyn AddressFormatter>> {
+    registry()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(&chain.to_lowercase())
+        .cloned()
+}
+
+/// Address on `chain` for a serialized `curve` verifying key. Fails if no
+/// formatter is registered for `chain` or it takes another curve's keys.
+pub fn format_address(curve: &str, chain: &str, verifying_key: &[u8]) -> Result<String> {
+    match address_formatter(chain) {
+        Some(formatter) if formatter.curve() == curve => formatter.format(verifying_key),
+        _ => Err(FrostError::InvalidState(format!(
+            "Unsupported chain for {}: {}",
+            curve,
+            chain.to_lowercase()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrostCurve;
+
+    /// Hypothetical chain whose address is the hex key behind a prefix
+    struct PrefixedHexFormatter;
+
+    impl AddressFormatter for PrefixedHexFormatter {
+        fn curve(&self) -> &str {
+            "secp256k1"
+        }
+
+        fn format(&self, verifying_key: &[u8]) -> Result<String> {
+            Ok(format!("hx{}", hex::encode(verifying_key)))
+        }
+    }
+
+    #[test]
+    fn test_custom_formatter_derives_address() {
+        // Compressed public key of private key 1 (the generator point)
+        let key_hex = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let key = frost_secp256k1::VerifyingKey::deserialize(&hex::decode(key_hex).unwrap()).unwrap();
+        assert!(Secp256k1Curve::get_address_for_chain(&key, "prefixed-hex").is_err());
+
+        register_address_formatter("Prefixed-Hex", Arc::new(PrefixedHexFormatter));
+        assert_eq!(
+            Secp256k1Curve::get_address_for_chain(&key, "prefixed-hex").unwrap(),
+            format!("hx{}", key_hex)
+        );
+        // Built-ins still resolve alongside it
+        assert_eq!(
+            Secp256k1Curve::get_address_for_chain(&key, "ethereum").unwrap(),
+            "0x7e5f4552091a69125d5dfcb7b8c2659029395bdf"
+        );
+        assert_eq!(
+            format_address("ed25519", "prefixed-hex", &[0; 32]).unwrap_err().to_string(),
+            "Invalid state: Unsupported chain for ed25519: prefixed-hex"
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Signed envelopes for passing signing commitments and shares through a
+//! relay.
+//!
+//! A [`SigningBundle`] carries one participant's commitment or signature
+//! share together with the session and ciphersuite it belongs to. The sender
+//! signs it with its key share, so a receiver can check it against that
+//! participant's public share from the DKG before ingesting it, whoever
+//! forwarded it.
+
+use crate::errors::{FrostError, Result};
+use frost_core::keys::{KeyPackage, PublicKeyPackage};
+use frost_core::round1::SigningCommitments;
+use frost_core::round2::SignatureShare;
+use frost_core::{Ciphersuite, Identifier, Signature, SigningKey, VerifyingKey};
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+
+/// Prefixed to the signed bytes so a bundle signature can't be replayed as
+/// anything else made with the same key share
+const BUNDLE_DOMAIN: &str = "mpc-wallet/signing-bundle/v1";
+
+/// What a bundle's payload holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BundleContents {
+    Commitment,
+    SignatureShare,
+}
+
+/// One participant's signing round message, signed by that participant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningBundle {
+    pub ciphersuite_context: String,
+    pub session_id: String,
+    /// 1-based participant index of the sender
+    pub participant_index: u16,
+    pub contents: BundleContents,
+    /// Hex of the commitment's or share's canonical FROST serialization
+    pub payload: String,
+    /// Hex Schnorr signature over the fields above by the sender's key share
+    pub signature: String,
+}
+
+fn invalid(participant_index: u16, reason: &str) -> FrostError {
+    FrostError::InvalidContribution {
+        participant: participant_index.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+fn identifier<C: Ciphersuite>(participant_index: u16) -> Result<Identifier<C>> {
+    Identifier::try_from(participant_index)
+        .map_err(|_| FrostError::InvalidIdentifier(format!("Invalid participant index {}", participant_index)))
+}
+
+impl SigningBundle {
+    /// Bundle `commitments`, signed with `key_package`
+    pub fn commitment<C: Ciphersuite>(
+        key_package: &KeyPackage<C>,
+        participant_index: u16,
+        ciphersuite_context: &str,
+        session_id: &str,
+        commitments: &SigningCommitments<C>,
+    ) -> Result<Self> {
+        let payload = commitments
+            .serialize()
+            .map_err(|e| FrostError::SerializationError(e.to_string()))?;
+        Self::seal(key_package, participant_index, ciphersuite_context, session_id, BundleContents::Commitment, &payload)
+    }
+
+    /// Bundle a signature share, signed with the `key_package` that made it
+    pub fn signature_share<C: Ciphersuite>(
+        key_package: &KeyPackage<C>,
+        participant_index: u16,
+        ciphersuite_context: &str,
+        session_id: &str,
+        share: &SignatureShare<C>,
+    ) -> Result<Self> {
+        Self::seal(
+            key_package,
+            participant_index,
+            ciphersuite_context,
+            session_id,
+            BundleContents::SignatureShare,
+            &share.serialize(),
+        )
+    }
+
+    fn seal<C: Ciphersuite>(
+        key_package: &KeyPackage<C>,
+        participant_index: u16,
+        ciphersuite_context: &str,
+        session_id: &str,
+        contents: BundleContents,
+        payload: &[u8],
+    ) -> Result<Self> {
+        if *key_package.identifier() != identifier::<C>(participant_index)? {
+            return Err(FrostError::InvalidState(format!(
+                "Key package does not belong to participant {}",
+                participant_index
+            )));
+        }
+        let mut bundle = Self {
+            ciphersuite_context: ciphersuite_context.to_string(),
+            session_id: session_id.to_string(),
+            participant_index,
+            contents,
+            payload: hex::encode(payload),
+            signature: String::new(),
+        };
+        let signing_key = SigningKey::<C>::deserialize(&key_package.signing_share().serialize())
+            .map_err(|e| FrostError::SigningError(e.to_string()))?;
+        let signature = signing_key
+            .sign(OsRng, &bundle.signed_bytes())
+            .serialize()
+            .map_err(|e| FrostError::SerializationError(e.to_string()))?;
+        bundle.signature = hex::encode(signature);
+        Ok(bundle)
+    }
+
+    // Length-prefixed by way of JSON, so no two bundles sign the same bytes
+    fn signed_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(&(
+            BUNDLE_DOMAIN,
+            &self.ciphersuite_context,
+            &self.session_id,
+            self.participant_index,
+            self.contents,
+            &self.payload,
+        ))
+        .expect("tuple of strings and integers serializes")
+    }
+
+    /// Checks that the bundle was made for this ciphersuite and session, by
+    /// a member of `public_key_package`, and is signed by that member's key
+    /// share. Returns the sender's identifier.
+    pub fn verify<C: Ciphersuite>(
+        &self,
+        ciphersuite_context: &str,
+        session_id: &str,
+        public_key_package: &PublicKeyPackage<C>,
+    ) -> Result<Identifier<C>> {
+        if self.ciphersuite_context != ciphersuite_context {
+            return Err(FrostError::InvalidState(format!(
+                "Signing bundle uses ciphersuite {}, this node uses {}",
+                self.ciphersuite_context, ciphersuite_context
+            )));
+        }
+        if self.session_id != session_id {
+            return Err(FrostError::InvalidState(format!(
+                "Signing bundle is for session {}, expected {}",
+                self.session_id, session_id
+            )));
+        }
+        let sender = identifier::<C>(self.participant_index)?;
+        let verifying_share = public_key_package
+            .verifying_shares()
+            .get(&sender)
+            .ok_or_else(|| invalid(self.participant_index, "is not part of the group"))?;
+        let verifying_key = verifying_share
+            .serialize()
+            .ok()
+            .and_then(|bytes| VerifyingKey::<C>::deserialize(&bytes).ok())
+            .ok_or_else(|| invalid(self.participant_index, "has an unusable public share"))?;
+        let signature = hex::decode(&self.signature)
+            .ok()
+            .and_then(|bytes| Signature::<C>::deserialize(&bytes).ok())
+            .ok_or_else(|| invalid(self.participant_index, "sent a malformed bundle signature"))?;
+        verifying_key
+            .verify(&self.signed_bytes(), &signature)
+            .map_err(|_| invalid(self.participant_index, "sent a bundle it did not sign"))?;
+        Ok(sender)
+    }
+
+    /// The commitment inside a `BundleContents::Commitment` bundle
+    pub fn to_commitment<C: Ciphersuite>(&self) -> Result<SigningCommitments<C>> {
+        let payload = self.payload_bytes(BundleContents::Commitment)?;
+        SigningCommitments::deserialize(&payload).map_err(|e| FrostError::SerializationError(e.to_string()))
+    }
+
+    /// The share inside a `BundleContents::SignatureShare` bundle
+    pub fn to_signature_share<C: Ciphersuite>(&self) -> Result<SignatureShare<C>> {
+        let payload = self.payload_bytes(BundleContents::SignatureShare)?;
+        SignatureShare::deserialize(&payload).map_err(|e| FrostError::SerializationError(e.to_string()))
+    }
+
+    fn payload_bytes(&self, expected: BundleContents) -> Result<Vec<u8>> {
+        if self.contents != expected {
+            return Err(FrostError::InvalidState(format!(
+                "Signing bundle holds {:?}, expected {:?}",
+                self.contents, expected
+            )));
+        }
+        hex::decode(&self.payload).map_err(|e| FrostError::SerializationError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1::Secp256K1Sha256;
+    use frost_secp256k1::keys::IdentifierList;
+
+    #[test]
+    fn test_bundle_round_trip_and_tampering() {
+        let (shares, public_key_package) =
+            frost_secp256k1::keys::generate_with_dealer(3, 2, IdentifierList::Default, OsRng).unwrap();
+        let sender = Identifier::try_from(2).unwrap();
+        let key_package = KeyPackage::try_from(shares[&sender].clone()).unwrap();
+        let (_, commitments) = frost_secp256k1::round1::commit(key_package.signing_share(), &mut OsRng);
+
+        let bundle = SigningBundle::commitment(&key_package, 2, "FROST-secp256k1", "session-1", &commitments).unwrap();
+        let bundle: SigningBundle = serde_json::from_str(&serde_json::to_string(&bundle).unwrap()).unwrap();
+        assert_eq!(bundle.verify("FROST-secp256k1", "session-1", &public_key_package).unwrap(), sender);
+        assert_eq!(bundle.to_commitment::<Secp256K1Sha256>().unwrap(), commitments);
+        assert!(bundle.to_signature_share::<Secp256K1Sha256>().is_err());
+        assert!(bundle.verify("FROST-secp256k1", "session-2", &public_key_package).is_err());
+
+        // Claiming to be another participant breaks the signature
+        let forged = SigningBundle { participant_index: 3, ..bundle.clone() };
+        let error = forged.verify("FROST-secp256k1", "session-1", &public_key_package).unwrap_err();
+        assert_eq!(error.to_string(), "Participant 3 sent a bundle it did not sign");
+
+        // And a key package can't sign under someone else's index
+        assert!(SigningBundle::commitment(&key_package, 1, "FROST-secp256k1", "session-1", &commitments).is_err());
+    }
+}
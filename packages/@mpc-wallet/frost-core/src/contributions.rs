@@ -0,0 +1,127 @@
+//! Per-participant checks run before signature aggregation.
+//!
+//! FROST's `aggregate` fails as a whole when any one signer's commitment or
+//! share is bad, which leaves the coordinator guessing whom to exclude.
+//! [`validate_contributions`] checks each signer on its own against the
+//! public shares from the DKG and names the first one that does not fit.
+
+use crate::errors::{FrostError, Result};
+use frost_core::keys::PublicKeyPackage;
+use frost_core::round2::SignatureShare;
+use frost_core::{Ciphersuite, Identifier, SigningPackage};
+use std::collections::BTreeMap;
+
+/// The 1-based participant index behind `identifier`, or its hex encoding
+/// for identifiers not derived from an index
+pub fn participant_label<C: Ciphersuite>(identifier: &Identifier<C>) -> String {
+    (1..=u16::MAX)
+        .find(|index| Identifier::<C>::try_from(*index).is_ok_and(|id| id == *identifier))
+        .map_or_else(|| hex::encode(identifier.serialize()), |index| index.to_string())
+}
+
+fn invalid<C: Ciphersuite>(identifier: &Identifier<C>, reason: &str) -> FrostError {
+    FrostError::InvalidContribution {
+        participant: participant_label(identifier),
+        reason: reason.to_string(),
+    }
+}
+
+/// Checks that every signer in `signing_package` sent a share, that no share
+/// comes from outside it, and that each signer's commitment and share verify
+/// against its public share in `public_key_package`
+pub fn validate_contributions<C: Ciphersuite>(
+    signing_package: &SigningPackage<C>,
+    signature_shares: &BTreeMap<Identifier<C>, SignatureShare<C>>,
+    public_key_package: &PublicKeyPackage<C>,
+) -> Result<()> {
+    let commitments = signing_package.signing_commitments();
+    if let Some(identifier) = signature_shares.keys().find(|id| !commitments.contains_key(id)) {
+        return Err(invalid(identifier, "sent a signature share without a commitment"));
+    }
+    for identifier in commitments.keys() {
+        let verifying_share = public_key_package
+            .verifying_shares()
+            .get(identifier)
+            .ok_or_else(|| invalid(identifier, "is not part of the group"))?;
+        let share = signature_shares
+            .get(identifier)
+            .ok_or_else(|| invalid(identifier, "sent a commitment without a signature share"))?;
+        frost_core::verify_signature_share(
+            *identifier,
+            verifying_share,
+            share,
+            signing_package,
+            public_key_package.verifying_key(),
+        )
+        .map_err(|_| invalid(identifier, "sent an invalid commitment or signature share"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_secp256k1::Secp256K1Sha256;
+    use frost_secp256k1::keys::{IdentifierList, KeyPackage};
+    use rand_core::OsRng;
+
+    type Shares = BTreeMap<Identifier<Secp256K1Sha256>, SignatureShare<Secp256K1Sha256>>;
+
+    /// Participants 1 and 3 of a 2-of-3 group sign a message. If `cheater` is
+    /// set, the commitment it sends the coordinator is not the one it signs
+    /// with.
+    fn sign(cheater: Option<u16>) -> (SigningPackage<Secp256K1Sha256>, Shares, PublicKeyPackage<Secp256K1Sha256>) {
+        let (secret_shares, public_key_package) =
+            frost_secp256k1::keys::generate_with_dealer(3, 2, IdentifierList::Default, OsRng).unwrap();
+        let mut nonces = BTreeMap::new();
+        let mut commitments = BTreeMap::new();
+        let mut key_packages = BTreeMap::new();
+        for index in [1u16, 3] {
+            let id = Identifier::try_from(index).unwrap();
+            let key_package = KeyPackage::try_from(secret_shares[&id].clone()).unwrap();
+            let (nonce, commitment) = frost_secp256k1::round1::commit(key_package.signing_share(), &mut OsRng);
+            nonces.insert(id, nonce);
+            commitments.insert(id, commitment);
+            key_packages.insert(id, key_package);
+        }
+        let package = SigningPackage::new(commitments.clone(), b"message");
+        let mut shares: Shares = key_packages
+            .iter()
+            .map(|(id, key_package)| (*id, frost_secp256k1::round2::sign(&package, &nonces[id], key_package).unwrap()))
+            .collect();
+
+        if let Some(index) = cheater {
+            let id = Identifier::try_from(index).unwrap();
+            let (nonce, commitment) = frost_secp256k1::round1::commit(key_packages[&id].signing_share(), &mut OsRng);
+            commitments.insert(id, commitment);
+            let signed = SigningPackage::new(commitments, b"message");
+            let share = frost_secp256k1::round2::sign(&signed, &nonce, &key_packages[&id]).unwrap();
+            shares.insert(id, share);
+        }
+        (package, shares, public_key_package)
+    }
+
+    #[test]
+    fn test_bad_commitment_names_participant() {
+        let (package, shares, public_key_package) = sign(None);
+        validate_contributions(&package, &shares, &public_key_package).unwrap();
+
+        let (package, shares, public_key_package) = sign(Some(3));
+        assert!(frost_core::aggregate(&package, &shares, &public_key_package).is_err());
+        match validate_contributions(&package, &shares, &public_key_package).unwrap_err() {
+            FrostError::InvalidContribution { participant, reason } => {
+                assert_eq!(participant, "3");
+                assert!(reason.contains("invalid commitment"));
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_missing_share_names_participant() {
+        let (package, mut shares, public_key_package) = sign(None);
+        shares.remove(&Identifier::try_from(1).unwrap());
+        let error = validate_contributions(&package, &shares, &public_key_package).unwrap_err();
+        assert_eq!(error.to_string(), "Participant 1 sent a commitment without a signature share");
+    }
+}
@@ -0,0 +1,353 @@
+//! Offline forensic replay of archived DKG ceremonies.
+//!
+//! Given the round 1 and round 2 packages recorded during a historical DKG,
+//! this module re-runs every public verification step of the protocol in a
+//! fixed order and reports the first point at which the ceremony diverged:
+//!
+//! 1. every participant published a round 1 package with `threshold`
+//!    commitments and a valid proof of knowledge;
+//! 2. every round 2 share `f_sender(recipient)` is consistent with the
+//!    sender's round 1 commitment.
+//!
+//! When a participant's [`RootSecret`] is available, their round 1 and
+//! round 2 output is also regenerated from the deterministic RNG and compared
+//! byte-for-byte with the archive, which catches packages that are internally
+//! valid but were not the ones that participant should have produced.
+
+use crate::errors::{FrostError, Result};
+use crate::root_secret::RootSecret;
+use frost_core::keys::dkg::{self, round1, round2};
+use frost_core::keys::SecretShare;
+use frost_core::{Ciphersuite, Identifier};
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Archived packages of a single DKG ceremony for one curve.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct DkgArchive<C: Ciphersuite> {
+    pub threshold: u16,
+    pub total: u16,
+    /// participant_index -> round 1 package it broadcast
+    pub round1: BTreeMap<u16, round1::Package<C>>,
+    /// sender_index -> recipient_index -> round 2 package
+    pub round2: BTreeMap<u16, BTreeMap<u16, round2::Package<C>>>,
+}
+
+impl<C: Ciphersuite> DkgArchive<C> {
+    /// Load an archive from its JSON representation.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| FrostError::SerializationError(e.to_string()))
+    }
+
+    /// Serialize the archive to JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|e| FrostError::SerializationError(e.to_string()))
+    }
+}
+
+/// DKG round in which a divergence was found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DkgRound {
+    Round1,
+    Round2,
+}
+
+/// The first inconsistency found while replaying a ceremony.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    pub round: DkgRound,
+    /// Participant whose output was inconsistent
+    pub participant: u16,
+    /// Recipient of the offending round 2 package, if any
+    pub recipient: Option<u16>,
+    pub reason: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.recipient {
+            Some(recipient) => write!(
+                f,
+                "{:?}: participant {} -> {}: {}",
+                self.round, self.participant, recipient, self.reason
+            ),
+            None => write!(f, "{:?}: participant {}: {}", self.round, self.participant, self.reason),
+        }
+    }
+}
+
+/// Outcome of a forensic replay.
+#[derive(Clone, Debug, Default)]
+pub struct ReplayReport {
+    /// Number of archived packages that passed verification
+    pub packages_verified: usize,
+    pub divergence: Option<Divergence>,
+}
+
+impl ReplayReport {
+    /// True if the whole archive replayed without divergence.
+    pub fn is_consistent(&self) -> bool {
+        self.divergence.is_none()
+    }
+
+    fn diverged(
+        mut self,
+        round: DkgRound,
+        participant: u16,
+        recipient: Option<u16>,
+        reason: impl Into<String>,
+    ) -> Self {
+        self.divergence = Some(Divergence {
+            round,
+            participant,
+            recipient,
+            reason: reason.into(),
+        });
+        self
+    }
+}
+
+/// Replay an archived DKG ceremony and report where it diverged.
+///
+/// `secrets` holds the root secrets of any participants that are available
+/// for the investigation (it may be empty for a public-only replay);
+/// `rng_tag` selects the curve-specific RNG derived from them, e.g.
+/// [`crate::root_secret::ED25519_RNG_TAG`].
+pub fn replay_dkg<C: Ciphersuite>(
+    archive: &DkgArchive<C>,
+    secrets: &BTreeMap<u16, RootSecret>,
+    rng_tag: &str,
+) -> Result<ReplayReport> {
+    let report = ReplayReport::default();
+    let ids = identifiers::<C>(archive.total)?;
+
+    // Round 1: structure and proofs of knowledge
+    let mut round1_packages = BTreeMap::new();
+    for (&index, &id) in &ids {
+        let Some(package) = archive.round1.get(&index) else {
+            return Ok(report.diverged(DkgRound::Round1, index, None, "round 1 package missing"));
+        };
+        if package.commitment().coefficients().len() != archive.threshold as usize {
+            return Ok(report.diverged(
+                DkgRound::Round1,
+                index,
+                None,
+                format!(
+                    "expected {} commitments, found {}",
+                    archive.threshold,
+                    package.commitment().coefficients().len()
+                ),
+            ));
+        }
+        round1_packages.insert(id, package.clone());
+    }
+    if let Err(e) = verify_proofs_of_knowledge(archive, &round1_packages) {
+        let participant = e.culprit().and_then(|id| index_of(&ids, &id)).unwrap_or(0);
+        return Ok(report.diverged(DkgRound::Round1, participant, None, e.to_string()));
+    }
+
+    // Round 1: regenerate from root secrets where available
+    let mut round1_secrets = BTreeMap::new();
+    for (&index, secret) in secrets {
+        let Some(&id) = ids.get(&index) else {
+            return Err(FrostError::InvalidIdentifier(format!(
+                "root secret supplied for unknown participant {}",
+                index
+            )));
+        };
+        let mut rng = secret.derive_rng(rng_tag)?;
+        let (secret_package, package) = dkg::part1(id, archive.total, archive.threshold, &mut rng)
+            .map_err(|e| FrostError::DkgError(e.to_string()))?;
+        if round1_packages.get(&id) != Some(&package) {
+            return Ok(report.diverged(
+                DkgRound::Round1,
+                index,
+                None,
+                "archived package does not match the one regenerated from the root secret",
+            ));
+        }
+        round1_secrets.insert(index, secret_package);
+    }
+    let mut report = ReplayReport {
+        packages_verified: round1_packages.len(),
+        ..report
+    };
+
+    // Round 2: every share must match the sender's commitment
+    for (&sender, &sender_id) in &ids {
+        let sent = archive.round2.get(&sender);
+        let regenerated = match round1_secrets.remove(&sender) {
+            Some(secret_package) => {
+                let others = without(&round1_packages, &sender_id);
+                let (_, packages) = dkg::part2(secret_package, &others)
+                    .map_err(|e| FrostError::DkgError(e.to_string()))?;
+                Some(packages)
+            }
+            None => None,
+        };
+
+        for (&recipient, &recipient_id) in &ids {
+            if recipient == sender {
+                continue;
+            }
+            let Some(package) = sent.and_then(|m| m.get(&recipient)) else {
+                return Ok(report.diverged(
+                    DkgRound::Round2,
+                    sender,
+                    Some(recipient),
+                    "round 2 package missing",
+                ));
+            };
+            let share = SecretShare::new(
+                recipient_id,
+                *package.signing_share(),
+                round1_packages[&sender_id].commitment().clone(),
+            );
+            if let Err(e) = share.verify() {
+                return Ok(report.diverged(DkgRound::Round2, sender, Some(recipient), e.to_string()));
+            }
+            if let Some(regenerated) = &regenerated
+                && regenerated.get(&recipient_id) != Some(package)
+            {
+                return Ok(report.diverged(
+                    DkgRound::Round2,
+                    sender,
+                    Some(recipient),
+                    "archived share does not match the one regenerated from the root secret",
+                ));
+            }
+            report.packages_verified += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Map participant indices `1..=total` to their FROST identifiers.
+fn identifiers<C: Ciphersuite>(total: u16) -> Result<BTreeMap<u16, Identifier<C>>> {
+    (1..=total)
+        .map(|index| {
+            Identifier::<C>::try_from(index)
+                .map(|id| (index, id))
+                .map_err(|e| FrostError::InvalidIdentifier(e.to_string()))
+        })
+        .collect()
+}
+
+fn index_of<C: Ciphersuite>(ids: &BTreeMap<u16, Identifier<C>>, id: &Identifier<C>) -> Option<u16> {
+    ids.iter().find(|(_, v)| *v == id).map(|(k, _)| *k)
+}
+
+fn without<C: Ciphersuite>(
+    packages: &BTreeMap<Identifier<C>, round1::Package<C>>,
+    id: &Identifier<C>,
+) -> BTreeMap<Identifier<C>, round1::Package<C>> {
+    packages
+        .iter()
+        .filter(|(k, _)| *k != id)
+        .map(|(k, v)| (*k, v.clone()))
+        .collect()
+}
+
+/// Verify every round 1 proof of knowledge.
+///
+/// FROST only checks proofs inside `part2`, so an auditor identifier outside
+/// the ceremony runs a throwaway `part2` over all archived packages.
+fn verify_proofs_of_knowledge<C: Ciphersuite>(
+    archive: &DkgArchive<C>,
+    round1_packages: &BTreeMap<Identifier<C>, round1::Package<C>>,
+) -> std::result::Result<(), frost_core::Error<C>> {
+    let auditor = Identifier::<C>::try_from(archive.total + 1)?;
+    let (auditor_secret, _) = dkg::part1(auditor, archive.total + 1, archive.threshold, OsRng)?;
+    dkg::part2(auditor_secret, round1_packages).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::root_secret::ED25519_RNG_TAG;
+    use frost_ed25519::Ed25519Sha512;
+
+    /// Record an honest 2-of-3 ceremony driven by deterministic root secrets.
+    fn record_ceremony() -> (DkgArchive<Ed25519Sha512>, BTreeMap<u16, RootSecret>) {
+        let secrets: BTreeMap<u16, RootSecret> = (1..=3u16)
+            .map(|i| (i, RootSecret::from_bytes([i as u8; 32])))
+            .collect();
+        let ids = identifiers::<Ed25519Sha512>(3).unwrap();
+
+        let mut r1_secrets = BTreeMap::new();
+        let mut round1 = BTreeMap::new();
+        for (&i, &id) in &ids {
+            let mut rng = secrets[&i].derive_rng(ED25519_RNG_TAG).unwrap();
+            let (secret, package) = dkg::part1(id, 3, 2, &mut rng).unwrap();
+            r1_secrets.insert(i, secret);
+            round1.insert(i, package);
+        }
+
+        let all: BTreeMap<_, _> = round1.iter().map(|(i, p)| (ids[i], p.clone())).collect();
+        let mut round2 = BTreeMap::new();
+        for (&i, &id) in &ids {
+            let (_, packages) = dkg::part2(r1_secrets.remove(&i).unwrap(), &without(&all, &id)).unwrap();
+            let by_index = packages
+                .into_iter()
+                .map(|(to, p)| (index_of(&ids, &to).unwrap(), p))
+                .collect();
+            round2.insert(i, by_index);
+        }
+
+        let archive = DkgArchive {
+            threshold: 2,
+            total: 3,
+            round1,
+            round2,
+        };
+        (archive, secrets)
+    }
+
+    #[test]
+    fn test_replay_honest_ceremony() {
+        let (archive, secrets) = record_ceremony();
+        let archive = DkgArchive::<Ed25519Sha512>::from_json(&archive.to_json().unwrap()).unwrap();
+
+        let public = replay_dkg(&archive, &BTreeMap::new(), ED25519_RNG_TAG).unwrap();
+        assert!(public.is_consistent());
+        assert_eq!(public.packages_verified, 3 + 6);
+
+        let full = replay_dkg(&archive, &secrets, ED25519_RNG_TAG).unwrap();
+        assert!(full.is_consistent());
+    }
+
+    #[test]
+    fn test_replay_pinpoints_bad_round2_share() {
+        let (mut archive, _) = record_ceremony();
+        // Participant 2 sent participant 3 the share meant for participant 1
+        let misrouted = archive.round2[&2][&1].clone();
+        archive.round2.get_mut(&2).unwrap().insert(3, misrouted);
+
+        let report = replay_dkg(&archive, &BTreeMap::new(), ED25519_RNG_TAG).unwrap();
+        let divergence = report.divergence.unwrap();
+        assert_eq!(divergence.round, DkgRound::Round2);
+        assert_eq!(divergence.participant, 2);
+        assert_eq!(divergence.recipient, Some(3));
+        // Round 1 (3 packages), participant 1's shares (2) and 2 -> 1 were fine
+        assert_eq!(report.packages_verified, 6);
+    }
+
+    #[test]
+    fn test_replay_pinpoints_substituted_round1_package() {
+        let (mut archive, secrets) = record_ceremony();
+        // A well-formed package that participant 3 never produced
+        let id = Identifier::<Ed25519Sha512>::try_from(3u16).unwrap();
+        let (_, forged) = dkg::part1(id, 3, 2, OsRng).unwrap();
+        archive.round1.insert(3, forged);
+
+        let report = replay_dkg(&archive, &secrets, ED25519_RNG_TAG).unwrap();
+        let divergence = report.divergence.unwrap();
+        assert_eq!(divergence.round, DkgRound::Round1);
+        assert_eq!(divergence.participant, 3);
+        assert_eq!(divergence.recipient, None);
+    }
+}
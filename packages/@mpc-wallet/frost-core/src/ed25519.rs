@@ -10,7 +10,7 @@ use frost_ed25519::{
     round2::SignatureShare,
     SigningPackage,
 };
-use rand_core::OsRng;
+use rand_core::CryptoRngCore;
 use std::collections::BTreeMap;
 
 pub struct Ed25519Curve;
@@ -30,6 +30,14 @@ impl FrostCurve for Ed25519Curve {
     type Signature = Signature;
     type SigningPackage = SigningPackage;
 
+    fn ciphersuite_context() -> String {
+        format!(
+            "{}/frost-core-{}",
+            <frost_ed25519::Ed25519Sha512 as frost_core::Ciphersuite>::ID,
+            crate::traits::FROST_CORE_VERSION
+        )
+    }
+
     fn identifier_from_u16(value: u16) -> Result<Self::Identifier> {
         let bytes = crate::traits::identifier_bytes_from_u16(value);
         Identifier::deserialize(&bytes)
@@ -40,7 +48,7 @@ impl FrostCurve for Ed25519Curve {
         identifier: Self::Identifier,
         total: u16,
         threshold: u16,
-        rng: &mut OsRng,
+        rng: &mut dyn CryptoRngCore,
     ) -> Result<(Self::Round1SecretPackage, Self::Round1Package)> {
         dkg::part1(identifier, total, threshold, rng)
             .map_err(|e| FrostError::DkgError(e.to_string()))
@@ -77,10 +85,15 @@ impl FrostCurve for Ed25519Curve {
         bs58::encode(pubkey_bytes).into_string()
     }
 
-    fn generate_signing_commitment(
+    // Sui and Aptos hash the key with a scheme flag; no formatters for them yet
+    fn get_address_for_chain(key: &Self::VerifyingKey, chain: &str) -> Result<String> {
+        crate::address::format_address("ed25519", chain, &Self::serialize_verifying_key(key)?)
+    }
+
+    fn generate_signing_commitment_with_rng(
         key_package: &Self::KeyPackage,
+        mut rng: &mut dyn CryptoRngCore,
     ) -> Result<(Self::SigningNonces, Self::SigningCommitments)> {
-        let mut rng = OsRng;
         let (nonces, commitments) = frost_ed25519::round1::commit(key_package.signing_share(), &mut rng);
         Ok((nonces, commitments))
     }
@@ -99,6 +112,7 @@ impl FrostCurve for Ed25519Curve {
         signature_shares: &BTreeMap<Self::Identifier, Self::SignatureShare>,
         public_key_package: &Self::PublicKeyPackage,
     ) -> Result<Self::Signature> {
+        crate::contributions::validate_contributions(signing_package, signature_shares, public_key_package)?;
         frost_ed25519::aggregate(signing_package, signature_shares, public_key_package)
             .map_err(|e| FrostError::SigningError(e.to_string()))
     }
@@ -119,4 +133,57 @@ impl FrostCurve for Ed25519Curve {
             .map(|bytes| bytes.to_vec())
             .map_err(|e| FrostError::SerializationError(e.to_string()))
     }
-}
\ No newline at end of file
+}
+
+// Additional Solana-specific functions
+impl Ed25519Curve {
+    /// Base58 Solana address, checked to round-trip to a 32-byte public key
+    pub fn get_solana_address(verifying_key: &frost_ed25519::VerifyingKey) -> Result<String> {
+        let pubkey_bytes = verifying_key.serialize()
+            .map_err(|e| FrostError::SerializationError(e.to_string()))?;
+        if pubkey_bytes.len() != SOLANA_PUBKEY_LEN {
+            return Err(FrostError::SerializationError(format!(
+                "Solana public key must be {} bytes, got {}",
+                SOLANA_PUBKEY_LEN,
+                pubkey_bytes.len()
+            )));
+        }
+
+        let address = bs58::encode(&pubkey_bytes).into_string();
+        let decoded = bs58::decode(&address)
+            .into_vec()
+            .map_err(|e| FrostError::SerializationError(e.to_string()))?;
+        if decoded != pubkey_bytes || !(32..=44).contains(&address.len()) {
+            return Err(FrostError::SerializationError(format!(
+                "Invalid Solana address: {}",
+                address
+            )));
+        }
+        Ok(address)
+    }
+}
+
+const SOLANA_PUBKEY_LEN: usize = 32;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_solana_address_matches_default() {
+        let (_, public_key_package) = frost_ed25519::keys::generate_with_dealer(
+            3,
+            2,
+            frost_ed25519::keys::IdentifierList::Default,
+            OsRng,
+        )
+        .unwrap();
+        let key = Ed25519Curve::verifying_key(&public_key_package);
+
+        let address = Ed25519Curve::get_address_for_chain(&key, "solana").unwrap();
+        assert_eq!(address, Ed25519Curve::get_address(&key));
+        assert_eq!(bs58::decode(&address).into_vec().unwrap(), key.serialize().unwrap());
+        assert!(Ed25519Curve::get_address_for_chain(&key, "ethereum").is_err());
+    }
+}
@@ -25,6 +25,9 @@ pub enum FrostError {
 
     #[error("Derivation error: {0}")]
     DerivationError(String),
+
+    #[error("Participant {participant} {reason}")]
+    InvalidContribution { participant: String, reason: String },
     
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
@@ -72,7 +72,7 @@ pub struct DerivationPath {
 }
 
 /// Marker for hardened derivation (BIP-32).
-const HARDENED_BIT: u32 = 0x80000000;
+pub const HARDENED_BIT: u32 = 0x80000000;
 
 impl DerivationPath {
     /// Create a derivation path from raw segments.
@@ -382,6 +382,39 @@ pub fn derive_child_key_path<C: Ciphersuite>(
     })
 }
 
+/// Derive only the child group verifying key along a derivation path.
+///
+/// Every step depends on public data alone (the parent group key and chain
+/// code), so anyone holding the group key can check that a key claimed to
+/// sit at `path` really belongs to the wallet.
+pub fn derive_child_verifying_key<C: Ciphersuite>(
+    verifying_key: &frost_core::VerifyingKey<C>,
+    chain_code: &ChainCode,
+    path: &DerivationPath,
+) -> Result<frost_core::VerifyingKey<C>> {
+    let mut current_vk = *verifying_key;
+    let mut current_cc = chain_code.clone();
+
+    for &index in path.segments() {
+        let vk_bytes = current_vk
+            .serialize()
+            .map_err(|e| FrostError::DerivationError(format!("serialize vk: {e}")))?;
+        let (scalar_seed, child_chaincode_bytes) =
+            hmac_derive(current_cc.as_bytes(), vk_bytes.as_ref(), index);
+        let offset_scalar = scalar_from_seed::<C>(&scalar_seed)?;
+        let offset_point = <C::Group as frost_core::Group>::generator() * offset_scalar;
+
+        let child_vk_element = bytes_to_element::<C>(vk_bytes.as_ref())? + offset_point;
+        let child_vk_bytes = <C::Group as frost_core::Group>::serialize(&child_vk_element)
+            .map_err(|e| FrostError::DerivationError(format!("serialize child vk: {e}")))?;
+        current_vk = frost_core::VerifyingKey::<C>::deserialize(child_vk_bytes.as_ref())
+            .map_err(|e| FrostError::DerivationError(format!("child verifying key: {e}")))?;
+        current_cc = ChainCode(child_chaincode_bytes);
+    }
+
+    Ok(current_vk)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -676,4 +709,20 @@ mod tests {
         // Verify it produces a valid key by checking we can serialize
         let _vk_bytes = derived.public_key_package.verifying_key().serialize().unwrap();
     }
+
+    #[test]
+    fn test_child_verifying_key_from_public_data() {
+        let participants = run_dkg();
+        let p = &participants[0];
+
+        let secp_kp = p.secp256k1_key_package().unwrap();
+        let secp_pub = p.secp256k1_public_key_package().unwrap();
+        let secp_vk_bytes = secp_pub.verifying_key().serialize().unwrap();
+        let cc = ChainCode::from_group_key(secp_vk_bytes.as_ref());
+
+        let path = DerivationPath::parse("m/84'/0'/0'/1/0").unwrap();
+        let derived = derive_child_key_path::<frost_secp256k1::Secp256K1Sha256>(secp_kp, secp_pub, &cc, &path).unwrap();
+        let child_vk = derive_child_verifying_key(secp_pub.verifying_key(), &cc, &path).unwrap();
+        assert_eq!(&child_vk, derived.public_key_package.verifying_key());
+    }
 }
@@ -13,6 +13,10 @@ pub struct KeystoreData {
     pub participant_index: u16,
     pub participant_indices: Vec<u16>,
     pub curve: String,  // "secp256k1" or "ed25519"
+    /// `FrostCurve::ciphersuite_context` of the build that wrote this;
+    /// absent in older keystores
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ciphersuite_context: Option<String>,
     
     // Additional fields for UI/management
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -62,6 +66,7 @@ impl Keystore {
             participant_index,
             participant_indices,
             curve: curve.to_string(),
+            ciphersuite_context: Some(C::ciphersuite_context()),
             wallet_id: None,
             device_id: None,
             device_name: None,
@@ -74,6 +79,9 @@ impl Keystore {
     pub fn import_keystore<C: crate::traits::FrostCurve>(
         keystore_data: &KeystoreData,
     ) -> Result<(C::KeyPackage, C::PublicKeyPackage)> {
+        if let Some(context) = &keystore_data.ciphersuite_context {
+            crate::traits::check_ciphersuite_context::<C>(context)?;
+        }
         let key_package_bytes = BASE64.decode(&keystore_data.key_package)
             .map_err(|e| FrostError::SerializationError(format!("Failed to decode key package: {}", e)))?;
         let public_key_package_bytes = BASE64.decode(&keystore_data.public_key_package)
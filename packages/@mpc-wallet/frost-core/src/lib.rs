@@ -8,9 +8,17 @@ pub mod errors;
 pub mod root_secret;
 pub mod unified_dkg;
 pub mod hd_derivation;
+pub mod dkg_replay;
+pub mod single_signer;
+pub mod participants;
+pub mod repair;
+pub mod reshare;
+pub mod contributions;
+pub mod bundle;
+pub mod address;
 
 // Re-export main types
-pub use traits::FrostCurve;
+pub use traits::{FrostCurve, check_ciphersuite_context};
 pub use errors::{FrostError, Result};
 pub use keystore::{Keystore, KeystoreData, MultiCurveKeystoreData};
 
@@ -21,4 +29,12 @@ pub use secp256k1::Secp256k1Curve;
 // Re-export unified DKG types
 pub use root_secret::RootSecret;
 pub use unified_dkg::UnifiedDkg;
-pub use hd_derivation::{ChainCode, DerivationPath, DerivedKeys, derive_child_key, derive_child_key_path};
\ No newline at end of file
+pub use dkg_replay::{DkgArchive, ReplayReport, replay_dkg};
+pub use single_signer::sign_single_party;
+pub use participants::{participant_index, participant_index_map};
+pub use contributions::{participant_label, validate_contributions};
+pub use bundle::{BundleContents, SigningBundle};
+pub use address::{AddressFormatter, address_formatter, format_address, register_address_formatter};
+pub use repair::{repair_share_step1, repair_share_step2, repair_share_step3};
+pub use reshare::{reshare_digest, reshare_step1, reshare_step2, reshare_step3};
+pub use hd_derivation::{ChainCode, DerivationPath, DerivedKeys, derive_child_key, derive_child_key_path, derive_child_verifying_key};
\ No newline at end of file
@@ -0,0 +1,66 @@
+//! Deterministic participant numbering for DKG.
+//!
+//! FROST identifiers are built from a 1-based participant index. Taking that
+//! index from join order lets two nodes disagree about who is participant 2;
+//! numbering the device ids in lexicographic order gives every node the same
+//! answer with no extra signalling. Call this before `init_dkg`.
+
+use crate::errors::{FrostError, Result};
+use std::collections::BTreeMap;
+
+/// Map each device id to its 1-based participant index, assigned in
+/// lexicographic order of the ids. Fails on duplicate ids.
+pub fn participant_index_map<S: AsRef<str>>(device_ids: &[S]) -> Result<BTreeMap<String, u16>> {
+    let mut sorted: Vec<&str> = device_ids.iter().map(AsRef::as_ref).collect();
+    sorted.sort_unstable();
+    if let Some(pair) = sorted.windows(2).find(|pair| pair[0] == pair[1]) {
+        return Err(FrostError::InvalidIdentifier(format!("duplicate device id {}", pair[0])));
+    }
+    sorted
+        .into_iter()
+        .enumerate()
+        .map(|(position, device_id)| {
+            let index = u16::try_from(position + 1).map_err(|_| {
+                FrostError::InvalidIdentifier(format!("too many participants ({})", device_ids.len()))
+            })?;
+            Ok((device_id.to_string(), index))
+        })
+        .collect()
+}
+
+/// The 1-based participant index of `device_id` among `device_ids`
+pub fn participant_index<S: AsRef<str>>(device_ids: &[S], device_id: &str) -> Result<u16> {
+    participant_index_map(device_ids)?
+        .get(device_id)
+        .copied()
+        .ok_or_else(|| FrostError::InvalidIdentifier(format!("device {} is not a participant", device_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mapping_ignores_join_order() {
+        let orderings = [
+            ["mpc-3", "mpc-1", "mpc-2"],
+            ["mpc-1", "mpc-2", "mpc-3"],
+            ["mpc-2", "mpc-3", "mpc-1"],
+        ];
+        let expected = BTreeMap::from([
+            ("mpc-1".to_string(), 1),
+            ("mpc-2".to_string(), 2),
+            ("mpc-3".to_string(), 3),
+        ]);
+        for ordering in orderings {
+            assert_eq!(participant_index_map(&ordering).unwrap(), expected);
+            assert_eq!(participant_index(&ordering, "mpc-3").unwrap(), 3);
+        }
+    }
+
+    #[test]
+    fn test_rejects_duplicates_and_strangers() {
+        assert!(participant_index_map(&["a", "b", "a"]).is_err());
+        assert!(participant_index(&["a", "b"], "c").is_err());
+    }
+}
@@ -0,0 +1,203 @@
+//! Share repair for a lost participant.
+//!
+//! Wraps FROST's Repairable Threshold Scheme so that `min_signers` surviving
+//! participants (the helpers) can rebuild the key share of a destroyed
+//! device without changing the group key:
+//!
+//! 1. every helper runs [`repair_share_step1`] and sends each other helper
+//!    its delta;
+//! 2. every helper sums the deltas it received with [`repair_share_step2`]
+//!    and sends the resulting sigma to the replacement device;
+//! 3. the replacement device runs [`repair_share_step3`] on all sigmas to get
+//!    the lost `KeyPackage` back.
+//!
+//! Deltas and sigmas travel as serialized scalars.
+
+use crate::errors::{FrostError, Result};
+use frost_core::keys::repairable;
+use frost_core::keys::{KeyPackage, PublicKeyPackage, SecretShare, VerifiableSecretSharingCommitment, VerifyingShare};
+use rand_core::{CryptoRng, RngCore};
+use frost_core::{Ciphersuite, Field, Group, Identifier, Scalar};
+use std::collections::BTreeMap;
+
+type SerializedScalar<C> = <<<C as Ciphersuite>::Group as Group>::Field as Field>::Serialization;
+
+pub(crate) fn serialize_scalar<C: Ciphersuite>(scalar: &Scalar<C>) -> Vec<u8> {
+    <<C::Group as Group>::Field as Field>::serialize(scalar).as_ref().to_vec()
+}
+
+pub(crate) fn deserialize_scalar<C: Ciphersuite>(bytes: &[u8]) -> Result<Scalar<C>> {
+    let serialization = SerializedScalar::<C>::try_from(bytes.to_vec())
+        .map_err(|_| FrostError::SerializationError(format!("invalid scalar length {}", bytes.len())))?;
+    <<C::Group as Group>::Field as Field>::deserialize(&serialization)
+        .map_err(|e| FrostError::SerializationError(e.to_string()))
+}
+
+/// The repair steps only read a share's identifier and signing share; the
+/// DKG left no single sharing commitment to put in a `SecretShare` anyway
+fn empty_commitment<C: Ciphersuite>() -> Result<VerifiableSecretSharingCommitment<C>> {
+    VerifiableSecretSharingCommitment::deserialize(Vec::<Vec<u8>>::new())
+        .map_err(|e| FrostError::DkgError(e.to_string()))
+}
+
+/// Step 1, run by each helper: split this helper's part of the lost share
+/// into one delta per helper (its own included). `helpers` must list at
+/// least `min_signers` distinct surviving participants, this one among them.
+pub fn repair_share_step1<C: Ciphersuite, R: RngCore + CryptoRng>(
+    helpers: &[Identifier<C>],
+    key_package: &KeyPackage<C>,
+    lost: Identifier<C>,
+    rng: &mut R,
+) -> Result<BTreeMap<Identifier<C>, Vec<u8>>> {
+    if helpers.len() < *key_package.min_signers() as usize {
+        return Err(FrostError::DkgError(format!(
+            "repair needs {} helpers, got {}",
+            key_package.min_signers(),
+            helpers.len()
+        )));
+    }
+    if helpers.contains(&lost) || !helpers.contains(key_package.identifier()) {
+        return Err(FrostError::InvalidIdentifier(
+            "helpers must include this participant and not the lost one".to_string(),
+        ));
+    }
+
+    let commitment = empty_commitment::<C>()?;
+    let share = SecretShare::new(*key_package.identifier(), *key_package.signing_share(), commitment);
+    let deltas = repairable::repair_share_step_1(helpers, &share, rng, lost)
+        .map_err(|e| FrostError::DkgError(e.to_string()))?;
+    Ok(deltas
+        .iter()
+        .map(|(helper, delta)| (*helper, serialize_scalar::<C>(delta)))
+        .collect())
+}
+
+/// Step 2, run by each helper: combine the deltas addressed to it by every
+/// helper into the sigma it sends to the replacement device
+pub fn repair_share_step2<C: Ciphersuite>(deltas: &[Vec<u8>]) -> Result<Vec<u8>> {
+    let deltas = deltas
+        .iter()
+        .map(|delta| deserialize_scalar::<C>(delta))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(serialize_scalar::<C>(&repairable::repair_share_step_2::<C>(&deltas)))
+}
+
+/// Step 3, run by the replacement device: rebuild the lost participant's
+/// `KeyPackage` from every helper's sigma. Fails unless the repaired share
+/// matches the verifying share the group already holds for `lost`.
+pub fn repair_share_step3<C: Ciphersuite>(
+    sigmas: &[Vec<u8>],
+    lost: Identifier<C>,
+    public_key_package: &PublicKeyPackage<C>,
+    min_signers: u16,
+) -> Result<KeyPackage<C>> {
+    let expected = public_key_package
+        .verifying_shares()
+        .get(&lost)
+        .copied()
+        .ok_or_else(|| FrostError::InvalidIdentifier("lost participant is not in the group".to_string()))?;
+    let sigmas = sigmas
+        .iter()
+        .map(|sigma| deserialize_scalar::<C>(sigma))
+        .collect::<Result<Vec<_>>>()?;
+    let commitment = empty_commitment::<C>()?;
+    let signing_share = *repairable::repair_share_step_3(&sigmas, lost, &commitment).signing_share();
+
+    if VerifyingShare::from(signing_share) != expected {
+        return Err(FrostError::DkgError(
+            "repaired share does not match the group's verifying share".to_string(),
+        ));
+    }
+    Ok(KeyPackage::new(
+        lost,
+        signing_share,
+        expected,
+        *public_key_package.verifying_key(),
+        min_signers,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_core::{round1, round2, SigningPackage};
+    use frost_secp256k1::Secp256K1Sha256;
+    use rand_core::OsRng;
+
+    type C = Secp256K1Sha256;
+
+    #[test]
+    fn test_two_survivors_repair_third_share() {
+        let (shares, public_key_package) = frost_secp256k1::keys::generate_with_dealer(
+            3,
+            2,
+            frost_secp256k1::keys::IdentifierList::Default,
+            OsRng,
+        )
+        .unwrap();
+        let key_packages: BTreeMap<_, _> = shares
+            .into_iter()
+            .map(|(id, share)| (id, KeyPackage::<C>::try_from(share).unwrap()))
+            .collect();
+        let ids: Vec<Identifier<C>> = key_packages.keys().copied().collect();
+        let (helpers, lost) = (vec![ids[0], ids[1]], ids[2]);
+
+        // Each helper deals one delta to every helper
+        let mut inbox: BTreeMap<Identifier<C>, Vec<Vec<u8>>> = BTreeMap::new();
+        for helper in &helpers {
+            let deltas = repair_share_step1(&helpers, &key_packages[helper], lost, &mut OsRng).unwrap();
+            for (to, delta) in deltas {
+                inbox.entry(to).or_default().push(delta);
+            }
+        }
+        let sigmas: Vec<Vec<u8>> = helpers
+            .iter()
+            .map(|helper| repair_share_step2::<C>(&inbox[helper]).unwrap())
+            .collect();
+        let repaired = repair_share_step3(&sigmas, lost, &public_key_package, 2).unwrap();
+        assert_eq!(repaired, key_packages[&lost]);
+
+        // The replacement device signs with an untouched member
+        let message = b"repaired";
+        let signers = [&key_packages[&ids[0]], &repaired];
+        let (nonces, commitments): (Vec<_>, BTreeMap<_, _>) = signers
+            .iter()
+            .map(|kp| {
+                let (nonces, commitments) = round1::commit(kp.signing_share(), &mut OsRng);
+                (nonces, (*kp.identifier(), commitments))
+            })
+            .unzip();
+        let signing_package = SigningPackage::new(commitments, message);
+        let signature_shares = signers
+            .iter()
+            .zip(&nonces)
+            .map(|(kp, nonces)| (*kp.identifier(), round2::sign(&signing_package, nonces, kp).unwrap()))
+            .collect();
+        let signature = frost_core::aggregate(&signing_package, &signature_shares, &public_key_package).unwrap();
+        assert!(public_key_package.verifying_key().verify(message, &signature).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_too_few_helpers_and_bad_sigmas() {
+        let (shares, public_key_package) = frost_secp256k1::keys::generate_with_dealer(
+            3,
+            2,
+            frost_secp256k1::keys::IdentifierList::Default,
+            OsRng,
+        )
+        .unwrap();
+        let mut key_packages = shares
+            .into_values()
+            .map(|share| KeyPackage::<C>::try_from(share).unwrap());
+        let helper = key_packages.next().unwrap();
+        let lost = *key_packages.nth(1).unwrap().identifier();
+
+        let err = repair_share_step1(&[*helper.identifier()], &helper, lost, &mut OsRng).unwrap_err();
+        assert!(matches!(err, FrostError::DkgError(_)));
+
+        // A single helper's sigma doesn't reconstruct the share
+        let sigma = helper.signing_share().serialize();
+        let err = repair_share_step3(&[sigma], lost, &public_key_package, 2).unwrap_err();
+        assert!(matches!(err, FrostError::DkgError(_)));
+    }
+}
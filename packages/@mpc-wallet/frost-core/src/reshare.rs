@@ -0,0 +1,381 @@
+//! Re-sharing an existing group key under a new threshold.
+//!
+//! Moves a group from one `(min_signers, max_signers)` to another, e.g.
+//! 2-of-3 to 3-of-5, without changing the group verifying key:
+//!
+//! 1. at least `min_signers` current participants (the dealers) each run
+//!    [`reshare_step1`], which splits their Lagrange-weighted share of the
+//!    group secret into one `SecretShare` per new participant;
+//! 2. every new participant collects the share each dealer addressed to it
+//!    and runs [`reshare_step2`] to get its `KeyPackage` and the group's new
+//!    `PublicKeyPackage`, then broadcasts that package's [`reshare_digest`];
+//! 3. every new participant runs [`reshare_step3`] on the digests the others
+//!    broadcast, and only switches to its new `KeyPackage` once they all
+//!    match its own.
+//!
+//! The dealers' weighted shares sum to the group secret, so the sum of the
+//! polynomials they deal has the same constant term and the group key stays
+//! put. Old shares stop being useful once everyone has switched over, which
+//! is why nobody switches before step 3: a dealer that sent inconsistent
+//! shares to different participants leaves them with public key packages
+//! that each verify locally but can't sign together.
+
+use crate::errors::{FrostError, Result};
+use crate::repair::{deserialize_scalar, serialize_scalar};
+use frost_core::keys::{self, IdentifierList, KeyPackage, PublicKeyPackage, SecretShare, SigningShare, VerifiableSecretSharingCommitment, VerifyingShare};
+use frost_core::{Ciphersuite, Element, Field, Group, Identifier, Scalar, SigningKey};
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+
+fn to_scalar<C: Ciphersuite>(identifier: &Identifier<C>) -> Result<Scalar<C>> {
+    deserialize_scalar::<C>(&identifier.serialize())
+}
+
+fn deserialize_element<C: Ciphersuite>(bytes: &[u8]) -> Result<Element<C>> {
+    let serialization = <C::Group as Group>::Serialization::try_from(bytes.to_vec())
+        .map_err(|_| FrostError::SerializationError(format!("invalid element length {}", bytes.len())))?;
+    <C::Group as Group>::deserialize(&serialization).map_err(|e| FrostError::SerializationError(e.to_string()))
+}
+
+fn serialize_element<C: Ciphersuite>(element: &Element<C>) -> Result<Vec<u8>> {
+    <C::Group as Group>::serialize(element)
+        .map(|bytes| bytes.as_ref().to_vec())
+        .map_err(|e| FrostError::SerializationError(e.to_string()))
+}
+
+/// The Lagrange coefficient at zero of `dealer` within `dealers`
+fn lagrange_at_zero<C: Ciphersuite>(dealer: &Identifier<C>, dealers: &[Identifier<C>]) -> Result<Scalar<C>> {
+    let x_i = to_scalar(dealer)?;
+    let mut numerator = <<C::Group as Group>::Field as Field>::one();
+    let mut denominator = <<C::Group as Group>::Field as Field>::one();
+    for other in dealers.iter().filter(|other| *other != dealer) {
+        let x_j = to_scalar(other)?;
+        numerator = numerator * x_j;
+        denominator = denominator * (x_j - x_i);
+    }
+    let inverse = <<C::Group as Group>::Field as Field>::invert(&denominator)
+        .map_err(|_| FrostError::InvalidIdentifier("dealers must be distinct".to_string()))?;
+    Ok(numerator * inverse)
+}
+
+fn check_dealers<C: Ciphersuite>(dealers: &[Identifier<C>], min_signers: u16) -> Result<()> {
+    if dealers.len() < min_signers as usize {
+        return Err(FrostError::DkgError(format!(
+            "re-sharing needs {} dealers, got {}",
+            min_signers,
+            dealers.len()
+        )));
+    }
+    if dealers.iter().collect::<BTreeSet<_>>().len() != dealers.len() {
+        return Err(FrostError::InvalidIdentifier("dealers must be distinct".to_string()));
+    }
+    Ok(())
+}
+
+/// Step 1, run by each dealer: split this dealer's part of the group secret
+/// into one `SecretShare` per entry of `new_participants`, any `new_min_signers`
+/// of which can sign afterwards. `dealers` must list at least the current
+/// `min_signers` participants, this one among them, and be the same list on
+/// every dealer.
+pub fn reshare_step1<C: Ciphersuite, R: RngCore + CryptoRng>(
+    key_package: &KeyPackage<C>,
+    dealers: &[Identifier<C>],
+    new_participants: &[Identifier<C>],
+    new_min_signers: u16,
+    rng: &mut R,
+) -> Result<BTreeMap<Identifier<C>, SecretShare<C>>> {
+    check_dealers(dealers, *key_package.min_signers())?;
+    if !dealers.contains(key_package.identifier()) {
+        return Err(FrostError::InvalidIdentifier("dealers must include this participant".to_string()));
+    }
+    let new_max_signers = u16::try_from(new_participants.len())
+        .map_err(|_| FrostError::DkgError("too many new participants".to_string()))?;
+
+    let share = deserialize_scalar::<C>(&key_package.signing_share().serialize())?;
+    let weighted = lagrange_at_zero(key_package.identifier(), dealers)? * share;
+    let secret = SigningKey::<C>::from_scalar(weighted).map_err(|e| FrostError::DkgError(e.to_string()))?;
+    let (shares, _) = keys::split(
+        &secret,
+        new_max_signers,
+        new_min_signers,
+        IdentifierList::Custom(new_participants),
+        rng,
+    )
+    .map_err(|e| FrostError::DkgError(e.to_string()))?;
+    Ok(shares)
+}
+
+/// Step 2, run by each new participant: combine the share every dealer sent
+/// it into its new `KeyPackage`, and the new `PublicKeyPackage` for the whole
+/// of `new_participants`. Fails if a dealer's share does not verify, does not
+/// commit to that dealer's weighted share of the old group, or if the result
+/// would change the group key, which is what too few dealers leads to.
+pub fn reshare_step2<C: Ciphersuite>(
+    identifier: Identifier<C>,
+    old_public_key_package: &PublicKeyPackage<C>,
+    new_participants: &[Identifier<C>],
+    new_min_signers: u16,
+    shares: &BTreeMap<Identifier<C>, SecretShare<C>>,
+) -> Result<(KeyPackage<C>, PublicKeyPackage<C>)> {
+    let dealers: Vec<Identifier<C>> = shares.keys().copied().collect();
+    if !new_participants.contains(&identifier) {
+        return Err(FrostError::InvalidIdentifier("participant is not in the new group".to_string()));
+    }
+
+    let mut signing_share = <<C::Group as Group>::Field as Field>::zero();
+    let mut summed: Vec<Element<C>> = vec![<C::Group as Group>::identity(); new_min_signers as usize];
+    for (dealer, share) in shares {
+        let invalid = |reason: &str| FrostError::DkgError(format!("share from dealer {} {}", crate::participant_label(dealer), reason));
+        if *share.identifier() != identifier {
+            return Err(invalid("is addressed to another participant"));
+        }
+        share.verify().map_err(|_| invalid("does not match its commitment"))?;
+        let coefficients = share
+            .commitment()
+            .serialize()
+            .map_err(|e| FrostError::SerializationError(e.to_string()))?
+            .iter()
+            .map(|bytes| deserialize_element::<C>(bytes))
+            .collect::<Result<Vec<_>>>()?;
+        if coefficients.len() != summed.len() {
+            return Err(invalid("has the wrong threshold"));
+        }
+
+        let old_share = old_public_key_package
+            .verifying_shares()
+            .get(dealer)
+            .ok_or_else(|| invalid("comes from outside the group"))?
+            .serialize()
+            .map_err(|e| FrostError::SerializationError(e.to_string()))?;
+        let expected = deserialize_element::<C>(&old_share)? * lagrange_at_zero(dealer, &dealers)?;
+        if coefficients[0] != expected {
+            return Err(invalid("does not re-share the dealer's key share"));
+        }
+
+        signing_share = signing_share + deserialize_scalar::<C>(&share.signing_share().serialize())?;
+        for (sum, coefficient) in summed.iter_mut().zip(coefficients) {
+            *sum = *sum + coefficient;
+        }
+    }
+
+    let commitment = VerifiableSecretSharingCommitment::<C>::deserialize(
+        summed.iter().map(serialize_element::<C>).collect::<Result<Vec<_>>>()?,
+    )
+    .map_err(|e| FrostError::SerializationError(e.to_string()))?;
+    let ids: BTreeSet<Identifier<C>> = new_participants.iter().copied().collect();
+    let public_key_package = PublicKeyPackage::from_commitment(&ids, &commitment)
+        .map_err(|e| FrostError::DkgError(e.to_string()))?;
+    if public_key_package.verifying_key() != old_public_key_package.verifying_key() {
+        return Err(FrostError::DkgError("re-sharing would change the group key".to_string()));
+    }
+
+    let signing_share = SigningShare::deserialize(&serialize_scalar::<C>(&signing_share))
+        .map_err(|e| FrostError::SerializationError(e.to_string()))?;
+    let verifying_share = public_key_package.verifying_shares()[&identifier];
+    if VerifyingShare::from(signing_share) != verifying_share {
+        return Err(FrostError::DkgError(
+            "re-shared key share does not match the group's verifying share".to_string(),
+        ));
+    }
+    let key_package = KeyPackage::new(
+        identifier,
+        signing_share,
+        verifying_share,
+        *public_key_package.verifying_key(),
+        new_min_signers,
+    );
+    Ok((key_package, public_key_package))
+}
+
+/// SHA-256 of a re-shared `PublicKeyPackage`, the proposal each new
+/// participant broadcasts after [`reshare_step2`]
+pub fn reshare_digest<C: Ciphersuite>(public_key_package: &PublicKeyPackage<C>) -> Result<[u8; 32]> {
+    let bytes = public_key_package
+        .serialize()
+        .map_err(|e| FrostError::SerializationError(e.to_string()))?;
+    Ok(Sha256::digest(bytes).into())
+}
+
+/// Step 3, run by each new participant before switching to the `KeyPackage`
+/// from [`reshare_step2`]: approve the re-sharing only if every other entry
+/// of `new_participants` broadcast the same [`reshare_digest`] as
+/// `public_key_package`, this participant's own result.
+pub fn reshare_step3<C: Ciphersuite>(
+    identifier: Identifier<C>,
+    public_key_package: &PublicKeyPackage<C>,
+    new_participants: &[Identifier<C>],
+    digests: &BTreeMap<Identifier<C>, [u8; 32]>,
+) -> Result<()> {
+    let own = reshare_digest(public_key_package)?;
+    if let Some(outsider) = digests.keys().find(|id| !new_participants.contains(id)) {
+        return Err(FrostError::InvalidIdentifier(format!(
+            "participant {} is not in the new group",
+            crate::participant_label(outsider)
+        )));
+    }
+    for participant in new_participants.iter().filter(|id| **id != identifier) {
+        let label = crate::participant_label(participant);
+        match digests.get(participant) {
+            None => {
+                return Err(FrostError::DkgError(format!(
+                    "participant {} has not reported its public key package",
+                    label
+                )));
+            }
+            Some(digest) if *digest != own => {
+                return Err(FrostError::DkgError(format!(
+                    "participant {} re-shared to a different public key package",
+                    label
+                )));
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_core::{round1, round2, SigningPackage};
+    use frost_secp256k1::Secp256K1Sha256;
+    use rand_core::OsRng;
+
+    type C = Secp256K1Sha256;
+
+    fn two_of_three() -> (BTreeMap<Identifier<C>, KeyPackage<C>>, PublicKeyPackage<C>) {
+        let (shares, public_key_package) = frost_secp256k1::keys::generate_with_dealer(
+            3,
+            2,
+            frost_secp256k1::keys::IdentifierList::Default,
+            OsRng,
+        )
+        .unwrap();
+        let key_packages = shares
+            .into_iter()
+            .map(|(id, share)| (id, KeyPackage::<C>::try_from(share).unwrap()))
+            .collect();
+        (key_packages, public_key_package)
+    }
+
+    fn ids(indices: &[u16]) -> Vec<Identifier<C>> {
+        indices.iter().map(|i| Identifier::try_from(*i).unwrap()).collect()
+    }
+
+    /// Every dealer deals to every new participant; returns each new
+    /// participant's inbox
+    fn deal(
+        key_packages: &BTreeMap<Identifier<C>, KeyPackage<C>>,
+        dealers: &[Identifier<C>],
+        new_participants: &[Identifier<C>],
+        new_min_signers: u16,
+    ) -> BTreeMap<Identifier<C>, BTreeMap<Identifier<C>, SecretShare<C>>> {
+        let mut inbox: BTreeMap<_, BTreeMap<_, _>> = BTreeMap::new();
+        for dealer in dealers {
+            let shares =
+                reshare_step1(&key_packages[dealer], dealers, new_participants, new_min_signers, &mut OsRng).unwrap();
+            for (to, share) in shares {
+                inbox.entry(to).or_default().insert(*dealer, share);
+            }
+        }
+        inbox
+    }
+
+    #[test]
+    fn test_two_of_three_becomes_three_of_five() {
+        let (key_packages, old_public_key_package) = two_of_three();
+        let dealers = ids(&[1, 3]);
+        let new_participants = ids(&[1, 2, 3, 4, 5]);
+        let inbox = deal(&key_packages, &dealers, &new_participants, 3);
+
+        let mut new_key_packages = BTreeMap::new();
+        let mut packages = BTreeMap::new();
+        for id in &new_participants {
+            let (key_package, package) =
+                reshare_step2(*id, &old_public_key_package, &new_participants, 3, &inbox[id]).unwrap();
+            assert_eq!(key_package.verifying_key(), old_public_key_package.verifying_key());
+            assert_eq!(*key_package.min_signers(), 3);
+            new_key_packages.insert(*id, key_package);
+            packages.insert(*id, package);
+        }
+        let digests: BTreeMap<_, _> = packages.iter().map(|(id, p)| (*id, reshare_digest(p).unwrap())).collect();
+        for (id, package) in &packages {
+            let others = digests.iter().filter(|(other, _)| *other != id).map(|(k, v)| (*k, *v)).collect();
+            reshare_step3(*id, package, &new_participants, &others).unwrap();
+        }
+        let public_key_package = packages.remove(&new_participants[0]).unwrap();
+
+        // Two newcomers and an existing participant sign for the old key
+        let message = b"reshared";
+        let signers: Vec<_> = ids(&[2, 4, 5]).iter().map(|id| new_key_packages[id].clone()).collect();
+        let (nonces, commitments): (Vec<_>, BTreeMap<_, _>) = signers
+            .iter()
+            .map(|kp| {
+                let (nonces, commitments) = round1::commit(kp.signing_share(), &mut OsRng);
+                (nonces, (*kp.identifier(), commitments))
+            })
+            .unzip();
+        let signing_package = SigningPackage::new(commitments, message);
+        let signature_shares = signers
+            .iter()
+            .zip(&nonces)
+            .map(|(kp, nonces)| (*kp.identifier(), round2::sign(&signing_package, nonces, kp).unwrap()))
+            .collect();
+        let signature = frost_core::aggregate(&signing_package, &signature_shares, &public_key_package).unwrap();
+        assert!(old_public_key_package.verifying_key().verify(message, &signature).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_too_few_dealers_and_forged_shares() {
+        let (key_packages, old_public_key_package) = two_of_three();
+        let new_participants = ids(&[1, 2, 3, 4]);
+
+        let err = reshare_step1(&key_packages[&ids(&[1])[0]], &ids(&[1]), &new_participants, 3, &mut OsRng).unwrap_err();
+        assert!(matches!(err, FrostError::DkgError(_)));
+
+        // A dealer that shares a fresh secret instead of its own key share
+        let dealers = ids(&[1, 2]);
+        let mut inbox = deal(&key_packages, &dealers, &new_participants, 3);
+        let target = new_participants[3];
+        let forged = frost_secp256k1::keys::split(
+            &SigningKey::new(&mut OsRng),
+            4,
+            3,
+            frost_secp256k1::keys::IdentifierList::Custom(&new_participants),
+            &mut OsRng,
+        )
+        .unwrap()
+        .0;
+        inbox.get_mut(&target).unwrap().insert(dealers[1], forged[&target].clone());
+        let err = reshare_step2(target, &old_public_key_package, &new_participants, 3, &inbox[&target]).unwrap_err();
+        assert!(err.to_string().contains("dealer 2 does not re-share"));
+    }
+
+    #[test]
+    fn test_step3_refuses_diverging_or_missing_digests() {
+        let (key_packages, old_public_key_package) = two_of_three();
+        let dealers = ids(&[1, 2]);
+        let new_participants = ids(&[1, 2, 3]);
+        let inbox = deal(&key_packages, &dealers, &new_participants, 2);
+        let (_, package) = reshare_step2(new_participants[0], &old_public_key_package, &new_participants, 2, &inbox[&new_participants[0]]).unwrap();
+        let own = reshare_digest(&package).unwrap();
+
+        // Participant 3 ended up with another package, e.g. from a dealer
+        // that dealt it a different polynomial
+        let mut digests = BTreeMap::from([(new_participants[1], own), (new_participants[2], [7; 32])]);
+        let err = reshare_step3(new_participants[0], &package, &new_participants, &digests).unwrap_err();
+        assert_eq!(err.to_string(), "DKG error: participant 3 re-shared to a different public key package");
+
+        digests.remove(&new_participants[2]);
+        let err = reshare_step3(new_participants[0], &package, &new_participants, &digests).unwrap_err();
+        assert_eq!(err.to_string(), "DKG error: participant 3 has not reported its public key package");
+
+        digests.insert(new_participants[2], own);
+        digests.insert(ids(&[4])[0], own);
+        let err = reshare_step3(new_participants[0], &package, &new_participants, &digests).unwrap_err();
+        assert!(matches!(err, FrostError::InvalidIdentifier(_)));
+        digests.remove(&ids(&[4])[0]);
+        assert!(reshare_step3(new_participants[0], &package, &new_participants, &digests).is_ok());
+    }
+}
@@ -14,6 +14,12 @@ use sha2::Sha256;
 
 const ROOT_SECRET_LEN: usize = 32;
 
+/// HKDF info tag for the ed25519 DKG RNG.
+pub const ED25519_RNG_TAG: &str = "frost-dkg/ed25519";
+
+/// HKDF info tag for the secp256k1 DKG RNG.
+pub const SECP256K1_RNG_TAG: &str = "frost-dkg/secp256k1";
+
 /// A 32-byte root secret from which curve-specific DKG randomness is derived.
 #[derive(Clone)]
 pub struct RootSecret([u8; ROOT_SECRET_LEN]);
@@ -50,12 +56,12 @@ impl RootSecret {
 
     /// Derive a deterministic RNG for the ed25519 curve DKG.
     pub fn derive_ed25519_rng(&self) -> Result<ChaCha20Rng> {
-        self.derive_rng("frost-dkg/ed25519")
+        self.derive_rng(ED25519_RNG_TAG)
     }
 
     /// Derive a deterministic RNG for the secp256k1 curve DKG.
     pub fn derive_secp256k1_rng(&self) -> Result<ChaCha20Rng> {
-        self.derive_rng("frost-dkg/secp256k1")
+        self.derive_rng(SECP256K1_RNG_TAG)
     }
 }
 
@@ -10,7 +10,7 @@ use frost_secp256k1::{
     round2::SignatureShare,
     SigningPackage,
 };
-use rand_core::OsRng;
+use rand_core::CryptoRngCore;
 use std::collections::BTreeMap;
 use sha3::{Digest, Keccak256};
 use k256::ecdsa::VerifyingKey as K256VerifyingKey;
@@ -32,6 +32,14 @@ impl FrostCurve for Secp256k1Curve {
     type Signature = Signature;
     type SigningPackage = SigningPackage;
 
+    fn ciphersuite_context() -> String {
+        format!(
+            "{}/frost-core-{}",
+            <frost_secp256k1::Secp256K1Sha256 as frost_core::Ciphersuite>::ID,
+            crate::traits::FROST_CORE_VERSION
+        )
+    }
+
     fn identifier_from_u16(value: u16) -> Result<Self::Identifier> {
         let bytes = crate::traits::identifier_bytes_from_u16(value);
         Identifier::deserialize(&bytes)
@@ -42,7 +50,7 @@ impl FrostCurve for Secp256k1Curve {
         identifier: Self::Identifier,
         total: u16,
         threshold: u16,
-        rng: &mut OsRng,
+        rng: &mut dyn CryptoRngCore,
     ) -> Result<(Self::Round1SecretPackage, Self::Round1Package)> {
         dkg::part1(identifier, total, threshold, rng)
             .map_err(|e| FrostError::DkgError(e.to_string()))
@@ -81,10 +89,14 @@ impl FrostCurve for Secp256k1Curve {
         hex::encode(&pubkey_bytes)
     }
 
-    fn generate_signing_commitment(
+    fn get_address_for_chain(key: &Self::VerifyingKey, chain: &str) -> Result<String> {
+        crate::address::format_address("secp256k1", chain, &Self::serialize_verifying_key(key)?)
+    }
+
+    fn generate_signing_commitment_with_rng(
         key_package: &Self::KeyPackage,
+        mut rng: &mut dyn CryptoRngCore,
     ) -> Result<(Self::SigningNonces, Self::SigningCommitments)> {
-        let mut rng = OsRng;
         let (nonces, commitments) = frost_secp256k1::round1::commit(key_package.signing_share(), &mut rng);
         Ok((nonces, commitments))
     }
@@ -103,6 +115,7 @@ impl FrostCurve for Secp256k1Curve {
         signature_shares: &BTreeMap<Self::Identifier, Self::SignatureShare>,
         public_key_package: &Self::PublicKeyPackage,
     ) -> Result<Self::Signature> {
+        crate::contributions::validate_contributions(signing_package, signature_shares, public_key_package)?;
         frost_secp256k1::aggregate(signing_package, signature_shares, public_key_package)
             .map_err(|e| FrostError::SigningError(e.to_string()))
     }
@@ -117,6 +130,9 @@ impl FrostCurve for Secp256k1Curve {
         ))
     }
 
+    /// FROST Schnorr output (compressed `R` || `z`). It is not malleable like
+    /// ECDSA, so no low-`s` normalization applies here; chain handlers do
+    /// that for `SignatureFormat::Ecdsa` input.
     fn serialize_signature(signature: &Self::Signature) -> Result<Vec<u8>> {
         signature
             .serialize()
@@ -149,4 +165,41 @@ impl Secp256k1Curve {
             Err(FrostError::SerializationError("Failed to parse verifying key".to_string()))
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_core::OsRng;
+
+    fn verifying_key() -> frost_secp256k1::VerifyingKey {
+        let (_, public_key_package) = frost_secp256k1::keys::generate_with_dealer(
+            3,
+            2,
+            frost_secp256k1::keys::IdentifierList::Default,
+            OsRng,
+        )
+        .unwrap();
+        Secp256k1Curve::verifying_key(&public_key_package)
+    }
+
+    #[test]
+    fn test_ethereum_address_for_chain() {
+        let key = verifying_key();
+        let address = Secp256k1Curve::get_address_for_chain(&key, "ethereum").unwrap();
+        assert_eq!(address, Secp256k1Curve::get_eth_address(&key).unwrap());
+        assert!(address.starts_with("0x"));
+        assert_eq!(address.len(), 42);
+        assert!(Secp256k1Curve::get_address_for_chain(&key, "solana").is_err());
+    }
+
+    #[test]
+    fn test_no_bitcoin_address() {
+        // Neither an ECDSA-only SegWit v0 address nor an untweaked-key P2TR
+        // one would be spendable by a FROST signature
+        assert_eq!(
+            Secp256k1Curve::get_address_for_chain(&verifying_key(), "bitcoin").unwrap_err().to_string(),
+            "Invalid state: Unsupported chain for secp256k1: bitcoin"
+        );
+    }
 }
\ No newline at end of file
@@ -0,0 +1,104 @@
+//! One-call signing for degenerate 1-of-n wallets.
+//!
+//! With a threshold of 1 any single share can sign on its own, so the
+//! commit / sign / aggregate rounds collapse into a local computation with no
+//! network round-trip. Such wallets offer no threshold protection at all and
+//! exist for testing; every call logs a security warning.
+
+use crate::errors::{FrostError, Result};
+use frost_core::keys::{KeyPackage, PublicKeyPackage};
+use frost_core::{Ciphersuite, Signature, SigningPackage, round1, round2};
+use rand_core::OsRng;
+use std::collections::BTreeMap;
+
+/// Warning logged whenever the single-signer path is used.
+pub const SINGLE_SIGNER_WARNING: &str =
+    "signing with a 1-of-n wallet: any single share controls the funds";
+
+/// Produce, aggregate and verify a signature with a single key share.
+///
+/// Fails with [`FrostError::InvalidState`] unless the key package has a
+/// threshold (`min_signers`) of exactly 1.
+pub fn sign_single_party<C: Ciphersuite>(
+    key_package: &KeyPackage<C>,
+    public_key_package: &PublicKeyPackage<C>,
+    message: &[u8],
+) -> Result<Signature<C>> {
+    if *key_package.min_signers() != 1 {
+        return Err(FrostError::InvalidState(format!(
+            "single-party signing requires threshold 1, wallet threshold is {}",
+            key_package.min_signers()
+        )));
+    }
+    tracing::warn!("{}", SINGLE_SIGNER_WARNING);
+
+    let identifier = *key_package.identifier();
+    let (nonces, commitments) = round1::commit(key_package.signing_share(), &mut OsRng);
+    let signing_package = SigningPackage::new(BTreeMap::from([(identifier, commitments)]), message);
+
+    let share = round2::sign(&signing_package, &nonces, key_package)
+        .map_err(|e| FrostError::SigningError(e.to_string()))?;
+    let signature = frost_core::aggregate(
+        &signing_package,
+        &BTreeMap::from([(identifier, share)]),
+        public_key_package,
+    )
+    .map_err(|e| FrostError::SigningError(e.to_string()))?;
+
+    public_key_package
+        .verifying_key()
+        .verify(message, &signature)
+        .map_err(|e| FrostError::SigningError(format!("signature failed verification: {}", e)))?;
+    Ok(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frost_core::keys::{SigningShare, VerifyingShare};
+    use frost_core::{Identifier, SigningKey, VerifyingKey};
+    use frost_ed25519::Ed25519Sha512;
+
+    /// A 1-of-3 wallet: the sharing polynomial is constant, so every share
+    /// equals the group secret.
+    fn one_of_three() -> (Vec<KeyPackage<Ed25519Sha512>>, PublicKeyPackage<Ed25519Sha512>) {
+        let group_secret = SigningKey::<Ed25519Sha512>::new(&mut OsRng);
+        let signing_share = SigningShare::deserialize(&group_secret.serialize()).unwrap();
+        let verifying_share = VerifyingShare::from(signing_share);
+        let verifying_key = VerifyingKey::from(&group_secret);
+
+        let ids: Vec<Identifier<Ed25519Sha512>> =
+            (1..=3u16).map(|i| Identifier::try_from(i).unwrap()).collect();
+        let key_packages = ids
+            .iter()
+            .map(|id| KeyPackage::new(*id, signing_share, verifying_share, verifying_key, 1))
+            .collect();
+        let shares = ids.iter().map(|id| (*id, verifying_share)).collect();
+        (key_packages, PublicKeyPackage::new(shares, verifying_key))
+    }
+
+    #[test]
+    fn test_one_of_three_signs_in_one_call() {
+        let (key_packages, public_key_package) = one_of_three();
+        let message = b"single round-trip";
+
+        let signature = sign_single_party(&key_packages[2], &public_key_package, message).unwrap();
+
+        assert!(public_key_package.verifying_key().verify(message, &signature).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_real_threshold_wallet() {
+        let (shares, public_key_package) = frost_ed25519::keys::generate_with_dealer(
+            3,
+            2,
+            frost_ed25519::keys::IdentifierList::Default,
+            OsRng,
+        )
+        .unwrap();
+        let key_package = KeyPackage::try_from(shares.into_values().next().unwrap()).unwrap();
+
+        let err = sign_single_party(&key_package, &public_key_package, b"msg").unwrap_err();
+        assert!(matches!(err, FrostError::InvalidState(_)));
+    }
+}
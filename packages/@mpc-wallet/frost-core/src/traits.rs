@@ -1,7 +1,7 @@
-use crate::errors::Result;
+use crate::errors::{FrostError, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use rand_core::OsRng;
+use rand_core::{CryptoRngCore, OsRng};
 
 /// Serialize a u16 participant index into a 32-byte big-endian identifier.
 ///
@@ -15,6 +15,23 @@ pub fn identifier_bytes_from_u16(value: u16) -> [u8; 32] {
     bytes
 }
 
+/// The `frost-core` release the curve impls are built on. Hashing contexts
+/// may change between releases, so it is part of `ciphersuite_context`.
+/// A test keeps it in step with the version in `Cargo.lock`.
+pub const FROST_CORE_VERSION: &str = "2.2";
+
+/// Fails if a peer's `ciphersuite_context` differs from this build's for `C`
+pub fn check_ciphersuite_context<C: FrostCurve>(peer_context: &str) -> Result<()> {
+    let ours = C::ciphersuite_context();
+    if peer_context != ours {
+        return Err(FrostError::InvalidState(format!(
+            "Incompatible FROST ciphersuite: peer uses {}, this node uses {}",
+            peer_context, ours
+        )));
+    }
+    Ok(())
+}
+
 /// Generic trait for FROST curve operations
 /// This abstracts over Ed25519 and Secp256k1 curves
 pub trait FrostCurve {
@@ -34,6 +51,11 @@ pub trait FrostCurve {
     type Signature: Clone + Serialize + for<'de> Deserialize<'de>;
     type SigningPackage;
 
+    /// Ciphersuite ID plus `FROST_CORE_VERSION`, e.g.
+    /// "FROST-ED25519-SHA512-v1/frost-core-2.2". Nodes whose contexts differ
+    /// produce shares that will not combine.
+    fn ciphersuite_context() -> String;
+
     // DKG operations
     fn identifier_from_u16(value: u16) -> Result<Self::Identifier>;
     
@@ -41,7 +63,7 @@ pub trait FrostCurve {
         identifier: Self::Identifier,
         total: u16,
         threshold: u16,
-        rng: &mut OsRng,
+        rng: &mut dyn CryptoRngCore,
     ) -> Result<(Self::Round1SecretPackage, Self::Round1Package)>;
     
     fn dkg_part2(
@@ -59,10 +81,23 @@ pub trait FrostCurve {
     fn verifying_key(public_key_package: &Self::PublicKeyPackage) -> Self::VerifyingKey;
     fn serialize_verifying_key(key: &Self::VerifyingKey) -> Result<Vec<u8>>;
     fn get_address(key: &Self::VerifyingKey) -> String;
+    /// Address of `key` on a specific chain (e.g. "solana", "ethereum"),
+    /// from the formatter registered for it in [`crate::address`].
+    /// Fails for chains this curve cannot produce addresses for.
+    fn get_address_for_chain(key: &Self::VerifyingKey, chain: &str) -> Result<String>;
     
     // Signing operations
     fn generate_signing_commitment(
         key_package: &Self::KeyPackage,
+    ) -> Result<(Self::SigningNonces, Self::SigningCommitments)> {
+        Self::generate_signing_commitment_with_rng(key_package, &mut OsRng)
+    }
+
+    /// `generate_signing_commitment` drawing its nonces from `rng`, e.g. a
+    /// seeded one to reproduce test vectors
+    fn generate_signing_commitment_with_rng(
+        key_package: &Self::KeyPackage,
+        rng: &mut dyn CryptoRngCore,
     ) -> Result<(Self::SigningNonces, Self::SigningCommitments)>;
     
     fn generate_signature_share(
@@ -83,4 +118,30 @@ pub trait FrostCurve {
     ) -> Result<Self::SigningPackage>;
     
     fn serialize_signature(signature: &Self::Signature) -> Result<Vec<u8>>;
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frost_core_version_matches_lockfile() {
+        let lockfile = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../../../Cargo.lock"));
+        let resolved: Vec<&str> = lockfile
+            .split("[[package]]")
+            .filter(|package| package.contains("\nname = \"frost-core\"\n"))
+            .filter_map(|package| package.lines().find_map(|line| line.strip_prefix("version = ")))
+            .map(|version| version.trim_matches('"'))
+            .collect();
+        assert!(!resolved.is_empty(), "frost-core not found in Cargo.lock");
+        for version in resolved {
+            let major_minor: Vec<&str> = version.split('.').take(2).collect();
+            assert_eq!(
+                major_minor.join("."),
+                FROST_CORE_VERSION,
+                "Cargo.lock resolves frost-core {}; update FROST_CORE_VERSION",
+                version
+            );
+        }
+    }
+}
@@ -255,8 +255,9 @@ impl UnifiedDkg {
 
     /// Check if DKG can be finalized.
     pub fn can_finalize(&self) -> bool {
-        self.ed25519_round2_packages.len() >= (self.threshold - 1) as usize
-            && self.secp256k1_round2_packages.len() >= (self.threshold - 1) as usize
+        let expected = (self.total as usize).saturating_sub(1);
+        self.ed25519_round2_packages.len() == expected
+            && self.secp256k1_round2_packages.len() == expected
             && self.ed25519_round2_secret.is_some()
             && self.secp256k1_round2_secret.is_some()
     }
@@ -0,0 +1,167 @@
+//! In-process N-party DKG and threshold signing over both curves.
+//!
+//! Every participant is a separate `Party` that only sees what the others
+//! send it, and packages cross a JSON round trip as they would on the wire.
+//! This catches routing mistakes (wrong recipient, own package included,
+//! sender/recipient swapped) without a browser or a signal server.
+
+use std::collections::BTreeMap;
+
+use mpc_wallet_frost_core::{Ed25519Curve, FrostCurve, Secp256k1Curve};
+use rand_core::OsRng;
+
+/// (threshold, total) combinations exercised for each curve
+const SHAPES: &[(u16, u16)] = &[(2, 2), (2, 3), (3, 5)];
+
+struct Party<C: FrostCurve> {
+    index: u16,
+    identifier: C::Identifier,
+    round1_secret: Option<C::Round1SecretPackage>,
+    round2_secret: Option<C::Round2SecretPackage>,
+    round1_received: BTreeMap<C::Identifier, C::Round1Package>,
+    round2_received: BTreeMap<C::Identifier, C::Round2Package>,
+    key_package: Option<C::KeyPackage>,
+    public_key_package: Option<C::PublicKeyPackage>,
+}
+
+impl<C: FrostCurve> Party<C> {
+    fn new(index: u16) -> Self {
+        Self {
+            index,
+            identifier: C::identifier_from_u16(index).unwrap(),
+            round1_secret: None,
+            round2_secret: None,
+            round1_received: BTreeMap::new(),
+            round2_received: BTreeMap::new(),
+            key_package: None,
+            public_key_package: None,
+        }
+    }
+}
+
+/// Serialize and parse back, as a network hop would
+fn over_the_wire<T: serde::Serialize + serde::de::DeserializeOwned>(value: &T) -> T {
+    serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+}
+
+fn run_dkg<C: FrostCurve>(threshold: u16, total: u16) -> Vec<Party<C>> {
+    let mut parties: Vec<Party<C>> = (1..=total).map(Party::new).collect();
+
+    // Round 1: everyone broadcasts to everyone else
+    let mut broadcasts = Vec::new();
+    for party in parties.iter_mut() {
+        let (secret, package) = C::dkg_part1(party.identifier, total, threshold, &mut OsRng).unwrap();
+        party.round1_secret = Some(secret);
+        broadcasts.push((party.identifier, package));
+    }
+    for party in parties.iter_mut() {
+        for (sender, package) in broadcasts.iter().filter(|(id, _)| *id != party.identifier) {
+            party.round1_received.insert(*sender, over_the_wire(package));
+        }
+        assert_eq!(party.round1_received.len(), total as usize - 1);
+    }
+
+    // Round 2: each party sends a distinct package to every other party
+    let mut outboxes = Vec::new();
+    for party in parties.iter_mut() {
+        let (secret, packages) =
+            C::dkg_part2(party.round1_secret.take().unwrap(), &party.round1_received).unwrap();
+        assert_eq!(packages.len(), total as usize - 1);
+        assert!(!packages.contains_key(&party.identifier));
+        party.round2_secret = Some(secret);
+        outboxes.push((party.identifier, packages));
+    }
+    for party in parties.iter_mut() {
+        for (sender, packages) in outboxes.iter().filter(|(id, _)| *id != party.identifier) {
+            party.round2_received.insert(*sender, over_the_wire(&packages[&party.identifier]));
+        }
+    }
+
+    // Finalize
+    for party in parties.iter_mut() {
+        let (key_package, public_key_package) = C::dkg_part3(
+            party.round2_secret.as_ref().unwrap(),
+            &party.round1_received,
+            &party.round2_received,
+        )
+        .unwrap();
+        party.key_package = Some(key_package);
+        party.public_key_package = Some(public_key_package);
+    }
+    parties
+}
+
+fn group_key<C: FrostCurve>(party: &Party<C>) -> Vec<u8> {
+    C::serialize_verifying_key(&C::verifying_key(party.public_key_package.as_ref().unwrap())).unwrap()
+}
+
+/// Threshold signing among `signers`; the first signer acts as coordinator
+fn sign<C: FrostCurve>(signers: &[&Party<C>], message: &[u8]) -> C::Signature {
+    let mut nonces = Vec::new();
+    let mut commitments = BTreeMap::new();
+    for signer in signers {
+        let (nonce, commitment) = C::generate_signing_commitment(signer.key_package.as_ref().unwrap()).unwrap();
+        nonces.push(nonce);
+        commitments.insert(signer.identifier, over_the_wire(&commitment));
+    }
+
+    let mut shares = BTreeMap::new();
+    for (signer, nonce) in signers.iter().zip(&nonces) {
+        // Each signer builds its own signing package from the same commitments
+        let signing_package = C::create_signing_package(&commitments, message).unwrap();
+        let share = C::generate_signature_share(&signing_package, nonce, signer.key_package.as_ref().unwrap()).unwrap();
+        shares.insert(signer.identifier, over_the_wire(&share));
+    }
+
+    let coordinator = signers[0];
+    let signing_package = C::create_signing_package(&commitments, message).unwrap();
+    C::aggregate_signature(&signing_package, &shares, coordinator.public_key_package.as_ref().unwrap()).unwrap()
+}
+
+/// Runs DKG for every shape, checks all parties agree on the group key, then
+/// signs with the lowest and the highest `threshold` participants
+fn check_curve<C: FrostCurve>(verify: impl Fn(&C::PublicKeyPackage, &[u8], &C::Signature) -> bool) {
+    for &(threshold, total) in SHAPES {
+        let parties = run_dkg::<C>(threshold, total);
+        let expected = group_key(&parties[0]);
+        assert!(parties.iter().all(|p| group_key(p) == expected), "group key mismatch for {}-of-{}", threshold, total);
+
+        let t = threshold as usize;
+        for signers in [&parties[..t], &parties[parties.len() - t..]] {
+            let signers: Vec<&Party<C>> = signers.iter().collect();
+            let message = format!("{}-of-{} signed by {:?}", threshold, total, signers.iter().map(|p| p.index).collect::<Vec<_>>());
+            let signature = sign(&signers, message.as_bytes());
+            assert!(
+                verify(parties[0].public_key_package.as_ref().unwrap(), message.as_bytes(), &signature),
+                "invalid signature for {}",
+                message
+            );
+        }
+    }
+}
+
+#[test]
+fn ed25519_dkg_and_threshold_signing() {
+    check_curve::<Ed25519Curve>(|public_key_package, message, signature| {
+        public_key_package.verifying_key().verify(message, signature).is_ok()
+    });
+}
+
+#[test]
+fn secp256k1_dkg_and_threshold_signing() {
+    check_curve::<Secp256k1Curve>(|public_key_package, message, signature| {
+        public_key_package.verifying_key().verify(message, signature).is_ok()
+    });
+}
+
+#[test]
+fn below_threshold_signing_fails() {
+    let parties = run_dkg::<Secp256k1Curve>(3, 5);
+    let key_package = parties[0].key_package.as_ref().unwrap();
+    let (nonces, commitment) = Secp256k1Curve::generate_signing_commitment(key_package).unwrap();
+    let commitments = BTreeMap::from([(parties[0].identifier, commitment)]);
+
+    // FROST refuses a signing package with fewer commitments than min_signers
+    let signing_package = Secp256k1Curve::create_signing_package(&commitments, b"too few").unwrap();
+    assert!(Secp256k1Curve::generate_signature_share(&signing_package, &nonces, key_package).is_err());
+}
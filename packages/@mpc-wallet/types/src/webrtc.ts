@@ -25,6 +25,7 @@ export type WebRTCAppMessage =
   // DKG (Distributed Key Generation) Messages
   | { webrtc_msg_type: 'DkgRound1Package'; package: any } // frost_core::keys::dkg::round1::Package<Ed25519Sha512>
   | { webrtc_msg_type: 'DkgRound2Package'; package: any } // frost_core::keys::dkg::round2::Package<Ed25519Sha512>
+  | { webrtc_msg_type: 'DkgGroupKeyFingerprint'; fingerprint: string } // SHA-256 of the group verifying key, hex
 
   // FROST Signing Process Messages
   | { webrtc_msg_type: 'SigningRequest'; signing_id: string; transaction_data: string; required_signers: number }